@@ -0,0 +1,379 @@
+// 此文件由 rust-core 的 ast-codegen 根据 ast.rs 自动生成，请勿手动修改
+// 重新生成：cd rust-core && cargo run --features schema --bin ast-codegen -- kotlin > ../android/ASTModels.kt
+
+package com.imparse.renderer
+
+import kotlinx.serialization.KSerializer
+import kotlinx.serialization.SerialName
+import kotlinx.serialization.Serializable
+import kotlinx.serialization.descriptors.SerialDescriptor
+import kotlinx.serialization.encoding.Decoder
+import kotlinx.serialization.encoding.Encoder
+import kotlinx.serialization.json.Json
+import kotlinx.serialization.json.JsonDecoder
+import kotlinx.serialization.json.JsonElement
+import kotlinx.serialization.json.JsonEncoder
+import kotlinx.serialization.json.JsonObject
+import kotlinx.serialization.json.jsonObject
+import kotlinx.serialization.modules.SerializersModule
+import kotlinx.serialization.modules.polymorphic
+
+const val AST_SCHEMA_VERSION = 10
+
+/** AST 节点类型 */
+@Serializable
+sealed class ASTNode
+
+/** 根节点 */
+@Serializable
+@SerialName("root")
+data class RootNode(
+    val schemaVersion: Int = 1,
+    val children: List<ASTNode>
+) : ASTNode()
+
+/** 段落节点 */
+@Serializable
+@SerialName("paragraph")
+data class ParagraphNode(
+    val children: List<ASTNode>
+) : ASTNode()
+
+/** 标题节点 */
+@Serializable
+@SerialName("heading")
+data class HeadingNode(
+    val level: Int,
+    val children: List<ASTNode>
+) : ASTNode()
+
+/** 文本节点 */
+@Serializable
+@SerialName("text")
+data class TextNode(
+    val content: String
+) : ASTNode()
+
+/** 粗体节点 */
+@Serializable
+@SerialName("strong")
+data class StrongNode(
+    val children: List<ASTNode>
+) : ASTNode()
+
+/** 斜体节点 */
+@Serializable
+@SerialName("em")
+data class EmNode(
+    val children: List<ASTNode>
+) : ASTNode()
+
+/** 下划线节点 */
+@Serializable
+@SerialName("underline")
+data class UnderlineNode(
+    val children: List<ASTNode>
+) : ASTNode()
+
+/** 删除线节点 */
+@Serializable
+@SerialName("strike")
+data class StrikeNode(
+    val children: List<ASTNode>
+) : ASTNode()
+
+/** 行内代码节点 */
+@Serializable
+@SerialName("code")
+data class CodeNode(
+    val content: String
+) : ASTNode()
+
+/** 代码块节点 */
+@Serializable
+@SerialName("codeBlock")
+data class CodeBlockNode(
+    val language: String? = null,
+    val content: String
+) : ASTNode()
+
+/** 链接节点 */
+@Serializable
+@SerialName("link")
+data class LinkNode(
+    val url: String,
+    val children: List<ASTNode>
+) : ASTNode()
+
+/** 图片节点 */
+@Serializable
+@SerialName("image")
+data class ImageNode(
+    val url: String,
+    val width: Float? = null,
+    val height: Float? = null,
+    val alt: String? = null
+) : ASTNode()
+
+/** 列表节点 */
+@Serializable
+@SerialName("list")
+data class ListNode(
+    val listType: ListType,
+    val items: List<ListItemNode>
+) : ASTNode()
+
+/** 列表项节点 */
+@Serializable
+@SerialName("listItem")
+data class ListItemNode(
+    val children: List<ASTNode>,
+    val checked: Boolean? = null
+) : ASTNode()
+
+/** 表格节点 */
+@Serializable
+@SerialName("table")
+data class TableNode(
+    val rows: List<TableRow>
+) : ASTNode()
+
+/** 表格行 */
+@Serializable
+@SerialName("tableRow")
+data class TableRow(
+    val cells: List<TableCell>
+) : ASTNode()
+
+/** 表格单元格 */
+@Serializable
+@SerialName("tableCell")
+data class TableCell(
+    val children: List<ASTNode>,
+    val align: TextAlign? = null
+) : ASTNode()
+
+/** 数学公式节点 */
+@Serializable
+@SerialName("math")
+data class MathNode(
+    val content: String,
+    val display: Boolean
+) : ASTNode()
+
+/** Mermaid 图表节点 */
+@Serializable
+@SerialName("mermaid")
+data class MermaidNode(
+    val content: String
+) : ASTNode()
+
+/** 卡片节点 */
+@Serializable
+@SerialName("card")
+data class CardNode(
+    val subtype: String,
+    val content: String,
+    val metadata: Map<String, JsonElement>,
+    val header: CardHeader? = null,
+    val fields: List<CardField> = emptyList(),
+    val images: List<CardImage> = emptyList(),
+    val actions: List<CardAction> = emptyList()
+) : ASTNode()
+
+/** @提及节点 */
+@Serializable
+@SerialName("mention")
+data class MentionNode(
+    val id: String,
+    val name: String,
+    val kind: MentionKind = MentionKind.User,
+    val status: MentionStatus = MentionStatus.Unresolved
+) : ASTNode()
+
+/** 水平分割线节点 */
+@Serializable
+@SerialName("horizontalRule")
+object HorizontalRuleNode : ASTNode()
+
+/** 引用块节点 */
+@Serializable
+@SerialName("blockquote")
+data class BlockquoteNode(
+    val children: List<ASTNode>
+) : ASTNode()
+
+/** 脚注引用节点 */
+@Serializable
+@SerialName("footnoteReference")
+data class FootnoteReferenceNode(
+    val label: String,
+    val index: Int
+) : ASTNode()
+
+/** 脚注定义节点 */
+@Serializable
+@SerialName("footnoteDefinition")
+data class FootnoteDefinitionNode(
+    val label: String,
+    val index: Int,
+    val children: List<ASTNode>
+) : ASTNode()
+
+/** 高亮节点（`==text==`） */
+@Serializable
+@SerialName("highlight")
+data class HighlightNode(
+    val children: List<ASTNode>
+) : ASTNode()
+
+/** 剧透节点（`||text||`），子节点可以是行内内容，也可以是块级内容（块级剧透） */
+@Serializable
+@SerialName("spoiler")
+data class SpoilerNode(
+    val children: List<ASTNode>
+) : ASTNode()
+
+/** 自定义节点 */
+@Serializable
+@SerialName("custom")
+data class CustomNode(
+    val kind: String,
+    val attrs: Map<String, JsonElement> = emptyMap(),
+    val children: List<ASTNode> = emptyList()
+) : ASTNode()
+
+/** 时间戳节点 */
+@Serializable
+@SerialName("timestamp")
+data class TimestampNode(
+    val timestamp: Int,
+    val style: String? = null
+) : ASTNode()
+
+/** 自定义表情节点 */
+@Serializable
+@SerialName("customEmoji")
+data class CustomEmojiNode(
+    val id: String,
+    val name: String,
+    val animated: Boolean = false
+) : ASTNode()
+
+/** 卡片交互元素 */
+@Serializable
+data class CardAction(
+    val id: String,
+    val kind: CardActionKind = CardActionKind.Button,
+    val label: String = "",
+    val style: CardActionStyle = CardActionStyle.Default,
+    val value: String? = null,
+    val url: String? = null,
+    val placeholder: String? = null,
+    val options: List<CardSelectOption> = emptyList()
+)
+
+/** 卡片交互元素类型 */
+@Serializable
+enum class CardActionKind {
+    @SerialName("button") Button,
+    @SerialName("select") Select,
+    @SerialName("input") Input
+}
+
+/** 按钮样式 */
+@Serializable
+enum class CardActionStyle {
+    @SerialName("default") Default,
+    @SerialName("primary") Primary,
+    @SerialName("danger") Danger
+}
+
+/** 卡片键值字段 */
+@Serializable
+data class CardField(
+    val label: String,
+    val value: String,
+    val short: Boolean = false
+)
+
+/** 卡片头部 */
+@Serializable
+data class CardHeader(
+    val title: String,
+    val subtitle: String? = null,
+    val icon: String? = null
+)
+
+/** 卡片图片 */
+@Serializable
+data class CardImage(
+    val url: String,
+    val alt: String? = null
+)
+
+/** 下拉框选项 */
+@Serializable
+data class CardSelectOption(
+    val label: String,
+    val value: String
+)
+
+/** 列表类型 */
+@Serializable
+enum class ListType {
+    @SerialName("bullet") Bullet,
+    @SerialName("ordered") Ordered
+}
+
+/** 提及类型 */
+@Serializable
+enum class MentionKind {
+    @SerialName("user") User,
+    @SerialName("channel") Channel,
+    @SerialName("all") All,
+    @SerialName("here") Here,
+    @SerialName("role") Role
+}
+
+/** 提及目标的解析状态，由 [`resolve_mentions`](crate::resolve_mentions) 填写 */
+@Serializable
+enum class MentionStatus {
+    @SerialName("active") Active,
+    @SerialName("unresolved") Unresolved,
+    @SerialName("deactivated") Deactivated,
+    @SerialName("unknown") Unknown
+}
+
+/** 文本对齐方式 */
+@Serializable
+enum class TextAlign {
+    @SerialName("left") Left,
+    @SerialName("center") Center,
+    @SerialName("right") Right
+}
+
+/** 未知节点（前向兼容），保留原始 JSON */
+@Serializable(with = UnknownNodeSerializer::class)
+data class UnknownNode(val raw: JsonObject) : ASTNode()
+
+object UnknownNodeSerializer : KSerializer<UnknownNode> {
+    override val descriptor: SerialDescriptor = JsonObject.serializer().descriptor
+
+    override fun deserialize(decoder: Decoder): UnknownNode =
+        UnknownNode((decoder as JsonDecoder).decodeJsonElement().jsonObject)
+
+    override fun serialize(encoder: Encoder, value: UnknownNode) =
+        (encoder as JsonEncoder).encodeJsonElement(value.raw)
+}
+
+/** 解析 AST JSON 使用的配置：以 `type` 区分节点，未知类型回退为 [UnknownNode] */
+val astJson = Json {
+    classDiscriminator = "type"
+    ignoreUnknownKeys = true
+    serializersModule = SerializersModule {
+        polymorphic(ASTNode::class) {
+            defaultDeserializer { UnknownNode.serializer() }
+        }
+    }
+}
@@ -8,7 +8,6 @@ import androidx.compose.ui.Alignment
 import androidx.compose.ui.Modifier
 import androidx.compose.ui.graphics.Color
 import androidx.compose.ui.text.font.FontWeight
-import androidx.compose.ui.text.style.TextAlign
 import androidx.compose.ui.unit.dp
 import androidx.compose.ui.unit.sp
 import coil.compose.AsyncImage
@@ -452,57 +451,3 @@ private fun RenderInlineNode(
         else -> {}
     }
 }
-
-// MARK: - AST 节点类型定义（简化版，实际应从 Rust 绑定生成）
-
-sealed class ASTNode
-
-data class RootNode(val children: List<ASTNode>) : ASTNode()
-
-data class ParagraphNode(val children: List<ASTNode>) : ASTNode()
-
-data class HeadingNode(val level: Int, val children: List<ASTNode>) : ASTNode()
-
-data class TextNode(val content: String) : ASTNode()
-
-data class StrongNode(val children: List<ASTNode>) : ASTNode()
-
-data class EmNode(val children: List<ASTNode>) : ASTNode()
-
-data class UnderlineNode(val children: List<ASTNode>) : ASTNode()
-
-data class StrikeNode(val children: List<ASTNode>) : ASTNode()
-
-data class CodeNode(val content: String) : ASTNode()
-
-data class CodeBlockNode(val language: String?, val content: String) : ASTNode()
-
-data class LinkNode(val url: String, val children: List<ASTNode>) : ASTNode()
-
-data class ImageNode(val url: String, val width: Float?, val height: Float?, val alt: String?) : ASTNode()
-
-data class ListNode(val listType: ListType, val items: List<ListItemNode>) : ASTNode()
-
-enum class ListType {
-    Bullet,
-    Ordered
-}
-
-data class ListItemNode(val children: List<ASTNode>, val checked: Boolean?)
-
-data class TableNode(val rows: List<TableRow>) : ASTNode()
-
-data class TableRow(val cells: List<TableCell>) : ASTNode()
-
-data class TableCell(val children: List<ASTNode>, val align: TextAlign?) : ASTNode()
-
-data class MathNode(val content: String, val display: Boolean) : ASTNode()
-
-data class MermaidNode(val content: String) : ASTNode()
-
-data class MentionNode(val id: String, val name: String) : ASTNode()
-
-data class BlockquoteNode(val children: List<ASTNode>) : ASTNode()
-
-object HorizontalRuleNode : ASTNode()
-
@@ -27,6 +27,70 @@ IMParseResult * _Nullable parse_markdown_to_json(const char * _Nonnull input);
 /// @return 解析结果，需要调用 free_parse_result 释放
 IMParseResult * _Nullable parse_delta_to_json(const char * _Nonnull input);
 
+/// 解析 HTML 为 JSON AST（只保留安全的标签子集）
+/// @param input HTML 字符串
+/// @return 解析结果，需要调用 free_parse_result 释放
+IMParseResult * _Nullable parse_html_to_json(const char * _Nonnull input);
+
+/// 批量解析消息（加载会话历史时使用，减少 FFI 调用次数）
+/// @param items_json 消息数组 JSON：[{"id": "...", "format": "markdown" | "delta" | "html", "content": "..."}]
+/// @return 解析结果，ast_json 为结果数组 JSON（每项为 {"id", "ast"} 或 {"id", "error"}，顺序与输入一致），
+///         需要调用 free_parse_result 释放
+IMParseResult * _Nullable parse_batch_to_json(const char * _Nonnull items_json);
+
+/// 可复用的解析器句柄（不透明类型）
+typedef struct IMParseParser IMParseParser;
+
+/// 创建可复用的解析器句柄
+/// @param options_json 解析器配置 JSON（markdown、normalize、limits、sanitize、style），为 null 时使用默认配置
+/// @param error_out 可为 null；创建失败时写入错误信息（需要调用 free_string 释放），成功时写入 null
+/// @return 解析器句柄，配置不合法时返回 null；需要调用 imparse_parser_free 释放，可在多个线程间共享
+IMParseParser * _Nullable imparse_parser_new(const char * _Nullable options_json, char * _Nullable * _Nullable error_out);
+
+/// 使用解析器句柄解析为 JSON AST
+/// @param handle 解析器句柄
+/// @param format 输入格式："markdown"、"delta" 或 "html"
+/// @param input 输入内容
+/// @return 解析结果，需要调用 free_parse_result 释放
+IMParseResult * _Nullable imparse_parse(const IMParseParser * _Nonnull handle, const char * _Nonnull format, const char * _Nonnull input);
+
+/// 使用解析器句柄解析并渲染为 HTML
+/// @param handle 解析器句柄
+/// @param format 输入格式："markdown"、"delta" 或 "html"
+/// @param input 输入内容
+/// @return 解析结果，需要调用 free_parse_result 释放
+IMParseResult * _Nullable imparse_render_html(const IMParseParser * _Nonnull handle, const char * _Nonnull format, const char * _Nonnull input);
+
+/// 释放解析器句柄
+/// @param handle 解析器句柄
+void imparse_parser_free(IMParseParser * _Nullable handle);
+
+/// 提及查找回调
+/// @param context 调用方传入的上下文指针
+/// @param kind 提及类型："user"、"channel" 或 "role"
+/// @param id 用户、频道或角色 ID
+/// @return 目标 JSON（{"name": "Alice", "deactivated": false}），找不到时返回 NULL；只需保持有效到回调返回
+typedef const char * _Nullable (*IMParseMentionLookup)(void * _Nullable context, const char * _Nonnull kind, const char * _Nonnull id);
+
+/// 使用查找表解析 JSON AST 中的提及，填入当前名称和状态
+/// @param ast_json JSON AST
+/// @param directory_json 通讯录 JSON：{"users": {"U1": {"name": "Alice"}}, "channels": {...}}
+/// @return 解析结果，需要调用 free_parse_result 释放
+IMParseResult * _Nullable imparse_resolve_mentions(const char * _Nonnull ast_json, const char * _Nonnull directory_json);
+
+/// 使用回调解析 JSON AST 中的提及，填入当前名称和状态
+/// @param ast_json JSON AST
+/// @param lookup 查找回调
+/// @param context 传给回调的上下文指针
+/// @return 解析结果，需要调用 free_parse_result 释放
+IMParseResult * _Nullable imparse_resolve_mentions_with_callback(const char * _Nonnull ast_json, IMParseMentionLookup _Nonnull lookup, void * _Nullable context);
+
+/// 判断消息是否提及了指定用户（直接提及或 @all / @here），用于通知角标
+/// @param ast_json JSON AST
+/// @param user_id 当前用户 ID
+/// @return 是否提及；AST 不合法时返回 false
+bool imparse_mentions_user(const char * _Nonnull ast_json, const char * _Nonnull user_id);
+
 /// 释放解析结果
 /// @param result 解析结果指针
 void free_parse_result(IMParseResult * _Nullable result);
@@ -61,6 +125,21 @@ const char * _Nullable get_default_style_config(void);
 /// @return JSON 字符串，需要调用 free_string 释放
 const char * _Nullable get_dark_style_config(void);
 
+/// 启用全局解析缓存（已启用时以新配置重建）
+/// @param max_entries 最多缓存的条目数
+/// @param max_bytes 缓存内容的估算总字节数上限
+void enable_parse_cache(size_t max_entries, size_t max_bytes);
+
+/// 停用并释放全局解析缓存
+void disable_parse_cache(void);
+
+/// 清空全局解析缓存
+void clear_parse_cache(void);
+
+/// 获取全局解析缓存统计 JSON（hits、misses、evictions、entries、bytes、hitRate）
+/// @return JSON 字符串，未启用缓存时为 null，需要调用 free_string 释放
+const char * _Nullable get_parse_cache_stats(void);
+
 /// 将数学公式转换为 HTML
 /// @param formula 数学公式字符串（LaTeX 格式）
 /// @param display 是否为块级公式（true 为块级，false 为行内）
@@ -74,6 +153,67 @@ IMParseResult * _Nullable math_to_html(const char * _Nonnull formula, bool displ
 /// @return 解析结果，需要调用 free_parse_result 释放
 IMParseResult * _Nullable mermaid_to_html(const char * _Nonnull mermaid_code, const char * _Nonnull text_color, const char * _Nonnull background_color);
 
+/// 二进制解析结果（紧凑编码 / MessagePack / CBOR）
+typedef struct {
+    bool success;
+    const uint8_t * _Nullable data;
+    size_t len;
+    int error_code;
+    const char * _Nullable error_message;
+} IMParseBinaryResult;
+
+/// 解析 Markdown 为紧凑编码 AST（用于消息缓存）
+/// @param input Markdown 字符串
+/// @return 解析结果，需要调用 free_binary_result 释放
+IMParseBinaryResult * _Nullable parse_markdown_to_compact(const char * _Nonnull input);
+
+/// 解析 Delta 为紧凑编码 AST（用于消息缓存）
+/// @param input Delta JSON 字符串
+/// @return 解析结果，需要调用 free_binary_result 释放
+IMParseBinaryResult * _Nullable parse_delta_to_compact(const char * _Nonnull input);
+
+/// 将紧凑编码 AST 转换为 JSON AST
+/// @param data 紧凑编码字节
+/// @param len 字节长度
+/// @return 解析结果，需要调用 free_parse_result 释放
+IMParseResult * _Nullable compact_to_json(const uint8_t * _Nonnull data, size_t len);
+
+/// 解析 Markdown 为 MessagePack AST（需启用 msgpack feature）
+/// @param input Markdown 字符串
+/// @return 解析结果，需要调用 free_binary_result 释放
+IMParseBinaryResult * _Nullable parse_markdown_to_msgpack(const char * _Nonnull input);
+
+/// 解析 Delta 为 MessagePack AST（需启用 msgpack feature）
+/// @param input Delta JSON 字符串
+/// @return 解析结果，需要调用 free_binary_result 释放
+IMParseBinaryResult * _Nullable parse_delta_to_msgpack(const char * _Nonnull input);
+
+/// 将 MessagePack AST 转换为 JSON AST（需启用 msgpack feature）
+/// @param data MessagePack 字节
+/// @param len 字节长度
+/// @return 解析结果，需要调用 free_parse_result 释放
+IMParseResult * _Nullable msgpack_to_json(const uint8_t * _Nonnull data, size_t len);
+
+/// 解析 Markdown 为 CBOR AST（需启用 cbor feature）
+/// @param input Markdown 字符串
+/// @return 解析结果，需要调用 free_binary_result 释放
+IMParseBinaryResult * _Nullable parse_markdown_to_cbor(const char * _Nonnull input);
+
+/// 解析 Delta 为 CBOR AST（需启用 cbor feature）
+/// @param input Delta JSON 字符串
+/// @return 解析结果，需要调用 free_binary_result 释放
+IMParseBinaryResult * _Nullable parse_delta_to_cbor(const char * _Nonnull input);
+
+/// 将 CBOR AST 转换为 JSON AST（需启用 cbor feature）
+/// @param data CBOR 字节
+/// @param len 字节长度
+/// @return 解析结果，需要调用 free_parse_result 释放
+IMParseResult * _Nullable cbor_to_json(const uint8_t * _Nonnull data, size_t len);
+
+/// 释放二进制解析结果
+/// @param result 解析结果指针
+void free_binary_result(IMParseBinaryResult * _Nullable result);
+
 /// 释放字符串
 /// @param ptr 字符串指针（const，因为只是释放内存，不修改内容）
 void free_string(const char * _Nullable ptr);
@@ -0,0 +1,32 @@
+import Foundation
+
+// 渲染器和宿主代码直接使用生成的 IMParseAST 类型，这里只提供不带命名空间的简短名称。
+// 新增节点类型时重新运行 ast-codegen 即可，不要在此处手写镜像类型。
+
+public typealias RootNode = IMParseAST.RootNode
+public typealias ParagraphNode = IMParseAST.ParagraphNode
+public typealias HeadingNode = IMParseAST.HeadingNode
+public typealias TextNode = IMParseAST.TextNode
+public typealias StrongNode = IMParseAST.StrongNode
+public typealias EmNode = IMParseAST.EmNode
+public typealias UnderlineNode = IMParseAST.UnderlineNode
+public typealias StrikeNode = IMParseAST.StrikeNode
+public typealias CodeNode = IMParseAST.CodeNode
+public typealias CodeBlockNode = IMParseAST.CodeBlockNode
+public typealias LinkNode = IMParseAST.LinkNode
+public typealias ImageNode = IMParseAST.ImageNode
+public typealias ListType = IMParseAST.ListType
+public typealias ListNode = IMParseAST.ListNode
+public typealias ListItemNode = IMParseAST.ListItemNode
+public typealias TableNode = IMParseAST.TableNode
+public typealias TableRow = IMParseAST.TableRow
+public typealias TableCell = IMParseAST.TableCell
+public typealias TextAlign = IMParseAST.TextAlign
+public typealias MathNode = IMParseAST.MathNode
+public typealias MermaidNode = IMParseAST.MermaidNode
+public typealias MentionNode = IMParseAST.MentionNode
+public typealias BlockquoteNode = IMParseAST.BlockquoteNode
+public typealias HorizontalRuleNode = IMParseAST.HorizontalRuleNode
+
+@available(*, deprecated, renamed: "IMParseAST.Node")
+public typealias ASTNodeWrapper = IMParseAST.Node
@@ -0,0 +1,722 @@
+// 此文件由 rust-core 的 ast-codegen 根据 ast.rs 自动生成，请勿手动修改
+// 重新生成：cd rust-core && cargo run --features schema --bin ast-codegen -- swift > ../ios/IMParseSDK/IMParseSDK/Classes/Models/IMParseAST.swift
+
+import Foundation
+
+/// 与 Rust `ast.rs` 一致的 AST 数据模型
+public enum IMParseAST {
+    public static let schemaVersion = 10
+
+    /// AST 节点类型
+    public indirect enum Node: Codable, Equatable {
+        case root(RootNode)
+        case paragraph(ParagraphNode)
+        case heading(HeadingNode)
+        case text(TextNode)
+        case strong(StrongNode)
+        case em(EmNode)
+        case underline(UnderlineNode)
+        case strike(StrikeNode)
+        case code(CodeNode)
+        case codeBlock(CodeBlockNode)
+        case link(LinkNode)
+        case image(ImageNode)
+        case list(ListNode)
+        case listItem(ListItemNode)
+        case table(TableNode)
+        case tableRow(TableRow)
+        case tableCell(TableCell)
+        case math(MathNode)
+        case mermaid(MermaidNode)
+        case card(CardNode)
+        case mention(MentionNode)
+        case horizontalRule(HorizontalRuleNode)
+        case blockquote(BlockquoteNode)
+        case footnoteReference(FootnoteReferenceNode)
+        case footnoteDefinition(FootnoteDefinitionNode)
+        case highlight(HighlightNode)
+        case spoiler(SpoilerNode)
+        case custom(CustomNode)
+        case timestamp(TimestampNode)
+        case customEmoji(CustomEmojiNode)
+        /// 未知节点（前向兼容），保留原始 JSON
+        case unknown(JSONValue)
+
+        private enum CodingKeys: String, CodingKey {
+            case type
+        }
+
+        public init(from decoder: Decoder) throws {
+            let container = try decoder.container(keyedBy: CodingKeys.self)
+            switch try container.decodeIfPresent(String.self, forKey: .type) {
+            case "root":
+                self = .root(try RootNode(from: decoder))
+            case "paragraph":
+                self = .paragraph(try ParagraphNode(from: decoder))
+            case "heading":
+                self = .heading(try HeadingNode(from: decoder))
+            case "text":
+                self = .text(try TextNode(from: decoder))
+            case "strong":
+                self = .strong(try StrongNode(from: decoder))
+            case "em":
+                self = .em(try EmNode(from: decoder))
+            case "underline":
+                self = .underline(try UnderlineNode(from: decoder))
+            case "strike":
+                self = .strike(try StrikeNode(from: decoder))
+            case "code":
+                self = .code(try CodeNode(from: decoder))
+            case "codeBlock":
+                self = .codeBlock(try CodeBlockNode(from: decoder))
+            case "link":
+                self = .link(try LinkNode(from: decoder))
+            case "image":
+                self = .image(try ImageNode(from: decoder))
+            case "list":
+                self = .list(try ListNode(from: decoder))
+            case "listItem":
+                self = .listItem(try ListItemNode(from: decoder))
+            case "table":
+                self = .table(try TableNode(from: decoder))
+            case "tableRow":
+                self = .tableRow(try TableRow(from: decoder))
+            case "tableCell":
+                self = .tableCell(try TableCell(from: decoder))
+            case "math":
+                self = .math(try MathNode(from: decoder))
+            case "mermaid":
+                self = .mermaid(try MermaidNode(from: decoder))
+            case "card":
+                self = .card(try CardNode(from: decoder))
+            case "mention":
+                self = .mention(try MentionNode(from: decoder))
+            case "horizontalRule":
+                self = .horizontalRule(try HorizontalRuleNode(from: decoder))
+            case "blockquote":
+                self = .blockquote(try BlockquoteNode(from: decoder))
+            case "footnoteReference":
+                self = .footnoteReference(try FootnoteReferenceNode(from: decoder))
+            case "footnoteDefinition":
+                self = .footnoteDefinition(try FootnoteDefinitionNode(from: decoder))
+            case "highlight":
+                self = .highlight(try HighlightNode(from: decoder))
+            case "spoiler":
+                self = .spoiler(try SpoilerNode(from: decoder))
+            case "custom":
+                self = .custom(try CustomNode(from: decoder))
+            case "timestamp":
+                self = .timestamp(try TimestampNode(from: decoder))
+            case "customEmoji":
+                self = .customEmoji(try CustomEmojiNode(from: decoder))
+            default:
+                self = .unknown(try JSONValue(from: decoder))
+            }
+        }
+
+        public func encode(to encoder: Encoder) throws {
+            switch self {
+            case .root(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("root", forKey: .type)
+                try node.encode(to: encoder)
+            case .paragraph(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("paragraph", forKey: .type)
+                try node.encode(to: encoder)
+            case .heading(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("heading", forKey: .type)
+                try node.encode(to: encoder)
+            case .text(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("text", forKey: .type)
+                try node.encode(to: encoder)
+            case .strong(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("strong", forKey: .type)
+                try node.encode(to: encoder)
+            case .em(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("em", forKey: .type)
+                try node.encode(to: encoder)
+            case .underline(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("underline", forKey: .type)
+                try node.encode(to: encoder)
+            case .strike(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("strike", forKey: .type)
+                try node.encode(to: encoder)
+            case .code(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("code", forKey: .type)
+                try node.encode(to: encoder)
+            case .codeBlock(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("codeBlock", forKey: .type)
+                try node.encode(to: encoder)
+            case .link(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("link", forKey: .type)
+                try node.encode(to: encoder)
+            case .image(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("image", forKey: .type)
+                try node.encode(to: encoder)
+            case .list(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("list", forKey: .type)
+                try node.encode(to: encoder)
+            case .listItem(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("listItem", forKey: .type)
+                try node.encode(to: encoder)
+            case .table(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("table", forKey: .type)
+                try node.encode(to: encoder)
+            case .tableRow(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("tableRow", forKey: .type)
+                try node.encode(to: encoder)
+            case .tableCell(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("tableCell", forKey: .type)
+                try node.encode(to: encoder)
+            case .math(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("math", forKey: .type)
+                try node.encode(to: encoder)
+            case .mermaid(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("mermaid", forKey: .type)
+                try node.encode(to: encoder)
+            case .card(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("card", forKey: .type)
+                try node.encode(to: encoder)
+            case .mention(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("mention", forKey: .type)
+                try node.encode(to: encoder)
+            case .horizontalRule(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("horizontalRule", forKey: .type)
+                try node.encode(to: encoder)
+            case .blockquote(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("blockquote", forKey: .type)
+                try node.encode(to: encoder)
+            case .footnoteReference(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("footnoteReference", forKey: .type)
+                try node.encode(to: encoder)
+            case .footnoteDefinition(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("footnoteDefinition", forKey: .type)
+                try node.encode(to: encoder)
+            case .highlight(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("highlight", forKey: .type)
+                try node.encode(to: encoder)
+            case .spoiler(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("spoiler", forKey: .type)
+                try node.encode(to: encoder)
+            case .custom(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("custom", forKey: .type)
+                try node.encode(to: encoder)
+            case .timestamp(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("timestamp", forKey: .type)
+                try node.encode(to: encoder)
+            case .customEmoji(let node):
+                var container = encoder.container(keyedBy: CodingKeys.self)
+                try container.encode("customEmoji", forKey: .type)
+                try node.encode(to: encoder)
+            case .unknown(let raw):
+                try raw.encode(to: encoder)
+            }
+        }
+    }
+
+    /// 根节点
+    public struct RootNode: Codable, Equatable {
+        public var schemaVersion: Int?
+        public var children: [Node]
+
+        public init(schemaVersion: Int? = nil, children: [Node]) {
+            self.schemaVersion = schemaVersion
+            self.children = children
+        }
+    }
+
+    /// 段落节点
+    public struct ParagraphNode: Codable, Equatable {
+        public var children: [Node]
+
+        public init(children: [Node]) {
+            self.children = children
+        }
+    }
+
+    /// 标题节点
+    public struct HeadingNode: Codable, Equatable {
+        public var level: Int
+        public var children: [Node]
+
+        public init(level: Int, children: [Node]) {
+            self.level = level
+            self.children = children
+        }
+    }
+
+    /// 文本节点
+    public struct TextNode: Codable, Equatable {
+        public var content: String
+
+        public init(content: String) {
+            self.content = content
+        }
+    }
+
+    /// 粗体节点
+    public struct StrongNode: Codable, Equatable {
+        public var children: [Node]
+
+        public init(children: [Node]) {
+            self.children = children
+        }
+    }
+
+    /// 斜体节点
+    public struct EmNode: Codable, Equatable {
+        public var children: [Node]
+
+        public init(children: [Node]) {
+            self.children = children
+        }
+    }
+
+    /// 下划线节点
+    public struct UnderlineNode: Codable, Equatable {
+        public var children: [Node]
+
+        public init(children: [Node]) {
+            self.children = children
+        }
+    }
+
+    /// 删除线节点
+    public struct StrikeNode: Codable, Equatable {
+        public var children: [Node]
+
+        public init(children: [Node]) {
+            self.children = children
+        }
+    }
+
+    /// 行内代码节点
+    public struct CodeNode: Codable, Equatable {
+        public var content: String
+
+        public init(content: String) {
+            self.content = content
+        }
+    }
+
+    /// 代码块节点
+    public struct CodeBlockNode: Codable, Equatable {
+        public var language: String?
+        public var content: String
+
+        public init(language: String? = nil, content: String) {
+            self.language = language
+            self.content = content
+        }
+    }
+
+    /// 链接节点
+    public struct LinkNode: Codable, Equatable {
+        public var url: String
+        public var children: [Node]
+
+        public init(url: String, children: [Node]) {
+            self.url = url
+            self.children = children
+        }
+    }
+
+    /// 图片节点
+    public struct ImageNode: Codable, Equatable {
+        public var url: String
+        public var width: Double?
+        public var height: Double?
+        public var alt: String?
+
+        public init(url: String, width: Double? = nil, height: Double? = nil, alt: String? = nil) {
+            self.url = url
+            self.width = width
+            self.height = height
+            self.alt = alt
+        }
+    }
+
+    /// 列表节点
+    public struct ListNode: Codable, Equatable {
+        public var listType: ListType
+        public var items: [ListItemNode]
+
+        public init(listType: ListType, items: [ListItemNode]) {
+            self.listType = listType
+            self.items = items
+        }
+    }
+
+    /// 列表项节点
+    public struct ListItemNode: Codable, Equatable {
+        public var children: [Node]
+        public var checked: Bool?
+
+        public init(children: [Node], checked: Bool? = nil) {
+            self.children = children
+            self.checked = checked
+        }
+    }
+
+    /// 表格节点
+    public struct TableNode: Codable, Equatable {
+        public var rows: [TableRow]
+
+        public init(rows: [TableRow]) {
+            self.rows = rows
+        }
+    }
+
+    /// 表格行
+    public struct TableRow: Codable, Equatable {
+        public var cells: [TableCell]
+
+        public init(cells: [TableCell]) {
+            self.cells = cells
+        }
+    }
+
+    /// 表格单元格
+    public struct TableCell: Codable, Equatable {
+        public var children: [Node]
+        public var align: TextAlign?
+
+        public init(children: [Node], align: TextAlign? = nil) {
+            self.children = children
+            self.align = align
+        }
+    }
+
+    /// 数学公式节点
+    public struct MathNode: Codable, Equatable {
+        public var content: String
+        public var display: Bool
+
+        public init(content: String, display: Bool) {
+            self.content = content
+            self.display = display
+        }
+    }
+
+    /// Mermaid 图表节点
+    public struct MermaidNode: Codable, Equatable {
+        public var content: String
+
+        public init(content: String) {
+            self.content = content
+        }
+    }
+
+    /// 卡片节点
+    public struct CardNode: Codable, Equatable {
+        public var subtype: String
+        public var content: String
+        public var metadata: [String: JSONValue]
+        public var header: CardHeader?
+        public var fields: [CardField]?
+        public var images: [CardImage]?
+        public var actions: [CardAction]?
+
+        public init(subtype: String, content: String, metadata: [String: JSONValue], header: CardHeader? = nil, fields: [CardField]? = nil, images: [CardImage]? = nil, actions: [CardAction]? = nil) {
+            self.subtype = subtype
+            self.content = content
+            self.metadata = metadata
+            self.header = header
+            self.fields = fields
+            self.images = images
+            self.actions = actions
+        }
+    }
+
+    /// @提及节点
+    public struct MentionNode: Codable, Equatable {
+        public var id: String
+        public var name: String
+        public var kind: MentionKind?
+        public var status: MentionStatus?
+
+        public init(id: String, name: String, kind: MentionKind? = nil, status: MentionStatus? = nil) {
+            self.id = id
+            self.name = name
+            self.kind = kind
+            self.status = status
+        }
+    }
+
+    /// 水平分割线节点
+    public struct HorizontalRuleNode: Codable, Equatable {
+
+        public init() {
+        }
+    }
+
+    /// 引用块节点
+    public struct BlockquoteNode: Codable, Equatable {
+        public var children: [Node]
+
+        public init(children: [Node]) {
+            self.children = children
+        }
+    }
+
+    /// 脚注引用节点
+    public struct FootnoteReferenceNode: Codable, Equatable {
+        public var label: String
+        public var index: Int
+
+        public init(label: String, index: Int) {
+            self.label = label
+            self.index = index
+        }
+    }
+
+    /// 脚注定义节点
+    public struct FootnoteDefinitionNode: Codable, Equatable {
+        public var label: String
+        public var index: Int
+        public var children: [Node]
+
+        public init(label: String, index: Int, children: [Node]) {
+            self.label = label
+            self.index = index
+            self.children = children
+        }
+    }
+
+    /// 高亮节点（`==text==`）
+    public struct HighlightNode: Codable, Equatable {
+        public var children: [Node]
+
+        public init(children: [Node]) {
+            self.children = children
+        }
+    }
+
+    /// 剧透节点（`||text||`），子节点可以是行内内容，也可以是块级内容（块级剧透）
+    public struct SpoilerNode: Codable, Equatable {
+        public var children: [Node]
+
+        public init(children: [Node]) {
+            self.children = children
+        }
+    }
+
+    /// 自定义节点
+    public struct CustomNode: Codable, Equatable {
+        public var kind: String
+        public var attrs: [String: JSONValue]?
+        public var children: [Node]?
+
+        public init(kind: String, attrs: [String: JSONValue]? = nil, children: [Node]? = nil) {
+            self.kind = kind
+            self.attrs = attrs
+            self.children = children
+        }
+    }
+
+    /// 时间戳节点
+    public struct TimestampNode: Codable, Equatable {
+        public var timestamp: Int
+        public var style: String?
+
+        public init(timestamp: Int, style: String? = nil) {
+            self.timestamp = timestamp
+            self.style = style
+        }
+    }
+
+    /// 自定义表情节点
+    public struct CustomEmojiNode: Codable, Equatable {
+        public var id: String
+        public var name: String
+        public var animated: Bool?
+
+        public init(id: String, name: String, animated: Bool? = nil) {
+            self.id = id
+            self.name = name
+            self.animated = animated
+        }
+    }
+
+    /// 卡片交互元素
+    public struct CardAction: Codable, Equatable {
+        public var id: String
+        public var kind: CardActionKind?
+        public var label: String?
+        public var style: CardActionStyle?
+        public var value: String?
+        public var url: String?
+        public var placeholder: String?
+        public var options: [CardSelectOption]?
+
+        public init(id: String, kind: CardActionKind? = nil, label: String? = nil, style: CardActionStyle? = nil, value: String? = nil, url: String? = nil, placeholder: String? = nil, options: [CardSelectOption]? = nil) {
+            self.id = id
+            self.kind = kind
+            self.label = label
+            self.style = style
+            self.value = value
+            self.url = url
+            self.placeholder = placeholder
+            self.options = options
+        }
+    }
+
+    /// 卡片交互元素类型
+    public enum CardActionKind: String, Codable, Equatable {
+        case button
+        case select
+        case input
+    }
+
+    /// 按钮样式
+    public enum CardActionStyle: String, Codable, Equatable {
+        case `default` = "default"
+        case primary
+        case danger
+    }
+
+    /// 卡片键值字段
+    public struct CardField: Codable, Equatable {
+        public var label: String
+        public var value: String
+        public var short: Bool?
+
+        public init(label: String, value: String, short: Bool? = nil) {
+            self.label = label
+            self.value = value
+            self.short = short
+        }
+    }
+
+    /// 卡片头部
+    public struct CardHeader: Codable, Equatable {
+        public var title: String
+        public var subtitle: String?
+        public var icon: String?
+
+        public init(title: String, subtitle: String? = nil, icon: String? = nil) {
+            self.title = title
+            self.subtitle = subtitle
+            self.icon = icon
+        }
+    }
+
+    /// 卡片图片
+    public struct CardImage: Codable, Equatable {
+        public var url: String
+        public var alt: String?
+
+        public init(url: String, alt: String? = nil) {
+            self.url = url
+            self.alt = alt
+        }
+    }
+
+    /// 下拉框选项
+    public struct CardSelectOption: Codable, Equatable {
+        public var label: String
+        public var value: String
+
+        public init(label: String, value: String) {
+            self.label = label
+            self.value = value
+        }
+    }
+
+    /// 列表类型
+    public enum ListType: String, Codable, Equatable {
+        case bullet
+        case ordered
+    }
+
+    /// 提及类型
+    public enum MentionKind: String, Codable, Equatable {
+        case user
+        case channel
+        case all
+        case here
+        case role
+    }
+
+    /// 提及目标的解析状态，由 [`resolve_mentions`](crate::resolve_mentions) 填写
+    public enum MentionStatus: String, Codable, Equatable {
+        case active
+        case unresolved
+        case deactivated
+        case unknown
+    }
+
+    /// 文本对齐方式
+    public enum TextAlign: String, Codable, Equatable {
+        case left
+        case center
+        case right
+    }
+
+    /// 任意 JSON 值，用于未知节点和无固定结构的字段
+    public enum JSONValue: Codable, Equatable {
+        case null
+        case bool(Bool)
+        case number(Double)
+        case string(String)
+        case array([JSONValue])
+        case object([String: JSONValue])
+
+        public init(from decoder: Decoder) throws {
+            let container = try decoder.singleValueContainer()
+            if container.decodeNil() {
+                self = .null
+            } else if let value = try? container.decode(Bool.self) {
+                self = .bool(value)
+            } else if let value = try? container.decode(Double.self) {
+                self = .number(value)
+            } else if let value = try? container.decode(String.self) {
+                self = .string(value)
+            } else if let value = try? container.decode([JSONValue].self) {
+                self = .array(value)
+            } else {
+                self = .object(try container.decode([String: JSONValue].self))
+            }
+        }
+
+        public func encode(to encoder: Encoder) throws {
+            var container = encoder.singleValueContainer()
+            switch self {
+            case .null: try container.encodeNil()
+            case .bool(let value): try container.encode(value)
+            case .number(let value): try container.encode(value)
+            case .string(let value): try container.encode(value)
+            case .array(let value): try container.encode(value)
+            case .object(let value): try container.encode(value)
+            }
+        }
+    }
+}
@@ -33,6 +33,9 @@ public struct StyleConfig: Codable {
     public var imageMargin: Float
     public var mentionBackground: String
     public var mentionTextColor: String
+    public var highlightBackground: String
+    public var highlightTextColor: String
+    public var spoilerBackground: String
     public var cardBackground: String
     public var cardBorderColor: String
     public var cardPadding: Float
@@ -64,6 +67,9 @@ public struct StyleConfig: Codable {
                 imageMargin: Float,
                 mentionBackground: String,
                 mentionTextColor: String,
+                highlightBackground: String,
+                highlightTextColor: String,
+                spoilerBackground: String,
                 cardBackground: String,
                 cardBorderColor: String,
                 cardPadding: Float,
@@ -94,6 +100,9 @@ public struct StyleConfig: Codable {
         self.imageMargin = imageMargin
         self.mentionBackground = mentionBackground
         self.mentionTextColor = mentionTextColor
+        self.highlightBackground = highlightBackground
+        self.highlightTextColor = highlightTextColor
+        self.spoilerBackground = spoilerBackground
         self.cardBackground = cardBackground
         self.cardBorderColor = cardBorderColor
         self.cardPadding = cardPadding
@@ -127,6 +136,9 @@ public struct StyleConfig: Codable {
         case imageMargin = "image_margin"
         case mentionBackground = "mention_background"
         case mentionTextColor = "mention_text_color"
+        case highlightBackground = "highlight_background"
+        case highlightTextColor = "highlight_text_color"
+        case spoilerBackground = "spoiler_background"
         case cardBackground = "card_background"
         case cardBorderColor = "card_border_color"
         case cardPadding = "card_padding"
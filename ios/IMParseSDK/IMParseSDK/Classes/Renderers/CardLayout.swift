@@ -0,0 +1,45 @@
+//
+//  CardLayout.swift
+//  IMParseSDK
+//
+//  卡片渲染的共用布局逻辑，SwiftUI 和 UIKit 渲染器共享，结构与 Rust 端 HtmlRenderer 输出的卡片一致
+//
+
+import Foundation
+
+extension IMParseAST.CardNode {
+    /// 字段按行分组：相邻的短字段两两并排，其余字段独占一行
+    var fieldRows: [[IMParseAST.CardField]] {
+        var rows: [[IMParseAST.CardField]] = []
+        for field in fields ?? [] {
+            if field.short == true, let last = rows.last, last.count == 1, last[0].short == true {
+                rows[rows.count - 1].append(field)
+            } else {
+                rows.append([field])
+            }
+        }
+        return rows
+    }
+}
+
+extension IMParseAST.CardAction {
+    /// 交互元素类型，缺省为按钮
+    var resolvedKind: IMParseAST.CardActionKind {
+        kind ?? .button
+    }
+
+    /// 交互元素上显示的文字：优先使用标签，输入框和下拉框没有标签时显示占位提示
+    var displayTitle: String {
+        if let label = label, !label.isEmpty {
+            return label
+        }
+        return placeholder ?? id
+    }
+
+    /// 选中下拉框的某一项后回调给宿主的动作，`value` 为选项值
+    func selecting(_ option: IMParseAST.CardSelectOption) -> IMParseAST.CardAction {
+        var action = self
+        action.value = option.value
+        return action
+    }
+}
@@ -7,6 +7,8 @@ public struct RenderContext {
     public var onLinkTap: ((URL) -> Void)?
     public var onImageTap: ((ImageNode) -> Void)?
     public var onMentionTap: ((MentionNode) -> Void)?
+    // 卡片按钮、下拉框和输入框的交互回调；下拉框选中后 `value` 为选项值
+    public var onCardAction: ((IMParseAST.CardAction) -> Void)?
     // 当前文本样式（用于标题等需要特殊样式的场景）
     public var currentFont: Font?
     public var currentTextColor: Color?
@@ -16,6 +18,7 @@ public struct RenderContext {
                 onLinkTap: ((URL) -> Void)? = nil,
                 onImageTap: ((ImageNode) -> Void)? = nil,
                 onMentionTap: ((MentionNode) -> Void)? = nil,
+                onCardAction: ((IMParseAST.CardAction) -> Void)? = nil,
                 currentFont: Font? = nil,
                 currentTextColor: Color? = nil) {
         self.theme = theme
@@ -23,6 +26,7 @@ public struct RenderContext {
         self.onLinkTap = onLinkTap
         self.onImageTap = onImageTap
         self.onMentionTap = onMentionTap
+        self.onCardAction = onCardAction
         self.currentFont = currentFont
         self.currentTextColor = currentTextColor
     }
@@ -52,6 +56,9 @@ public struct Theme {
     public var imageMargin: CGFloat
     public var mentionBackground: Color
     public var mentionTextColor: Color
+    public var highlightBackground: Color
+    public var highlightTextColor: Color?  // nil 表示沿用当前文字颜色
+    public var spoilerBackground: Color
     public var cardBackground: Color
     public var cardBorderColor: Color
     public var cardPadding: CGFloat
@@ -83,6 +90,9 @@ public struct Theme {
                 imageMargin: CGFloat,
                 mentionBackground: Color,
                 mentionTextColor: Color,
+                highlightBackground: Color,
+                highlightTextColor: Color?,
+                spoilerBackground: Color,
                 cardBackground: Color,
                 cardBorderColor: Color,
                 cardPadding: CGFloat,
@@ -113,6 +123,9 @@ public struct Theme {
         self.imageMargin = imageMargin
         self.mentionBackground = mentionBackground
         self.mentionTextColor = mentionTextColor
+        self.highlightBackground = highlightBackground
+        self.highlightTextColor = highlightTextColor
+        self.spoilerBackground = spoilerBackground
         self.cardBackground = cardBackground
         self.cardBorderColor = cardBorderColor
         self.cardPadding = cardPadding
@@ -149,6 +162,10 @@ extension Theme {
         self.imageMargin = CGFloat(config.imageMargin)
         self.mentionBackground = Color(hex: config.mentionBackground) ?? Color.blue.opacity(0.1)
         self.mentionTextColor = Color(hex: config.mentionTextColor) ?? .blue
+        self.highlightBackground = Color(hex: config.highlightBackground) ?? Color.yellow.opacity(0.4)
+        // "inherit" 等非颜色值表示沿用当前文字颜色
+        self.highlightTextColor = Color(hex: config.highlightTextColor)
+        self.spoilerBackground = Color(hex: config.spoilerBackground) ?? Color.gray
         self.cardBackground = Color(hex: config.cardBackground) ?? Color(white: 0.95)
         self.cardBorderColor = Color(hex: config.cardBorderColor) ?? Color.gray.opacity(0.3)
         self.cardPadding = CGFloat(config.cardPadding)
@@ -188,6 +205,9 @@ extension Theme {
             imageMargin: 16,
             mentionBackground: Color.blue.opacity(0.1),
             mentionTextColor: .blue,
+            highlightBackground: Color.yellow.opacity(0.4),
+            highlightTextColor: nil,
+            spoilerBackground: Color.gray,
             cardBackground: Color(white: 0.95),
             cardBorderColor: Color.gray.opacity(0.3),
             cardPadding: 16,
@@ -200,11 +220,6 @@ extension Theme {
     }
 }
 
-/// AST 节点协议
-public protocol ASTNode {
-    func render(context: RenderContext) -> AnyView
-}
-
 /// SwiftUI 渲染器
 public struct SwiftUIRenderer {
     public init() {}
@@ -217,17 +232,11 @@ public struct SwiftUIRenderer {
         }
     }
     
-    private func renderNodeWrapper(_ wrapper: ASTNodeWrapper, context: RenderContext) -> AnyView {
+    private func renderNodeWrapper(_ wrapper: IMParseAST.Node, context: RenderContext) -> AnyView {
         switch wrapper {
         case .root(let node):
             // Root 节点不应该在子节点中出现，但为了安全起见还是处理一下
-            return AnyView(
-                VStack(alignment: .leading, spacing: context.theme.paragraphSpacing) {
-                    ForEach(Array(node.children.enumerated()), id: \.offset) { index, child in
-                        renderNodeWrapper(child, context: context)
-                    }
-                }
-            )
+            return AnyView(renderBlockChildren(node.children, context: context))
         case .paragraph(let node):
             return AnyView(renderParagraph(node, context: context))
         case .heading(let node):
@@ -273,6 +282,36 @@ public struct SwiftUIRenderer {
             return AnyView(renderBlockquote(node, context: context))
         case .horizontalRule(_):
             return AnyView(renderHorizontalRule(context: context))
+        case .highlight, .footnoteReference, .timestamp, .customEmoji:
+            // 行内节点单独出现在块级位置时按段落渲染
+            return AnyView(
+                buildText(from: [wrapper], context: context)
+                    .fixedSize(horizontal: false, vertical: true)
+            )
+        case .spoiler(let node):
+            // 块级剧透：内容以占位样式遮盖
+            return AnyView(
+                renderBlockChildren(node.children, context: context)
+                    .redacted(reason: .placeholder)
+            )
+        case .footnoteDefinition(let node):
+            return AnyView(renderBlockChildren(node.children, context: context))
+        case .custom(let node):
+            return AnyView(renderBlockChildren(node.children ?? [], context: context))
+        case .card(let node):
+            return AnyView(renderCard(node, context: context))
+        case .unknown:
+            // 当前版本不认识的节点（前向兼容），跳过
+            return AnyView(EmptyView())
+        }
+    }
+
+    /// 按块级内容纵向渲染子节点
+    private func renderBlockChildren(_ children: [IMParseAST.Node], context: RenderContext) -> some View {
+        VStack(alignment: .leading, spacing: context.theme.paragraphSpacing) {
+            ForEach(Array(children.enumerated()), id: \.offset) { _, child in
+                renderNodeWrapper(child, context: context)
+            }
         }
     }
     
@@ -318,14 +357,14 @@ public struct SwiftUIRenderer {
     
     /// 行内节点分组类型
     private enum InlineNodeGroup {
-        case textNodes([ASTNodeWrapper])
-        case specialNode(ASTNodeWrapper)
+        case textNodes([IMParseAST.Node])
+        case specialNode(IMParseAST.Node)
     }
     
     /// 将行内节点分组：连续的文本节点合并，特殊节点单独处理
-    private func groupInlineNodes(_ nodes: [ASTNodeWrapper]) -> [InlineNodeGroup] {
+    private func groupInlineNodes(_ nodes: [IMParseAST.Node]) -> [InlineNodeGroup] {
         var result: [InlineNodeGroup] = []
-        var currentTextNodes: [ASTNodeWrapper] = []
+        var currentTextNodes: [IMParseAST.Node] = []
         
         for node in nodes {
             switch node {
@@ -351,7 +390,7 @@ public struct SwiftUIRenderer {
     
     /// 从行内节点构建组合的 Text 视图（使用 AttributedString）
     @ViewBuilder
-    private func buildText(from nodes: [ASTNodeWrapper], context: RenderContext) -> some View {
+    private func buildText(from nodes: [IMParseAST.Node], context: RenderContext) -> some View {
         if #available(iOS 15.0, *) {
             // iOS 15+ 使用 AttributedString
             let attributedString = buildAttributedString(from: nodes, context: context)
@@ -364,7 +403,7 @@ public struct SwiftUIRenderer {
     
     /// 从行内节点构建 AttributedString（iOS 15+）
     @available(iOS 15.0, *)
-    private func buildAttributedString(from nodes: [ASTNodeWrapper], context: RenderContext) -> AttributedString {
+    private func buildAttributedString(from nodes: [IMParseAST.Node], context: RenderContext) -> AttributedString {
         var result = AttributedString()
         
         for node in nodes {
@@ -377,7 +416,7 @@ public struct SwiftUIRenderer {
     
     /// 从单个行内节点构建 AttributedString（iOS 15+）
     @available(iOS 15.0, *)
-    private func buildAttributedString(from node: ASTNodeWrapper, context: RenderContext) -> AttributedString {
+    private func buildAttributedString(from node: IMParseAST.Node, context: RenderContext) -> AttributedString {
         switch node {
         case .text(let textNode):
             let font = context.currentFont ?? context.theme.font
@@ -502,6 +541,49 @@ public struct SwiftUIRenderer {
             attributedString.backgroundColor = colorToSwiftUIColor(context.theme.codeBackgroundColor)
             return attributedString
             
+        case .highlight(let highlightNode):
+            var result = AttributedString()
+            for child in highlightNode.children {
+                var childString = buildAttributedString(from: child, context: context)
+                childString.backgroundColor = colorToSwiftUIColor(context.theme.highlightBackground)
+                if let textColor = context.theme.highlightTextColor {
+                    childString.foregroundColor = colorToSwiftUIColor(textColor)
+                }
+                result.append(childString)
+            }
+            return result
+
+        case .spoiler(let spoilerNode):
+            // 行内剧透：文字颜色与背景相同，遮住内容但保留占位
+            var result = AttributedString()
+            for child in spoilerNode.children {
+                var childString = buildAttributedString(from: child, context: context)
+                childString.foregroundColor = colorToSwiftUIColor(context.theme.spoilerBackground)
+                childString.backgroundColor = colorToSwiftUIColor(context.theme.spoilerBackground)
+                result.append(childString)
+            }
+            return result
+
+        case .footnoteReference(let referenceNode):
+            var attributedString = AttributedString("[\(referenceNode.index)]")
+            attributedString.font = .system(size: context.theme.fontSize * 0.75)
+            attributedString.baselineOffset = context.theme.fontSize * 0.3
+            attributedString.foregroundColor = colorToSwiftUIColor(context.theme.linkColor)
+            return attributedString
+
+        case .timestamp(let timestampNode):
+            let date = Date(timeIntervalSince1970: TimeInterval(timestampNode.timestamp))
+            var attributedString = AttributedString(DateFormatter.localizedString(from: date, dateStyle: .medium, timeStyle: .short))
+            attributedString.font = context.currentFont ?? context.theme.font
+            attributedString.backgroundColor = colorToSwiftUIColor(context.theme.codeBackgroundColor)
+            return attributedString
+
+        case .customEmoji(let emojiNode):
+            var attributedString = AttributedString(":\(emojiNode.name):")
+            attributedString.font = context.currentFont ?? context.theme.font
+            attributedString.foregroundColor = colorToSwiftUIColor(context.currentTextColor ?? context.theme.textColor)
+            return attributedString
+
         default:
             // 对于其他类型（图片、数学公式、Mermaid、提及），返回空字符串
             // 这些节点会在 renderParagraphWithSpecialNodes 中单独处理
@@ -532,7 +614,7 @@ public struct SwiftUIRenderer {
     
     /// 旧版 Text 组合方式（iOS 14 及以下降级处理）
     @ViewBuilder
-    private func buildTextLegacy(from nodes: [ASTNodeWrapper], context: RenderContext) -> some View {
+    private func buildTextLegacy(from nodes: [IMParseAST.Node], context: RenderContext) -> some View {
         // 简化的降级实现
         let text = nodes.compactMap { node -> String? in
             switch node {
@@ -746,7 +828,7 @@ public struct SwiftUIRenderer {
     
     /// 渲染列表项的行内内容
     @ViewBuilder
-    private func renderListItemInlineContent(nodes: [ASTNodeWrapper], context: RenderContext) -> some View {
+    private func renderListItemInlineContent(nodes: [IMParseAST.Node], context: RenderContext) -> some View {
         // 检查是否包含需要单独渲染的节点（图片、数学公式、Mermaid、提及）
         // 行内代码现在可以嵌入到 AttributedString 中，不需要单独处理
         let hasSpecialNodes = nodes.contains { wrapper in
@@ -1026,6 +1108,158 @@ public struct SwiftUIRenderer {
         }
     }
     
+    /// 渲染卡片：头部、正文、字段、图片和交互元素
+    @ViewBuilder
+    private func renderCard(_ node: IMParseAST.CardNode, context: RenderContext) -> some View {
+        VStack(alignment: .leading, spacing: context.theme.listItemSpacing) {
+            if let header = node.header {
+                renderCardHeader(header, context: context)
+            }
+            if !node.content.isEmpty {
+                Text(node.content)
+                    .font(context.theme.font)
+                    .foregroundColor(context.theme.textColor)
+                    .fixedSize(horizontal: false, vertical: true)
+            }
+            ForEach(Array(node.fieldRows.enumerated()), id: \.offset) { _, row in
+                HStack(alignment: .top, spacing: context.theme.cardPadding) {
+                    ForEach(Array(row.enumerated()), id: \.offset) { _, field in
+                        VStack(alignment: .leading, spacing: 2) {
+                            Text(field.label)
+                                .font(.system(size: context.theme.fontSize * 0.875, weight: .semibold))
+                                .foregroundColor(context.theme.blockquoteTextColor)
+                            Text(field.value)
+                                .font(context.theme.font)
+                                .foregroundColor(context.theme.textColor)
+                                .fixedSize(horizontal: false, vertical: true)
+                        }
+                        .frame(maxWidth: .infinity, alignment: .leading)
+                    }
+                }
+            }
+            ForEach(Array((node.images ?? []).enumerated()), id: \.offset) { _, image in
+                renderImage(ImageNode(url: image.url, alt: image.alt), context: context)
+            }
+            if let actions = node.actions, !actions.isEmpty {
+                HStack(spacing: 8) {
+                    ForEach(Array(actions.enumerated()), id: \.offset) { _, action in
+                        renderCardAction(action, context: context)
+                    }
+                }
+            }
+        }
+        .padding(context.theme.cardPadding)
+        .frame(maxWidth: .infinity, alignment: .leading)
+        .background(context.theme.cardBackground)
+        .cornerRadius(context.theme.cardBorderRadius)
+        .overlay(
+            RoundedRectangle(cornerRadius: context.theme.cardBorderRadius)
+                .stroke(context.theme.cardBorderColor, lineWidth: 1)
+        )
+    }
+
+    @ViewBuilder
+    private func renderCardHeader(_ header: IMParseAST.CardHeader, context: RenderContext) -> some View {
+        HStack(alignment: .center, spacing: 8) {
+            if let icon = header.icon, let url = URL(string: icon) {
+                AsyncImage(url: url) { image in
+                    image
+                        .resizable()
+                        .aspectRatio(contentMode: .fit)
+                } placeholder: {
+                    context.theme.codeBackgroundColor
+                }
+                .frame(width: context.theme.fontSize * 2, height: context.theme.fontSize * 2)
+                .cornerRadius(context.theme.imageBorderRadius)
+            }
+            VStack(alignment: .leading, spacing: 2) {
+                Text(header.title)
+                    .font(.system(size: context.theme.fontSize * 1.125, weight: .semibold))
+                    .foregroundColor(context.theme.textColor)
+                if let subtitle = header.subtitle {
+                    Text(subtitle)
+                        .font(.system(size: context.theme.fontSize * 0.875))
+                        .foregroundColor(context.theme.blockquoteTextColor)
+                }
+            }
+        }
+    }
+
+    /// 渲染卡片交互元素；点击后通过 `onCardAction` 交给宿主处理，带 URL 的按钮直接打开链接
+    @ViewBuilder
+    private func renderCardAction(_ action: IMParseAST.CardAction, context: RenderContext) -> some View {
+        switch action.resolvedKind {
+        case .button:
+            let background: Color = {
+                switch action.style ?? .default {
+                case .primary: return context.theme.linkColor
+                case .danger: return .red
+                case .default: return context.theme.cardBackground
+                }
+            }()
+            let foreground: Color = action.style == nil || action.style == .default ? context.theme.textColor : .white
+            let label = Text(action.displayTitle)
+                .font(context.theme.font)
+                .foregroundColor(foreground)
+                .padding(.horizontal, 12)
+                .padding(.vertical, 6)
+                .background(background)
+                .cornerRadius(context.theme.codeBlockBorderRadius)
+                .overlay(
+                    RoundedRectangle(cornerRadius: context.theme.codeBlockBorderRadius)
+                        .stroke(context.theme.cardBorderColor, lineWidth: 1)
+                )
+            if let urlString = action.url, let url = URL(string: urlString) {
+                Link(destination: url) { label }
+            } else {
+                Button {
+                    context.onCardAction?(action)
+                } label: {
+                    label
+                }
+                .buttonStyle(.plain)
+            }
+        case .select:
+            Menu {
+                ForEach(Array((action.options ?? []).enumerated()), id: \.offset) { _, option in
+                    Button(option.label) {
+                        context.onCardAction?(action.selecting(option))
+                    }
+                }
+            } label: {
+                HStack(spacing: 4) {
+                    Text(action.displayTitle)
+                    Image(systemName: "chevron.down")
+                }
+                .font(context.theme.font)
+                .foregroundColor(context.theme.textColor)
+                .padding(.horizontal, 12)
+                .padding(.vertical, 6)
+                .overlay(
+                    RoundedRectangle(cornerRadius: context.theme.codeBlockBorderRadius)
+                        .stroke(context.theme.cardBorderColor, lineWidth: 1)
+                )
+            }
+        case .input:
+            // 消息内不直接编辑，点击后由宿主弹出输入界面
+            Button {
+                context.onCardAction?(action)
+            } label: {
+                Text(action.placeholder ?? action.displayTitle)
+                    .font(context.theme.font)
+                    .foregroundColor(context.theme.blockquoteTextColor)
+                    .padding(.horizontal, 12)
+                    .padding(.vertical, 6)
+                    .frame(minWidth: 120, alignment: .leading)
+                    .overlay(
+                        RoundedRectangle(cornerRadius: context.theme.codeBlockBorderRadius)
+                            .stroke(context.theme.cardBorderColor, lineWidth: 1)
+                    )
+            }
+            .buttonStyle(.plain)
+        }
+    }
+
     @ViewBuilder
     private func renderHorizontalRule(context: RenderContext) -> some View {
         Divider()
@@ -1115,7 +1349,7 @@ public struct SwiftUIRenderer {
             .cornerRadius(3)
     }
     
-    private func renderInlineNodeWrapper(_ wrapper: ASTNodeWrapper, context: RenderContext) -> AnyView {
+    private func renderInlineNodeWrapper(_ wrapper: IMParseAST.Node, context: RenderContext) -> AnyView {
         switch wrapper {
         case .text(let node):
             return AnyView(renderText(node, context: context))
@@ -1401,872 +1635,3 @@ class MathSVGCache {
         }
     }
 }
-
-// MARK: - AST 节点类型定义（简化版，实际应从 Rust 绑定生成）
-
-public struct RootNode: ASTNode, Codable {
-    public var children: [ASTNodeWrapper]
-    
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView()) // 由渲染器处理
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case children
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        // RootNode 直接包含 children，没有 type 字段
-        children = try container.decode([ASTNodeWrapper].self, forKey: .children)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode(children, forKey: .children)
-    }
-    
-    public init(children: [ASTNodeWrapper]) {
-        self.children = children
-    }
-}
-
-public struct ParagraphNode: ASTNode, Codable {
-    public var children: [ASTNodeWrapper]
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView()) // 由渲染器处理
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case children
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "paragraph" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'paragraph', got '\(typeString)'"
-            ))
-        }
-        children = try container.decode([ASTNodeWrapper].self, forKey: .children)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("paragraph", forKey: .type)
-        try container.encode(children, forKey: .children)
-    }
-}
-
-public struct HeadingNode: ASTNode, Codable {
-    public var level: UInt8
-    public var children: [ASTNodeWrapper]
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case level
-        case children
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "heading" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'heading', got '\(typeString)'"
-            ))
-        }
-        level = try container.decode(UInt8.self, forKey: .level)
-        children = try container.decode([ASTNodeWrapper].self, forKey: .children)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("heading", forKey: .type)
-        try container.encode(level, forKey: .level)
-        try container.encode(children, forKey: .children)
-    }
-}
-
-public struct TextNode: ASTNode, Codable {
-    public var content: String
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case content
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "text" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'text', got '\(typeString)'"
-            ))
-        }
-        content = try container.decode(String.self, forKey: .content)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("text", forKey: .type)
-        try container.encode(content, forKey: .content)
-    }
-}
-
-public struct StrongNode: ASTNode, Codable {
-    public var children: [ASTNodeWrapper]
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case children
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "strong" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'strong', got '\(typeString)'"
-            ))
-        }
-        children = try container.decode([ASTNodeWrapper].self, forKey: .children)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("strong", forKey: .type)
-        try container.encode(children, forKey: .children)
-    }
-}
-
-public struct EmNode: ASTNode, Codable {
-    public var children: [ASTNodeWrapper]
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case children
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "em" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'em', got '\(typeString)'"
-            ))
-        }
-        children = try container.decode([ASTNodeWrapper].self, forKey: .children)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("em", forKey: .type)
-        try container.encode(children, forKey: .children)
-    }
-}
-
-public struct UnderlineNode: ASTNode, Codable {
-    public var children: [ASTNodeWrapper]
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case children
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "underline" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'underline', got '\(typeString)'"
-            ))
-        }
-        children = try container.decode([ASTNodeWrapper].self, forKey: .children)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("underline", forKey: .type)
-        try container.encode(children, forKey: .children)
-    }
-}
-
-public struct StrikeNode: ASTNode, Codable {
-    public var children: [ASTNodeWrapper]
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case children
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "strike" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'strike', got '\(typeString)'"
-            ))
-        }
-        children = try container.decode([ASTNodeWrapper].self, forKey: .children)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("strike", forKey: .type)
-        try container.encode(children, forKey: .children)
-    }
-}
-
-public struct CodeNode: ASTNode, Codable {
-    public var content: String
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case content
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "code" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'code', got '\(typeString)'"
-            ))
-        }
-        content = try container.decode(String.self, forKey: .content)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("code", forKey: .type)
-        try container.encode(content, forKey: .content)
-    }
-}
-
-public struct CodeBlockNode: ASTNode, Codable {
-    public var language: String?
-    public var content: String
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case language
-        case content
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "codeBlock" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'codeBlock', got '\(typeString)'"
-            ))
-        }
-        language = try container.decodeIfPresent(String.self, forKey: .language)
-        content = try container.decode(String.self, forKey: .content)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("codeBlock", forKey: .type)
-        try container.encodeIfPresent(language, forKey: .language)
-        try container.encode(content, forKey: .content)
-    }
-}
-
-public struct LinkNode: ASTNode, Codable {
-    public var url: String
-    public var children: [ASTNodeWrapper]
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case url
-        case children
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "link" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'link', got '\(typeString)'"
-            ))
-        }
-        url = try container.decode(String.self, forKey: .url)
-        children = try container.decode([ASTNodeWrapper].self, forKey: .children)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("link", forKey: .type)
-        try container.encode(url, forKey: .url)
-        try container.encode(children, forKey: .children)
-    }
-}
-
-public struct ImageNode: ASTNode, Codable {
-    public var url: String
-    public var width: Float?
-    public var height: Float?
-    public var alt: String?
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case url
-        case width
-        case height
-        case alt
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "image" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'image', got '\(typeString)'"
-            ))
-        }
-        url = try container.decode(String.self, forKey: .url)
-        width = try container.decodeIfPresent(Float.self, forKey: .width)
-        height = try container.decodeIfPresent(Float.self, forKey: .height)
-        alt = try container.decodeIfPresent(String.self, forKey: .alt)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("image", forKey: .type)
-        try container.encode(url, forKey: .url)
-        try container.encodeIfPresent(width, forKey: .width)
-        try container.encodeIfPresent(height, forKey: .height)
-        try container.encodeIfPresent(alt, forKey: .alt)
-    }
-}
-
-public enum ListType: String, Codable {
-    case bullet = "bullet"
-    case ordered = "ordered"
-}
-
-public struct ListItemNode: Codable {
-    public var children: [ASTNodeWrapper]
-    public var checked: Bool?
-    
-    enum CodingKeys: String, CodingKey {
-        case children
-        case checked
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        // ListItemNode 在 items 数组中时没有 type 字段，直接解码
-        // 如果作为 ASTNode 枚举的一部分，ASTNodeWrapper 会处理 type 字段
-        children = try container.decode([ASTNodeWrapper].self, forKey: .children)
-        checked = try container.decodeIfPresent(Bool.self, forKey: .checked)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode(children, forKey: .children)
-        try container.encodeIfPresent(checked, forKey: .checked)
-    }
-}
-
-public struct ListNode: ASTNode, Codable {
-    public var listType: ListType
-    public var items: [ListItemNode]
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case listType
-        case items
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "list" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'list', got '\(typeString)'"
-            ))
-        }
-        listType = try container.decode(ListType.self, forKey: .listType)
-        items = try container.decode([ListItemNode].self, forKey: .items)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("list", forKey: .type)
-        try container.encode(listType, forKey: .listType)
-        try container.encode(items, forKey: .items)
-    }
-}
-
-public struct TableNode: ASTNode, Codable {
-    public var rows: [TableRow]
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case rows
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "table" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'table', got '\(typeString)'"
-            ))
-        }
-        rows = try container.decode([TableRow].self, forKey: .rows)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("table", forKey: .type)
-        try container.encode(rows, forKey: .rows)
-    }
-}
-
-public struct TableRow: Codable {
-    public var cells: [TableCell]
-    
-    enum CodingKeys: String, CodingKey {
-        case cells
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        // TableRow 直接包含 cells，没有 type 字段（因为它不是 ASTNode 枚举）
-        cells = try container.decode([TableCell].self, forKey: .cells)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode(cells, forKey: .cells)
-    }
-}
-
-public struct TableCell: Codable {
-    public var children: [ASTNodeWrapper]
-    public var align: TextAlign?
-    
-    enum CodingKeys: String, CodingKey {
-        case children
-        case align
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        // TableCell 直接包含 children 和 align，没有 type 字段（因为它不是 ASTNode 枚举）
-        children = try container.decode([ASTNodeWrapper].self, forKey: .children)
-        align = try container.decodeIfPresent(TextAlign.self, forKey: .align)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode(children, forKey: .children)
-        try container.encodeIfPresent(align, forKey: .align)
-    }
-}
-
-public enum TextAlign: String, Codable {
-    case left
-    case center
-    case right
-}
-
-public struct MathNode: ASTNode, Codable {
-    public var content: String
-    public var display: Bool
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case content
-        case display
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "math" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'math', got '\(typeString)'"
-            ))
-        }
-        content = try container.decode(String.self, forKey: .content)
-        display = try container.decode(Bool.self, forKey: .display)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("math", forKey: .type)
-        try container.encode(content, forKey: .content)
-        try container.encode(display, forKey: .display)
-    }
-}
-
-public struct MermaidNode: ASTNode, Codable {
-    public var content: String
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case content
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "mermaid" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'mermaid', got '\(typeString)'"
-            ))
-        }
-        content = try container.decode(String.self, forKey: .content)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("mermaid", forKey: .type)
-        try container.encode(content, forKey: .content)
-    }
-}
-
-public struct MentionNode: ASTNode, Codable {
-    public var id: String
-    public var name: String
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case id
-        case name
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "mention" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'mention', got '\(typeString)'"
-            ))
-        }
-        id = try container.decode(String.self, forKey: .id)
-        name = try container.decode(String.self, forKey: .name)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("mention", forKey: .type)
-        try container.encode(id, forKey: .id)
-        try container.encode(name, forKey: .name)
-    }
-}
-
-public struct BlockquoteNode: ASTNode, Codable {
-    public var children: [ASTNodeWrapper]
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-        case children
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "blockquote" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'blockquote', got '\(typeString)'"
-            ))
-        }
-        children = try container.decode([ASTNodeWrapper].self, forKey: .children)
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("blockquote", forKey: .type)
-        try container.encode(children, forKey: .children)
-    }
-}
-
-public struct HorizontalRuleNode: ASTNode, Codable {
-    public func render(context: RenderContext) -> AnyView {
-        AnyView(EmptyView())
-    }
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        guard typeString == "horizontalRule" else {
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Expected type 'horizontalRule', got '\(typeString)'"
-            ))
-        }
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        var container = encoder.container(keyedBy: CodingKeys.self)
-        try container.encode("horizontalRule", forKey: .type)
-    }
-}
-
-// MARK: - ASTNodeWrapper
-
-/// ASTNode 包装器，用于 JSON 序列化/反序列化
-public enum ASTNodeWrapper: Codable {
-    case root(RootNode)
-    case paragraph(ParagraphNode)
-    case heading(HeadingNode)
-    case text(TextNode)
-    case strong(StrongNode)
-    case em(EmNode)
-    case underline(UnderlineNode)
-    case strike(StrikeNode)
-    case code(CodeNode)
-    case codeBlock(CodeBlockNode)
-    case link(LinkNode)
-    case image(ImageNode)
-    case list(ListNode)
-    case listItem(ListItemNode)
-    case table(TableNode)
-    case tableRow(TableRow)
-    case tableCell(TableCell)
-    case math(MathNode)
-    case mermaid(MermaidNode)
-    case mention(MentionNode)
-    case blockquote(BlockquoteNode)
-    case horizontalRule(HorizontalRuleNode)
-    
-    enum CodingKeys: String, CodingKey {
-        case type
-    }
-    
-    public init(from decoder: Decoder) throws {
-        let container = try decoder.container(keyedBy: CodingKeys.self)
-        let typeString = try container.decode(String.self, forKey: .type)
-        
-        switch typeString {
-        case "root":
-            self = .root(try RootNode(from: decoder))
-        case "paragraph":
-            self = .paragraph(try ParagraphNode(from: decoder))
-        case "heading":
-            self = .heading(try HeadingNode(from: decoder))
-        case "text":
-            self = .text(try TextNode(from: decoder))
-        case "strong":
-            self = .strong(try StrongNode(from: decoder))
-        case "em":
-            self = .em(try EmNode(from: decoder))
-        case "underline":
-            self = .underline(try UnderlineNode(from: decoder))
-        case "strike":
-            self = .strike(try StrikeNode(from: decoder))
-        case "code":
-            self = .code(try CodeNode(from: decoder))
-        case "codeBlock":
-            self = .codeBlock(try CodeBlockNode(from: decoder))
-        case "link":
-            self = .link(try LinkNode(from: decoder))
-        case "image":
-            self = .image(try ImageNode(from: decoder))
-        case "list":
-            self = .list(try ListNode(from: decoder))
-        case "listItem":
-            self = .listItem(try ListItemNode(from: decoder))
-        case "table":
-            self = .table(try TableNode(from: decoder))
-        case "tableRow":
-            self = .tableRow(try TableRow(from: decoder))
-        case "tableCell":
-            self = .tableCell(try TableCell(from: decoder))
-        case "math":
-            self = .math(try MathNode(from: decoder))
-        case "mermaid":
-            self = .mermaid(try MermaidNode(from: decoder))
-        case "mention":
-            self = .mention(try MentionNode(from: decoder))
-        case "blockquote":
-            self = .blockquote(try BlockquoteNode(from: decoder))
-        case "horizontalRule":
-            self = .horizontalRule(try HorizontalRuleNode(from: decoder))
-        default:
-            throw DecodingError.dataCorrupted(DecodingError.Context(
-                codingPath: decoder.codingPath,
-                debugDescription: "Unknown node type: \(typeString)"
-            ))
-        }
-    }
-    
-    public func encode(to encoder: Encoder) throws {
-        switch self {
-        case .root(let node):
-            try node.encode(to: encoder)
-        case .paragraph(let node):
-            try node.encode(to: encoder)
-        case .heading(let node):
-            try node.encode(to: encoder)
-        case .text(let node):
-            try node.encode(to: encoder)
-        case .strong(let node):
-            try node.encode(to: encoder)
-        case .em(let node):
-            try node.encode(to: encoder)
-        case .underline(let node):
-            try node.encode(to: encoder)
-        case .strike(let node):
-            try node.encode(to: encoder)
-        case .code(let node):
-            try node.encode(to: encoder)
-        case .codeBlock(let node):
-            try node.encode(to: encoder)
-        case .link(let node):
-            try node.encode(to: encoder)
-        case .image(let node):
-            try node.encode(to: encoder)
-        case .list(let node):
-            try node.encode(to: encoder)
-        case .listItem(let node):
-            try node.encode(to: encoder)
-        case .table(let node):
-            try node.encode(to: encoder)
-        case .tableRow(let node):
-            try node.encode(to: encoder)
-        case .tableCell(let node):
-            try node.encode(to: encoder)
-        case .math(let node):
-            try node.encode(to: encoder)
-        case .mermaid(let node):
-            try node.encode(to: encoder)
-        case .mention(let node):
-            try node.encode(to: encoder)
-        case .blockquote(let node):
-            try node.encode(to: encoder)
-        case .horizontalRule(let node):
-            try node.encode(to: encoder)
-        }
-    }
-    
-    var asASTNode: ASTNode {
-        switch self {
-        case .root(let node):
-            return node
-        case .paragraph(let node):
-            return node
-        case .heading(let node):
-            return node
-        case .text(let node):
-            return node
-        case .strong(let node):
-            return node
-        case .em(let node):
-            return node
-        case .underline(let node):
-            return node
-        case .strike(let node):
-            return node
-        case .code(let node):
-            return node
-        case .codeBlock(let node):
-            return node
-        case .link(let node):
-            return node
-        case .image(let node):
-            return node
-        case .list(let node):
-            return node
-        case .listItem:
-            // ListItemNode 不是 ASTNode，需要特殊处理
-            fatalError("ListItemNode cannot be converted to ASTNode")
-        case .table(let node):
-            return node
-        case .tableRow:
-            // TableRow 不是 ASTNode
-            fatalError("TableRow cannot be converted to ASTNode")
-        case .tableCell:
-            // TableCell 不是 ASTNode
-            fatalError("TableCell cannot be converted to ASTNode")
-        case .math(let node):
-            return node
-        case .mermaid(let node):
-            return node
-        case .mention(let node):
-            return node
-        case .blockquote(let node):
-            return node
-        case .horizontalRule(let node):
-            return node
-        }
-    }
-}
-
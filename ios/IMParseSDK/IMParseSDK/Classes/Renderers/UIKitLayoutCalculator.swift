@@ -11,7 +11,7 @@ import UIKit
 public class NodeLayout {
     public let frame: CGRect
     public let children: [NodeLayout]
-    public let node: ASTNodeWrapper? // 关联的 AST 节点
+    public let node: IMParseAST.Node? // 关联的 AST 节点
     
     // 预计算的内容（如 NSAttributedString）
     public let content: Any?
@@ -24,7 +24,7 @@ public class NodeLayout {
     
     public init(frame: CGRect, 
          children: [NodeLayout] = [], 
-         node: ASTNodeWrapper? = nil, 
+         node: IMParseAST.Node? = nil, 
          content: Any? = nil,
          backgroundColor: UIColor? = nil,
          cornerRadius: CGFloat = 0,
@@ -171,7 +171,7 @@ class UIKitLayoutCalculator {
     // MARK: - Private Layout Helpers
     
     /// 计算垂直堆栈布局
-    private static func calculateVerticalStackLayout(children: [ASTNodeWrapper], 
+    private static func calculateVerticalStackLayout(children: [IMParseAST.Node], 
                                                    context: UIKitRenderContext, 
                                                    origin: CGPoint, 
                                                    width: CGFloat,
@@ -200,7 +200,7 @@ class UIKitLayoutCalculator {
     }
     
     /// 计算单个节点的布局
-    private static func calculateNodeLayout(_ node: ASTNodeWrapper, context: UIKitRenderContext, origin: CGPoint, width: CGFloat) -> NodeLayout {
+    private static func calculateNodeLayout(_ node: IMParseAST.Node, context: UIKitRenderContext, origin: CGPoint, width: CGFloat) -> NodeLayout {
         switch node {
         case .paragraph(let pNode):
             // 段落布局：检查是否包含特殊节点
@@ -485,7 +485,7 @@ class UIKitLayoutCalculator {
         var childLayouts: [NodeLayout] = []
         
         // 将行内节点分组：连续的文本节点合并，特殊节点单独处理
-        var currentTextNodes: [ASTNodeWrapper] = []
+        var currentTextNodes: [IMParseAST.Node] = []
         
         func flushTextNodes() {
             if !currentTextNodes.isEmpty {
@@ -547,7 +547,7 @@ class UIKitLayoutCalculator {
         var childLayouts: [NodeLayout] = []
         
         // 将行内节点分组：连续的文本节点合并，特殊节点单独处理
-        var currentTextNodes: [ASTNodeWrapper] = []
+        var currentTextNodes: [IMParseAST.Node] = []
         
         func flushTextNodes() {
             if !currentTextNodes.isEmpty {
@@ -712,7 +712,7 @@ class UIKitLayoutCalculator {
             } else {
                 // 否则，将列表项内容当作行内内容处理
                 // 提取所有行内节点（包括段落内的行内节点）
-                var inlineNodes: [ASTNodeWrapper] = []
+                var inlineNodes: [IMParseAST.Node] = []
                 for child in item.children {
                     if case .paragraph(let pNode) = child {
                         // 如果子节点是段落，提取段落内的行内节点
@@ -796,12 +796,12 @@ class UIKitLayoutCalculator {
     }
     
     /// 计算包含特殊节点的列表项行内内容布局
-    private static func calculateListItemInlineContentWithSpecialNodes(nodes: [ASTNodeWrapper], context: UIKitRenderContext, origin: CGPoint, width: CGFloat) -> NodeLayout {
+    private static func calculateListItemInlineContentWithSpecialNodes(nodes: [IMParseAST.Node], context: UIKitRenderContext, origin: CGPoint, width: CGFloat) -> NodeLayout {
         var currentY: CGFloat = 0
         var childLayouts: [NodeLayout] = []
         
         // 将行内节点分组：连续的文本节点合并，特殊节点单独处理
-        var currentTextNodes: [ASTNodeWrapper] = []
+        var currentTextNodes: [IMParseAST.Node] = []
         
         func flushTextNodes() {
             if !currentTextNodes.isEmpty {
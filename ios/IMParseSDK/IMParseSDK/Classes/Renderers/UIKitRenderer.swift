@@ -24,6 +24,8 @@ public struct UIKitRenderContext {
     public var onLinkTap: ((URL) -> Void)?
     public var onImageTap: ((ImageNode) -> Void)?
     public var onMentionTap: ((MentionNode) -> Void)?
+    // 卡片按钮、下拉框和输入框的交互回调；下拉框选中后 `value` 为选项值
+    public var onCardAction: ((IMParseAST.CardAction) -> Void)?
     // 当前文本样式（用于标题等需要特殊样式的场景）
     public var currentFont: UIFont?
     public var currentTextColor: UIColor?
@@ -37,6 +39,7 @@ public struct UIKitRenderContext {
                 onLinkTap: ((URL) -> Void)? = nil,
                 onImageTap: ((ImageNode) -> Void)? = nil,
                 onMentionTap: ((MentionNode) -> Void)? = nil,
+                onCardAction: ((IMParseAST.CardAction) -> Void)? = nil,
                 currentFont: UIFont? = nil,
                 currentTextColor: UIColor? = nil,
                 imageLoaderDelegate: UIKitImageLoaderDelegate? = nil,
@@ -46,6 +49,7 @@ public struct UIKitRenderContext {
         self.onLinkTap = onLinkTap
         self.onImageTap = onImageTap
         self.onMentionTap = onMentionTap
+        self.onCardAction = onCardAction
         self.currentFont = currentFont
         self.currentTextColor = currentTextColor
         self.imageLoaderDelegate = imageLoaderDelegate
@@ -77,6 +81,9 @@ public struct UIKitTheme {
     public var imageMargin: CGFloat
     public var mentionBackground: UIColor
     public var mentionTextColor: UIColor
+    public var highlightBackground: UIColor
+    public var highlightTextColor: UIColor?  // nil 表示沿用当前文字颜色
+    public var spoilerBackground: UIColor
     public var cardBackground: UIColor
     public var cardBorderColor: UIColor
     public var cardPadding: CGFloat
@@ -108,6 +115,9 @@ public struct UIKitTheme {
                 imageMargin: CGFloat,
                 mentionBackground: UIColor,
                 mentionTextColor: UIColor,
+                highlightBackground: UIColor,
+                highlightTextColor: UIColor?,
+                spoilerBackground: UIColor,
                 cardBackground: UIColor,
                 cardBorderColor: UIColor,
                 cardPadding: CGFloat,
@@ -138,6 +148,9 @@ public struct UIKitTheme {
         self.imageMargin = imageMargin
         self.mentionBackground = mentionBackground
         self.mentionTextColor = mentionTextColor
+        self.highlightBackground = highlightBackground
+        self.highlightTextColor = highlightTextColor
+        self.spoilerBackground = spoilerBackground
         self.cardBackground = cardBackground
         self.cardBorderColor = cardBorderColor
         self.cardPadding = cardPadding
@@ -174,6 +187,10 @@ extension UIKitTheme {
         self.imageMargin = CGFloat(config.imageMargin)
         self.mentionBackground = UIColor(hex: config.mentionBackground) ?? UIColor.systemBlue.withAlphaComponent(0.1)
         self.mentionTextColor = UIColor(hex: config.mentionTextColor) ?? .systemBlue
+        self.highlightBackground = UIColor(hex: config.highlightBackground) ?? UIColor.systemYellow.withAlphaComponent(0.4)
+        // "inherit" 等非颜色值表示沿用当前文字颜色
+        self.highlightTextColor = UIColor(hex: config.highlightTextColor)
+        self.spoilerBackground = UIColor(hex: config.spoilerBackground) ?? .systemGray
         self.cardBackground = UIColor(hex: config.cardBackground) ?? UIColor.systemGray6
         self.cardBorderColor = UIColor(hex: config.cardBorderColor) ?? UIColor.gray.withAlphaComponent(0.3)
         self.cardPadding = CGFloat(config.cardPadding)
@@ -213,6 +230,9 @@ extension UIKitTheme {
             imageMargin: 16,
             mentionBackground: UIColor.systemBlue.withAlphaComponent(0.1),
             mentionTextColor: .systemBlue,
+            highlightBackground: UIColor.systemYellow.withAlphaComponent(0.4),
+            highlightTextColor: nil,
+            spoilerBackground: .systemGray,
             cardBackground: UIColor.systemGray6,
             cardBorderColor: UIColor.gray.withAlphaComponent(0.3),
             cardPadding: 16,
@@ -276,21 +296,11 @@ public class UIKitRenderer {
     }
     
     /// 渲染节点包装器
-    private func renderNodeWrapper(_ wrapper: ASTNodeWrapper, context: UIKitRenderContext) -> UIView {
+    private func renderNodeWrapper(_ wrapper: IMParseAST.Node, context: UIKitRenderContext) -> UIView {
         switch wrapper {
         case .root(let node):
             // Root 节点不应该在子节点中出现，但为了安全起见还是处理一下
-            let containerView = UIStackView()
-            containerView.axis = .vertical
-            containerView.alignment = .leading
-            containerView.spacing = context.theme.paragraphSpacing
-            containerView.distribution = .fill
-            
-            for child in node.children {
-                let childView = renderNodeWrapper(child, context: context)
-                containerView.addArrangedSubview(childView)
-            }
-            return containerView
+            return renderBlockChildren(node.children, context: context)
             
         case .paragraph(let node):
             return renderParagraph(node, context: context)
@@ -337,7 +347,45 @@ public class UIKitRenderer {
             return renderBlockquote(node, context: context)
         case .horizontalRule(let node):
             return renderHorizontalRule(context: context)
+        case .highlight, .footnoteReference, .timestamp, .customEmoji:
+            // 行内节点单独出现在块级位置时按段落渲染
+            let label = UILabel()
+            label.numberOfLines = 0
+            label.attributedText = buildAttributedString(from: wrapper, context: context)
+            return label
+        case .spoiler:
+            // 块级剧透：不渲染内容，只显示占位
+            let label = UILabel()
+            label.text = "[spoiler]"
+            label.font = context.theme.font
+            label.textColor = context.theme.textColor
+            label.backgroundColor = context.theme.spoilerBackground
+            return label
+        case .footnoteDefinition(let node):
+            return renderBlockChildren(node.children, context: context)
+        case .custom(let node):
+            return renderBlockChildren(node.children ?? [], context: context)
+        case .card(let node):
+            return renderCard(node, context: context)
+        case .unknown:
+            // 当前版本不认识的节点（前向兼容），跳过
+            return UIView()
+        }
+    }
+
+    /// 按块级内容纵向渲染子节点
+    private func renderBlockChildren(_ children: [IMParseAST.Node], context: UIKitRenderContext) -> UIView {
+        let containerView = UIStackView()
+        containerView.axis = .vertical
+        containerView.alignment = .leading
+        containerView.spacing = context.theme.paragraphSpacing
+        containerView.distribution = .fill
+
+        for child in children {
+            let childView = renderNodeWrapper(child, context: context)
+            containerView.addArrangedSubview(childView)
         }
+        return containerView
     }
     
     /// 渲染段落
@@ -393,7 +441,7 @@ public class UIKitRenderer {
         ])
         
         // 将行内节点分组：连续的文本节点合并，特殊节点单独处理
-        var currentTextNodes: [ASTNodeWrapper] = []
+        var currentTextNodes: [IMParseAST.Node] = []
         
         func flushTextNodes() {
             if !currentTextNodes.isEmpty {
@@ -424,7 +472,7 @@ public class UIKitRenderer {
     }
     
     /// 从行内节点构建 NSAttributedString
-    func buildAttributedString(from nodes: [ASTNodeWrapper], context: UIKitRenderContext) -> NSAttributedString {
+    func buildAttributedString(from nodes: [IMParseAST.Node], context: UIKitRenderContext) -> NSAttributedString {
         let result = NSMutableAttributedString()
         
         for node in nodes {
@@ -436,7 +484,7 @@ public class UIKitRenderer {
     }
     
     /// 从单个行内节点构建 NSAttributedString
-    func buildAttributedString(from node: ASTNodeWrapper, context: UIKitRenderContext) -> NSAttributedString {
+    func buildAttributedString(from node: IMParseAST.Node, context: UIKitRenderContext) -> NSAttributedString {
         switch node {
         case .text(let textNode):
             let font = context.currentFont ?? context.theme.font
@@ -529,6 +577,61 @@ public class UIKitRenderer {
             )
             return attributedString
             
+        case .highlight(let highlightNode):
+            let result = NSMutableAttributedString()
+            for child in highlightNode.children {
+                let mutableString = NSMutableAttributedString(attributedString: buildAttributedString(from: child, context: context))
+                let range = NSRange(location: 0, length: mutableString.length)
+                mutableString.addAttribute(.backgroundColor, value: context.theme.highlightBackground, range: range)
+                if let textColor = context.theme.highlightTextColor {
+                    mutableString.addAttribute(.foregroundColor, value: textColor, range: range)
+                }
+                result.append(mutableString)
+            }
+            return result
+
+        case .spoiler(let spoilerNode):
+            // 行内剧透：文字颜色与背景相同，遮住内容但保留占位
+            let result = NSMutableAttributedString()
+            for child in spoilerNode.children {
+                let mutableString = NSMutableAttributedString(attributedString: buildAttributedString(from: child, context: context))
+                let range = NSRange(location: 0, length: mutableString.length)
+                mutableString.addAttribute(.foregroundColor, value: context.theme.spoilerBackground, range: range)
+                mutableString.addAttribute(.backgroundColor, value: context.theme.spoilerBackground, range: range)
+                result.append(mutableString)
+            }
+            return result
+
+        case .footnoteReference(let referenceNode):
+            return NSAttributedString(
+                string: "[\(referenceNode.index)]",
+                attributes: [
+                    .font: UIFont.systemFont(ofSize: context.theme.fontSize * 0.75),
+                    .baselineOffset: context.theme.fontSize * 0.3,
+                    .foregroundColor: context.theme.linkColor
+                ]
+            )
+
+        case .timestamp(let timestampNode):
+            let date = Date(timeIntervalSince1970: TimeInterval(timestampNode.timestamp))
+            return NSAttributedString(
+                string: DateFormatter.localizedString(from: date, dateStyle: .medium, timeStyle: .short),
+                attributes: [
+                    .font: context.currentFont ?? context.theme.font,
+                    .foregroundColor: context.currentTextColor ?? context.theme.textColor,
+                    .backgroundColor: context.theme.codeBackgroundColor
+                ]
+            )
+
+        case .customEmoji(let emojiNode):
+            return NSAttributedString(
+                string: ":\(emojiNode.name):",
+                attributes: [
+                    .font: context.currentFont ?? context.theme.font,
+                    .foregroundColor: context.currentTextColor ?? context.theme.textColor
+                ]
+            )
+
         default:
             // 对于其他类型（图片、数学公式、Mermaid、提及），返回空字符串
             // 这些节点会在 renderParagraphWithSpecialNodes 中单独处理
@@ -597,7 +700,7 @@ public class UIKitRenderer {
         ])
         
         // 将行内节点分组：连续的文本节点合并，特殊节点单独处理
-        var currentTextNodes: [ASTNodeWrapper] = []
+        var currentTextNodes: [IMParseAST.Node] = []
         
         func flushTextNodes() {
             if !currentTextNodes.isEmpty {
@@ -1216,7 +1319,7 @@ public class UIKitRenderer {
     }
     
     /// 渲染列表项的行内内容
-    private func renderListItemInlineContent(nodes: [ASTNodeWrapper], context: UIKitRenderContext) -> UIView {
+    private func renderListItemInlineContent(nodes: [IMParseAST.Node], context: UIKitRenderContext) -> UIView {
         // 检查是否包含需要单独渲染的节点（图片、数学公式、Mermaid、提及）
         // 行内代码现在可以嵌入到 NSAttributedString 中，不需要单独处理
         let hasSpecialNodes = nodes.contains { wrapper in
@@ -1247,7 +1350,7 @@ public class UIKitRenderer {
             ])
             
             // 将行内节点分组：连续的文本节点合并，特殊节点单独处理
-            var currentTextNodes: [ASTNodeWrapper] = []
+            var currentTextNodes: [IMParseAST.Node] = []
             
             func flushTextNodes() {
                 if !currentTextNodes.isEmpty {
@@ -1371,7 +1474,7 @@ public class UIKitRenderer {
             containerView.addSubview(contentStackView)
             
             // 将行内节点分组：连续的文本节点合并，特殊节点单独处理
-            var currentTextNodes: [ASTNodeWrapper] = []
+            var currentTextNodes: [IMParseAST.Node] = []
             
             func flushTextNodes() {
                 if !currentTextNodes.isEmpty {
@@ -1721,7 +1824,7 @@ public class UIKitRenderer {
                 ])
                 
                 // 将行内节点分组：连续的文本节点合并，特殊节点单独处理
-                var currentTextNodes: [ASTNodeWrapper] = []
+                var currentTextNodes: [IMParseAST.Node] = []
                 
                 func flushTextNodes() {
                     if !currentTextNodes.isEmpty {
@@ -1766,6 +1869,185 @@ public class UIKitRenderer {
         return stackView
     }
     
+    /// 渲染卡片：头部、正文、字段、图片和交互元素
+    private func renderCard(_ node: IMParseAST.CardNode, context: UIKitRenderContext) -> UIView {
+        let stackView = UIStackView()
+        stackView.axis = .vertical
+        stackView.alignment = .fill
+        stackView.spacing = context.theme.listItemSpacing
+        stackView.distribution = .fill
+        stackView.isLayoutMarginsRelativeArrangement = true
+        stackView.layoutMargins = UIEdgeInsets(top: context.theme.cardPadding,
+                                               left: context.theme.cardPadding,
+                                               bottom: context.theme.cardPadding,
+                                               right: context.theme.cardPadding)
+        stackView.backgroundColor = context.theme.cardBackground
+        stackView.layer.cornerRadius = context.theme.cardBorderRadius
+        stackView.layer.borderWidth = 1
+        stackView.layer.borderColor = context.theme.cardBorderColor.cgColor
+        stackView.clipsToBounds = true
+
+        if let header = node.header {
+            stackView.addArrangedSubview(renderCardHeader(header, context: context))
+        }
+
+        if !node.content.isEmpty {
+            let label = UILabel()
+            label.numberOfLines = 0
+            label.text = node.content
+            label.font = context.theme.font
+            label.textColor = context.theme.textColor
+            stackView.addArrangedSubview(label)
+        }
+
+        for row in node.fieldRows {
+            let rowView = UIStackView()
+            rowView.axis = .horizontal
+            rowView.alignment = .top
+            rowView.spacing = context.theme.cardPadding
+            rowView.distribution = .fillEqually
+            for field in row {
+                let fieldView = UIStackView()
+                fieldView.axis = .vertical
+                fieldView.spacing = 2
+
+                let labelView = UILabel()
+                labelView.numberOfLines = 0
+                labelView.text = field.label
+                labelView.font = UIFont.systemFont(ofSize: context.theme.fontSize * 0.875, weight: .semibold)
+                labelView.textColor = context.theme.blockquoteTextColor
+                fieldView.addArrangedSubview(labelView)
+
+                let valueView = UILabel()
+                valueView.numberOfLines = 0
+                valueView.text = field.value
+                valueView.font = context.theme.font
+                valueView.textColor = context.theme.textColor
+                fieldView.addArrangedSubview(valueView)
+
+                rowView.addArrangedSubview(fieldView)
+            }
+            stackView.addArrangedSubview(rowView)
+        }
+
+        for image in node.images ?? [] {
+            stackView.addArrangedSubview(renderImage(ImageNode(url: image.url, alt: image.alt), context: context))
+        }
+
+        if let actions = node.actions, !actions.isEmpty {
+            let actionsView = UIStackView()
+            actionsView.axis = .horizontal
+            actionsView.alignment = .center
+            actionsView.spacing = 8
+            for action in actions {
+                actionsView.addArrangedSubview(renderCardAction(action, context: context))
+            }
+            // 按钮靠左排列，剩余空间留白
+            actionsView.addArrangedSubview(UIView())
+            stackView.addArrangedSubview(actionsView)
+        }
+
+        return stackView
+    }
+
+    private func renderCardHeader(_ header: IMParseAST.CardHeader, context: UIKitRenderContext) -> UIView {
+        let headerView = UIStackView()
+        headerView.axis = .horizontal
+        headerView.alignment = .center
+        headerView.spacing = 8
+
+        if let icon = header.icon, let url = URL(string: icon) {
+            let iconView = UIImageView()
+            iconView.contentMode = .scaleAspectFit
+            iconView.clipsToBounds = true
+            iconView.layer.cornerRadius = context.theme.imageBorderRadius
+            iconView.backgroundColor = context.theme.codeBackgroundColor
+            iconView.translatesAutoresizingMaskIntoConstraints = false
+            NSLayoutConstraint.activate([
+                iconView.widthAnchor.constraint(equalToConstant: context.theme.fontSize * 2),
+                iconView.heightAnchor.constraint(equalToConstant: context.theme.fontSize * 2)
+            ])
+            context.imageLoaderDelegate?.loadImage(url: url, into: iconView) { [weak iconView] image, _ in
+                DispatchQueue.main.async {
+                    iconView?.image = image
+                }
+            }
+            headerView.addArrangedSubview(iconView)
+        }
+
+        let titleView = UIStackView()
+        titleView.axis = .vertical
+        titleView.spacing = 2
+
+        let titleLabel = UILabel()
+        titleLabel.numberOfLines = 0
+        titleLabel.text = header.title
+        titleLabel.font = UIFont.systemFont(ofSize: context.theme.fontSize * 1.125, weight: .semibold)
+        titleLabel.textColor = context.theme.textColor
+        titleView.addArrangedSubview(titleLabel)
+
+        if let subtitle = header.subtitle {
+            let subtitleLabel = UILabel()
+            subtitleLabel.numberOfLines = 0
+            subtitleLabel.text = subtitle
+            subtitleLabel.font = UIFont.systemFont(ofSize: context.theme.fontSize * 0.875)
+            subtitleLabel.textColor = context.theme.blockquoteTextColor
+            titleView.addArrangedSubview(subtitleLabel)
+        }
+
+        headerView.addArrangedSubview(titleView)
+        return headerView
+    }
+
+    /// 渲染卡片交互元素；点击后通过 `onCardAction` 交给宿主处理，带 URL 的按钮直接打开链接
+    private func renderCardAction(_ action: IMParseAST.CardAction, context: UIKitRenderContext) -> UIView {
+        let button = UIButton(type: .system)
+        button.titleLabel?.font = context.theme.font
+        button.contentEdgeInsets = UIEdgeInsets(top: 6, left: 12, bottom: 6, right: 12)
+        button.layer.cornerRadius = context.theme.codeBlockBorderRadius
+        button.layer.borderWidth = 1
+        button.layer.borderColor = context.theme.cardBorderColor.cgColor
+
+        switch action.resolvedKind {
+        case .button:
+            button.setTitle(action.displayTitle, for: .normal)
+            switch action.style ?? .default {
+            case .primary:
+                button.backgroundColor = context.theme.linkColor
+                button.setTitleColor(.white, for: .normal)
+            case .danger:
+                button.backgroundColor = .systemRed
+                button.setTitleColor(.white, for: .normal)
+            case .default:
+                button.backgroundColor = context.theme.cardBackground
+                button.setTitleColor(context.theme.textColor, for: .normal)
+            }
+        case .select:
+            button.setTitle("\(action.displayTitle) ▾", for: .normal)
+            button.setTitleColor(context.theme.textColor, for: .normal)
+            if #available(iOS 14.0, *) {
+                let onCardAction = context.onCardAction
+                button.menu = UIMenu(children: (action.options ?? []).map { option in
+                    UIAction(title: option.label) { _ in
+                        onCardAction?(action.selecting(option))
+                    }
+                })
+                button.showsMenuAsPrimaryAction = true
+                return button
+            }
+        case .input:
+            // 消息内不直接编辑，点击后由宿主弹出输入界面
+            button.setTitle(action.placeholder ?? action.displayTitle, for: .normal)
+            button.setTitleColor(context.theme.blockquoteTextColor, for: .normal)
+            button.contentHorizontalAlignment = .leading
+        }
+
+        let target = CardActionTarget(action: action, onCardAction: context.onCardAction)
+        button.addTarget(target, action: #selector(CardActionTarget.invoke), for: .touchUpInside)
+        objc_setAssociatedObject(button, &AssociatedKeys.cardAction, target, .OBJC_ASSOCIATION_RETAIN_NONATOMIC)
+        return button
+    }
+
     /// 渲染水平分割线
     private func renderHorizontalRule(context: UIKitRenderContext) -> UIView {
         let view = UIView()
@@ -1778,7 +2060,7 @@ public class UIKitRenderer {
     }
     
     /// 渲染行内节点包装器
-    private func renderInlineNodeWrapper(_ wrapper: ASTNodeWrapper, context: UIKitRenderContext) -> UIView {
+    private func renderInlineNodeWrapper(_ wrapper: IMParseAST.Node, context: UIKitRenderContext) -> UIView {
         switch wrapper {
         case .text(let node):
             return renderText(node, context: context)
@@ -1867,6 +2149,26 @@ extension TextAlign {
 
 private struct AssociatedKeys {
     static var url = "url"
+    static var cardAction = "cardAction"
+}
+
+/// 卡片交互元素的点击目标，随按钮一起保留
+private final class CardActionTarget: NSObject {
+    let action: IMParseAST.CardAction
+    let onCardAction: ((IMParseAST.CardAction) -> Void)?
+
+    init(action: IMParseAST.CardAction, onCardAction: ((IMParseAST.CardAction) -> Void)?) {
+        self.action = action
+        self.onCardAction = onCardAction
+    }
+
+    @objc func invoke() {
+        if action.resolvedKind == .button, let urlString = action.url, let url = URL(string: urlString) {
+            UIApplication.shared.open(url)
+        } else {
+            onCardAction?(action)
+        }
+    }
 }
 
 // MARK: - UILabel 扩展（用于内边距）
@@ -129,8 +129,6 @@ struct MessageRowView: View {
                 
                 // 回到主线程更新 UI
                 DispatchQueue.main.async {
-                    // 将 RootNode 转换为 ASTNode（用于渲染）
-                    // 由于 RootNode 本身就是 ASTNode，直接使用
                     self.astNode = rootNode
                 }
             } catch {
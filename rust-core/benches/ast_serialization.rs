@@ -0,0 +1,128 @@
+//! 比较 JSON、MessagePack、CBOR 与紧凑编码的 AST 序列化体积和速度
+//!
+//! 用法：`cargo bench --features msgpack,cbor --bench ast_serialization`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use im_parse_core::*;
+
+const SAMPLE: &str = r#"# 发布说明
+
+本次更新包含 **性能优化**、*渲染修复* 以及 ~~废弃接口~~ 的清理，详见 [变更日志](https://example.com/changelog)。
+
+## 主要变更
+
+1. 新增 `serialize_ast_msgpack` 接口
+2. 修复列表嵌套时的缩进问题
+   - 有序列表
+   - 无序列表
+3. 优化表格渲染
+
+- [x] 完成 MessagePack 支持
+- [ ] 完成 CBOR 支持
+
+| 格式 | 可读性 | 体积 |
+| :--- | :----: | ---: |
+| JSON | 高 | 大 |
+| MessagePack | 低 | 小 |
+
+> 注意：二进制格式需要启用对应的 feature。
+
+```rust
+let bytes = serialize_ast_msgpack(&ast)?;
+let ast = deserialize_ast_msgpack(&bytes)?;
+```
+
+行内公式 $E = mc^2$ 与图片 ![示意图](https://example.com/diagram.png)
+
+---
+"#;
+
+/// 样本：单条消息和 20 条消息拼接的长文档
+fn samples() -> Vec<(&'static str, RootNode)> {
+    vec![
+        ("message", parse_markdown(SAMPLE).expect("sample parses")),
+        ("document", parse_markdown(&SAMPLE.repeat(20)).expect("sample parses")),
+    ]
+}
+
+fn report_sizes(samples: &[(&str, RootNode)]) {
+    for (name, ast) in samples {
+        let json = serialize_ast(ast).unwrap().len();
+        let msgpack = serialize_ast_msgpack(ast).unwrap().len();
+        let cbor = serialize_ast_cbor(ast).unwrap().len();
+        let compact = encode_compact(ast).len();
+        println!(
+            "{}: json {} B, msgpack {} B ({:.0}%), cbor {} B ({:.0}%), compact {} B ({:.0}%)",
+            name,
+            json,
+            msgpack,
+            msgpack as f64 * 100.0 / json as f64,
+            cbor,
+            cbor as f64 * 100.0 / json as f64,
+            compact,
+            compact as f64 * 100.0 / json as f64
+        );
+    }
+}
+
+fn bench_serialize(c: &mut Criterion) {
+    let samples = samples();
+    report_sizes(&samples);
+
+    let mut group = c.benchmark_group("serialize");
+    for (name, ast) in &samples {
+        group.bench_with_input(BenchmarkId::new("json", name), ast, |b, ast| {
+            b.iter(|| serialize_ast(black_box(ast)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("msgpack", name), ast, |b, ast| {
+            b.iter(|| serialize_ast_msgpack(black_box(ast)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("cbor", name), ast, |b, ast| {
+            b.iter(|| serialize_ast_cbor(black_box(ast)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("compact", name), ast, |b, ast| {
+            b.iter(|| encode_compact(black_box(ast)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_deserialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deserialize");
+    for (name, ast) in &samples() {
+        let json = serialize_ast(ast).unwrap();
+        let msgpack = serialize_ast_msgpack(ast).unwrap();
+        let cbor = serialize_ast_cbor(ast).unwrap();
+        let compact = encode_compact(ast);
+
+        group.bench_with_input(BenchmarkId::new("json", name), &json, |b, json| {
+            b.iter(|| deserialize_ast(black_box(json)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("msgpack", name), &msgpack, |b, bytes| {
+            b.iter(|| deserialize_ast_msgpack(black_box(bytes)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("cbor", name), &cbor, |b, bytes| {
+            b.iter(|| deserialize_ast_cbor(black_box(bytes)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("compact", name), &compact, |b, bytes| {
+            b.iter(|| decode_compact(black_box(bytes)).unwrap())
+        });
+        // 只读场景：校验后直接遍历借用视图，不构建 RootNode
+        group.bench_with_input(BenchmarkId::new("compact_view", name), &compact, |b, bytes| {
+            b.iter(|| text_len(CompactAst::from_bytes(black_box(bytes)).unwrap().root()))
+        });
+    }
+    group.finish();
+}
+
+/// 遍历所有文本节点，模拟渲染时读取内容
+fn text_len(node: CompactNode) -> usize {
+    let own = match node.view() {
+        CompactNodeView::Text { content } => content.len(),
+        _ => 0,
+    };
+    own + node.children().map(text_len).sum::<usize>()
+}
+
+criterion_group!(benches, bench_serialize, bench_deserialize);
+criterion_main!(benches);
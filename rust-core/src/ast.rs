@@ -1,8 +1,49 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// 当前 AST 结构版本
+///
+/// 序列化时写入根节点的 `schemaVersion` 字段。新增节点类型或字段时递增；
+/// 旧版本客户端遇到不认识的节点类型会将其反序列化为 [`ASTNode::Unknown`]，而不是报错。
+pub const AST_SCHEMA_VERSION: u32 = 10;
+
+/// 当前版本已知的节点类型名（与 [`ASTNode::type_name`] 一一对应，由 `tests/ast_schema.rs` 检查）
+pub const KNOWN_NODE_TYPES: &[&str] = &[
+    "root",
+    "paragraph",
+    "heading",
+    "text",
+    "strong",
+    "em",
+    "underline",
+    "strike",
+    "code",
+    "codeBlock",
+    "link",
+    "image",
+    "list",
+    "listItem",
+    "table",
+    "tableRow",
+    "tableCell",
+    "math",
+    "mermaid",
+    "card",
+    "mention",
+    "horizontalRule",
+    "blockquote",
+    "footnoteReference",
+    "footnoteDefinition",
+    "highlight",
+    "spoiler",
+    "custom",
+    "timestamp",
+    "customEmoji",
+];
+
 /// AST 节点类型
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum ASTNode {
     #[serde(rename = "root")]
@@ -43,87 +84,124 @@ pub enum ASTNode {
     Math(MathNode),
     #[serde(rename = "mermaid")]
     Mermaid(MermaidNode),
+    // 装箱以免结构化卡片撑大所有节点
     #[serde(rename = "card")]
-    Card(CardNode),
+    Card(Box<CardNode>),
     #[serde(rename = "mention")]
     Mention(MentionNode),
     #[serde(rename = "horizontalRule")]
     HorizontalRule(HorizontalRuleNode),
     #[serde(rename = "blockquote")]
     Blockquote(BlockquoteNode),
+    #[serde(rename = "footnoteReference")]
+    FootnoteReference(FootnoteReferenceNode),
+    #[serde(rename = "footnoteDefinition")]
+    FootnoteDefinition(FootnoteDefinitionNode),
+    #[serde(rename = "highlight")]
+    Highlight(HighlightNode),
+    #[serde(rename = "spoiler")]
+    Spoiler(SpoilerNode),
+    #[serde(rename = "custom")]
+    Custom(CustomNode),
+    #[serde(rename = "timestamp")]
+    Timestamp(TimestampNode),
+    #[serde(rename = "customEmoji")]
+    CustomEmoji(CustomEmojiNode),
+    /// 未知节点（前向兼容），必须位于最后
+    #[serde(untagged, deserialize_with = "deserialize_unknown_node")]
+    Unknown(UnknownNode),
 }
 
 /// 根节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct RootNode {
+    /// AST 结构版本，缺省时视为 1
+    #[serde(rename = "schemaVersion", default = "default_schema_version")]
+    pub schema_version: u32,
     pub children: Vec<ASTNode>,
 }
 
+fn default_schema_version() -> u32 {
+    1
+}
+
 /// 段落节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ParagraphNode {
     pub children: Vec<ASTNode>,
 }
 
 /// 标题节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct HeadingNode {
     pub level: u8, // 1-6
     pub children: Vec<ASTNode>,
 }
 
 /// 文本节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TextNode {
     pub content: String,
 }
 
 /// 粗体节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct StrongNode {
     pub children: Vec<ASTNode>,
 }
 
 /// 斜体节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct EmNode {
     pub children: Vec<ASTNode>,
 }
 
 /// 下划线节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct UnderlineNode {
     pub children: Vec<ASTNode>,
 }
 
 /// 删除线节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct StrikeNode {
     pub children: Vec<ASTNode>,
 }
 
 /// 行内代码节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CodeNode {
     pub content: String,
 }
 
 /// 代码块节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CodeBlockNode {
     pub language: Option<String>,
     pub content: String,
 }
 
 /// 链接节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LinkNode {
     pub url: String,
     pub children: Vec<ASTNode>,
 }
 
 /// 图片节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ImageNode {
     pub url: String,
     pub width: Option<f32>,
@@ -133,6 +211,7 @@ pub struct ImageNode {
 
 /// 列表类型
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum ListType {
     Bullet,
@@ -140,7 +219,8 @@ pub enum ListType {
 }
 
 /// 列表节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ListNode {
     #[serde(rename = "listType")]
     pub list_type: ListType,
@@ -148,14 +228,16 @@ pub struct ListNode {
 }
 
 /// 列表项节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ListItemNode {
     pub children: Vec<ASTNode>,
     pub checked: Option<bool>, // None = 普通列表项, Some(true) = 已完成, Some(false) = 未完成
 }
 
 /// 文本对齐方式
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum TextAlign {
     Left,
@@ -164,65 +246,399 @@ pub enum TextAlign {
 }
 
 /// 表格行
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TableRow {
     pub cells: Vec<TableCell>,
 }
 
 /// 表格单元格
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TableCell {
     pub children: Vec<ASTNode>,
     pub align: Option<TextAlign>,
 }
 
 /// 表格节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TableNode {
     pub rows: Vec<TableRow>,
 }
 
 /// 数学公式节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MathNode {
     pub content: String,
     pub display: bool, // true for $$, false for $
 }
 
 /// Mermaid 图表节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MermaidNode {
     pub content: String,
 }
 
 /// 卡片节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// `content` 和 `metadata` 适用于简单卡片；审批、投票、表单等交互卡片使用结构化的
+/// `header`、`fields`、`images` 和 `actions`，缺省时为空。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CardNode {
     pub subtype: String,
     pub content: String,
-    pub metadata: HashMap<String, String>,
+    /// 卡片字段，值保留 JSON 类型（字符串、数字、布尔值、列表等）
+    pub metadata: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub header: Option<CardHeader>,
+    /// 键值字段（如"金额：¥120"）
+    #[serde(default)]
+    pub fields: Vec<CardField>,
+    #[serde(default)]
+    pub images: Vec<CardImage>,
+    /// 按钮、下拉框和输入框，宿主按 `id` 分发交互事件
+    #[serde(default)]
+    pub actions: Vec<CardAction>,
+}
+
+/// 卡片头部
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CardHeader {
+    pub title: String,
+    #[serde(default)]
+    pub subtitle: Option<String>,
+    /// 图标 URL
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+/// 卡片键值字段
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CardField {
+    pub label: String,
+    pub value: String,
+    /// 短字段，宽度允许时与相邻的短字段并排显示
+    #[serde(default)]
+    pub short: bool,
+}
+
+/// 卡片图片
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CardImage {
+    pub url: String,
+    #[serde(default)]
+    pub alt: Option<String>,
+}
+
+/// 卡片交互元素类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum CardActionKind {
+    #[default]
+    Button,
+    /// 下拉框，从 `options` 中选择
+    Select,
+    /// 文本输入框
+    Input,
+}
+
+/// 按钮样式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum CardActionStyle {
+    #[default]
+    Default,
+    Primary,
+    Danger,
+}
+
+/// 卡片交互元素
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CardAction {
+    /// 同一卡片内唯一，点击或提交时回传给宿主
+    pub id: String,
+    #[serde(default)]
+    pub kind: CardActionKind,
+    /// 按钮文字，或下拉框、输入框的标签
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub style: CardActionStyle,
+    /// 按钮携带的值，或下拉框、输入框的初始值
+    #[serde(default)]
+    pub value: Option<String>,
+    /// 链接按钮的地址，点击时打开链接而不是回传动作（仅按钮）
+    #[serde(default)]
+    pub url: Option<String>,
+    /// 占位文字（下拉框、输入框）
+    #[serde(default)]
+    pub placeholder: Option<String>,
+    /// 下拉框选项
+    #[serde(default)]
+    pub options: Vec<CardSelectOption>,
+}
+
+/// 下拉框选项
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CardSelectOption {
+    pub label: String,
+    pub value: String,
+}
+
+/// 提及类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum MentionKind {
+    #[default]
+    User,
+    Channel,
+    /// `@all` / `@everyone` / `@channel`
+    All,
+    /// `@here`
+    Here,
+    /// 角色（用户组）提及，如 Discord 的 `<@&id>`
+    Role,
+}
+
+/// 提及目标的解析状态，由 [`resolve_mentions`](crate::resolve_mentions) 填写
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum MentionStatus {
+    /// 尚未解析（解析器输出的默认值）
+    #[default]
+    Unresolved,
+    Active,
+    /// 目标存在但已停用
+    Deactivated,
+    /// 目录中找不到目标
+    Unknown,
 }
 
 /// @提及节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MentionNode {
     pub id: String,
     pub name: String,
+    /// 旧版本数据没有该字段，缺省为用户提及
+    #[serde(default)]
+    pub kind: MentionKind,
+    #[serde(default)]
+    pub status: MentionStatus,
 }
 
 /// 水平分割线节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct HorizontalRuleNode;
 
 /// 引用块节点
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct BlockquoteNode {
     pub children: Vec<ASTNode>,
 }
 
+/// 脚注引用节点
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FootnoteReferenceNode {
+    /// 源文本中的脚注标签（`[^label]`）
+    pub label: String,
+    /// 脚注编号，从 1 开始，与对应的脚注定义一致
+    pub index: u32,
+}
+
+/// 脚注定义节点
+///
+/// 解析器将所有脚注定义按编号顺序放在根节点末尾。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FootnoteDefinitionNode {
+    pub label: String,
+    pub index: u32,
+    pub children: Vec<ASTNode>,
+}
+
+/// 高亮节点（`==text==`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct HighlightNode {
+    pub children: Vec<ASTNode>,
+}
+
+/// 剧透节点（`||text||`），子节点可以是行内内容，也可以是块级内容（块级剧透）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SpoilerNode {
+    pub children: Vec<ASTNode>,
+}
+
+/// 自定义节点
+///
+/// 由宿主注册的解析钩子（[`InlineNodeParser`](crate::InlineNodeParser)、[`BlockNodeParser`](crate::BlockNodeParser)）
+/// 生成，如位置、贴纸等；渲染由 [`CustomNodeRenderer`](crate::CustomNodeRenderer) 负责。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CustomNode {
+    /// 自定义类型名（如 `location`）
+    pub kind: String,
+    /// 属性，保留 JSON 类型
+    #[serde(default)]
+    pub attrs: HashMap<String, serde_json::Value>,
+    /// 子节点，可以是行内内容，也可以是块级内容
+    #[serde(default)]
+    pub children: Vec<ASTNode>,
+}
+
+/// 时间戳节点
+///
+/// 如 Discord 的 `<t:1618953630:R>`，由客户端按本地时区和语言格式化显示。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TimestampNode {
+    /// Unix 时间戳（秒）
+    pub timestamp: i64,
+    /// 显示格式：`t` / `T` 短 / 长时间，`d` / `D` 短 / 长日期，`f` / `F` 短 / 长日期时间，`R` 相对时间；缺省为 `f`
+    #[serde(default)]
+    pub style: Option<String>,
+}
+
+/// 自定义表情节点
+///
+/// 如 Discord 的 `<:name:id>`、`<a:name:id>`，客户端按 `id` 加载表情图片。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CustomEmojiNode {
+    pub id: String,
+    /// 表情名（不含冒号），图片无法加载时显示为 `:name:`
+    pub name: String,
+    /// 动态表情
+    #[serde(default)]
+    pub animated: bool,
+}
+
+/// 未知节点
+///
+/// 反序列化时遇到当前版本不认识的节点类型，原始 JSON 完整保存在 `raw` 中，
+/// 再次序列化时原样输出，便于转发给支持该类型的新版本客户端。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UnknownNode {
+    pub raw: serde_json::Value,
+}
+
+/// 只接受类型名未知的节点；类型已知但字段不合法的节点（以及缺少 `type` 的对象）会报错，
+/// 而不是被静默保留为未知节点
+fn deserialize_unknown_node<'de, D>(deserializer: D) -> Result<UnknownNode, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let node = UnknownNode::deserialize(deserializer)?;
+    match node.node_type() {
+        Some(node_type) if !ASTNode::is_known_type(node_type) => Ok(node),
+        node_type => Err(serde::de::Error::custom(format!(
+            "invalid `{}` node",
+            node_type.unwrap_or("untyped")
+        ))),
+    }
+}
+
+impl UnknownNode {
+    /// 原始 JSON 中的 `type` 字段
+    pub fn node_type(&self) -> Option<&str> {
+        self.raw.get("type").and_then(|v| v.as_str())
+    }
+}
+
+impl TimestampNode {
+    /// UTC 时间的 ISO 8601 表示（如 `2021-04-20T21:20:30Z`），用于无法按本地时区格式化的场景
+    pub fn iso8601(&self) -> String {
+        let days = self.timestamp.div_euclid(86_400);
+        let seconds = self.timestamp.rem_euclid(86_400);
+        // 公历日期换算（Howard Hinnant 的 civil_from_days）
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            year,
+            month,
+            day,
+            seconds / 3_600,
+            seconds % 3_600 / 60,
+            seconds % 60
+        )
+    }
+}
+
+impl ASTNode {
+    /// 节点的序列化类型名（与 JSON 中的 `type` 字段一致，未知节点为 `unknown`）
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ASTNode::Root(_) => "root",
+            ASTNode::Paragraph(_) => "paragraph",
+            ASTNode::Heading(_) => "heading",
+            ASTNode::Text(_) => "text",
+            ASTNode::Strong(_) => "strong",
+            ASTNode::Em(_) => "em",
+            ASTNode::Underline(_) => "underline",
+            ASTNode::Strike(_) => "strike",
+            ASTNode::Code(_) => "code",
+            ASTNode::CodeBlock(_) => "codeBlock",
+            ASTNode::Link(_) => "link",
+            ASTNode::Image(_) => "image",
+            ASTNode::List(_) => "list",
+            ASTNode::ListItem(_) => "listItem",
+            ASTNode::Table(_) => "table",
+            ASTNode::TableRow(_) => "tableRow",
+            ASTNode::TableCell(_) => "tableCell",
+            ASTNode::Math(_) => "math",
+            ASTNode::Mermaid(_) => "mermaid",
+            ASTNode::Card(_) => "card",
+            ASTNode::Mention(_) => "mention",
+            ASTNode::HorizontalRule(_) => "horizontalRule",
+            ASTNode::Blockquote(_) => "blockquote",
+            ASTNode::FootnoteReference(_) => "footnoteReference",
+            ASTNode::FootnoteDefinition(_) => "footnoteDefinition",
+            ASTNode::Highlight(_) => "highlight",
+            ASTNode::Spoiler(_) => "spoiler",
+            ASTNode::Custom(_) => "custom",
+            ASTNode::Timestamp(_) => "timestamp",
+            ASTNode::CustomEmoji(_) => "customEmoji",
+            ASTNode::Unknown(_) => "unknown",
+        }
+    }
+
+    /// 是否是当前版本已知的节点类型名
+    pub fn is_known_type(name: &str) -> bool {
+        KNOWN_NODE_TYPES.contains(&name)
+    }
+}
+
 impl RootNode {
     pub fn new() -> Self {
         Self {
+            schema_version: AST_SCHEMA_VERSION,
             children: Vec::new(),
         }
     }
@@ -70,6 +70,11 @@ impl ASTBuilder {
         }
     }
 
+    /// 已完成的顶层块数量
+    pub(crate) fn block_count(&self) -> usize {
+        self.root.children.len()
+    }
+
     /// 添加文本
     pub fn add_text(&mut self, text: String) {
         if text.is_empty() {
@@ -93,7 +98,7 @@ impl ASTBuilder {
     pub fn add_heading(&mut self, level: u8, children: Vec<ASTNode>) {
         self.end_paragraph(); // 结束当前段落
         self.root.children.push(ASTNode::Heading(HeadingNode {
-            level: level.min(6).max(1),
+            level: level.clamp(1, 6),
             children,
         }));
     }
@@ -122,6 +127,24 @@ impl ASTBuilder {
         self.add_inline_node(strike_node);
     }
 
+    /// 添加高亮
+    pub fn add_highlight(&mut self, children: Vec<ASTNode>) {
+        let highlight_node = ASTNode::Highlight(HighlightNode { children });
+        self.add_inline_node(highlight_node);
+    }
+
+    /// 添加剧透
+    pub fn add_spoiler(&mut self, children: Vec<ASTNode>) {
+        let spoiler_node = ASTNode::Spoiler(SpoilerNode { children });
+        self.add_inline_node(spoiler_node);
+    }
+
+    /// 添加行内自定义节点
+    pub fn add_custom(&mut self, kind: String, attrs: HashMap<String, serde_json::Value>, children: Vec<ASTNode>) {
+        let custom_node = ASTNode::Custom(CustomNode { kind, attrs, children });
+        self.add_inline_node(custom_node);
+    }
+
     /// 添加行内代码
     pub fn add_code(&mut self, content: String) {
         let code_node = ASTNode::Code(CodeNode { content });
@@ -257,21 +280,42 @@ impl ASTBuilder {
     }
 
     /// 添加卡片
-    pub fn add_card(&mut self, subtype: String, content: String, metadata: HashMap<String, String>) {
-        self.end_paragraph(); // 结束当前段落
-        self.root.children.push(ASTNode::Card(CardNode {
+    pub fn add_card(&mut self, subtype: String, content: String, metadata: HashMap<String, serde_json::Value>) {
+        self.add_card_node(CardNode {
             subtype,
             content,
             metadata,
-        }));
+            ..CardNode::default()
+        });
+    }
+
+    /// 添加带结构化内容（头部、字段、图片、交互元素）的卡片
+    pub fn add_card_node(&mut self, card: CardNode) {
+        self.end_paragraph(); // 结束当前段落
+        self.root.children.push(ASTNode::Card(Box::new(card)));
     }
 
     /// 添加@提及
     pub fn add_mention(&mut self, id: String, name: String) {
-        let mention_node = ASTNode::Mention(MentionNode { id, name });
+        self.add_mention_with_kind(id, name, MentionKind::User);
+    }
+
+    /// 添加指定类型的提及（频道、@all、@here）
+    pub fn add_mention_with_kind(&mut self, id: String, name: String, kind: MentionKind) {
+        let mention_node = ASTNode::Mention(MentionNode { id, name, kind, status: MentionStatus::default() });
         self.add_inline_node(mention_node);
     }
 
+    /// 添加时间戳，`style` 见 [`TimestampNode::style`]
+    pub fn add_timestamp(&mut self, timestamp: i64, style: Option<String>) {
+        self.add_inline_node(ASTNode::Timestamp(TimestampNode { timestamp, style }));
+    }
+
+    /// 添加自定义表情
+    pub fn add_custom_emoji(&mut self, id: String, name: String, animated: bool) {
+        self.add_inline_node(ASTNode::CustomEmoji(CustomEmojiNode { id, name, animated }));
+    }
+
     /// 添加水平分割线
     pub fn add_horizontal_rule(&mut self) {
         self.end_paragraph(); // 结束当前段落
@@ -284,8 +328,23 @@ impl ASTBuilder {
         self.root.children.push(ASTNode::Blockquote(BlockquoteNode { children }));
     }
 
+    /// 添加脚注引用（编号由 [`number_footnotes`](crate::number_footnotes) 分配）
+    pub fn add_footnote_reference(&mut self, label: String) {
+        self.add_inline_node(ASTNode::FootnoteReference(FootnoteReferenceNode { label, index: 0 }));
+    }
+
+    /// 添加脚注定义
+    pub fn add_footnote_definition(&mut self, label: String, children: Vec<ASTNode>) {
+        self.end_paragraph(); // 结束当前段落
+        self.root.children.push(ASTNode::FootnoteDefinition(FootnoteDefinitionNode {
+            label,
+            index: 0,
+            children,
+        }));
+    }
+
     /// 添加内联节点到当前段落
-    fn add_inline_node(&mut self, node: ASTNode) {
+    pub(crate) fn add_inline_node(&mut self, node: ASTNode) {
         if let Some(para) = &mut self.current_paragraph {
             para.children.push(node);
         } else {
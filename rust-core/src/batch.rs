@@ -0,0 +1,61 @@
+use crate::ast::RootNode;
+use crate::cache::InputFormat;
+use crate::{parse_delta, parse_html, parse_markdown};
+use serde::{Deserialize, Serialize};
+
+/// 批量解析的一条输入
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchItem {
+    /// 调用方的消息 ID，原样带回结果中
+    pub id: String,
+    pub format: InputFormat,
+    pub content: String,
+}
+
+/// 批量解析的一条结果，`ast` 与 `error` 二者有且只有一个
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchResult {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ast: Option<RootNode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 批量解析消息，结果顺序与输入一致
+///
+/// 单条消息解析失败不影响其他消息。启用 `parallel` feature 时使用 rayon 线程池并行解析；
+/// 启用全局解析缓存时同样会使用缓存。
+pub fn parse_batch(items: &[BatchItem]) -> Vec<BatchResult> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        items.par_iter().map(parse_batch_item).collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(parse_batch_item).collect()
+    }
+}
+
+fn parse_batch_item(item: &BatchItem) -> BatchResult {
+    let result = match item.format {
+        InputFormat::Markdown => parse_markdown(&item.content),
+        InputFormat::Delta => parse_delta(&item.content),
+        InputFormat::Html => parse_html(&item.content),
+    };
+
+    match result {
+        Ok(ast) => BatchResult {
+            id: item.id.clone(),
+            ast: Some(ast),
+            error: None,
+        },
+        Err(e) => BatchResult {
+            id: item.id.clone(),
+            ast: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
@@ -0,0 +1,20 @@
+//! 根据 AST 定义生成各平台的类型代码
+//!
+//! 用法：`cargo run --features schema --bin ast-codegen -- <typescript|swift|kotlin>`
+
+use im_parse_core::AstCodegen;
+
+fn main() {
+    let target = std::env::args().nth(1).unwrap_or_default();
+    let codegen = AstCodegen::new();
+    let output = match target.as_str() {
+        "typescript" | "ts" => codegen.typescript(),
+        "swift" => codegen.swift(),
+        "kotlin" | "kt" => codegen.kotlin(),
+        _ => {
+            eprintln!("usage: ast-codegen <typescript|swift|kotlin>");
+            std::process::exit(2);
+        }
+    };
+    print!("{}", output);
+}
@@ -0,0 +1,8 @@
+//! 输出 AST 的 JSON Schema
+//!
+//! 用法：`cargo run --features schema --bin ast-schema > schema/ast.schema.json`
+
+fn main() {
+    let schema = im_parse_core::ast_json_schema();
+    println!("{}", serde_json::to_string_pretty(&schema).expect("schema is valid JSON"));
+}
@@ -0,0 +1,436 @@
+//! 顶层块级扩展语法
+//!
+//! `||` 剧透围栏、`:::card` 容器、Discord 的 `-# ` 小字行等语法由顶层段落中独占一行的标记构成，`<details>` 折叠块由顶层 HTML 块中的行构成。
+//! 解析时在 pulldown-cmark 的事件流中找出这些行，在标记处拆开所在段落并用 [`BLOCK_MARKER`] 事件代替标记行；构建 AST 时记录每个标记事件之前已完成的顶层块数量，
+//! 解析完成后由 [`apply_block_markers`] 把配对标记之间的块包装为对应节点。
+//! 整篇输入只解析一次，链接引用定义等文档级信息在标记内外共享，缩进代码块、列表项等其他块中的同名行不受影响。
+
+use crate::ast::*;
+use crate::raw_html::{details_open_line, summary_line};
+use pulldown_cmark::{Event, Tag};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// 代替标记行的 HTML 事件内容，以非字符开头，pulldown-cmark 不会给出相同内容的 HTML 事件
+pub(crate) const BLOCK_MARKER: &str = "\u{FDD3}block";
+
+/// 按容器在输入中的范围（开始行行首到结束行行尾）、开始行中的属性和容器正文构造卡片，不合法时返回 `None`
+pub(crate) type CardBuilder<'p> = &'p dyn Fn(Range<usize>, &str, &str) -> Option<CardNode>;
+
+/// 把 `<summary>` 标题解析为行内节点
+pub(crate) type InlineParser<'p> = &'p dyn Fn(&str) -> Vec<ASTNode>;
+
+/// 启用的块级扩展语法
+pub(crate) struct BlockSyntax<'p> {
+    /// 独占一行的 `||` 剧透围栏
+    pub spoiler: bool,
+    /// `:::card{...}` 开始、`:::` 结束的卡片容器
+    pub cards: Option<CardBuilder<'p>>,
+    /// 顶层 HTML 块中独占一行的 `<details>` 和 `</details>`
+    pub details: Option<InlineParser<'p>>,
+    /// 行首没有缩进的 `-# ` 小字行
+    pub subtext: bool,
+}
+
+impl BlockSyntax<'_> {
+    fn is_empty(&self) -> bool {
+        !self.spoiler && self.cards.is_none() && self.details.is_none() && !self.subtext
+    }
+}
+
+/// 已配对的块级标记，按在输入中出现的顺序排列
+pub(crate) enum BlockMarker {
+    SpoilerOpen,
+    SpoilerClose,
+    /// 卡片容器，开始行和结束行之间的块不输出
+    Card(Box<CardNode>),
+    DetailsOpen { expanded: bool, summary: Option<Vec<ASTNode>> },
+    DetailsClose,
+    /// 紧随其后的段落是去掉 `-# ` 的小字行
+    Subtext,
+}
+
+type Spanned<'a> = (Event<'a>, Range<usize>);
+
+/// 可能成为标记的行
+#[derive(Clone, Copy, PartialEq)]
+enum LineKind {
+    SpoilerFence,
+    CardOpen,
+    CardClose,
+    DetailsOpen,
+    DetailsClose,
+    Summary,
+    Subtext,
+}
+
+/// 拆分后的事件流片段
+enum Piece {
+    /// 顶层段落之外的单个事件
+    Event(usize),
+    /// 顶层段落中的一行，或顶层 HTML 块中的一行
+    Line(Line),
+    /// 卡片容器内部或已并入折叠块的标题行，不输出
+    Dropped,
+}
+
+struct Line {
+    /// 所在段落开始和结束事件的下标，HTML 块中的行为 `None`
+    paragraph: Option<(usize, usize)>,
+    /// 行内容的事件下标范围
+    events: Range<usize>,
+    /// 行尾换行事件的下标，段落的最后一行没有
+    line_break: Option<usize>,
+    kind: Option<LineKind>,
+    marker: Option<BlockMarker>,
+}
+
+/// 找出顶层段落中的标记行并配对，返回改写后的事件流和其中每个 [`BLOCK_MARKER`] 事件对应的标记
+///
+/// 没有配对的标记行保留在段落中，按普通文本解析。
+pub(crate) fn mark_blocks<'a>(
+    input: &str,
+    events: Vec<Spanned<'a>>,
+    syntax: &BlockSyntax,
+) -> (Vec<Spanned<'a>>, Vec<BlockMarker>) {
+    if syntax.is_empty() {
+        return (events, Vec::new());
+    }
+
+    let mut pieces = split_pieces(input, &events, syntax);
+    pair_cards(input, &events, &mut pieces, syntax);
+    pair_containers(input, &events, &mut pieces, syntax);
+    for piece in pieces.iter_mut() {
+        if kind_of(piece) == Some(LineKind::Subtext) {
+            set_marker(piece, BlockMarker::Subtext);
+        }
+    }
+    if !pieces.iter().any(|piece| matches!(piece, Piece::Line(Line { marker: Some(_), .. }))) {
+        return (events, Vec::new());
+    }
+
+    let mut output = Vec::with_capacity(events.len());
+    let mut markers = Vec::new();
+    // 当前段落片段是否已输出开始事件
+    let mut open = false;
+    let mut pending_break = None;
+    for piece in pieces {
+        let line = match piece {
+            Piece::Event(index) => {
+                output.push(events[index].clone());
+                continue;
+            }
+            Piece::Line(line) => line,
+            Piece::Dropped => continue,
+        };
+        let Some((start, end)) = line.paragraph else {
+            match line.marker {
+                Some(marker) => {
+                    output.push((Event::Html(BLOCK_MARKER.into()), line_range(&events, &line.events)));
+                    markers.push(marker);
+                }
+                None => output.extend(events[line.events].iter().cloned()),
+            }
+            continue;
+        };
+        match line.marker {
+            Some(marker) => {
+                if open {
+                    output.push(events[end].clone());
+                    open = false;
+                }
+                output.push((Event::Html(BLOCK_MARKER.into()), line_range(&events, &line.events)));
+                if matches!(marker, BlockMarker::Subtext) {
+                    output.push(events[start].clone());
+                    output.extend(strip_subtext_prefix(&events[line.events.clone()]));
+                    output.push(events[end].clone());
+                }
+                markers.push(marker);
+            }
+            None => {
+                if open {
+                    output.extend(pending_break.map(|index: usize| events[index].clone()));
+                } else {
+                    output.push(events[start].clone());
+                    open = true;
+                }
+                output.extend(events[line.events].iter().cloned());
+            }
+        }
+        pending_break = line.line_break;
+        if pending_break.is_none() && open {
+            output.push(events[end].clone());
+            open = false;
+        }
+    }
+    (output, markers)
+}
+
+/// 把事件流拆成顶层段落的行和其余事件
+fn split_pieces(input: &str, events: &[Spanned<'_>], syntax: &BlockSyntax) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut depth = 0usize;
+    let mut index = 0;
+    while index < events.len() {
+        match &events[index].0 {
+            Event::Start(Tag::Paragraph) if depth == 0 => {
+                let end = events[index..]
+                    .iter()
+                    .position(|(event, _)| matches!(event, Event::End(Tag::Paragraph)))
+                    .map_or(events.len() - 1, |offset| index + offset);
+                split_lines(input, events, (index, end), syntax, &mut pieces);
+                index = end + 1;
+                continue;
+            }
+            Event::Html(html) if depth == 0 && syntax.details.is_some() => {
+                let kind = classify_html(html.trim());
+                pieces.push(Piece::Line(Line { paragraph: None, events: index..index + 1, line_break: None, kind, marker: None }));
+                index += 1;
+                continue;
+            }
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        pieces.push(Piece::Event(index));
+        index += 1;
+    }
+    pieces
+}
+
+/// 按不在行内标签中的换行拆分段落，拆出的每一行内部标签都是配对的
+fn split_lines(input: &str, events: &[Spanned<'_>], paragraph: (usize, usize), syntax: &BlockSyntax, pieces: &mut Vec<Piece>) {
+    let (start, end) = paragraph;
+    let mut line_start = start + 1;
+    let mut depth = 0usize;
+    let push = |range: Range<usize>, line_break: Option<usize>, pieces: &mut Vec<Piece>| {
+        let kind = classify(input, events, &range, syntax);
+        pieces.push(Piece::Line(Line { paragraph: Some(paragraph), events: range, line_break, kind, marker: None }));
+    };
+    for (index, (event, _)) in events.iter().enumerate().take(end).skip(start + 1) {
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::SoftBreak | Event::HardBreak if depth == 0 => {
+                push(line_start..index, Some(index), pieces);
+                line_start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    push(line_start..end, None, pieces);
+}
+
+/// 按行的源码识别标记行
+fn classify(input: &str, events: &[Spanned<'_>], range: &Range<usize>, syntax: &BlockSyntax) -> Option<LineKind> {
+    if range.is_empty() {
+        return None;
+    }
+    let source = input[line_range(events, range)].trim();
+    if syntax.spoiler && source == "||" {
+        return Some(LineKind::SpoilerFence);
+    }
+    if syntax.subtext && is_subtext_line(input, events, range) {
+        return Some(LineKind::Subtext);
+    }
+    if syntax.cards.is_some() {
+        if source.strip_prefix(":::card").is_some_and(|rest| rest.starts_with('{')) {
+            return Some(LineKind::CardOpen);
+        }
+        if source == ":::" {
+            return Some(LineKind::CardClose);
+        }
+    }
+    None
+}
+
+/// 小字行：行首没有缩进，以 `-# ` 开头
+fn is_subtext_line(input: &str, events: &[Spanned<'_>], range: &Range<usize>) -> bool {
+    let (event, span) = &events[range.start];
+    let unindented = span.start == 0 || input[..span.start].ends_with('\n');
+    unindented && matches!(event, Event::Text(text) if text.starts_with("-# "))
+}
+
+/// 去掉小字行第一个文本事件开头的 `-# `
+fn strip_subtext_prefix<'a>(line: &[Spanned<'a>]) -> Vec<Spanned<'a>> {
+    let mut events = line.to_vec();
+    if let Some((Event::Text(text), span)) = events.first_mut() {
+        *text = text["-# ".len()..].to_string().into();
+        span.start += "-# ".len();
+        if text.is_empty() {
+            events.remove(0);
+        }
+    }
+    events
+}
+
+/// 识别 HTML 块中的折叠块标记行
+fn classify_html(line: &str) -> Option<LineKind> {
+    if details_open_line(line).is_some() {
+        Some(LineKind::DetailsOpen)
+    } else if line == "</details>" {
+        Some(LineKind::DetailsClose)
+    } else if summary_line(line).is_some() {
+        Some(LineKind::Summary)
+    } else {
+        None
+    }
+}
+
+/// 一行事件覆盖的源码范围
+fn line_range(events: &[Spanned<'_>], range: &Range<usize>) -> Range<usize> {
+    events[range.start].1.start..events[range.end - 1].1.end
+}
+
+fn line_of(piece: &Piece) -> Option<&Line> {
+    match piece {
+        Piece::Line(line) => Some(line),
+        _ => None,
+    }
+}
+
+fn kind_of(piece: &Piece) -> Option<LineKind> {
+    line_of(piece).and_then(|line| line.kind)
+}
+
+/// 卡片容器的开始行与其后第一个 `:::` 行配对，容器不能嵌套
+///
+/// 合法的容器替换为卡片，其中的块连同其他标记行一起丢弃；不合法或没有结束行的容器保留为普通文本。
+fn pair_cards(input: &str, events: &[Spanned<'_>], pieces: &mut [Piece], syntax: &BlockSyntax<'_>) {
+    let Some(build) = syntax.cards else {
+        return;
+    };
+    let mut index = 0;
+    while index < pieces.len() {
+        if kind_of(&pieces[index]) != Some(LineKind::CardOpen) {
+            index += 1;
+            continue;
+        }
+        let Some(close) = (index + 1..pieces.len()).find(|&k| kind_of(&pieces[k]) == Some(LineKind::CardClose)) else {
+            return;
+        };
+        let (Some(open_line), Some(close_line)) = (line_of(&pieces[index]), line_of(&pieces[close])) else {
+            return;
+        };
+        let open_range = line_range(events, &open_line.events);
+        let close_range = line_range(events, &close_line.events);
+        let attributes = input[open_range.clone()].trim().trim_start_matches(":::card");
+        // 正文从开始行的下一行起，到结束行所在行的行首为止
+        let body_start = input[open_range.end..].find('\n').map_or(input.len(), |offset| open_range.end + offset + 1);
+        let body_end = input[..close_range.start].rfind('\n').map_or(0, |offset| offset + 1);
+        let body = input.get(body_start..body_end.max(body_start)).unwrap_or_default();
+        if let Some(card) = build(open_range.start..close_range.end, attributes, body) {
+            set_marker(&mut pieces[index], BlockMarker::Card(Box::new(card)));
+            pieces[index + 1..=close].iter_mut().for_each(|piece| *piece = Piece::Dropped);
+        }
+        index = close + 1;
+    }
+}
+
+/// 按嵌套关系配对剧透围栏和折叠块
+///
+/// 剧透不能直接嵌套在剧透中：栈顶是剧透时围栏结束它，否则开始新的剧透。`</details>` 结束最近的折叠块，
+/// 其间未闭合的剧透围栏和没有配对的开始行一样保留为文本。
+fn pair_containers(input: &str, events: &[Spanned<'_>], pieces: &mut [Piece], syntax: &BlockSyntax<'_>) {
+    let mut stack: Vec<(usize, LineKind)> = Vec::new();
+    for index in 0..pieces.len() {
+        match kind_of(&pieces[index]) {
+            Some(LineKind::SpoilerFence) => match stack.last() {
+                Some(&(start, LineKind::SpoilerFence)) => {
+                    stack.pop();
+                    set_marker(&mut pieces[start], BlockMarker::SpoilerOpen);
+                    set_marker(&mut pieces[index], BlockMarker::SpoilerClose);
+                }
+                _ => stack.push((index, LineKind::SpoilerFence)),
+            },
+            Some(LineKind::DetailsOpen) => stack.push((index, LineKind::DetailsOpen)),
+            Some(LineKind::DetailsClose) => {
+                let Some(position) = stack.iter().rposition(|(_, kind)| *kind == LineKind::DetailsOpen) else {
+                    continue;
+                };
+                let start = stack[position].0;
+                stack.truncate(position);
+                let marker = details_marker(input, events, pieces, start, syntax);
+                set_marker(&mut pieces[start], marker);
+                set_marker(&mut pieces[index], BlockMarker::DetailsClose);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 折叠块的开始标记；标题写在开始行中，或是紧随其后的 `<summary>` 行，后者并入开始标记
+fn details_marker(input: &str, events: &[Spanned<'_>], pieces: &mut [Piece], start: usize, syntax: &BlockSyntax<'_>) -> BlockMarker {
+    let source = |piece: &Piece| line_of(piece).map(|line| input[line_range(events, &line.events)].trim());
+    let (expanded, mut summary) = source(&pieces[start]).and_then(details_open_line).unwrap_or((false, None));
+    if summary.is_none() && pieces.get(start + 1).and_then(kind_of) == Some(LineKind::Summary) {
+        summary = source(&pieces[start + 1]).and_then(summary_line);
+        pieces[start + 1] = Piece::Dropped;
+    }
+    let summary = summary.zip(syntax.details).map(|(title, parse)| parse(title));
+    BlockMarker::DetailsOpen { expanded, summary }
+}
+
+fn set_marker(piece: &mut Piece, marker: BlockMarker) {
+    if let Piece::Line(line) = piece {
+        line.marker = Some(marker);
+    }
+}
+
+/// 把配对标记之间的顶层块包装为对应节点
+///
+/// `positions` 是每个标记事件之前已完成的顶层块数量，与 [`mark_blocks`] 返回的标记一一对应。
+pub(crate) fn apply_block_markers(root: &mut RootNode, markers: Vec<BlockMarker>, positions: &[usize]) {
+    if markers.is_empty() {
+        return;
+    }
+    let mut blocks = std::mem::take(&mut root.children).into_iter().enumerate().peekable();
+    let mut children = Vec::new();
+    // 未闭合的容器及其已收集的子节点
+    let mut stack: Vec<(BlockMarker, Vec<ASTNode>)> = Vec::new();
+    for (marker, &position) in markers.into_iter().zip(positions) {
+        let target = stack.last_mut().map_or(&mut children, |(_, nodes)| nodes);
+        while let Some((_, block)) = blocks.next_if(|(index, _)| *index < position) {
+            target.push(block);
+        }
+        match marker {
+            BlockMarker::Card(card) => target.push(ASTNode::Card(card)),
+            BlockMarker::Subtext => {
+                if let Some((_, ASTNode::Paragraph(paragraph))) = blocks.next_if(|(index, _)| *index == position) {
+                    let children = paragraph.children;
+                    target.push(ASTNode::Custom(CustomNode { kind: "subtext".to_string(), attrs: HashMap::new(), children }));
+                }
+            }
+            BlockMarker::SpoilerOpen | BlockMarker::DetailsOpen { .. } => stack.push((marker, Vec::new())),
+            BlockMarker::SpoilerClose | BlockMarker::DetailsClose => {
+                if let Some((open, nodes)) = stack.pop() {
+                    let node = container_node(open, nodes);
+                    stack.last_mut().map_or(&mut children, |(_, nodes)| nodes).push(node);
+                }
+            }
+        }
+    }
+    // 标记都已配对，这里只是防御性地展开未闭合容器
+    for (_, nodes) in stack {
+        children.extend(nodes);
+    }
+    children.extend(blocks.map(|(_, block)| block));
+    root.children = children;
+}
+
+/// 由开始标记和收集到的子节点构造容器节点：折叠块为 `kind` 为 `details` 的 CustomNode，标题是第一个 `summary` 子节点
+fn container_node(open: BlockMarker, nodes: Vec<ASTNode>) -> ASTNode {
+    let BlockMarker::DetailsOpen { expanded, summary } = open else {
+        return ASTNode::Spoiler(SpoilerNode { children: nodes });
+    };
+    let mut children = Vec::new();
+    if let Some(title) = summary {
+        children.push(ASTNode::Custom(CustomNode { kind: "summary".to_string(), attrs: HashMap::new(), children: title }));
+    }
+    children.extend(nodes);
+    let mut attrs = HashMap::new();
+    if expanded {
+        attrs.insert("open".to_string(), serde_json::Value::Bool(true));
+    }
+    ASTNode::Custom(CustomNode { kind: "details".to_string(), attrs, children })
+}
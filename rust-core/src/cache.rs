@@ -0,0 +1,379 @@
+use crate::ast::*;
+use crate::style_config::StyleConfig;
+use crate::visitor::*;
+use crate::ParseError;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// 输入格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputFormat {
+    Markdown,
+    Delta,
+    Html,
+}
+
+/// 缓存配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheConfig {
+    /// 最多缓存的条目数
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+    /// 缓存内容的估算总字节数上限
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: usize,
+}
+
+fn default_max_entries() -> usize {
+    1000
+}
+
+fn default_max_bytes() -> usize {
+    16 * 1024 * 1024
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: default_max_entries(),
+            max_bytes: default_max_bytes(),
+        }
+    }
+}
+
+/// 缓存统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// 因超出条目数或字节数上限被淘汰的条目数
+    pub evictions: u64,
+    pub entries: usize,
+    pub bytes: usize,
+    /// 命中率（0-1），尚无请求时为 0
+    pub hit_rate: f64,
+}
+
+/// 缓存键：输出类型、输入格式、解析器选项（以及 HTML 的样式配置）和输入内容
+///
+/// 保存上述内容经 [`Hash`] 写出的完整字节序列，查找时除哈希值外还会逐字节比较，
+/// 哈希碰撞不会返回其他输入的结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheKey {
+    hash: u64,
+    bytes: Box<[u8]>,
+}
+
+impl Hash for CacheKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+/// 缓存的产物类型，保证同一输入的 AST 和 HTML 不会互相覆盖
+#[derive(Hash)]
+enum CacheOutput {
+    Ast,
+    Html,
+}
+
+impl CacheKey {
+    /// AST 缓存键
+    pub fn ast(format: InputFormat, input: &str, options: &impl Hash) -> Self {
+        let mut key = KeyBytes::default();
+        CacheOutput::Ast.hash(&mut key);
+        format.hash(&mut key);
+        options.hash(&mut key);
+        input.hash(&mut key);
+        key.into()
+    }
+
+    /// HTML 缓存键
+    pub fn html(format: InputFormat, input: &str, options: &impl Hash, config: &StyleConfig) -> Self {
+        let mut key = KeyBytes::default();
+        CacheOutput::Html.hash(&mut key);
+        format.hash(&mut key);
+        options.hash(&mut key);
+        // StyleConfig 含浮点字段，按序列化结果参与哈希
+        serde_json::to_string(config).unwrap_or_default().hash(&mut key);
+        input.hash(&mut key);
+        key.into()
+    }
+
+    /// 键本身占用的字节数，计入缓存的字节上限
+    fn size(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+/// 记录 [`Hash`] 写出的全部字节，作为缓存键的完整内容
+#[derive(Default)]
+struct KeyBytes(Vec<u8>);
+
+impl Hasher for KeyBytes {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&self.0);
+        hasher.finish()
+    }
+}
+
+impl From<KeyBytes> for CacheKey {
+    fn from(key: KeyBytes) -> Self {
+        CacheKey { hash: key.finish(), bytes: key.0.into_boxed_slice() }
+    }
+}
+
+#[derive(Clone)]
+enum CacheValue {
+    Ast(Arc<RootNode>),
+    Html(Arc<str>),
+}
+
+struct CacheEntry {
+    value: CacheValue,
+    size: usize,
+}
+
+struct CacheState {
+    entries: LruCache<CacheKey, CacheEntry>,
+    bytes: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+/// 线程安全的 LRU 解析缓存
+///
+/// 同时缓存 AST 和 HTML，按条目数和估算字节数两个上限淘汰最久未使用的条目。
+pub struct ParseCache {
+    config: CacheConfig,
+    state: Mutex<CacheState>,
+}
+
+impl ParseCache {
+    pub fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CacheState {
+                entries: LruCache::unbounded(),
+                bytes: 0,
+                hits: 0,
+                misses: 0,
+                evictions: 0,
+            }),
+        }
+    }
+
+    pub fn config(&self) -> CacheConfig {
+        self.config
+    }
+
+    /// 查找 AST，未命中时调用 `parse` 并写入缓存（解析失败不缓存）
+    pub fn get_or_parse(
+        &self,
+        key: CacheKey,
+        parse: impl FnOnce() -> Result<RootNode, ParseError>,
+    ) -> Result<Arc<RootNode>, ParseError> {
+        if let Some(CacheValue::Ast(ast)) = self.get(&key) {
+            return Ok(ast);
+        }
+        let ast = Arc::new(parse()?);
+        self.insert(key, CacheValue::Ast(ast.clone()), estimate_ast_size(&ast));
+        Ok(ast)
+    }
+
+    /// 查找 HTML，未命中时调用 `render` 并写入缓存（失败不缓存）
+    pub fn get_or_render(
+        &self,
+        key: CacheKey,
+        render: impl FnOnce() -> Result<String, ParseError>,
+    ) -> Result<Arc<str>, ParseError> {
+        if let Some(CacheValue::Html(html)) = self.get(&key) {
+            return Ok(html);
+        }
+        let html: Arc<str> = render()?.into();
+        self.insert(key, CacheValue::Html(html.clone()), html.len());
+        Ok(html)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let state = self.lock();
+        let requests = state.hits + state.misses;
+        CacheStats {
+            hits: state.hits,
+            misses: state.misses,
+            evictions: state.evictions,
+            entries: state.entries.len(),
+            bytes: state.bytes,
+            hit_rate: if requests == 0 { 0.0 } else { state.hits as f64 / requests as f64 },
+        }
+    }
+
+    /// 清空缓存内容和统计
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.entries.clear();
+        state.bytes = 0;
+        state.hits = 0;
+        state.misses = 0;
+        state.evictions = 0;
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<CacheValue> {
+        let mut state = self.lock();
+        match state.entries.get(key).map(|entry| entry.value.clone()) {
+            Some(value) => {
+                state.hits += 1;
+                Some(value)
+            }
+            None => {
+                state.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&self, key: CacheKey, value: CacheValue, size: usize) {
+        let size = size + key.size();
+        // 单个条目超过字节上限时不缓存，避免清空整个缓存
+        if size > self.config.max_bytes || self.config.max_entries == 0 {
+            return;
+        }
+
+        let mut state = self.lock();
+        if let Some(old) = state.entries.put(key, CacheEntry { value, size }) {
+            state.bytes -= old.size;
+        }
+        state.bytes += size;
+
+        while state.entries.len() > self.config.max_entries || state.bytes > self.config.max_bytes {
+            match state.entries.pop_lru() {
+                Some((_, evicted)) => {
+                    state.bytes -= evicted.size;
+                    state.evictions += 1;
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        // 缓存状态在 panic 时也保持一致（只有计数和 LRU 操作），忽略中毒
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for ParseCache {
+    fn default() -> Self {
+        Self::new(CacheConfig::default())
+    }
+}
+
+static GLOBAL_CACHE: RwLock<Option<Arc<ParseCache>>> = RwLock::new(None);
+
+/// 启用全局解析缓存（已启用时以新配置重建，原有内容被丢弃）
+///
+/// 启用后 [`parse_markdown`](crate::parse_markdown)、[`markdown_to_html`](crate::markdown_to_html)
+/// 等便捷函数以及对应的 FFI 接口都会使用该缓存。
+pub fn enable_parse_cache(config: CacheConfig) {
+    *GLOBAL_CACHE.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(ParseCache::new(config)));
+}
+
+/// 停用并释放全局解析缓存
+pub fn disable_parse_cache() {
+    *GLOBAL_CACHE.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// 清空全局解析缓存
+pub fn clear_parse_cache() {
+    if let Some(cache) = global_parse_cache() {
+        cache.clear();
+    }
+}
+
+/// 全局解析缓存的统计，未启用时返回 `None`
+pub fn parse_cache_stats() -> Option<CacheStats> {
+    global_parse_cache().map(|cache| cache.stats())
+}
+
+/// 当前启用的全局解析缓存
+pub fn global_parse_cache() -> Option<Arc<ParseCache>> {
+    GLOBAL_CACHE.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+}
+
+/// 估算 AST 占用的内存：节点本身的大小加上字符串内容的长度
+fn estimate_ast_size(root: &RootNode) -> usize {
+    let mut estimator = SizeEstimator { bytes: std::mem::size_of::<RootNode>() };
+    estimator.visit_root(root);
+    estimator.bytes
+}
+
+struct SizeEstimator {
+    bytes: usize,
+}
+
+impl Visitor for SizeEstimator {
+    fn visit_node(&mut self, node: &ASTNode) {
+        self.bytes += std::mem::size_of::<ASTNode>();
+        self.bytes += match node {
+            ASTNode::Text(TextNode { content })
+            | ASTNode::Code(CodeNode { content })
+            | ASTNode::Mermaid(MermaidNode { content })
+            | ASTNode::Math(MathNode { content, .. }) => content.len(),
+            ASTNode::CodeBlock(code_block) => {
+                code_block.content.len() + code_block.language.as_ref().map_or(0, String::len)
+            }
+            ASTNode::Link(link) => link.url.len(),
+            ASTNode::Image(image) => image.url.len() + image.alt.as_ref().map_or(0, String::len),
+            ASTNode::Card(card) => {
+                card.subtype.len()
+                    + card.content.len()
+                    + card.metadata.iter().map(|(key, value)| key.len() + value.to_string().len()).sum::<usize>()
+                    + card.header.as_ref().map_or(0, |header| header.title.len())
+                    + card.fields.iter().map(|field| field.label.len() + field.value.len()).sum::<usize>()
+                    + card.images.iter().map(|image| image.url.len()).sum::<usize>()
+                    + card.actions.iter()
+                        .map(|action| std::mem::size_of::<CardAction>() + action.id.len() + action.label.len())
+                        .sum::<usize>()
+            }
+            ASTNode::Mention(mention) => mention.id.len() + mention.name.len(),
+            ASTNode::Timestamp(timestamp) => timestamp.style.as_ref().map_or(0, String::len),
+            ASTNode::CustomEmoji(emoji) => emoji.id.len() + emoji.name.len(),
+            ASTNode::Custom(custom) => {
+                custom.kind.len()
+                    + custom.attrs.iter().map(|(key, value)| key.len() + value.to_string().len()).sum::<usize>()
+            }
+            ASTNode::FootnoteReference(FootnoteReferenceNode { label, .. })
+            | ASTNode::FootnoteDefinition(FootnoteDefinitionNode { label, .. }) => label.len(),
+            ASTNode::Unknown(unknown) => unknown.raw.to_string().len(),
+            _ => 0,
+        };
+        walk_node(self, node);
+    }
+
+    fn visit_list_item(&mut self, item: &ListItemNode) {
+        self.bytes += std::mem::size_of::<ListItemNode>();
+        walk_list_item(self, item);
+    }
+
+    fn visit_table_row(&mut self, row: &TableRow) {
+        self.bytes += std::mem::size_of::<TableRow>();
+        walk_table_row(self, row);
+    }
+
+    fn visit_table_cell(&mut self, cell: &TableCell) {
+        self.bytes += std::mem::size_of::<TableCell>();
+        walk_table_cell(self, cell);
+    }
+}
@@ -0,0 +1,601 @@
+use crate::ast::*;
+use crate::validation::{ValidationIssue, ValidationIssueKind};
+use crate::ParseError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// 卡片字段的值类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardFieldType {
+    String,
+    /// 整数
+    Integer,
+    /// 整数或小数
+    Number,
+    Boolean,
+    List,
+    Object,
+    /// 不限制类型
+    Any,
+}
+
+impl CardFieldType {
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            CardFieldType::String => value.is_string(),
+            CardFieldType::Integer => value.is_i64() || value.is_u64(),
+            CardFieldType::Number => value.is_number(),
+            CardFieldType::Boolean => value.is_boolean(),
+            CardFieldType::List => value.is_array(),
+            CardFieldType::Object => value.is_object(),
+            CardFieldType::Any => true,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            CardFieldType::String => "string",
+            CardFieldType::Integer => "integer",
+            CardFieldType::Number => "number",
+            CardFieldType::Boolean => "boolean",
+            CardFieldType::List => "list",
+            CardFieldType::Object => "object",
+            CardFieldType::Any => "any",
+        }
+    }
+}
+
+/// 卡片字段定义
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CardFieldSchema {
+    #[serde(rename = "type")]
+    pub field_type: CardFieldType,
+    #[serde(default)]
+    pub required: bool,
+    /// 字符串字段允许的取值，为空表示不限制
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed: Vec<String>,
+}
+
+/// 卡片子类型的 schema，描述 `metadata` 中的字段
+///
+/// JSON 格式：`{"fields": {"amount": {"type": "number", "required": true}}, "allow_unknown_fields": false}`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CardSchema {
+    pub fields: BTreeMap<String, CardFieldSchema>,
+    /// 是否允许 schema 中未声明的字段
+    pub allow_unknown_fields: bool,
+}
+
+impl CardSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加可选字段
+    pub fn with_field(mut self, name: impl Into<String>, field_type: CardFieldType) -> Self {
+        self.fields.insert(name.into(), CardFieldSchema { field_type, required: false, allowed: Vec::new() });
+        self
+    }
+
+    /// 添加必填字段
+    pub fn with_required_field(mut self, name: impl Into<String>, field_type: CardFieldType) -> Self {
+        self.fields.insert(name.into(), CardFieldSchema { field_type, required: true, allowed: Vec::new() });
+        self
+    }
+
+    /// 添加取值受限的字符串字段
+    pub fn with_enum_field(mut self, name: impl Into<String>, allowed: &[&str], required: bool) -> Self {
+        let allowed = allowed.iter().map(|value| value.to_string()).collect();
+        self.fields.insert(name.into(), CardFieldSchema { field_type: CardFieldType::String, required, allowed });
+        self
+    }
+
+    /// 设置是否允许未声明的字段
+    pub fn with_unknown_fields(mut self, allowed: bool) -> Self {
+        self.allow_unknown_fields = allowed;
+        self
+    }
+}
+
+/// 按子类型注册的卡片 schema
+///
+/// 未注册的子类型不做校验。JSON 格式为子类型到 [`CardSchema`] 的映射：`{"approval": {"fields": {...}}}`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CardSchemaRegistry {
+    schemas: BTreeMap<String, CardSchema>,
+}
+
+impl CardSchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ParseError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// 注册子类型的 schema，已存在时替换
+    pub fn register(&mut self, subtype: impl Into<String>, schema: CardSchema) {
+        self.schemas.insert(subtype.into(), schema);
+    }
+
+    pub fn with_schema(mut self, subtype: impl Into<String>, schema: CardSchema) -> Self {
+        self.register(subtype, schema);
+        self
+    }
+
+    pub fn get(&self, subtype: &str) -> Option<&CardSchema> {
+        self.schemas.get(subtype)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+    }
+
+    /// 校验单个卡片，`path` 是卡片节点的 JSON Pointer 路径
+    pub fn check_card(&self, card: &CardNode, path: &str) -> Vec<ValidationIssue> {
+        let Some(schema) = self.schemas.get(&card.subtype) else {
+            return Vec::new();
+        };
+
+        let mut issues = Vec::new();
+        let mut report = |key: &str, message: String| {
+            issues.push(ValidationIssue {
+                path: format!("{}/metadata/{}", path, escape_pointer(key)),
+                kind: ValidationIssueKind::InvalidCard,
+                message,
+            });
+        };
+
+        for (name, field) in &schema.fields {
+            match card.metadata.get(name) {
+                None if field.required => report(name, format!("missing required field `{}`", name)),
+                None => {}
+                Some(value) if !field.field_type.matches(value) => report(
+                    name,
+                    format!("field `{}` must be {}, found {}", name, field.field_type.name(), value_type_name(value)),
+                ),
+                Some(Value::String(value)) if !field.allowed.is_empty() && !field.allowed.contains(value) => report(
+                    name,
+                    format!("field `{}` must be one of {}, found `{}`", name, field.allowed.join(", "), value),
+                ),
+                Some(_) => {}
+            }
+        }
+
+        if !schema.allow_unknown_fields {
+            let mut unknown: Vec<&String> = card.metadata.keys().filter(|key| !schema.fields.contains_key(*key)).collect();
+            unknown.sort();
+            for key in unknown {
+                report(key, format!("unknown field `{}` for `{}` card", key, card.subtype));
+            }
+        }
+        issues
+    }
+
+    /// 校验 AST 中的全部卡片，返回的路径与 [`validate`](crate::validate) 相同
+    pub fn validate(&self, root: &RootNode) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        if !self.is_empty() {
+            self.check_children(&root.children, "/children", &mut issues);
+        }
+        issues
+    }
+
+    fn check_children(&self, children: &[ASTNode], path: &str, issues: &mut Vec<ValidationIssue>) {
+        for (index, child) in children.iter().enumerate() {
+            let child_path = format!("{}/{}", path, index);
+            match child {
+                ASTNode::Card(card) => issues.extend(self.check_card(card, &child_path)),
+                ASTNode::Root(RootNode { children, .. })
+                | ASTNode::Blockquote(BlockquoteNode { children })
+                | ASTNode::FootnoteDefinition(FootnoteDefinitionNode { children, .. })
+                | ASTNode::Spoiler(SpoilerNode { children }) => {
+                    self.check_children(children, &format!("{}/children", child_path), issues);
+                }
+                ASTNode::List(list) => {
+                    for (item_index, item) in list.items.iter().enumerate() {
+                        let item_path = format!("{}/items/{}/children", child_path, item_index);
+                        self.check_children(&item.children, &item_path, issues);
+                    }
+                }
+                ASTNode::ListItem(item) => {
+                    self.check_children(&item.children, &format!("{}/children", child_path), issues);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// 卡片语法未能生成卡片的原因，由 [`MarkdownParser::validate_cards`](crate::MarkdownParser::validate_cards) 返回
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CardIssue {
+    /// 卡片开始行（围栏行或 `:::card` 行）的行号，从 1 开始
+    pub line: usize,
+    /// 卡片子类型，容器属性格式错误时为空
+    pub subtype: String,
+    /// 问题所在位置相对于卡片节点的 JSON Pointer 路径，如 `/metadata/amount`、`/actions/1`；正文或属性整体格式错误时为空
+    pub path: String,
+    pub message: String,
+}
+
+/// 解析围栏卡片（```` ```card:approval ````）的正文
+///
+/// 正文为 JSON 对象或 YAML 子集。`content` 字段（字符串）作为卡片正文，`header`、`fields`、`images`、`actions`
+/// 作为结构化内容，其余字段作为 metadata。格式错误时返回指向出错位置的问题。
+///
+/// 结构化内容支持简写：`header` 可以是标题字符串，图片可以是 URL 字符串，下拉框选项可以是同时作为标签和值的字符串。
+pub(crate) fn parse_fenced_card(subtype: &str, body: &str) -> Result<CardNode, ValidationIssue> {
+    let mut metadata: HashMap<String, Value> = if body.trim_start().starts_with('{') {
+        serde_json::from_str::<Map<String, Value>>(body)
+            .map_err(|e| invalid_card("", format!("invalid JSON card body: {}", e)))?
+            .into_iter()
+            .collect()
+    } else {
+        parse_yaml(body).ok_or_else(|| invalid_card("", "card body is neither a JSON object nor a supported YAML mapping"))?
+    };
+    let content = match metadata.remove("content") {
+        Some(Value::String(content)) => content,
+        Some(other) => {
+            metadata.insert("content".to_string(), other);
+            String::new()
+        }
+        None => String::new(),
+    };
+
+    let header = match metadata.remove("header") {
+        Some(Value::String(title)) => Some(CardHeader { title, ..CardHeader::default() }),
+        Some(header) => {
+            Some(serde_json::from_value(header).map_err(|e| invalid_card("/header", format!("invalid `header`: {}", e)))?)
+        }
+        None => None,
+    };
+    let images = expand_list(metadata.remove("images"), "images", expand_image)?;
+    let actions = expand_list(metadata.remove("actions"), "actions", expand_action)?;
+    let fields = expand_list(metadata.remove("fields"), "fields", expand_field)?;
+
+    Ok(CardNode { subtype: subtype.to_string(), content, metadata, header, fields, images, actions })
+}
+
+/// 把卡片的问题（路径相对于卡片节点）标上卡片所在的行和子类型
+pub(crate) fn issues_at_line(line: usize, subtype: &str, issues: Vec<ValidationIssue>) -> Vec<CardIssue> {
+    issues
+        .into_iter()
+        .map(|issue| CardIssue { line, subtype: subtype.to_string(), path: issue.path, message: issue.message })
+        .collect()
+}
+
+/// 卡片问题，`path` 相对于卡片节点
+pub(crate) fn invalid_card(path: impl Into<String>, message: impl Into<String>) -> ValidationIssue {
+    ValidationIssue { path: path.into(), kind: ValidationIssueKind::InvalidCard, message: message.into() }
+}
+
+/// 展开结构化内容列表，`key` 的值不是列表或其中某项格式错误时返回指向该项的问题
+fn expand_list<T>(value: Option<Value>, key: &str, expand: fn(Value) -> Option<T>) -> Result<Vec<T>, ValidationIssue> {
+    match value {
+        Some(Value::Array(items)) => items
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| {
+                expand(item).ok_or_else(|| invalid_card(format!("/{}/{}", key, index), format!("invalid item in `{}`", key)))
+            })
+            .collect(),
+        Some(other) => Err(invalid_card(
+            format!("/{}", key),
+            format!("`{}` must be a list, found {}", key, value_type_name(&other)),
+        )),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn expand_image(image: Value) -> Option<CardImage> {
+    match image {
+        Value::String(url) => Some(CardImage { url, alt: None }),
+        image => serde_json::from_value(image).ok(),
+    }
+}
+
+fn expand_field(mut field: Value) -> Option<CardField> {
+    scalars_to_strings(&mut field, &["label", "value"]);
+    serde_json::from_value(field).ok()
+}
+
+fn expand_action(mut action: Value) -> Option<CardAction> {
+    scalars_to_strings(&mut action, &["id", "label", "value", "placeholder"]);
+    if let Some(Value::Array(options)) = action.get_mut("options") {
+        for option in options.iter_mut() {
+            if !option.is_object() {
+                let value = scalar_string(option)?;
+                *option = serde_json::json!({ "label": value, "value": value });
+            }
+            scalars_to_strings(option, &["label", "value"]);
+        }
+    }
+    serde_json::from_value(action).ok()
+}
+
+/// YAML 中的 `value: 120` 会推断为数字，结构化内容中的文本字段统一转为字符串
+fn scalars_to_strings(object: &mut Value, keys: &[&str]) {
+    for key in keys {
+        if let Some(value) = object.get_mut(*key) {
+            if let Some(text) = scalar_string(value) {
+                *value = Value::String(text);
+            }
+        }
+    }
+}
+
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+/// 解析容器卡片开始行中的属性，如 `{type=approval amount=120 title="团队聚餐"}`
+///
+/// `type` 属性作为子类型，其余属性按标量规则推断类型后作为 metadata。缺少 `type` 或格式错误时返回 `None`。
+pub(crate) fn parse_card_attributes(attributes: &str) -> Option<(String, HashMap<String, Value>)> {
+    let inner = attributes.trim().strip_prefix('{')?.strip_suffix('}')?;
+    let mut metadata = HashMap::new();
+    let mut rest = inner.trim_start();
+    while !rest.is_empty() {
+        let (key, after_key) = rest.split_once('=')?;
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return None;
+        }
+        let (raw, after_value) = if after_key.starts_with(['"', '\'']) {
+            let quote = after_key.chars().next()?;
+            let end = after_key[1..].find(quote)? + 2;
+            after_key.split_at(end)
+        } else {
+            after_key.split_at(after_key.find(char::is_whitespace).unwrap_or(after_key.len()))
+        };
+        metadata.insert(key.to_string(), parse_scalar(raw)?);
+        rest = after_value.trim_start();
+    }
+
+    match metadata.remove("type")? {
+        Value::String(subtype) if !subtype.is_empty() => Some((subtype, metadata)),
+        _ => None,
+    }
+}
+
+/// 解析 YAML 子集：顶层 `key: value`，值可以是标量、`[a, b]`、缩进的 `- item` 列表、
+/// 缩进的 `key: value` 对象或 `|` 多行文本。不支持的写法返回 `None`。
+fn parse_yaml(body: &str) -> Option<HashMap<String, Value>> {
+    let lines: Vec<&str> = body.lines().collect();
+    let mut result = HashMap::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index];
+        index += 1;
+        if is_blank_or_comment(line) {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let (key, value) = split_key(line)?;
+
+        // 收集属于该键的缩进行
+        let start = index;
+        while index < lines.len() && (lines[index].trim().is_empty() || lines[index].starts_with(char::is_whitespace)) {
+            index += 1;
+        }
+        let block = &lines[start..index];
+
+        let value = match value {
+            "|" => Value::String(literal_block(block)),
+            "" => nested_block(block)?,
+            scalar if block.iter().all(|line| is_blank_or_comment(line)) => parse_scalar(scalar)?,
+            _ => return None,
+        };
+        result.insert(key.to_string(), value);
+    }
+    Some(result)
+}
+
+fn is_blank_or_comment(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+/// 拆分 `key: value`，返回去掉首尾空白的键和值
+fn split_key(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let key = key.trim();
+    if key.is_empty() || !(value.is_empty() || value.starts_with(char::is_whitespace)) {
+        return None;
+    }
+    Some((key, value.trim()))
+}
+
+/// `|` 多行文本：去掉公共缩进，保留换行
+fn literal_block(block: &[&str]) -> String {
+    let indent = block.iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indent_of(line))
+        .min()
+        .unwrap_or(0);
+    let lines: Vec<&str> = block.iter().map(|line| line.get(indent..).unwrap_or("")).collect();
+    lines.join("\n").trim_end().to_string()
+}
+
+/// 缩进的 `- item` 列表或 `key: value` 对象，空块为 `null`
+///
+/// 列表项可以是标量，也可以是 `- key: value` 开头、后续行缩进对齐的对象。
+fn nested_block(block: &[&str]) -> Option<Value> {
+    let lines: Vec<&str> = block.iter().copied().filter(|line| !is_blank_or_comment(line)).collect();
+    let Some(first) = lines.first() else {
+        return Some(Value::Null);
+    };
+    if !is_list_item(first.trim()) {
+        let mut object = Map::new();
+        for line in lines {
+            let (key, value) = split_key(line.trim())?;
+            object.insert(key.to_string(), parse_scalar(value)?);
+        }
+        return Some(Value::Object(object));
+    }
+
+    let indent = indent_of(first);
+    let mut items: Vec<Vec<&str>> = Vec::new();
+    for line in lines {
+        if indent_of(line) == indent && is_list_item(line.trim()) {
+            items.push(vec![line.trim()[1..].trim()]);
+        } else if indent_of(line) > indent {
+            items.last_mut()?.push(line.trim());
+        } else {
+            return None;
+        }
+    }
+    items.into_iter().map(|item| list_item(&item)).collect::<Option<Vec<_>>>().map(Value::Array)
+}
+
+/// 列表项：单行标量，或由 `key: value` 行组成的对象
+fn list_item(lines: &[&str]) -> Option<Value> {
+    match lines {
+        [scalar] if scalar.starts_with(['"', '\'', '[']) || split_key(scalar).is_none() => parse_scalar(scalar),
+        _ => {
+            let mut object = Map::new();
+            for line in lines {
+                let (key, value) = split_key(line)?;
+                object.insert(key.to_string(), parse_scalar(value)?);
+            }
+            Some(Value::Object(object))
+        }
+    }
+}
+
+fn is_list_item(trimmed: &str) -> bool {
+    trimmed == "-" || trimmed.starts_with("- ")
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// 解析标量：引号字符串、布尔值、`null`、数字、`[a, b]` 列表，其余为字符串
+fn parse_scalar(raw: &str) -> Option<Value> {
+    let raw = strip_comment(raw.trim());
+    if raw.starts_with('"') {
+        return serde_json::from_str::<String>(raw).ok().map(Value::String);
+    }
+    if let Some(inner) = raw.strip_prefix('\'') {
+        return inner.strip_suffix('\'').map(|inner| Value::String(inner.replace("''", "'")));
+    }
+    if let Some(inner) = raw.strip_prefix('[') {
+        let inner = inner.strip_suffix(']')?.trim();
+        if inner.is_empty() {
+            return Some(Value::Array(Vec::new()));
+        }
+        return split_flow_items(inner).into_iter().map(parse_scalar).collect::<Option<Vec<_>>>().map(Value::Array);
+    }
+
+    Some(match raw {
+        "" | "~" | "null" => Value::Null,
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => {
+            if let Ok(integer) = raw.parse::<i64>() {
+                Value::Number(integer.into())
+            } else if let Some(number) = raw.parse::<f64>().ok().filter(|_| looks_numeric(raw)).and_then(Number::from_f64) {
+                Value::Number(number)
+            } else {
+                Value::String(raw.to_string())
+            }
+        }
+    })
+}
+
+/// 按不在引号或嵌套列表中的逗号拆分 `[a, b]` 列表的内容
+///
+/// 引号只在元素开头生效，`[it's, b]` 中的 `'` 是普通字符；未闭合的引号使其后的内容都属于同一个元素。
+fn split_flow_items(inner: &str) -> Vec<&str> {
+    let bytes = inner.as_bytes();
+    let mut items = Vec::new();
+    let mut quote: Option<u8> = None;
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        match (quote, bytes[index]) {
+            (Some(b'"'), b'\\') => index += 1,
+            (Some(open), byte) if byte == open => quote = None,
+            (Some(_), _) => {}
+            (None, byte @ (b'"' | b'\'')) if inner[start..index].trim().is_empty() => quote = Some(byte),
+            (None, b'[') => depth += 1,
+            (None, b']') => depth = depth.saturating_sub(1),
+            (None, b',') if depth == 0 => {
+                items.push(&inner[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    items.push(&inner[start..]);
+    items
+}
+
+/// 去掉值末尾的 ` # 注释`（引号内的 `#` 不算）
+fn strip_comment(raw: &str) -> &str {
+    let quote = match raw.chars().next() {
+        Some(quote @ ('"' | '\'')) => quote,
+        _ => {
+            return match raw.find(" #") {
+                Some(index) => raw[..index].trim_end(),
+                None => raw,
+            }
+        }
+    };
+
+    // 找到结束引号：双引号字符串跳过 `\` 转义，单引号字符串中 `''` 表示一个引号
+    let bytes = raw.as_bytes();
+    let mut index = 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' if quote == '"' => index += 1,
+            b'\'' if quote == '\'' && bytes.get(index + 1) == Some(&b'\'') => index += 1,
+            byte if byte == quote as u8 => {
+                let rest = raw[index + 1..].trim_start();
+                return if rest.is_empty() || rest.starts_with('#') { &raw[..=index] } else { raw };
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    raw
+}
+
+/// 排除 `inf`、`NaN` 等 Rust 能解析但 YAML 中应视为字符串的写法
+fn looks_numeric(raw: &str) -> bool {
+    raw.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+}
+
+fn value_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "list",
+        Value::Object(_) => "object",
+    }
+}
+
+/// JSON Pointer 路径段转义
+fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
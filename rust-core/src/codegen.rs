@@ -0,0 +1,687 @@
+use crate::ast::AST_SCHEMA_VERSION;
+use crate::schema::ast_json_schema;
+use serde_json::Value;
+use std::fmt::Write;
+
+/// 平台类型代码生成器
+///
+/// 以 [`ast_json_schema`] 生成的 JSON Schema 为输入（其中已包含 serde 重命名和可选字段信息），
+/// 输出 TypeScript 可辨识联合、Swift `Codable` 枚举和 Kotlin `@Serializable` 密封类，
+/// 避免各平台手工同步 `ast.rs` 的定义。
+pub struct AstCodegen {
+    model: AstModel,
+}
+
+impl AstCodegen {
+    pub fn new() -> Self {
+        Self {
+            model: AstModel::from_schema(&ast_json_schema()),
+        }
+    }
+
+    /// 生成 TypeScript 类型定义（`web/src/types.ts`）
+    pub fn typescript(&self) -> String {
+        let model = &self.model;
+        let mut out = String::new();
+        out.push_str(&generated_header("//", "typescript", "../web/src/types.ts"));
+        let _ = writeln!(out, "export const AST_SCHEMA_VERSION = {};\n", AST_SCHEMA_VERSION);
+
+        out.push_str("export type ASTNode =\n");
+        for (index, variant) in model.variants.iter().enumerate() {
+            let end = if index + 1 == model.variants.len() { ";" } else { "" };
+            let _ = writeln!(out, "  | {}{}", variant.struct_name, end);
+        }
+        out.push('\n');
+
+        out.push_str("/** 未知节点（前向兼容），保留原始 JSON 的全部字段 */\n");
+        out.push_str("export interface UnknownNode {\n  type: string;\n  [key: string]: unknown;\n}\n\n");
+        out.push_str("/** 可能包含未知节点类型的 AST 节点 */\n");
+        out.push_str("export type AnyASTNode = ASTNode | UnknownNode;\n");
+
+        for def in model.ordered_defs() {
+            out.push('\n');
+            if let Some(description) = &def.description {
+                let _ = writeln!(out, "/** {} */", first_line(description));
+            }
+            match &def.kind {
+                TypeKind::StringEnum(values) => {
+                    let values: Vec<String> = values.iter().map(|v| format!("'{}'", v)).collect();
+                    let _ = writeln!(out, "export type {} = {};", def.name, values.join(" | "));
+                }
+                TypeKind::Struct(fields) => {
+                    let _ = writeln!(out, "export interface {} {{", def.name);
+                    if let Some(variant) = model.variant_for(&def.name) {
+                        let optional = if model.is_untagged_use(&def.name) { "?" } else { "" };
+                        let _ = writeln!(out, "  type{}: '{}';", optional, variant.tag);
+                    }
+                    for field in fields {
+                        let optional = if field.required { "" } else { "?" };
+                        let _ = writeln!(out, "  {}{}: {};", field.name, optional, ts_type(&field.ty));
+                    }
+                    out.push_str("}\n");
+                }
+            }
+        }
+
+        out
+    }
+
+    /// 生成 Swift `Codable` 模型（命名空间 `IMParseAST`）
+    pub fn swift(&self) -> String {
+        let model = &self.model;
+        let mut out = String::new();
+        out.push_str(&generated_header(
+            "//",
+            "swift",
+            "../ios/IMParseSDK/IMParseSDK/Classes/Models/IMParseAST.swift",
+        ));
+        out.push_str("import Foundation\n\n");
+        out.push_str("/// 与 Rust `ast.rs` 一致的 AST 数据模型\n");
+        out.push_str("public enum IMParseAST {\n");
+        let _ = writeln!(out, "    public static let schemaVersion = {}\n", AST_SCHEMA_VERSION);
+
+        // 节点枚举
+        out.push_str("    /// AST 节点类型\n");
+        out.push_str("    public indirect enum Node: Codable, Equatable {\n");
+        for variant in &model.variants {
+            let _ = writeln!(out, "        case {}({})", swift_ident(&variant.tag), variant.struct_name);
+        }
+        out.push_str("        /// 未知节点（前向兼容），保留原始 JSON\n");
+        out.push_str("        case unknown(JSONValue)\n\n");
+        out.push_str("        private enum CodingKeys: String, CodingKey {\n            case type\n        }\n\n");
+        out.push_str("        public init(from decoder: Decoder) throws {\n");
+        out.push_str("            let container = try decoder.container(keyedBy: CodingKeys.self)\n");
+        out.push_str("            switch try container.decodeIfPresent(String.self, forKey: .type) {\n");
+        for variant in &model.variants {
+            let _ = writeln!(
+                out,
+                "            case \"{}\":\n                self = .{}(try {}(from: decoder))",
+                variant.tag,
+                swift_ident(&variant.tag),
+                variant.struct_name
+            );
+        }
+        out.push_str("            default:\n                self = .unknown(try JSONValue(from: decoder))\n");
+        out.push_str("            }\n        }\n\n");
+        out.push_str("        public func encode(to encoder: Encoder) throws {\n");
+        out.push_str("            switch self {\n");
+        for variant in &model.variants {
+            let _ = writeln!(
+                out,
+                "            case .{}(let node):\n                var container = encoder.container(keyedBy: CodingKeys.self)\n                try container.encode(\"{}\", forKey: .type)\n                try node.encode(to: encoder)",
+                swift_ident(&variant.tag),
+                variant.tag
+            );
+        }
+        out.push_str("            case .unknown(let raw):\n                try raw.encode(to: encoder)\n");
+        out.push_str("            }\n        }\n    }\n");
+
+        for def in model.ordered_defs() {
+            out.push('\n');
+            if let Some(description) = &def.description {
+                let _ = writeln!(out, "    /// {}", first_line(description));
+            }
+            match &def.kind {
+                TypeKind::StringEnum(values) => {
+                    let _ = writeln!(out, "    public enum {}: String, Codable, Equatable {{", def.name);
+                    for value in values {
+                        let case_name = swift_ident(&lower_camel(value));
+                        if case_name == *value {
+                            let _ = writeln!(out, "        case {}", case_name);
+                        } else {
+                            let _ = writeln!(out, "        case {} = \"{}\"", case_name, value);
+                        }
+                    }
+                    out.push_str("    }\n");
+                }
+                TypeKind::Struct(fields) => {
+                    let _ = writeln!(out, "    public struct {}: Codable, Equatable {{", def.name);
+                    for field in fields {
+                        let _ = writeln!(
+                            out,
+                            "        public var {}: {}",
+                            swift_ident(&field.name),
+                            swift_field_type(field)
+                        );
+                    }
+                    let params: Vec<String> = fields
+                        .iter()
+                        .map(|field| {
+                            let default = if swift_field_type(field).ends_with('?') { " = nil" } else { "" };
+                            format!("{}: {}{}", swift_ident(&field.name), swift_field_type(field), default)
+                        })
+                        .collect();
+                    let _ = writeln!(out, "\n        public init({}) {{", params.join(", "));
+                    for field in fields {
+                        let _ = writeln!(out, "            self.{0} = {0}", swift_ident(&field.name));
+                    }
+                    out.push_str("        }\n    }\n");
+                }
+            }
+        }
+
+        out.push_str(SWIFT_JSON_VALUE);
+        out.push_str("}\n");
+        out
+    }
+
+    /// 生成 Kotlin `kotlinx.serialization` 模型（`android/ASTModels.kt`）
+    pub fn kotlin(&self) -> String {
+        let model = &self.model;
+        let mut out = String::new();
+        out.push_str(&generated_header("//", "kotlin", "../android/ASTModels.kt"));
+        out.push_str("package com.imparse.renderer\n\n");
+        out.push_str("import kotlinx.serialization.KSerializer\n");
+        out.push_str("import kotlinx.serialization.SerialName\n");
+        out.push_str("import kotlinx.serialization.Serializable\n");
+        out.push_str("import kotlinx.serialization.descriptors.SerialDescriptor\n");
+        out.push_str("import kotlinx.serialization.encoding.Decoder\n");
+        out.push_str("import kotlinx.serialization.encoding.Encoder\n");
+        out.push_str("import kotlinx.serialization.json.Json\n");
+        out.push_str("import kotlinx.serialization.json.JsonDecoder\n");
+        out.push_str("import kotlinx.serialization.json.JsonElement\n");
+        out.push_str("import kotlinx.serialization.json.JsonEncoder\n");
+        out.push_str("import kotlinx.serialization.json.JsonObject\n");
+        out.push_str("import kotlinx.serialization.json.jsonObject\n");
+        out.push_str("import kotlinx.serialization.modules.SerializersModule\n");
+        out.push_str("import kotlinx.serialization.modules.polymorphic\n\n");
+        let _ = writeln!(out, "const val AST_SCHEMA_VERSION = {}\n", AST_SCHEMA_VERSION);
+
+        out.push_str("/** AST 节点类型 */\n@Serializable\nsealed class ASTNode\n");
+
+        for def in model.ordered_defs() {
+            out.push('\n');
+            if let Some(description) = &def.description {
+                let _ = writeln!(out, "/** {} */", first_line(description));
+            }
+            match &def.kind {
+                TypeKind::StringEnum(values) => {
+                    out.push_str("@Serializable\n");
+                    let _ = writeln!(out, "enum class {} {{", def.name);
+                    let entries: Vec<String> = values
+                        .iter()
+                        .map(|value| format!("    @SerialName(\"{}\") {}", value, upper_camel(value)))
+                        .collect();
+                    out.push_str(&entries.join(",\n"));
+                    out.push_str("\n}\n");
+                }
+                TypeKind::Struct(fields) => {
+                    out.push_str("@Serializable\n");
+                    let variant = model.variant_for(&def.name);
+                    if let Some(variant) = variant {
+                        let _ = writeln!(out, "@SerialName(\"{}\")", variant.tag);
+                    }
+                    let parent = if variant.is_some() { " : ASTNode()" } else { "" };
+                    if fields.is_empty() {
+                        let _ = writeln!(out, "object {}{}", def.name, parent);
+                        continue;
+                    }
+                    let _ = writeln!(out, "data class {}(", def.name);
+                    let params: Vec<String> = fields
+                        .iter()
+                        .map(|field| format!("    val {}: {}", kotlin_ident(&field.name), kotlin_field_type(field)))
+                        .collect();
+                    out.push_str(&params.join(",\n"));
+                    let _ = writeln!(out, "\n){}", parent);
+                }
+            }
+        }
+
+        out.push_str(KOTLIN_UNKNOWN_NODE);
+        out
+    }
+}
+
+impl Default for AstCodegen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn generated_header(comment: &str, target: &str, path: &str) -> String {
+    format!(
+        "{0} 此文件由 rust-core 的 ast-codegen 根据 ast.rs 自动生成，请勿手动修改\n\
+         {0} 重新生成：cd rust-core && cargo run --features schema --bin ast-codegen -- {1} > {2}\n\n",
+        comment, target, path
+    )
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default().trim()
+}
+
+/// 字段类型
+#[derive(Debug, Clone, PartialEq)]
+enum FieldType {
+    String,
+    Integer,
+    Float,
+    Boolean,
+    Any,
+    Ref(String),
+    Array(Box<FieldType>),
+    Map(Box<FieldType>),
+    Nullable(Box<FieldType>),
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    /// JSON 字段名（已应用 serde 重命名）
+    name: String,
+    ty: FieldType,
+    required: bool,
+    default: Option<Value>,
+}
+
+#[derive(Debug, Clone)]
+enum TypeKind {
+    Struct(Vec<Field>),
+    StringEnum(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+struct TypeDef {
+    name: String,
+    description: Option<String>,
+    kind: TypeKind,
+}
+
+/// `ASTNode` 的一个变体
+#[derive(Debug, Clone)]
+struct Variant {
+    /// `type` 字段的值
+    tag: String,
+    struct_name: String,
+}
+
+/// 从 JSON Schema 提取的 AST 类型模型
+struct AstModel {
+    variants: Vec<Variant>,
+    defs: Vec<TypeDef>,
+}
+
+impl AstModel {
+    fn from_schema(schema: &Value) -> Self {
+        let root_name = schema.get("title").and_then(Value::as_str).unwrap_or("RootNode").to_string();
+        let empty = serde_json::Map::new();
+        let schema_defs = schema.get("$defs").and_then(Value::as_object).unwrap_or(&empty);
+
+        let mut defs = vec![parse_def(&root_name, schema)];
+        for (name, def) in schema_defs {
+            if name == "ASTNode" || name == "UnknownNode" {
+                continue;
+            }
+            defs.push(parse_def(name, def));
+        }
+
+        let mut variants = Vec::new();
+        let variant_schemas = schema_defs
+            .get("ASTNode")
+            .and_then(|node| node.get("anyOf").or_else(|| node.get("oneOf")))
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        for variant in &variant_schemas {
+            let Some(tag) = variant.pointer("/properties/type/const").and_then(Value::as_str) else {
+                // 未知节点变体没有固定的 type
+                continue;
+            };
+            let struct_name = match variant.get("$ref").and_then(Value::as_str) {
+                Some("#") => root_name.clone(),
+                Some(reference) => ref_name(reference),
+                None => {
+                    // 单元结构体（如水平分割线）内联在变体中，按约定命名
+                    let name = format!("{}Node", upper_camel(tag));
+                    if !defs.iter().any(|def| def.name == name) {
+                        defs.push(TypeDef {
+                            name: name.clone(),
+                            description: variant.get("description").and_then(Value::as_str).map(str::to_string),
+                            kind: TypeKind::Struct(Vec::new()),
+                        });
+                    }
+                    name
+                }
+            };
+            variants.push(Variant {
+                tag: tag.to_string(),
+                struct_name,
+            });
+        }
+
+        Self { variants, defs }
+    }
+
+    fn variant_for(&self, struct_name: &str) -> Option<&Variant> {
+        self.variants.iter().find(|variant| variant.struct_name == struct_name)
+    }
+
+    /// 该结构体是否会在没有 `type` 字段的位置出现（根节点或被其他结构体字段直接引用）
+    fn is_untagged_use(&self, struct_name: &str) -> bool {
+        if self.defs.first().is_some_and(|def| def.name == struct_name) {
+            return true;
+        }
+        self.defs.iter().any(|def| match &def.kind {
+            TypeKind::Struct(fields) => fields.iter().any(|field| references(&field.ty, struct_name)),
+            TypeKind::StringEnum(_) => false,
+        })
+    }
+
+    /// 输出顺序：按 `ASTNode` 变体顺序排列节点结构体，其余类型按名称排列
+    fn ordered_defs(&self) -> Vec<&TypeDef> {
+        let mut ordered: Vec<&TypeDef> = self
+            .variants
+            .iter()
+            .filter_map(|variant| self.defs.iter().find(|def| def.name == variant.struct_name))
+            .collect();
+        let mut rest: Vec<&TypeDef> = self
+            .defs
+            .iter()
+            .filter(|def| self.variant_for(&def.name).is_none())
+            .collect();
+        rest.sort_by(|a, b| a.name.cmp(&b.name));
+        ordered.extend(rest);
+        ordered
+    }
+}
+
+fn references(ty: &FieldType, name: &str) -> bool {
+    match ty {
+        FieldType::Ref(reference) => reference == name,
+        FieldType::Array(inner) | FieldType::Map(inner) | FieldType::Nullable(inner) => references(inner, name),
+        _ => false,
+    }
+}
+
+fn ref_name(reference: &str) -> String {
+    reference.rsplit('/').next().unwrap_or(reference).to_string()
+}
+
+fn parse_def(name: &str, def: &Value) -> TypeDef {
+    let description = def.get("description").and_then(Value::as_str).map(str::to_string);
+
+    if let Some(values) = string_enum_values(def) {
+        return TypeDef {
+            name: name.to_string(),
+            description,
+            kind: TypeKind::StringEnum(values),
+        };
+    }
+
+    let required: Vec<&str> = def
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|values| values.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let fields = def
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|properties| {
+            properties
+                .iter()
+                .map(|(field_name, field)| Field {
+                    name: field_name.clone(),
+                    ty: parse_field_type(field),
+                    required: required.contains(&field_name.as_str()),
+                    default: field.get("default").cloned(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    TypeDef {
+        name: name.to_string(),
+        description,
+        kind: TypeKind::Struct(fields),
+    }
+}
+
+/// 字符串枚举的取值
+///
+/// 变体带文档注释时 schemars 生成 `oneOf`，每项是 `enum` 或 `const`，需要合并。
+fn string_enum_values(def: &Value) -> Option<Vec<String>> {
+    if let Some(values) = def.get("enum").and_then(Value::as_array) {
+        return Some(values.iter().filter_map(Value::as_str).map(str::to_string).collect());
+    }
+
+    let options = def.get("oneOf").and_then(Value::as_array)?;
+    let mut values = Vec::new();
+    for option in options {
+        if let Some(value) = option.get("const").and_then(Value::as_str) {
+            values.push(value.to_string());
+        } else {
+            let option_values = option.get("enum").and_then(Value::as_array)?;
+            values.extend(option_values.iter().filter_map(Value::as_str).map(str::to_string));
+        }
+    }
+    Some(values)
+}
+
+fn parse_field_type(schema: &Value) -> FieldType {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return FieldType::Ref(ref_name(reference));
+    }
+
+    if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+        let non_null: Vec<&Value> = options
+            .iter()
+            .filter(|option| option.get("type").and_then(Value::as_str) != Some("null"))
+            .collect();
+        return match non_null.as_slice() {
+            [single] if non_null.len() < options.len() => FieldType::Nullable(Box::new(parse_field_type(single))),
+            [single] => parse_field_type(single),
+            _ => FieldType::Any,
+        };
+    }
+
+    let (type_name, nullable) = match schema.get("type") {
+        Some(Value::String(type_name)) => (type_name.as_str(), false),
+        Some(Value::Array(types)) => {
+            let nullable = types.iter().any(|t| t == "null");
+            let type_name = types.iter().filter_map(Value::as_str).find(|t| *t != "null").unwrap_or("null");
+            (type_name, nullable)
+        }
+        _ => return FieldType::Any,
+    };
+
+    let ty = match type_name {
+        "string" => FieldType::String,
+        "integer" => FieldType::Integer,
+        "number" => FieldType::Float,
+        "boolean" => FieldType::Boolean,
+        "array" => FieldType::Array(Box::new(schema.get("items").map(parse_field_type).unwrap_or(FieldType::Any))),
+        "object" => match schema.get("additionalProperties") {
+            Some(value) if value.is_object() => FieldType::Map(Box::new(parse_field_type(value))),
+            // `serde_json::Value` 等任意类型的值
+            Some(Value::Bool(true)) => FieldType::Map(Box::new(FieldType::Any)),
+            _ => FieldType::Any,
+        },
+        _ => FieldType::Any,
+    };
+
+    if nullable {
+        FieldType::Nullable(Box::new(ty))
+    } else {
+        ty
+    }
+}
+
+fn ts_type(ty: &FieldType) -> String {
+    match ty {
+        FieldType::String => "string".to_string(),
+        FieldType::Integer | FieldType::Float => "number".to_string(),
+        FieldType::Boolean => "boolean".to_string(),
+        FieldType::Any => "unknown".to_string(),
+        FieldType::Ref(name) => name.clone(),
+        FieldType::Array(inner) => match inner.as_ref() {
+            FieldType::Nullable(_) => format!("({})[]", ts_type(inner)),
+            _ => format!("{}[]", ts_type(inner)),
+        },
+        FieldType::Map(inner) => format!("Record<string, {}>", ts_type(inner)),
+        FieldType::Nullable(inner) => format!("{} | null", ts_type(inner)),
+    }
+}
+
+fn swift_type(ty: &FieldType) -> String {
+    match ty {
+        FieldType::String => "String".to_string(),
+        FieldType::Integer => "Int".to_string(),
+        FieldType::Float => "Double".to_string(),
+        FieldType::Boolean => "Bool".to_string(),
+        FieldType::Any => "JSONValue".to_string(),
+        FieldType::Ref(name) if name == "ASTNode" => "Node".to_string(),
+        FieldType::Ref(name) => name.clone(),
+        FieldType::Array(inner) => format!("[{}]", swift_type(inner)),
+        FieldType::Map(inner) => format!("[String: {}]", swift_type(inner)),
+        FieldType::Nullable(inner) => format!("{}?", swift_type(inner)),
+    }
+}
+
+fn swift_field_type(field: &Field) -> String {
+    let ty = swift_type(&field.ty);
+    if field.required || ty.ends_with('?') {
+        ty
+    } else {
+        format!("{}?", ty)
+    }
+}
+
+fn kotlin_type(ty: &FieldType) -> String {
+    match ty {
+        FieldType::String => "String".to_string(),
+        FieldType::Integer => "Int".to_string(),
+        FieldType::Float => "Float".to_string(),
+        FieldType::Boolean => "Boolean".to_string(),
+        FieldType::Any => "JsonElement".to_string(),
+        FieldType::Ref(name) => name.clone(),
+        FieldType::Array(inner) => format!("List<{}>", kotlin_type(inner)),
+        FieldType::Map(inner) => format!("Map<String, {}>", kotlin_type(inner)),
+        FieldType::Nullable(inner) => format!("{}?", kotlin_type(inner)),
+    }
+}
+
+fn kotlin_field_type(field: &Field) -> String {
+    let ty = kotlin_type(&field.ty);
+    if ty.ends_with('?') {
+        return format!("{} = null", ty);
+    }
+    match (&field.default, field.required) {
+        // 枚举字段的默认值是枚举项
+        (Some(Value::String(default)), _) if matches!(field.ty, FieldType::Ref(_)) => {
+            format!("{} = {}.{}", ty, ty, upper_camel(default))
+        }
+        (Some(Value::Array(_)), _) => format!("{} = emptyList()", ty),
+        (Some(Value::Object(_)), _) => format!("{} = emptyMap()", ty),
+        (Some(default), _) => format!("{} = {}", ty, default),
+        (None, true) => ty,
+        (None, false) => format!("{}? = null", ty),
+    }
+}
+
+fn upper_camel(value: &str) -> String {
+    value
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn lower_camel(value: &str) -> String {
+    let upper = upper_camel(value);
+    let mut chars = upper.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn swift_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &["default", "case", "class", "enum", "func", "import", "let", "var", "in", "is", "self", "struct", "protocol", "extension", "internal", "public", "private", "return", "static", "switch", "where", "while", "for", "if", "else", "repeat", "do", "try", "throw", "throws", "operator", "init", "deinit", "subscript", "typealias", "associatedtype", "inout", "nil", "true", "false"];
+    if KEYWORDS.contains(&name) {
+        format!("`{}`", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn kotlin_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &["as", "break", "class", "continue", "do", "else", "false", "for", "fun", "if", "in", "interface", "is", "null", "object", "package", "return", "super", "this", "throw", "true", "try", "typealias", "typeof", "val", "var", "when", "while"];
+    if KEYWORDS.contains(&name) {
+        format!("`{}`", name)
+    } else {
+        name.to_string()
+    }
+}
+
+const SWIFT_JSON_VALUE: &str = r#"
+    /// 任意 JSON 值，用于未知节点和无固定结构的字段
+    public enum JSONValue: Codable, Equatable {
+        case null
+        case bool(Bool)
+        case number(Double)
+        case string(String)
+        case array([JSONValue])
+        case object([String: JSONValue])
+
+        public init(from decoder: Decoder) throws {
+            let container = try decoder.singleValueContainer()
+            if container.decodeNil() {
+                self = .null
+            } else if let value = try? container.decode(Bool.self) {
+                self = .bool(value)
+            } else if let value = try? container.decode(Double.self) {
+                self = .number(value)
+            } else if let value = try? container.decode(String.self) {
+                self = .string(value)
+            } else if let value = try? container.decode([JSONValue].self) {
+                self = .array(value)
+            } else {
+                self = .object(try container.decode([String: JSONValue].self))
+            }
+        }
+
+        public func encode(to encoder: Encoder) throws {
+            var container = encoder.singleValueContainer()
+            switch self {
+            case .null: try container.encodeNil()
+            case .bool(let value): try container.encode(value)
+            case .number(let value): try container.encode(value)
+            case .string(let value): try container.encode(value)
+            case .array(let value): try container.encode(value)
+            case .object(let value): try container.encode(value)
+            }
+        }
+    }
+"#;
+
+const KOTLIN_UNKNOWN_NODE: &str = r#"
+/** 未知节点（前向兼容），保留原始 JSON */
+@Serializable(with = UnknownNodeSerializer::class)
+data class UnknownNode(val raw: JsonObject) : ASTNode()
+
+object UnknownNodeSerializer : KSerializer<UnknownNode> {
+    override val descriptor: SerialDescriptor = JsonObject.serializer().descriptor
+
+    override fun deserialize(decoder: Decoder): UnknownNode =
+        UnknownNode((decoder as JsonDecoder).decodeJsonElement().jsonObject)
+
+    override fun serialize(encoder: Encoder, value: UnknownNode) =
+        (encoder as JsonEncoder).encodeJsonElement(value.raw)
+}
+
+/** 解析 AST JSON 使用的配置：以 `type` 区分节点，未知类型回退为 [UnknownNode] */
+val astJson = Json {
+    classDiscriminator = "type"
+    ignoreUnknownKeys = true
+    serializersModule = SerializersModule {
+        polymorphic(ASTNode::class) {
+            defaultDeserializer { UnknownNode.serializer() }
+        }
+    }
+}
+"#;
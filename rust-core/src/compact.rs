@@ -0,0 +1,1199 @@
+//! 紧凑 AST 编码
+//!
+//! 面向消息缓存的二进制布局：所有节点平铺在定长节点表中，字符串集中存放在共享缓冲区，
+//! 节点通过下标引用子节点和字符串。读取时无需反序列化，[`CompactAst`] 直接在字节切片上
+//! 提供借用视图，遍历过程不分配内存。
+//!
+//! 布局（小端序）：
+//!
+//! ```text
+//! header   24 字节  magic "IMPC" | format_version u16 | reserved u16 | schema_version u32
+//!                   | node_count u32 | string_count u32 | string_bytes u32
+//! nodes    node_count × 20 字节  kind u8 | flags u8 | aux u16 | slots [u32; 4]
+//! strings  string_count × 8 字节  offset u32 | len u32（相对 data 起始位置）
+//! data     string_bytes 字节  UTF-8 字符串
+//! ```
+//!
+//! 节点按广度优先顺序排列，0 号节点为根节点；每个容器节点的子节点在节点表中连续存放，
+//! `slots[0]` 为第一个子节点下标，`slots[1]` 为子节点数量。其余字段的含义见 `NodeKind` 的文档。
+
+use crate::ast::*;
+use std::collections::HashMap;
+
+/// 紧凑编码格式版本
+pub const COMPACT_FORMAT_VERSION: u16 = 2;
+
+const MAGIC: &[u8; 4] = b"IMPC";
+const HEADER_LEN: usize = 24;
+const NODE_LEN: usize = 20;
+const STRING_ENTRY_LEN: usize = 8;
+/// 可选字符串缺省时的占位下标
+const NONE: u32 = u32::MAX;
+/// 最大嵌套深度，超过时拒绝解码，避免递归转换时栈溢出
+const MAX_DEPTH: u32 = 128;
+
+/// 紧凑编码错误
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CompactError {
+    #[error("not a compact AST buffer")]
+    BadMagic,
+    #[error("unsupported compact format version {0}")]
+    UnsupportedVersion(u16),
+    #[error("compact AST buffer is truncated")]
+    Truncated,
+    #[error("invalid node {index}: {reason}")]
+    InvalidNode { index: u32, reason: &'static str },
+    #[error("invalid string {0}")]
+    InvalidString(u32),
+}
+
+/// 节点类型编码
+///
+/// | 类型 | flags | aux | slots |
+/// | --- | --- | --- | --- |
+/// | Root | | | 子节点, `[2]` schemaVersion |
+/// | Heading | | 级别 | 子节点 |
+/// | Link | | | 子节点, `[2]` url |
+/// | List | 1 = 有序 | | 子节点（ListItem） |
+/// | ListItem | 0 = 无, 1 = 未完成, 2 = 已完成 | | 子节点 |
+/// | Table / TableRow | | | 子节点（TableRow / TableCell） |
+/// | TableCell | 0 = 无, 1 = 左, 2 = 中, 3 = 右 | | 子节点 |
+/// | Text / Code / Mermaid | | | `[0]` content |
+/// | CodeBlock | | | `[0]` content, `[1]` language |
+/// | Image | bit0 有 width, bit1 有 height | | `[0]` url, `[1]` alt, `[2]` width, `[3]` height（f32 位模式） |
+/// | Math | 1 = 块级 | | `[0]` content |
+/// | Card | | | `[0]` subtype, `[1]` content, `[2]` 首个 metadata 字符串, `[3]` 键值对数量 |
+/// | Mention | 0 = 用户, 1 = 频道, 2 = all, 3 = here, 4 = 角色 | 0 = 未解析, 1 = 有效, 2 = 已停用, 3 = 未知 | `[0]` id, `[1]` name |
+/// | Highlight | | | 子节点 |
+/// | Spoiler | | | 子节点 |
+/// | Custom | | | 子节点, `[2]` kind, `[3]` attrs（JSON 文本，没有属性时为空字符串） |
+/// | Timestamp | | | `[0]` 时间戳低 32 位, `[1]` 高 32 位, `[2]` style |
+/// | CustomEmoji | 1 = 动态 | | `[0]` id, `[1]` name |
+/// | FootnoteReference | | | `[0]` label, `[2]` 编号 |
+/// | FootnoteDefinition | | | 子节点, `[2]` label, `[3]` 编号 |
+/// | Unknown | | | `[0]` 原始 JSON |
+///
+/// 其余容器节点只使用子节点字段。字符串字段均为字符串表下标，可选字符串缺省时为 `u32::MAX`；
+/// Card 的 metadata 按键排序，以键、值（JSON 文本）交替的方式连续存放在字符串表中，紧随其后的一项是
+/// 结构化部分（header、fields、images、actions）的 JSON 文本，没有结构化部分时为空字符串。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum NodeKind {
+    Root = 0,
+    Paragraph = 1,
+    Heading = 2,
+    Text = 3,
+    Strong = 4,
+    Em = 5,
+    Underline = 6,
+    Strike = 7,
+    Code = 8,
+    CodeBlock = 9,
+    Link = 10,
+    Image = 11,
+    List = 12,
+    ListItem = 13,
+    Table = 14,
+    TableRow = 15,
+    TableCell = 16,
+    Math = 17,
+    Mermaid = 18,
+    Card = 19,
+    Mention = 20,
+    HorizontalRule = 21,
+    Blockquote = 22,
+    FootnoteReference = 23,
+    FootnoteDefinition = 24,
+    Highlight = 25,
+    Spoiler = 26,
+    Custom = 27,
+    Timestamp = 28,
+    CustomEmoji = 29,
+    Unknown = 255,
+}
+
+impl NodeKind {
+    fn from_u8(value: u8) -> Option<Self> {
+        let kind = match value {
+            0 => NodeKind::Root,
+            1 => NodeKind::Paragraph,
+            2 => NodeKind::Heading,
+            3 => NodeKind::Text,
+            4 => NodeKind::Strong,
+            5 => NodeKind::Em,
+            6 => NodeKind::Underline,
+            7 => NodeKind::Strike,
+            8 => NodeKind::Code,
+            9 => NodeKind::CodeBlock,
+            10 => NodeKind::Link,
+            11 => NodeKind::Image,
+            12 => NodeKind::List,
+            13 => NodeKind::ListItem,
+            14 => NodeKind::Table,
+            15 => NodeKind::TableRow,
+            16 => NodeKind::TableCell,
+            17 => NodeKind::Math,
+            18 => NodeKind::Mermaid,
+            19 => NodeKind::Card,
+            20 => NodeKind::Mention,
+            21 => NodeKind::HorizontalRule,
+            22 => NodeKind::Blockquote,
+            23 => NodeKind::FootnoteReference,
+            24 => NodeKind::FootnoteDefinition,
+            25 => NodeKind::Highlight,
+            26 => NodeKind::Spoiler,
+            27 => NodeKind::Custom,
+            28 => NodeKind::Timestamp,
+            29 => NodeKind::CustomEmoji,
+            255 => NodeKind::Unknown,
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    fn has_children(self) -> bool {
+        matches!(
+            self,
+            NodeKind::Root
+                | NodeKind::Paragraph
+                | NodeKind::Heading
+                | NodeKind::Strong
+                | NodeKind::Em
+                | NodeKind::Underline
+                | NodeKind::Strike
+                | NodeKind::Link
+                | NodeKind::List
+                | NodeKind::ListItem
+                | NodeKind::Table
+                | NodeKind::TableRow
+                | NodeKind::TableCell
+                | NodeKind::Blockquote
+                | NodeKind::FootnoteDefinition
+                | NodeKind::Highlight
+                | NodeKind::Spoiler
+                | NodeKind::Custom
+        )
+    }
+
+    /// 子节点必须是的类型（列表项、表格行、单元格只能出现在对应容器中）
+    fn required_child_kind(self) -> Option<NodeKind> {
+        match self {
+            NodeKind::List => Some(NodeKind::ListItem),
+            NodeKind::Table => Some(NodeKind::TableRow),
+            NodeKind::TableRow => Some(NodeKind::TableCell),
+            _ => None,
+        }
+    }
+}
+
+/// 将 AST 编码为紧凑格式
+pub fn encode_compact(root: &RootNode) -> Vec<u8> {
+    CompactEncoder::default().encode(root)
+}
+
+/// 从紧凑格式解码为 AST
+pub fn decode_compact(bytes: &[u8]) -> Result<RootNode, CompactError> {
+    CompactAst::from_bytes(bytes)?.to_root_node()
+}
+
+/// 编码时待写入的节点
+#[derive(Clone, Copy)]
+enum PendingNode<'r> {
+    Root(&'r RootNode),
+    Node(&'r ASTNode),
+    ListItem(&'r ListItemNode),
+    TableRow(&'r TableRow),
+    TableCell(&'r TableCell),
+}
+
+struct NodeRecord {
+    kind: NodeKind,
+    flags: u8,
+    aux: u16,
+    slots: [u32; 4],
+}
+
+#[derive(Default)]
+struct CompactEncoder<'r> {
+    nodes: Vec<NodeRecord>,
+    strings: Vec<(u32, u32)>,
+    data: String,
+    interned: HashMap<&'r str, u32>,
+}
+
+impl<'r> CompactEncoder<'r> {
+    fn encode(mut self, root: &'r RootNode) -> Vec<u8> {
+        // 广度优先：处理第 i 个节点时把它的子节点追加到队尾，节点下标即队列下标
+        let mut queue = vec![PendingNode::Root(root)];
+        let mut index = 0;
+        while index < queue.len() {
+            let record = self.encode_node(queue[index], &mut queue);
+            self.nodes.push(record);
+            index += 1;
+        }
+
+        let mut bytes = Vec::with_capacity(
+            HEADER_LEN + self.nodes.len() * NODE_LEN + self.strings.len() * STRING_ENTRY_LEN + self.data.len(),
+        );
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&COMPACT_FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&0u16.to_le_bytes());
+        bytes.extend_from_slice(&root.schema_version.to_le_bytes());
+        bytes.extend_from_slice(&(self.nodes.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.strings.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+        for node in &self.nodes {
+            bytes.push(node.kind as u8);
+            bytes.push(node.flags);
+            bytes.extend_from_slice(&node.aux.to_le_bytes());
+            for slot in node.slots {
+                bytes.extend_from_slice(&slot.to_le_bytes());
+            }
+        }
+        for (offset, len) in &self.strings {
+            bytes.extend_from_slice(&offset.to_le_bytes());
+            bytes.extend_from_slice(&len.to_le_bytes());
+        }
+        bytes.extend_from_slice(self.data.as_bytes());
+        bytes
+    }
+
+    fn encode_node(&mut self, node: PendingNode<'r>, queue: &mut Vec<PendingNode<'r>>) -> NodeRecord {
+        let mut record = NodeRecord {
+            kind: NodeKind::Unknown,
+            flags: 0,
+            aux: 0,
+            slots: [0; 4],
+        };
+
+        let mut enqueue = |record: &mut NodeRecord, children: &mut dyn Iterator<Item = PendingNode<'r>>| {
+            record.slots[0] = queue.len() as u32;
+            queue.extend(children);
+            record.slots[1] = queue.len() as u32 - record.slots[0];
+        };
+
+        let node = match node {
+            PendingNode::Root(root) => {
+                record.kind = NodeKind::Root;
+                record.slots[2] = root.schema_version;
+                enqueue(&mut record, &mut root.children.iter().map(PendingNode::Node));
+                return record;
+            }
+            PendingNode::ListItem(item) => {
+                record.kind = NodeKind::ListItem;
+                record.flags = match item.checked {
+                    None => 0,
+                    Some(false) => 1,
+                    Some(true) => 2,
+                };
+                enqueue(&mut record, &mut item.children.iter().map(PendingNode::Node));
+                return record;
+            }
+            PendingNode::TableRow(row) => {
+                record.kind = NodeKind::TableRow;
+                enqueue(&mut record, &mut row.cells.iter().map(PendingNode::TableCell));
+                return record;
+            }
+            PendingNode::TableCell(cell) => {
+                record.kind = NodeKind::TableCell;
+                record.flags = match cell.align {
+                    None => 0,
+                    Some(TextAlign::Left) => 1,
+                    Some(TextAlign::Center) => 2,
+                    Some(TextAlign::Right) => 3,
+                };
+                enqueue(&mut record, &mut cell.children.iter().map(PendingNode::Node));
+                return record;
+            }
+            PendingNode::Node(node) => node,
+        };
+
+        match node {
+            ASTNode::Root(root) => return self.encode_node(PendingNode::Root(root), queue),
+            ASTNode::ListItem(item) => return self.encode_node(PendingNode::ListItem(item), queue),
+            ASTNode::TableRow(row) => return self.encode_node(PendingNode::TableRow(row), queue),
+            ASTNode::TableCell(cell) => return self.encode_node(PendingNode::TableCell(cell), queue),
+            ASTNode::Paragraph(ParagraphNode { children }) => {
+                record.kind = NodeKind::Paragraph;
+                enqueue(&mut record, &mut children.iter().map(PendingNode::Node));
+            }
+            ASTNode::Heading(heading) => {
+                record.kind = NodeKind::Heading;
+                record.aux = heading.level as u16;
+                enqueue(&mut record, &mut heading.children.iter().map(PendingNode::Node));
+            }
+            ASTNode::Strong(StrongNode { children }) => {
+                record.kind = NodeKind::Strong;
+                enqueue(&mut record, &mut children.iter().map(PendingNode::Node));
+            }
+            ASTNode::Em(EmNode { children }) => {
+                record.kind = NodeKind::Em;
+                enqueue(&mut record, &mut children.iter().map(PendingNode::Node));
+            }
+            ASTNode::Underline(UnderlineNode { children }) => {
+                record.kind = NodeKind::Underline;
+                enqueue(&mut record, &mut children.iter().map(PendingNode::Node));
+            }
+            ASTNode::Strike(StrikeNode { children }) => {
+                record.kind = NodeKind::Strike;
+                enqueue(&mut record, &mut children.iter().map(PendingNode::Node));
+            }
+            ASTNode::Blockquote(BlockquoteNode { children }) => {
+                record.kind = NodeKind::Blockquote;
+                enqueue(&mut record, &mut children.iter().map(PendingNode::Node));
+            }
+            ASTNode::Highlight(HighlightNode { children }) => {
+                record.kind = NodeKind::Highlight;
+                enqueue(&mut record, &mut children.iter().map(PendingNode::Node));
+            }
+            ASTNode::Spoiler(SpoilerNode { children }) => {
+                record.kind = NodeKind::Spoiler;
+                enqueue(&mut record, &mut children.iter().map(PendingNode::Node));
+            }
+            ASTNode::Custom(custom) => {
+                record.kind = NodeKind::Custom;
+                enqueue(&mut record, &mut custom.children.iter().map(PendingNode::Node));
+                record.slots[2] = self.intern(&custom.kind);
+                record.slots[3] = if custom.attrs.is_empty() {
+                    self.intern("")
+                } else {
+                    // 按键排序，相同的属性总是得到相同的编码
+                    let sorted: std::collections::BTreeMap<_, _> = custom.attrs.iter().collect();
+                    self.push_string(&serde_json::to_string(&sorted).unwrap_or_default())
+                };
+            }
+            ASTNode::Timestamp(timestamp) => {
+                record.kind = NodeKind::Timestamp;
+                record.slots[0] = timestamp.timestamp as u32;
+                record.slots[1] = (timestamp.timestamp >> 32) as u32;
+                record.slots[2] = self.intern_optional(timestamp.style.as_deref());
+            }
+            ASTNode::CustomEmoji(emoji) => {
+                record.kind = NodeKind::CustomEmoji;
+                record.flags = emoji.animated as u8;
+                record.slots[0] = self.intern(&emoji.id);
+                record.slots[1] = self.intern(&emoji.name);
+            }
+            ASTNode::Link(link) => {
+                record.kind = NodeKind::Link;
+                enqueue(&mut record, &mut link.children.iter().map(PendingNode::Node));
+                record.slots[2] = self.intern(&link.url);
+            }
+            ASTNode::List(list) => {
+                record.kind = NodeKind::List;
+                record.flags = match list.list_type {
+                    ListType::Bullet => 0,
+                    ListType::Ordered => 1,
+                };
+                enqueue(&mut record, &mut list.items.iter().map(PendingNode::ListItem));
+            }
+            ASTNode::Table(table) => {
+                record.kind = NodeKind::Table;
+                enqueue(&mut record, &mut table.rows.iter().map(PendingNode::TableRow));
+            }
+            ASTNode::Text(TextNode { content }) => {
+                record.kind = NodeKind::Text;
+                record.slots[0] = self.intern(content);
+            }
+            ASTNode::Code(CodeNode { content }) => {
+                record.kind = NodeKind::Code;
+                record.slots[0] = self.intern(content);
+            }
+            ASTNode::Mermaid(MermaidNode { content }) => {
+                record.kind = NodeKind::Mermaid;
+                record.slots[0] = self.intern(content);
+            }
+            ASTNode::CodeBlock(code_block) => {
+                record.kind = NodeKind::CodeBlock;
+                record.slots[0] = self.intern(&code_block.content);
+                record.slots[1] = self.intern_optional(code_block.language.as_deref());
+            }
+            ASTNode::Image(image) => {
+                record.kind = NodeKind::Image;
+                record.slots[0] = self.intern(&image.url);
+                record.slots[1] = self.intern_optional(image.alt.as_deref());
+                if let Some(width) = image.width {
+                    record.flags |= 0b01;
+                    record.slots[2] = width.to_bits();
+                }
+                if let Some(height) = image.height {
+                    record.flags |= 0b10;
+                    record.slots[3] = height.to_bits();
+                }
+            }
+            ASTNode::Math(math) => {
+                record.kind = NodeKind::Math;
+                record.flags = math.display as u8;
+                record.slots[0] = self.intern(&math.content);
+            }
+            ASTNode::Card(card) => {
+                record.kind = NodeKind::Card;
+                record.slots[0] = self.intern(&card.subtype);
+                record.slots[1] = self.intern(&card.content);
+                let mut metadata: Vec<_> = card.metadata.iter().collect();
+                metadata.sort_by(|a, b| a.0.cmp(b.0));
+                record.slots[2] = self.strings.len() as u32;
+                record.slots[3] = metadata.len() as u32;
+                for (key, value) in metadata {
+                    self.push_string(key);
+                    self.push_string(&value.to_string());
+                }
+                self.push_string(&CardParts::encode(card));
+            }
+            ASTNode::Mention(mention) => {
+                record.kind = NodeKind::Mention;
+                record.flags = match mention.kind {
+                    MentionKind::User => 0,
+                    MentionKind::Channel => 1,
+                    MentionKind::All => 2,
+                    MentionKind::Here => 3,
+                    MentionKind::Role => 4,
+                };
+                record.aux = match mention.status {
+                    MentionStatus::Unresolved => 0,
+                    MentionStatus::Active => 1,
+                    MentionStatus::Deactivated => 2,
+                    MentionStatus::Unknown => 3,
+                };
+                record.slots[0] = self.intern(&mention.id);
+                record.slots[1] = self.intern(&mention.name);
+            }
+            ASTNode::HorizontalRule(_) => {
+                record.kind = NodeKind::HorizontalRule;
+            }
+            ASTNode::FootnoteReference(reference) => {
+                record.kind = NodeKind::FootnoteReference;
+                record.slots[0] = self.intern(&reference.label);
+                record.slots[2] = reference.index;
+            }
+            ASTNode::FootnoteDefinition(definition) => {
+                record.kind = NodeKind::FootnoteDefinition;
+                enqueue(&mut record, &mut definition.children.iter().map(PendingNode::Node));
+                record.slots[2] = self.intern(&definition.label);
+                record.slots[3] = definition.index;
+            }
+            ASTNode::Unknown(unknown) => {
+                record.kind = NodeKind::Unknown;
+                record.slots[0] = self.push_string(&unknown.raw.to_string());
+            }
+        }
+
+        record
+    }
+
+    /// 写入字符串（相同内容只存一份）
+    fn intern(&mut self, value: &'r str) -> u32 {
+        if let Some(&index) = self.interned.get(value) {
+            return index;
+        }
+        let index = self.push_string(value);
+        self.interned.insert(value, index);
+        index
+    }
+
+    fn intern_optional(&mut self, value: Option<&'r str>) -> u32 {
+        value.map_or(NONE, |value| self.intern(value))
+    }
+
+    /// 在字符串表末尾追加一项（不去重，用于需要连续存放的字符串）
+    fn push_string(&mut self, value: &str) -> u32 {
+        let index = self.strings.len() as u32;
+        self.strings.push((self.data.len() as u32, value.len() as u32));
+        self.data.push_str(value);
+        index
+    }
+}
+
+/// 卡片的结构化部分，在紧凑编码中整体存为一项 JSON 文本
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct CardParts {
+    #[serde(default)]
+    header: Option<CardHeader>,
+    #[serde(default)]
+    fields: Vec<CardField>,
+    #[serde(default)]
+    images: Vec<CardImage>,
+    #[serde(default)]
+    actions: Vec<CardAction>,
+}
+
+impl CardParts {
+    fn encode(card: &CardNode) -> String {
+        if card.header.is_none() && card.fields.is_empty() && card.images.is_empty() && card.actions.is_empty() {
+            return String::new();
+        }
+        let parts = CardParts {
+            header: card.header.clone(),
+            fields: card.fields.clone(),
+            images: card.images.clone(),
+            actions: card.actions.clone(),
+        };
+        serde_json::to_string(&parts).unwrap_or_default()
+    }
+
+    fn decode(json: &str) -> Option<Self> {
+        if json.is_empty() {
+            return Some(Self::default());
+        }
+        serde_json::from_str(json).ok()
+    }
+}
+
+/// 紧凑编码 AST 的只读视图
+///
+/// 通过 [`CompactAst::from_bytes`] 创建时会一次性校验整个缓冲区（边界、UTF-8、树结构），
+/// 之后的所有访问都直接借用原始字节。
+#[derive(Debug, Clone, Copy)]
+pub struct CompactAst<'a> {
+    bytes: &'a [u8],
+    schema_version: u32,
+    node_count: u32,
+    string_count: u32,
+}
+
+impl<'a> CompactAst<'a> {
+    /// 校验并包装紧凑编码的字节
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, CompactError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(if bytes.starts_with(MAGIC) || MAGIC.starts_with(bytes) {
+                CompactError::Truncated
+            } else {
+                CompactError::BadMagic
+            });
+        }
+        if &bytes[0..4] != MAGIC {
+            return Err(CompactError::BadMagic);
+        }
+        let version = read_u16(bytes, 4);
+        if version != COMPACT_FORMAT_VERSION {
+            return Err(CompactError::UnsupportedVersion(version));
+        }
+
+        let ast = CompactAst {
+            bytes,
+            schema_version: read_u32(bytes, 8),
+            node_count: read_u32(bytes, 12),
+            string_count: read_u32(bytes, 16),
+        };
+        let string_bytes = read_u32(bytes, 20) as usize;
+        let expected_len = (ast.node_count as usize)
+            .checked_mul(NODE_LEN)
+            .and_then(|len| len.checked_add(ast.string_count as usize * STRING_ENTRY_LEN))
+            .and_then(|len| len.checked_add(HEADER_LEN + string_bytes))
+            .ok_or(CompactError::Truncated)?;
+        if bytes.len() != expected_len {
+            return Err(CompactError::Truncated);
+        }
+
+        ast.validate_strings(string_bytes)?;
+        ast.validate_nodes()?;
+        Ok(ast)
+    }
+
+    /// 根节点的 `schemaVersion`
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// 节点总数（包括根节点）
+    pub fn node_count(&self) -> usize {
+        self.node_count as usize
+    }
+
+    pub fn root(&self) -> CompactNode<'a> {
+        CompactNode { ast: *self, index: 0 }
+    }
+
+    /// 转换为 [`RootNode`]
+    pub fn to_root_node(&self) -> Result<RootNode, CompactError> {
+        Ok(RootNode {
+            schema_version: self.schema_version,
+            children: self.root().children().map(|child| child.to_ast_node()).collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn nodes_offset(&self) -> usize {
+        HEADER_LEN
+    }
+
+    fn strings_offset(&self) -> usize {
+        self.nodes_offset() + self.node_count as usize * NODE_LEN
+    }
+
+    fn data_offset(&self) -> usize {
+        self.strings_offset() + self.string_count as usize * STRING_ENTRY_LEN
+    }
+
+    fn record(&self, index: u32) -> (u8, u8, u16, [u32; 4]) {
+        let offset = self.nodes_offset() + index as usize * NODE_LEN;
+        let bytes = self.bytes;
+        (
+            bytes[offset],
+            bytes[offset + 1],
+            read_u16(bytes, offset + 2),
+            [
+                read_u32(bytes, offset + 4),
+                read_u32(bytes, offset + 8),
+                read_u32(bytes, offset + 12),
+                read_u32(bytes, offset + 16),
+            ],
+        )
+    }
+
+    fn string_range(&self, index: u32) -> (usize, usize) {
+        let entry = self.strings_offset() + index as usize * STRING_ENTRY_LEN;
+        let start = self.data_offset() + read_u32(self.bytes, entry) as usize;
+        (start, start + read_u32(self.bytes, entry + 4) as usize)
+    }
+
+    fn string(&self, index: u32) -> &'a str {
+        let (start, end) = self.string_range(index);
+        // SAFETY: from_bytes 已校验所有字符串表项都在 data 区内且是合法 UTF-8
+        unsafe { std::str::from_utf8_unchecked(&self.bytes[start..end]) }
+    }
+
+    fn optional_string(&self, index: u32) -> Option<&'a str> {
+        (index != NONE).then(|| self.string(index))
+    }
+
+    fn validate_strings(&self, string_bytes: usize) -> Result<(), CompactError> {
+        for index in 0..self.string_count {
+            let entry = self.strings_offset() + index as usize * STRING_ENTRY_LEN;
+            let offset = read_u32(self.bytes, entry) as usize;
+            let len = read_u32(self.bytes, entry + 4) as usize;
+            if offset.checked_add(len).is_none_or(|end| end > string_bytes) {
+                return Err(CompactError::InvalidString(index));
+            }
+            let (start, end) = self.string_range(index);
+            if std::str::from_utf8(&self.bytes[start..end]).is_err() {
+                return Err(CompactError::InvalidString(index));
+            }
+        }
+        Ok(())
+    }
+
+    /// 校验节点表：类型合法、字符串下标有效、嵌套深度不超过上限，且子节点区间按广度优先顺序首尾相接，
+    /// 保证每个非根节点恰好属于一个父节点（不会出现环或共享子树）
+    fn validate_nodes(&self) -> Result<(), CompactError> {
+        if self.node_count == 0 {
+            return Err(CompactError::InvalidNode { index: 0, reason: "missing root node" });
+        }
+
+        let invalid = |index: u32, reason: &'static str| CompactError::InvalidNode { index, reason };
+        let check_string = |index: u32, string: u32, optional: bool| {
+            if string < self.string_count || (optional && string == NONE) {
+                Ok(())
+            } else {
+                Err(invalid(index, "string index out of range"))
+            }
+        };
+
+        let mut next_child = 1u64;
+        // 广度优先布局下每一层的节点连续存放：当前层结束时，已分配的子节点区间恰好是下一层
+        let mut depth = 0u32;
+        let mut level_end = 1u64;
+        for index in 0..self.node_count {
+            if index as u64 == level_end {
+                depth += 1;
+                level_end = next_child;
+                if depth > MAX_DEPTH {
+                    return Err(invalid(index, "nesting too deep"));
+                }
+            }
+            let (kind, flags, aux, slots) = self.record(index);
+            let kind = NodeKind::from_u8(kind).ok_or_else(|| invalid(index, "unknown node kind"))?;
+            if index == 0 && kind != NodeKind::Root {
+                return Err(invalid(index, "first node must be the root"));
+            }
+
+            if kind.has_children() && slots[1] > 0 {
+                if slots[0] as u64 != next_child {
+                    return Err(invalid(index, "children are not laid out in breadth-first order"));
+                }
+                next_child += slots[1] as u64;
+                if next_child > self.node_count as u64 {
+                    return Err(invalid(index, "children out of range"));
+                }
+                if let Some(required) = kind.required_child_kind() {
+                    for child in slots[0]..slots[0] + slots[1] {
+                        if NodeKind::from_u8(self.record(child).0) != Some(required) {
+                            return Err(invalid(child, "unexpected child kind"));
+                        }
+                    }
+                }
+            }
+
+            match kind {
+                NodeKind::Heading if aux > u8::MAX as u16 => return Err(invalid(index, "heading level out of range")),
+                NodeKind::List if flags > 1 => return Err(invalid(index, "invalid list type")),
+                NodeKind::ListItem if flags > 2 => return Err(invalid(index, "invalid checked state")),
+                NodeKind::TableCell if flags > 3 => return Err(invalid(index, "invalid alignment")),
+                NodeKind::Mention if flags > 4 => return Err(invalid(index, "invalid mention kind")),
+                NodeKind::Mention if aux > 3 => return Err(invalid(index, "invalid mention status")),
+                NodeKind::CustomEmoji if flags > 1 => return Err(invalid(index, "invalid animated flag")),
+                NodeKind::Timestamp => check_string(index, slots[2], true)?,
+                NodeKind::Link | NodeKind::FootnoteDefinition => check_string(index, slots[2], false)?,
+                NodeKind::Custom => {
+                    check_string(index, slots[2], false)?;
+                    check_string(index, slots[3], false)?;
+                }
+                NodeKind::Text
+                | NodeKind::Code
+                | NodeKind::Mermaid
+                | NodeKind::Math
+                | NodeKind::FootnoteReference
+                | NodeKind::Unknown => {
+                    check_string(index, slots[0], false)?
+                }
+                NodeKind::CodeBlock | NodeKind::Image => {
+                    check_string(index, slots[0], false)?;
+                    check_string(index, slots[1], true)?;
+                }
+                NodeKind::Mention | NodeKind::CustomEmoji => {
+                    check_string(index, slots[0], false)?;
+                    check_string(index, slots[1], false)?;
+                }
+                NodeKind::Card => {
+                    check_string(index, slots[0], false)?;
+                    check_string(index, slots[1], false)?;
+                    let end = slots[2] as u64 + slots[3] as u64 * 2 + 1;
+                    if end > self.string_count as u64 {
+                        return Err(invalid(index, "metadata out of range"));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if next_child != self.node_count as u64 {
+            return Err(invalid(0, "unreachable nodes"));
+        }
+        Ok(())
+    }
+}
+
+/// 紧凑编码中的一个节点
+#[derive(Debug, Clone, Copy)]
+pub struct CompactNode<'a> {
+    ast: CompactAst<'a>,
+    index: u32,
+}
+
+impl<'a> CompactNode<'a> {
+    /// 节点在节点表中的下标
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// 节点类型名（与 JSON 中的 `type` 一致，未知节点为 `"unknown"`）
+    pub fn type_name(&self) -> &'static str {
+        match self.kind() {
+            NodeKind::Root => "root",
+            NodeKind::Paragraph => "paragraph",
+            NodeKind::Heading => "heading",
+            NodeKind::Text => "text",
+            NodeKind::Strong => "strong",
+            NodeKind::Em => "em",
+            NodeKind::Underline => "underline",
+            NodeKind::Strike => "strike",
+            NodeKind::Code => "code",
+            NodeKind::CodeBlock => "codeBlock",
+            NodeKind::Link => "link",
+            NodeKind::Image => "image",
+            NodeKind::List => "list",
+            NodeKind::ListItem => "listItem",
+            NodeKind::Table => "table",
+            NodeKind::TableRow => "tableRow",
+            NodeKind::TableCell => "tableCell",
+            NodeKind::Math => "math",
+            NodeKind::Mermaid => "mermaid",
+            NodeKind::Card => "card",
+            NodeKind::Mention => "mention",
+            NodeKind::HorizontalRule => "horizontalRule",
+            NodeKind::Blockquote => "blockquote",
+            NodeKind::FootnoteReference => "footnoteReference",
+            NodeKind::FootnoteDefinition => "footnoteDefinition",
+            NodeKind::Highlight => "highlight",
+            NodeKind::Spoiler => "spoiler",
+            NodeKind::Custom => "custom",
+            NodeKind::Timestamp => "timestamp",
+            NodeKind::CustomEmoji => "customEmoji",
+            NodeKind::Unknown => "unknown",
+        }
+    }
+
+    /// 子节点（列表项、表格行、单元格也通过此方法访问）；叶子节点返回空迭代器
+    pub fn children(&self) -> CompactChildren<'a> {
+        let (_, _, _, slots) = self.ast.record(self.index);
+        if self.kind().has_children() {
+            CompactChildren { ast: self.ast, next: slots[0], end: slots[0] + slots[1] }
+        } else {
+            CompactChildren { ast: self.ast, next: 0, end: 0 }
+        }
+    }
+
+    /// 借用视图
+    pub fn view(&self) -> CompactNodeView<'a> {
+        let ast = self.ast;
+        let (_, flags, aux, slots) = ast.record(self.index);
+        let children = self.children();
+        match self.kind() {
+            NodeKind::Root => CompactNodeView::Root { children },
+            NodeKind::Paragraph => CompactNodeView::Paragraph { children },
+            NodeKind::Heading => CompactNodeView::Heading { level: aux as u8, children },
+            NodeKind::Text => CompactNodeView::Text { content: ast.string(slots[0]) },
+            NodeKind::Strong => CompactNodeView::Strong { children },
+            NodeKind::Em => CompactNodeView::Em { children },
+            NodeKind::Underline => CompactNodeView::Underline { children },
+            NodeKind::Strike => CompactNodeView::Strike { children },
+            NodeKind::Code => CompactNodeView::Code { content: ast.string(slots[0]) },
+            NodeKind::CodeBlock => CompactNodeView::CodeBlock {
+                language: ast.optional_string(slots[1]),
+                content: ast.string(slots[0]),
+            },
+            NodeKind::Link => CompactNodeView::Link { url: ast.string(slots[2]), children },
+            NodeKind::Image => CompactNodeView::Image {
+                url: ast.string(slots[0]),
+                width: (flags & 0b01 != 0).then(|| f32::from_bits(slots[2])),
+                height: (flags & 0b10 != 0).then(|| f32::from_bits(slots[3])),
+                alt: ast.optional_string(slots[1]),
+            },
+            NodeKind::List => CompactNodeView::List {
+                list_type: if flags == 1 { ListType::Ordered } else { ListType::Bullet },
+                items: children,
+            },
+            NodeKind::ListItem => CompactNodeView::ListItem {
+                checked: match flags {
+                    1 => Some(false),
+                    2 => Some(true),
+                    _ => None,
+                },
+                children,
+            },
+            NodeKind::Table => CompactNodeView::Table { rows: children },
+            NodeKind::TableRow => CompactNodeView::TableRow { cells: children },
+            NodeKind::TableCell => CompactNodeView::TableCell {
+                align: match flags {
+                    1 => Some(TextAlign::Left),
+                    2 => Some(TextAlign::Center),
+                    3 => Some(TextAlign::Right),
+                    _ => None,
+                },
+                children,
+            },
+            NodeKind::Math => CompactNodeView::Math {
+                content: ast.string(slots[0]),
+                display: flags != 0,
+            },
+            NodeKind::Mermaid => CompactNodeView::Mermaid { content: ast.string(slots[0]) },
+            NodeKind::Card => CompactNodeView::Card {
+                subtype: ast.string(slots[0]),
+                content: ast.string(slots[1]),
+                metadata: CompactMetadata { ast, next: slots[2], end: slots[2] + slots[3] * 2 },
+                parts_json: ast.string(slots[2] + slots[3] * 2),
+            },
+            NodeKind::Mention => CompactNodeView::Mention {
+                id: ast.string(slots[0]),
+                name: ast.string(slots[1]),
+                kind: match flags {
+                    1 => MentionKind::Channel,
+                    2 => MentionKind::All,
+                    3 => MentionKind::Here,
+                    4 => MentionKind::Role,
+                    _ => MentionKind::User,
+                },
+                status: match aux {
+                    1 => MentionStatus::Active,
+                    2 => MentionStatus::Deactivated,
+                    3 => MentionStatus::Unknown,
+                    _ => MentionStatus::Unresolved,
+                },
+            },
+            NodeKind::HorizontalRule => CompactNodeView::HorizontalRule,
+            NodeKind::Blockquote => CompactNodeView::Blockquote { children },
+            NodeKind::Highlight => CompactNodeView::Highlight { children },
+            NodeKind::Spoiler => CompactNodeView::Spoiler { children },
+            NodeKind::Custom => CompactNodeView::Custom {
+                kind: ast.string(slots[2]),
+                attrs_json: ast.string(slots[3]),
+                children,
+            },
+            NodeKind::Timestamp => CompactNodeView::Timestamp {
+                timestamp: (((slots[1] as u64) << 32) | slots[0] as u64) as i64,
+                style: ast.optional_string(slots[2]),
+            },
+            NodeKind::CustomEmoji => CompactNodeView::CustomEmoji {
+                id: ast.string(slots[0]),
+                name: ast.string(slots[1]),
+                animated: flags != 0,
+            },
+            NodeKind::FootnoteReference => CompactNodeView::FootnoteReference {
+                label: ast.string(slots[0]),
+                index: slots[2],
+            },
+            NodeKind::FootnoteDefinition => CompactNodeView::FootnoteDefinition {
+                label: ast.string(slots[2]),
+                index: slots[3],
+                children,
+            },
+            NodeKind::Unknown => CompactNodeView::Unknown { raw_json: ast.string(slots[0]) },
+        }
+    }
+
+    /// 转换为 [`ASTNode`]
+    pub fn to_ast_node(&self) -> Result<ASTNode, CompactError> {
+        let collect = |children: CompactChildren<'a>| -> Result<Vec<ASTNode>, CompactError> {
+            children.map(|child| child.to_ast_node()).collect()
+        };
+
+        let node = match self.view() {
+            CompactNodeView::Root { children } => ASTNode::Root(RootNode {
+                schema_version: self.ast.record(self.index).3[2],
+                children: collect(children)?,
+            }),
+            CompactNodeView::Paragraph { children } => ASTNode::Paragraph(ParagraphNode { children: collect(children)? }),
+            CompactNodeView::Heading { level, children } => ASTNode::Heading(HeadingNode {
+                level,
+                children: collect(children)?,
+            }),
+            CompactNodeView::Text { content } => ASTNode::Text(TextNode { content: content.to_string() }),
+            CompactNodeView::Strong { children } => ASTNode::Strong(StrongNode { children: collect(children)? }),
+            CompactNodeView::Em { children } => ASTNode::Em(EmNode { children: collect(children)? }),
+            CompactNodeView::Underline { children } => ASTNode::Underline(UnderlineNode { children: collect(children)? }),
+            CompactNodeView::Strike { children } => ASTNode::Strike(StrikeNode { children: collect(children)? }),
+            CompactNodeView::Code { content } => ASTNode::Code(CodeNode { content: content.to_string() }),
+            CompactNodeView::CodeBlock { language, content } => ASTNode::CodeBlock(CodeBlockNode {
+                language: language.map(str::to_string),
+                content: content.to_string(),
+            }),
+            CompactNodeView::Link { url, children } => ASTNode::Link(LinkNode {
+                url: url.to_string(),
+                children: collect(children)?,
+            }),
+            CompactNodeView::Image { url, width, height, alt } => ASTNode::Image(ImageNode {
+                url: url.to_string(),
+                width,
+                height,
+                alt: alt.map(str::to_string),
+            }),
+            CompactNodeView::List { list_type, items } => ASTNode::List(ListNode {
+                list_type,
+                items: items.map(|item| item.to_list_item()).collect::<Result<_, _>>()?,
+            }),
+            CompactNodeView::ListItem { .. } => ASTNode::ListItem(self.to_list_item()?),
+            CompactNodeView::Table { rows } => ASTNode::Table(TableNode {
+                rows: rows.map(|row| row.to_table_row()).collect::<Result<_, _>>()?,
+            }),
+            CompactNodeView::TableRow { .. } => ASTNode::TableRow(self.to_table_row()?),
+            CompactNodeView::TableCell { .. } => ASTNode::TableCell(self.to_table_cell()?),
+            CompactNodeView::Math { content, display } => ASTNode::Math(MathNode {
+                content: content.to_string(),
+                display,
+            }),
+            CompactNodeView::Mermaid { content } => ASTNode::Mermaid(MermaidNode { content: content.to_string() }),
+            CompactNodeView::Card { subtype, content, metadata, parts_json } => {
+                let parts = CardParts::decode(parts_json).ok_or(CompactError::InvalidNode {
+                    index: self.index,
+                    reason: "card parts are not valid JSON",
+                })?;
+                ASTNode::Card(Box::new(CardNode {
+                    subtype: subtype.to_string(),
+                    content: content.to_string(),
+                    metadata: metadata
+                        .map(|(key, value)| {
+                            let value = serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+                            (key.to_string(), value)
+                        })
+                        .collect(),
+                    header: parts.header,
+                    fields: parts.fields,
+                    images: parts.images,
+                    actions: parts.actions,
+                }))
+            }
+            CompactNodeView::Mention { id, name, kind, status } => ASTNode::Mention(MentionNode {
+                id: id.to_string(),
+                name: name.to_string(),
+                kind,
+                status,
+            }),
+            CompactNodeView::HorizontalRule => ASTNode::HorizontalRule(HorizontalRuleNode),
+            CompactNodeView::Blockquote { children } => ASTNode::Blockquote(BlockquoteNode { children: collect(children)? }),
+            CompactNodeView::Highlight { children } => ASTNode::Highlight(HighlightNode { children: collect(children)? }),
+            CompactNodeView::Spoiler { children } => ASTNode::Spoiler(SpoilerNode { children: collect(children)? }),
+            CompactNodeView::Custom { kind, attrs_json, children } => ASTNode::Custom(CustomNode {
+                kind: kind.to_string(),
+                attrs: if attrs_json.is_empty() {
+                    HashMap::new()
+                } else {
+                    serde_json::from_str(attrs_json).map_err(|_| CompactError::InvalidNode {
+                        index: self.index,
+                        reason: "custom node attrs are not valid JSON",
+                    })?
+                },
+                children: collect(children)?,
+            }),
+            CompactNodeView::Timestamp { timestamp, style } => ASTNode::Timestamp(TimestampNode {
+                timestamp,
+                style: style.map(str::to_string),
+            }),
+            CompactNodeView::CustomEmoji { id, name, animated } => ASTNode::CustomEmoji(CustomEmojiNode {
+                id: id.to_string(),
+                name: name.to_string(),
+                animated,
+            }),
+            CompactNodeView::FootnoteReference { label, index } => ASTNode::FootnoteReference(FootnoteReferenceNode {
+                label: label.to_string(),
+                index,
+            }),
+            CompactNodeView::FootnoteDefinition { label, index, children } => {
+                ASTNode::FootnoteDefinition(FootnoteDefinitionNode {
+                    label: label.to_string(),
+                    index,
+                    children: collect(children)?,
+                })
+            }
+            CompactNodeView::Unknown { raw_json } => ASTNode::Unknown(UnknownNode {
+                raw: serde_json::from_str(raw_json).map_err(|_| CompactError::InvalidNode {
+                    index: self.index,
+                    reason: "unknown node is not valid JSON",
+                })?,
+            }),
+        };
+        Ok(node)
+    }
+
+    fn to_list_item(self) -> Result<ListItemNode, CompactError> {
+        match self.view() {
+            CompactNodeView::ListItem { checked, children } => Ok(ListItemNode {
+                children: children.map(|child| child.to_ast_node()).collect::<Result<_, _>>()?,
+                checked,
+            }),
+            _ => Err(CompactError::InvalidNode { index: self.index, reason: "expected listItem" }),
+        }
+    }
+
+    fn to_table_row(self) -> Result<TableRow, CompactError> {
+        Ok(TableRow {
+            cells: self.children().map(|cell| cell.to_table_cell()).collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn to_table_cell(self) -> Result<TableCell, CompactError> {
+        match self.view() {
+            CompactNodeView::TableCell { align, children } => Ok(TableCell {
+                children: children.map(|child| child.to_ast_node()).collect::<Result<_, _>>()?,
+                align,
+            }),
+            _ => Err(CompactError::InvalidNode { index: self.index, reason: "expected tableCell" }),
+        }
+    }
+
+    fn kind(&self) -> NodeKind {
+        // from_bytes 已校验所有节点类型
+        NodeKind::from_u8(self.ast.record(self.index).0).unwrap_or(NodeKind::Unknown)
+    }
+}
+
+/// 节点的借用视图，字段与 [`ASTNode`] 各变体一一对应
+#[derive(Debug, Clone)]
+pub enum CompactNodeView<'a> {
+    Root { children: CompactChildren<'a> },
+    Paragraph { children: CompactChildren<'a> },
+    Heading { level: u8, children: CompactChildren<'a> },
+    Text { content: &'a str },
+    Strong { children: CompactChildren<'a> },
+    Em { children: CompactChildren<'a> },
+    Underline { children: CompactChildren<'a> },
+    Strike { children: CompactChildren<'a> },
+    Code { content: &'a str },
+    CodeBlock { language: Option<&'a str>, content: &'a str },
+    Link { url: &'a str, children: CompactChildren<'a> },
+    Image { url: &'a str, width: Option<f32>, height: Option<f32>, alt: Option<&'a str> },
+    List { list_type: ListType, items: CompactChildren<'a> },
+    ListItem { checked: Option<bool>, children: CompactChildren<'a> },
+    Table { rows: CompactChildren<'a> },
+    TableRow { cells: CompactChildren<'a> },
+    TableCell { align: Option<TextAlign>, children: CompactChildren<'a> },
+    Math { content: &'a str, display: bool },
+    Mermaid { content: &'a str },
+    /// `parts_json` 是结构化部分（header、fields、images、actions）的 JSON 文本，没有时为空字符串
+    Card { subtype: &'a str, content: &'a str, metadata: CompactMetadata<'a>, parts_json: &'a str },
+    Mention { id: &'a str, name: &'a str, kind: MentionKind, status: MentionStatus },
+    HorizontalRule,
+    Blockquote { children: CompactChildren<'a> },
+    FootnoteReference { label: &'a str, index: u32 },
+    FootnoteDefinition { label: &'a str, index: u32, children: CompactChildren<'a> },
+    Highlight { children: CompactChildren<'a> },
+    Spoiler { children: CompactChildren<'a> },
+    /// `attrs_json` 是属性的 JSON 文本，没有属性时为空字符串
+    Custom { kind: &'a str, attrs_json: &'a str, children: CompactChildren<'a> },
+    Timestamp { timestamp: i64, style: Option<&'a str> },
+    CustomEmoji { id: &'a str, name: &'a str, animated: bool },
+    /// 未知节点的原始 JSON
+    Unknown { raw_json: &'a str },
+}
+
+/// 子节点迭代器
+#[derive(Debug, Clone)]
+pub struct CompactChildren<'a> {
+    ast: CompactAst<'a>,
+    next: u32,
+    end: u32,
+}
+
+impl<'a> Iterator for CompactChildren<'a> {
+    type Item = CompactNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let node = CompactNode { ast: self.ast, index: self.next };
+        self.next += 1;
+        Some(node)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end - self.next) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CompactChildren<'_> {}
+
+/// 卡片 metadata 迭代器（按键排序），值为 JSON 文本
+#[derive(Debug, Clone)]
+pub struct CompactMetadata<'a> {
+    ast: CompactAst<'a>,
+    next: u32,
+    end: u32,
+}
+
+impl<'a> Iterator for CompactMetadata<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let pair = (self.ast.string(self.next), self.ast.string(self.next + 1));
+        self.next += 2;
+        Some(pair)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = ((self.end - self.next) / 2) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CompactMetadata<'_> {}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
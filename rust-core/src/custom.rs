@@ -0,0 +1,257 @@
+use crate::ast::*;
+use crate::delimiter::merge_text;
+use crate::visitor::*;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+/// 行内自定义语法的解析钩子
+///
+/// 解析完成后在文本节点中查找自定义语法，匹配的片段替换为返回的节点。与提及相同，只在同一容器合并后的相邻文本中匹配，
+/// 行内代码、代码块和链接文本不参与匹配。
+/// 闭包 `Fn(&str) -> Option<(Range<usize>, CustomNode)>` 也实现了该 trait。
+pub trait InlineNodeParser: Send + Sync {
+    /// 在文本中查找第一个匹配，返回匹配的字节范围和生成的节点
+    fn find(&self, text: &str) -> Option<(Range<usize>, CustomNode)>;
+}
+
+impl<F> InlineNodeParser for F
+where
+    F: Fn(&str) -> Option<(Range<usize>, CustomNode)> + Send + Sync,
+{
+    fn find(&self, text: &str) -> Option<(Range<usize>, CustomNode)> {
+        self(text)
+    }
+}
+
+/// 块级自定义语法的匹配结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomBlock {
+    /// 从当前行首起消耗的字节数，不在行尾结束时延伸到行尾
+    pub len: usize,
+    pub node: CustomNode,
+    /// 需要按 Markdown 解析为子节点的正文范围（相对当前行首），解析结果追加到 `node.children`
+    pub content: Option<Range<usize>>,
+}
+
+impl CustomBlock {
+    pub fn new(len: usize, node: CustomNode) -> Self {
+        Self { len, node, content: None }
+    }
+
+    /// 设置按 Markdown 解析的正文范围
+    pub fn with_content(mut self, content: Range<usize>) -> Self {
+        self.content = Some(content);
+        self
+    }
+}
+
+/// 块级自定义语法的解析钩子
+///
+/// 在每个行首（围栏代码块之外）调用，`input` 是从该行开始的剩余输入。
+/// 闭包 `Fn(&str) -> Option<CustomBlock>` 也实现了该 trait。
+pub trait BlockNodeParser: Send + Sync {
+    /// 当前行开始的内容是否为自定义块，不是时返回 `None`
+    fn parse(&self, input: &str) -> Option<CustomBlock>;
+}
+
+impl<F> BlockNodeParser for F
+where
+    F: Fn(&str) -> Option<CustomBlock> + Send + Sync,
+{
+    fn parse(&self, input: &str) -> Option<CustomBlock> {
+        self(input)
+    }
+}
+
+/// 自定义节点的 HTML 渲染钩子
+///
+/// `children` 是已渲染的子节点 HTML。返回值原样输出，属性值需要由实现自行转义。
+/// 闭包 `Fn(&CustomNode, &str) -> String` 也实现了该 trait。
+pub trait CustomNodeRenderer: Send + Sync {
+    fn render(&self, node: &CustomNode, children: &str) -> String;
+}
+
+impl<F> CustomNodeRenderer for F
+where
+    F: Fn(&CustomNode, &str) -> String + Send + Sync,
+{
+    fn render(&self, node: &CustomNode, children: &str) -> String {
+        self(node, children)
+    }
+}
+
+/// 已注册的自定义语法
+///
+/// 只按类型名参与哈希（解析缓存键），同一类型名应对应同一种实现。
+#[derive(Default)]
+pub(crate) struct CustomSyntax {
+    inline: Vec<(String, Box<dyn InlineNodeParser>)>,
+    block: Vec<(String, Box<dyn BlockNodeParser>)>,
+}
+
+impl Hash for CustomSyntax {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inline.iter().map(|(kind, _)| kind).collect::<Vec<_>>().hash(state);
+        self.block.iter().map(|(kind, _)| kind).collect::<Vec<_>>().hash(state);
+    }
+}
+
+impl CustomSyntax {
+    pub(crate) fn add_inline(&mut self, kind: String, parser: Box<dyn InlineNodeParser>) {
+        self.inline.push((kind, parser));
+    }
+
+    pub(crate) fn add_block(&mut self, kind: String, parser: Box<dyn BlockNodeParser>) {
+        self.block.push((kind, parser));
+    }
+
+    pub(crate) fn has_block(&self) -> bool {
+        !self.block.is_empty()
+    }
+
+    /// 按注册顺序尝试块级钩子，返回的节点没有类型名时使用注册的类型名
+    pub(crate) fn parse_block(&self, input: &str) -> Option<CustomBlock> {
+        self.block.iter().find_map(|(kind, parser)| {
+            let mut block = parser.parse(input)?;
+            if block.len == 0 || block.len > input.len() || !input.is_char_boundary(block.len) {
+                return None;
+            }
+            if block.node.kind.is_empty() {
+                block.node.kind = kind.clone();
+            }
+            Some(block)
+        })
+    }
+
+    /// 在整棵树的文本节点中识别行内自定义语法
+    pub(crate) fn apply_inline(&self, root: &mut RootNode) {
+        if !self.inline.is_empty() {
+            InlinePass { syntax: self }.visit_root_mut(root);
+        }
+    }
+
+    /// 拆分文本中所有的行内自定义节点，多个钩子同时匹配时取位置最靠前的（相同位置按注册顺序）；没有匹配时返回 `None`
+    fn split(&self, content: &str) -> Option<Vec<ASTNode>> {
+        let mut nodes = Vec::new();
+        let mut rest = 0;
+        while rest < content.len() {
+            let text = &content[rest..];
+            let found = self
+                .inline
+                .iter()
+                .filter_map(|(kind, parser)| {
+                    let (range, node) = parser.find(text)?;
+                    let valid = range.start < range.end
+                        && range.end <= text.len()
+                        && text.is_char_boundary(range.start)
+                        && text.is_char_boundary(range.end);
+                    valid.then_some((range, node, kind))
+                })
+                .min_by_key(|(range, _, _)| range.start);
+            let Some((range, mut node, kind)) = found else {
+                break;
+            };
+            if node.kind.is_empty() {
+                node.kind = kind.clone();
+            }
+            if range.start > 0 {
+                nodes.push(ASTNode::Text(TextNode { content: text[..range.start].to_string() }));
+            }
+            nodes.push(ASTNode::Custom(node));
+            rest += range.end;
+        }
+
+        if nodes.is_empty() {
+            return None;
+        }
+        if rest < content.len() {
+            nodes.push(ASTNode::Text(TextNode { content: content[rest..].to_string() }));
+        }
+        Some(nodes)
+    }
+}
+
+struct InlinePass<'a> {
+    syntax: &'a CustomSyntax,
+}
+
+impl InlinePass<'_> {
+    /// pulldown-cmark 会在 `[`、`:` 等字符处拆分文本，匹配前先合并相邻文本；没有匹配时保持原样
+    fn apply(&self, children: &mut Vec<ASTNode>) {
+        if !children.iter().any(|child| matches!(child, ASTNode::Text(_))) {
+            return;
+        }
+
+        let mut found = false;
+        let mut result = Vec::with_capacity(children.len());
+        for child in merge_text(children.clone()) {
+            match child {
+                ASTNode::Text(text) => match self.syntax.split(&text.content) {
+                    Some(nodes) => {
+                        result.extend(nodes);
+                        found = true;
+                    }
+                    None => result.push(ASTNode::Text(text)),
+                },
+                child => result.push(child),
+            }
+        }
+        if found {
+            *children = result;
+        }
+    }
+}
+
+impl VisitorMut for InlinePass<'_> {
+    fn visit_paragraph_mut(&mut self, para: &mut ParagraphNode) {
+        walk_paragraph_mut(self, para);
+        self.apply(&mut para.children);
+    }
+
+    fn visit_heading_mut(&mut self, heading: &mut HeadingNode) {
+        walk_heading_mut(self, heading);
+        self.apply(&mut heading.children);
+    }
+
+    fn visit_strong_mut(&mut self, strong: &mut StrongNode) {
+        walk_strong_mut(self, strong);
+        self.apply(&mut strong.children);
+    }
+
+    fn visit_em_mut(&mut self, em: &mut EmNode) {
+        walk_em_mut(self, em);
+        self.apply(&mut em.children);
+    }
+
+    fn visit_underline_mut(&mut self, underline: &mut UnderlineNode) {
+        walk_underline_mut(self, underline);
+        self.apply(&mut underline.children);
+    }
+
+    fn visit_strike_mut(&mut self, strike: &mut StrikeNode) {
+        walk_strike_mut(self, strike);
+        self.apply(&mut strike.children);
+    }
+
+    fn visit_highlight_mut(&mut self, highlight: &mut HighlightNode) {
+        walk_highlight_mut(self, highlight);
+        self.apply(&mut highlight.children);
+    }
+
+    fn visit_spoiler_mut(&mut self, spoiler: &mut SpoilerNode) {
+        walk_spoiler_mut(self, spoiler);
+        self.apply(&mut spoiler.children);
+    }
+
+    fn visit_link_mut(&mut self, _link: &mut LinkNode) {}
+
+    fn visit_list_item_mut(&mut self, item: &mut ListItemNode) {
+        walk_list_item_mut(self, item);
+        self.apply(&mut item.children);
+    }
+
+    fn visit_table_cell_mut(&mut self, cell: &mut TableCell) {
+        walk_table_cell_mut(self, cell);
+        self.apply(&mut cell.children);
+    }
+}
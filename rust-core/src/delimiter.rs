@@ -0,0 +1,273 @@
+use crate::ast::*;
+use crate::visitor::*;
+
+/// 行内定界符规则（如 `==高亮==`）
+///
+/// pulldown-cmark 不认识的扩展语法会原样留在文本节点中，
+/// 解析完成后由 [`apply_delimiter`] 在同一层兄弟节点间配对定界符并包裹为样式节点。
+pub(crate) struct DelimiterRule {
+    /// 由同一字符重复组成的定界符，只匹配长度恰好相等的字符串
+    pub marker: &'static str,
+    /// 用配对定界符之间的节点构造样式节点
+    pub wrap: fn(Vec<ASTNode>) -> ASTNode,
+}
+
+/// 在整棵树中应用定界符规则
+///
+/// 定界符只在同一容器的兄弟节点间配对，不跨越链接等行内容器的边界；
+/// 行内代码和代码块是独立节点，其中的内容不会被处理。
+pub(crate) fn apply_delimiter(root: &mut RootNode, rule: &DelimiterRule) {
+    DelimiterPass { rule }.visit_root_mut(root);
+}
+
+struct DelimiterPass<'a> {
+    rule: &'a DelimiterRule,
+}
+
+impl DelimiterPass<'_> {
+    fn apply(&self, children: &mut Vec<ASTNode>) {
+        let marker_char = self.rule.marker.chars().next().unwrap_or_default();
+        let has_marker = children.iter().any(|child| {
+            matches!(child, ASTNode::Text(text) if text.content.contains(marker_char))
+        });
+        if has_marker {
+            if let Some(paired) = pair_markers(children, self.rule) {
+                *children = paired;
+            }
+        }
+    }
+}
+
+impl VisitorMut for DelimiterPass<'_> {
+    fn visit_paragraph_mut(&mut self, para: &mut ParagraphNode) {
+        walk_paragraph_mut(self, para);
+        self.apply(&mut para.children);
+    }
+
+    fn visit_heading_mut(&mut self, heading: &mut HeadingNode) {
+        walk_heading_mut(self, heading);
+        self.apply(&mut heading.children);
+    }
+
+    fn visit_strong_mut(&mut self, strong: &mut StrongNode) {
+        walk_strong_mut(self, strong);
+        self.apply(&mut strong.children);
+    }
+
+    fn visit_em_mut(&mut self, em: &mut EmNode) {
+        walk_em_mut(self, em);
+        self.apply(&mut em.children);
+    }
+
+    fn visit_underline_mut(&mut self, underline: &mut UnderlineNode) {
+        walk_underline_mut(self, underline);
+        self.apply(&mut underline.children);
+    }
+
+    fn visit_strike_mut(&mut self, strike: &mut StrikeNode) {
+        walk_strike_mut(self, strike);
+        self.apply(&mut strike.children);
+    }
+
+    fn visit_highlight_mut(&mut self, highlight: &mut HighlightNode) {
+        walk_highlight_mut(self, highlight);
+        self.apply(&mut highlight.children);
+    }
+
+    fn visit_spoiler_mut(&mut self, spoiler: &mut SpoilerNode) {
+        walk_spoiler_mut(self, spoiler);
+        self.apply(&mut spoiler.children);
+    }
+
+    fn visit_custom_mut(&mut self, custom: &mut CustomNode) {
+        walk_custom_mut(self, custom);
+        self.apply(&mut custom.children);
+    }
+
+    fn visit_link_mut(&mut self, link: &mut LinkNode) {
+        walk_link_mut(self, link);
+        self.apply(&mut link.children);
+    }
+
+    fn visit_list_item_mut(&mut self, item: &mut ListItemNode) {
+        walk_list_item_mut(self, item);
+        self.apply(&mut item.children);
+    }
+
+    fn visit_table_cell_mut(&mut self, cell: &mut TableCell) {
+        walk_table_cell_mut(self, cell);
+        self.apply(&mut cell.children);
+    }
+}
+
+/// 转义的定界符字符（如 `\=`）在解析期间替换成的占位字符
+///
+/// 定界符配对只看文本内容，而 pulldown-cmark 输出的文本已经去掉了反斜杠。解析时按源码位置把转义的定界符字符
+/// 换成 Unicode 非字符（专供程序内部使用，不应出现在交换的文本中），配对完成后由 [`restore_escaped`] 还原。
+const ESCAPED_MARKERS: [(char, char); 3] = [('=', '\u{FDD0}'), ('|', '\u{FDD1}'), ('_', '\u{FDD2}')];
+
+/// 转义的定界符字符对应的占位字符
+pub(crate) fn escaped_placeholder(c: char) -> Option<char> {
+    ESCAPED_MARKERS.iter().find(|(marker, _)| *marker == c).map(|(_, placeholder)| *placeholder)
+}
+
+/// 把占位字符还原为原来的定界符字符，在所有定界符规则应用完成后调用
+pub(crate) fn restore_escaped(root: &mut RootNode) {
+    RestoreEscaped.visit_root_mut(root);
+}
+
+struct RestoreEscaped;
+
+impl VisitorMut for RestoreEscaped {
+    fn visit_text_mut(&mut self, text: &mut TextNode) {
+        restore(&mut text.content);
+    }
+
+    fn visit_image_mut(&mut self, image: &mut ImageNode) {
+        if let Some(alt) = &mut image.alt {
+            restore(alt);
+        }
+    }
+
+    // 由链接文本转换的提及
+    fn visit_mention_mut(&mut self, mention: &mut MentionNode) {
+        restore(&mut mention.name);
+    }
+}
+
+fn restore(content: &mut String) {
+    if content.contains(|c| ESCAPED_MARKERS.iter().any(|(_, placeholder)| *placeholder == c)) {
+        *content = content
+            .chars()
+            .map(|c| ESCAPED_MARKERS.iter().find(|(_, placeholder)| *placeholder == c).map_or(c, |(marker, _)| *marker))
+            .collect();
+    }
+}
+
+enum Piece {
+    Node(ASTNode),
+    Marker { can_open: bool, can_close: bool },
+}
+
+/// 拆分文本中的定界符并从左到右配对，未配对的定界符还原为文本
+///
+/// pulldown-cmark 可能把一段文本拆成多个文本节点，拆分前先合并相邻文本。没有任何定界符配对时返回 `None`。
+fn pair_markers(children: &[ASTNode], rule: &DelimiterRule) -> Option<Vec<ASTNode>> {
+    let mut pieces = Vec::with_capacity(children.len());
+    for child in merge_text(children.to_vec()) {
+        match child {
+            ASTNode::Text(text) => split_text(&text.content, rule.marker, &mut pieces),
+            node => pieces.push(Piece::Node(node)),
+        }
+    }
+
+    // 定界符前后是否紧邻非空白内容；非文本节点视为非空白，容器边界视为空白
+    let mut flanks = Vec::with_capacity(pieces.len());
+    for (index, piece) in pieces.iter().enumerate() {
+        if let Piece::Marker { .. } = piece {
+            let before = index.checked_sub(1).and_then(|i| pieces.get(i));
+            let after = pieces.get(index + 1);
+            flanks.push((index, solid_edge(after, true), solid_edge(before, false)));
+        }
+    }
+    for (index, can_open, can_close) in flanks {
+        pieces[index] = Piece::Marker { can_open, can_close };
+    }
+
+    let mut out: Vec<Piece> = Vec::with_capacity(pieces.len());
+    let mut opener: Option<usize> = None;
+    let mut paired = false;
+    for piece in pieces {
+        match piece {
+            Piece::Marker { can_open, can_close } => match opener {
+                Some(open) if can_close && out.len() > open + 1 => {
+                    let inner: Vec<ASTNode> = out.drain(open + 1..).map(|piece| piece_to_node(piece, rule)).collect();
+                    out.pop();
+                    out.push(Piece::Node((rule.wrap)(merge_text(inner))));
+                    opener = None;
+                    paired = true;
+                }
+                _ if can_open => {
+                    opener = Some(out.len());
+                    out.push(Piece::Marker { can_open, can_close });
+                }
+                _ => out.push(Piece::Node(text(rule.marker))),
+            },
+            piece => out.push(piece),
+        }
+    }
+
+    paired.then(|| merge_text(out.into_iter().map(|piece| piece_to_node(piece, rule)).collect()))
+}
+
+/// 按定界符拆分文本；同一字符的更长连续串（如 `===`）保留为文本
+fn split_text(content: &str, marker: &str, pieces: &mut Vec<Piece>) {
+    let marker_char = match marker.chars().next() {
+        Some(c) => c,
+        None => {
+            pieces.push(Piece::Node(text(content)));
+            return;
+        }
+    };
+
+    let mut pending = String::new();
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != marker_char {
+            pending.push(c);
+            continue;
+        }
+        let mut run = String::from(c);
+        while chars.peek() == Some(&marker_char) {
+            run.push(marker_char);
+            chars.next();
+        }
+        if run == marker {
+            if !pending.is_empty() {
+                pieces.push(Piece::Node(text(&std::mem::take(&mut pending))));
+            }
+            pieces.push(Piece::Marker { can_open: false, can_close: false });
+        } else {
+            pending.push_str(&run);
+        }
+    }
+    if !pending.is_empty() {
+        pieces.push(Piece::Node(text(&pending)));
+    }
+}
+
+/// 相邻片段靠近定界符一侧的字符是否为非空白
+fn solid_edge(piece: Option<&Piece>, leading: bool) -> bool {
+    match piece {
+        None => false,
+        Some(Piece::Marker { .. }) => false,
+        Some(Piece::Node(ASTNode::Text(text))) => {
+            let edge = if leading { text.content.chars().next() } else { text.content.chars().last() };
+            edge.is_some_and(|c| !c.is_whitespace())
+        }
+        Some(Piece::Node(_)) => true,
+    }
+}
+
+fn piece_to_node(piece: Piece, rule: &DelimiterRule) -> ASTNode {
+    match piece {
+        Piece::Node(node) => node,
+        Piece::Marker { .. } => text(rule.marker),
+    }
+}
+
+/// 合并相邻的文本节点
+pub(crate) fn merge_text(nodes: Vec<ASTNode>) -> Vec<ASTNode> {
+    let mut merged: Vec<ASTNode> = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        match (merged.last_mut(), node) {
+            (Some(ASTNode::Text(prev)), ASTNode::Text(next)) => prev.content.push_str(&next.content),
+            (_, node) => merged.push(node),
+        }
+    }
+    merged
+}
+
+fn text(content: &str) -> ASTNode {
+    ASTNode::Text(TextNode { content: content.to_string() })
+}
@@ -1,14 +1,42 @@
 use crate::ast::*;
 use crate::ast_builder::ASTBuilder;
+use crate::normalize::normalize;
 use crate::ParseError;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+/// Delta 属性映射选项
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeltaOptions {
+    /// 将 `background` 背景色属性解析为高亮（默认关闭，背景色按普通文本处理）
+    pub background_highlight: bool,
+}
+
 /// Delta 解析器
-pub struct DeltaParser;
+#[derive(Hash)]
+pub struct DeltaParser {
+    options: DeltaOptions,
+    normalize: bool,
+}
 
 impl DeltaParser {
     pub fn new() -> Self {
-        Self
+        Self::with_options(DeltaOptions::default())
+    }
+
+    pub fn with_options(options: DeltaOptions) -> Self {
+        Self { options, normalize: false }
+    }
+
+    pub fn options(&self) -> &DeltaOptions {
+        &self.options
+    }
+
+    /// 设置是否在解析完成后自动执行 [`normalize`]
+    pub fn with_normalize(mut self, enabled: bool) -> Self {
+        self.normalize = enabled;
+        self
     }
 
     pub fn parse(&self, input: &str) -> Result<RootNode, ParseError> {
@@ -16,105 +44,43 @@ impl DeltaParser {
         let mut builder = ASTBuilder::new();
         builder.start_document();
 
-        let mut current_paragraph_children: Vec<ASTNode> = Vec::new();
-        let mut in_list = false;
-        let mut list_type = ListType::Bullet;
+        let mut current_line_children: Vec<ASTNode> = Vec::new();
+        // 当前列表的类型及是否为任务列表
+        let mut current_list: Option<(ListType, bool)> = None;
 
         for op in &delta.ops {
             match op {
                 DeltaOp::Insert { insert, attributes } => {
                     match insert {
                         InsertValue::Text(text) => {
-                            if text == "\n" {
-                                // 换行，结束当前段落
-                                if !current_paragraph_children.is_empty() {
-                                    builder.start_paragraph();
-                                    if let Some(para) = &mut builder.current_paragraph {
-                                        para.children = std::mem::take(&mut current_paragraph_children);
-                                    }
-                                    builder.end_paragraph();
-                                } else {
-                                    // 空行，创建空段落
-                                    builder.start_paragraph();
-                                    builder.end_paragraph();
+                            // 按换行拆分，每个换行符结束一行；换行符上的属性是行属性（如列表）
+                            let mut segments = text.split('\n').peekable();
+                            while let Some(segment) = segments.next() {
+                                if !segment.is_empty() {
+                                    let styled_nodes = self.build_styled_text(segment, attributes);
+                                    current_line_children.extend(styled_nodes);
+                                }
+                                if segments.peek().is_some() {
+                                    self.end_line(
+                                        &mut builder,
+                                        &mut current_line_children,
+                                        attributes,
+                                        &mut current_list,
+                                    );
                                 }
-                            } else {
-                                // 添加文本，应用样式
-                                let styled_nodes = self.build_styled_text(text, attributes);
-                                current_paragraph_children.extend(styled_nodes);
                             }
                         }
                         InsertValue::Image { image } => {
-                            // 结束当前段落
-                            if !current_paragraph_children.is_empty() {
-                                builder.start_paragraph();
-                                if let Some(para) = &mut builder.current_paragraph {
-                                    para.children = std::mem::take(&mut current_paragraph_children);
-                                }
-                                builder.end_paragraph();
-                            }
-
+                            // 结束当前段落和列表
+                            self.flush_line(&mut builder, &mut current_line_children, &mut current_list);
                             builder.add_image(image.clone(), None, None, None);
                         }
                         InsertValue::Formula { formula } => {
-                            // 结束当前段落
-                            if !current_paragraph_children.is_empty() {
-                                builder.start_paragraph();
-                                if let Some(para) = &mut builder.current_paragraph {
-                                    para.children = std::mem::take(&mut current_paragraph_children);
-                                }
-                                builder.end_paragraph();
-                            }
-
+                            // 结束当前段落和列表
+                            self.flush_line(&mut builder, &mut current_line_children, &mut current_list);
                             builder.add_math(formula.clone(), true); // Delta 公式通常是 display 模式
                         }
                     }
-
-                    // 处理列表属性
-                    if let Some(attrs) = attributes {
-                        if let Some(list_attr) = attrs.get("list") {
-                            if let Some(list_str) = list_attr.as_str() {
-                                let new_list_type = if list_str == "ordered" {
-                                    ListType::Ordered
-                                } else {
-                                    ListType::Bullet
-                                };
-
-                                if !in_list || list_type != new_list_type {
-                                    // 结束旧列表
-                                    if in_list {
-                                        builder.end_list();
-                                    }
-
-                                    // 开始新列表
-                                    list_type = new_list_type;
-                                    in_list = true;
-                                    builder.start_list(list_type.clone());
-                                }
-
-                                // 检查是否是列表项结束
-                                if let InsertValue::Text(text) = insert {
-                                    if text == "\n" {
-                                        // 列表项结束
-                                        if !current_paragraph_children.is_empty() {
-                                            builder.add_list_item(
-                                                std::mem::take(&mut current_paragraph_children),
-                                                None,
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-                        } else if in_list {
-                            // 没有列表属性，结束列表
-                            builder.end_list();
-                            in_list = false;
-                        }
-                    } else if in_list {
-                        // 没有属性，结束列表
-                        builder.end_list();
-                        in_list = false;
-                    }
                 }
                 DeltaOp::Retain { .. } => {
                     // Retain 操作通常用于格式化，这里简化处理
@@ -126,19 +92,81 @@ impl DeltaParser {
         }
 
         // 处理剩余的段落和列表
-        if !current_paragraph_children.is_empty() {
+        self.flush_line(&mut builder, &mut current_line_children, &mut current_list);
+
+        let mut root = builder.end_document();
+        if self.normalize {
+            normalize(&mut root);
+        }
+        Ok(root)
+    }
+
+    /// 遇到换行符时结束当前行，根据行属性生成段落或列表项
+    fn end_line(
+        &self,
+        builder: &mut ASTBuilder,
+        children: &mut Vec<ASTNode>,
+        attributes: &Option<DeltaAttributes>,
+        current_list: &mut Option<(ListType, bool)>,
+    ) {
+        let list_attr = attributes
+            .as_ref()
+            .and_then(|attrs| attrs.get("list"))
+            .and_then(|v| v.as_str());
+
+        if let Some(list_str) = list_attr {
+            let (new_list_type, checked) = match list_str {
+                "ordered" => (ListType::Ordered, None),
+                "checked" => (ListType::Bullet, Some(true)),
+                "unchecked" => (ListType::Bullet, Some(false)),
+                _ => (ListType::Bullet, None),
+            };
+
+            // 列表类型或是否为任务列表发生变化时，结束旧列表，开始新列表
+            let list_kind = (new_list_type, checked.is_some());
+            if current_list.as_ref() != Some(&list_kind) {
+                if current_list.is_some() {
+                    builder.end_list();
+                }
+                builder.start_list(list_kind.0.clone());
+                *current_list = Some(list_kind);
+            }
+
+            // 空行也生成（空的）列表项，与编辑器中显示的空项目符号一致
+            builder.add_list_item(std::mem::take(children), checked);
+        } else {
+            // 没有列表属性，结束列表
+            if current_list.take().is_some() {
+                builder.end_list();
+            }
+
+            // 空行会生成空段落
             builder.start_paragraph();
             if let Some(para) = &mut builder.current_paragraph {
-                para.children = current_paragraph_children;
+                para.children = std::mem::take(children);
             }
             builder.end_paragraph();
         }
+    }
 
-        if in_list {
+    /// 结束未以换行符结尾的内容以及当前列表
+    fn flush_line(
+        &self,
+        builder: &mut ASTBuilder,
+        children: &mut Vec<ASTNode>,
+        current_list: &mut Option<(ListType, bool)>,
+    ) {
+        if current_list.take().is_some() {
             builder.end_list();
         }
 
-        Ok(builder.end_document())
+        if !children.is_empty() {
+            builder.start_paragraph();
+            if let Some(para) = &mut builder.current_paragraph {
+                para.children = std::mem::take(children);
+            }
+            builder.end_paragraph();
+        }
     }
 
     fn build_styled_text(
@@ -194,6 +222,15 @@ impl DeltaParser {
             if attrs.get("strike").and_then(|v| v.as_bool()).unwrap_or(false) {
                 styles.push(DeltaStyle::Strike);
             }
+            if self.options.background_highlight
+                && attrs.get("background").and_then(|v| v.as_str()).is_some_and(|color| !color.is_empty())
+            {
+                styles.push(DeltaStyle::Highlight);
+            }
+            // 自定义属性，编辑器中的剧透格式
+            if attrs.get("spoiler").and_then(|v| v.as_bool()).unwrap_or(false) {
+                styles.push(DeltaStyle::Spoiler);
+            }
 
             if let Some(link) = attrs.get("link").and_then(|v| v.as_str()) {
                 styles.push(DeltaStyle::Link(link.to_string()));
@@ -231,6 +268,12 @@ impl DeltaParser {
                     DeltaStyle::Strike => ASTNode::Strike(StrikeNode {
                         children: vec![current],
                     }),
+                    DeltaStyle::Highlight => ASTNode::Highlight(HighlightNode {
+                        children: vec![current],
+                    }),
+                    DeltaStyle::Spoiler => ASTNode::Spoiler(SpoilerNode {
+                        children: vec![current],
+                    }),
                     DeltaStyle::Link(url) => ASTNode::Link(LinkNode {
                         url: url.clone(),
                         children: vec![current],
@@ -325,8 +368,7 @@ impl DeltaParser {
                 let mut found_end = false;
                 
                 // 查找结束的 $
-                for j in (i + 1)..chars.len() {
-                    let (pos, ch) = chars[j];
+                for (j, &(pos, ch)) in chars.iter().enumerate().skip(i + 1) {
                     
                     // 检查是否是结束标记：单个 $ 且前面不是 $
                     if ch == '$' {
@@ -396,13 +438,16 @@ enum DeltaOp {
         #[serde(default)]
         attributes: Option<DeltaAttributes>,
     },
+    // Retain / Delete 只需识别出来并跳过，字段内容不使用
     Retain {
-        retain: u32,
-        #[serde(default)]
-        attributes: Option<DeltaAttributes>,
+        #[serde(rename = "retain")]
+        _retain: u32,
+        #[serde(default, rename = "attributes")]
+        _attributes: Option<DeltaAttributes>,
     },
     Delete {
-        delete: u32,
+        #[serde(rename = "delete")]
+        _delete: u32,
     },
 }
 
@@ -426,6 +471,8 @@ enum DeltaStyle {
     Italic,
     Underline,
     Strike,
+    Highlight,
+    Spoiler,
     Link(String),
 }
 
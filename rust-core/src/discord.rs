@@ -0,0 +1,170 @@
+use crate::ast::*;
+use crate::delimiter::merge_text;
+use crate::visitor::*;
+
+/// 把文本中的 Discord 时间戳 `<t:1618953630:R>` 和自定义表情 `<:name:id>` / `<a:name:id>` 转换为节点
+///
+/// pulldown-cmark 不把这些写法识别为 HTML 或自动链接，它们原样留在文本节点中；
+/// 行内代码和代码块是独立节点，其中的内容不会被处理。
+pub(crate) fn parse_discord_markup(root: &mut RootNode) {
+    DiscordMarkupPass.visit_root_mut(root);
+}
+
+struct DiscordMarkupPass;
+
+impl DiscordMarkupPass {
+    /// pulldown-cmark 可能把 `<` 拆成单独的文本节点，识别前先合并相邻文本；没有识别到时保持原样
+    fn apply(&self, children: &mut Vec<ASTNode>) {
+        let has_candidate = children.iter().any(|child| {
+            matches!(child, ASTNode::Text(text) if text.content.contains('<'))
+        });
+        if !has_candidate {
+            return;
+        }
+
+        let mut found = false;
+        let mut result = Vec::with_capacity(children.len());
+        for child in merge_text(children.clone()) {
+            match child {
+                ASTNode::Text(text) => match split_markup(&text.content) {
+                    Some(nodes) => {
+                        result.extend(nodes);
+                        found = true;
+                    }
+                    None => result.push(ASTNode::Text(text)),
+                },
+                child => result.push(child),
+            }
+        }
+        if found {
+            *children = result;
+        }
+    }
+}
+
+impl VisitorMut for DiscordMarkupPass {
+    fn visit_paragraph_mut(&mut self, para: &mut ParagraphNode) {
+        walk_paragraph_mut(self, para);
+        self.apply(&mut para.children);
+    }
+
+    fn visit_heading_mut(&mut self, heading: &mut HeadingNode) {
+        walk_heading_mut(self, heading);
+        self.apply(&mut heading.children);
+    }
+
+    fn visit_strong_mut(&mut self, strong: &mut StrongNode) {
+        walk_strong_mut(self, strong);
+        self.apply(&mut strong.children);
+    }
+
+    fn visit_em_mut(&mut self, em: &mut EmNode) {
+        walk_em_mut(self, em);
+        self.apply(&mut em.children);
+    }
+
+    fn visit_underline_mut(&mut self, underline: &mut UnderlineNode) {
+        walk_underline_mut(self, underline);
+        self.apply(&mut underline.children);
+    }
+
+    fn visit_strike_mut(&mut self, strike: &mut StrikeNode) {
+        walk_strike_mut(self, strike);
+        self.apply(&mut strike.children);
+    }
+
+    fn visit_highlight_mut(&mut self, highlight: &mut HighlightNode) {
+        walk_highlight_mut(self, highlight);
+        self.apply(&mut highlight.children);
+    }
+
+    fn visit_spoiler_mut(&mut self, spoiler: &mut SpoilerNode) {
+        walk_spoiler_mut(self, spoiler);
+        self.apply(&mut spoiler.children);
+    }
+
+    fn visit_custom_mut(&mut self, custom: &mut CustomNode) {
+        walk_custom_mut(self, custom);
+        self.apply(&mut custom.children);
+    }
+
+    fn visit_link_mut(&mut self, link: &mut LinkNode) {
+        walk_link_mut(self, link);
+        self.apply(&mut link.children);
+    }
+
+    fn visit_list_item_mut(&mut self, item: &mut ListItemNode) {
+        walk_list_item_mut(self, item);
+        self.apply(&mut item.children);
+    }
+
+    fn visit_table_cell_mut(&mut self, cell: &mut TableCell) {
+        walk_table_cell_mut(self, cell);
+        self.apply(&mut cell.children);
+    }
+}
+
+/// 拆分文本中的时间戳和自定义表情；都没有时返回 `None`
+fn split_markup(content: &str) -> Option<Vec<ASTNode>> {
+    let mut nodes = Vec::new();
+    let mut pending = 0;
+    let mut index = 0;
+
+    while let Some(offset) = content[index..].find('<') {
+        index += offset;
+        match parse_markup(&content[index..]) {
+            Some((len, node)) => {
+                if pending < index {
+                    nodes.push(ASTNode::Text(TextNode { content: content[pending..index].to_string() }));
+                }
+                nodes.push(node);
+                index += len;
+                pending = index;
+            }
+            None => index += 1,
+        }
+    }
+
+    if nodes.is_empty() {
+        return None;
+    }
+    if pending < content.len() {
+        nodes.push(ASTNode::Text(TextNode { content: content[pending..].to_string() }));
+    }
+    Some(nodes)
+}
+
+/// 解析 `<t:秒>`、`<t:秒:格式>`、`<:name:id>`、`<a:name:id>`，返回消耗的字节数
+fn parse_markup(input: &str) -> Option<(usize, ASTNode)> {
+    let end = input.find('>')?;
+    let inner = &input[1..end];
+
+    if let Some(rest) = inner.strip_prefix("t:") {
+        let (seconds, style) = match rest.split_once(':') {
+            Some((seconds, style)) => (seconds, Some(style)),
+            None => (rest, None),
+        };
+        if style.is_some_and(|style| !matches!(style, "t" | "T" | "d" | "D" | "f" | "F" | "R")) {
+            return None;
+        }
+        let digits = seconds.strip_prefix('-').unwrap_or(seconds);
+        if digits.is_empty() || digits.len() > 17 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let timestamp = TimestampNode { timestamp: seconds.parse().ok()?, style: style.map(str::to_string) };
+        return Some((end + 1, ASTNode::Timestamp(timestamp)));
+    }
+
+    let (animated, rest) = match inner.strip_prefix("a:") {
+        Some(rest) => (true, rest),
+        None => (false, inner.strip_prefix(':')?),
+    };
+    let (name, id) = rest.split_once(':')?;
+    // 表情名为 2 到 32 个字母、数字或下划线，ID 为数字
+    let valid_name = (2..=32).contains(&name.len()) && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_');
+    if !valid_name || id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let emoji = CustomEmojiNode { id: id.to_string(), name: name.to_string(), animated };
+    Some((end + 1, ASTNode::CustomEmoji(emoji)))
+}
@@ -0,0 +1,49 @@
+use crate::ast::*;
+use crate::normalize::normalize;
+use crate::visitor::*;
+
+/// 判断两棵 AST 在语义上是否等价
+///
+/// 比较前会对两棵树的副本执行 [`normalize`]，并忽略与渲染结果无关的差异：
+/// - 空段落（Delta 用空行表示段落间距，Markdown 不产生空段落）
+/// - 列表项中只有一个段落时的段落包装（Markdown 松散列表与紧凑列表的区别）
+pub fn ast_equivalent(a: &RootNode, b: &RootNode) -> bool {
+    canonical_form(a) == canonical_form(b)
+}
+
+/// 生成用于比较的规范形式
+fn canonical_form(root: &RootNode) -> RootNode {
+    let mut root = root.clone();
+    normalize(&mut root);
+    EquivalenceCanonicalizer.visit_root_mut(&mut root);
+    root
+}
+
+struct EquivalenceCanonicalizer;
+
+impl EquivalenceCanonicalizer {
+    fn remove_empty_paragraphs(children: &mut Vec<ASTNode>) {
+        children.retain(|child| !matches!(child, ASTNode::Paragraph(para) if para.children.is_empty()));
+    }
+}
+
+impl VisitorMut for EquivalenceCanonicalizer {
+    fn visit_root_mut(&mut self, root: &mut RootNode) {
+        walk_root_mut(self, root);
+        Self::remove_empty_paragraphs(&mut root.children);
+    }
+
+    fn visit_blockquote_mut(&mut self, blockquote: &mut BlockquoteNode) {
+        walk_blockquote_mut(self, blockquote);
+        Self::remove_empty_paragraphs(&mut blockquote.children);
+    }
+
+    fn visit_list_item_mut(&mut self, item: &mut ListItemNode) {
+        walk_list_item_mut(self, item);
+        Self::remove_empty_paragraphs(&mut item.children);
+
+        if let [ASTNode::Paragraph(para)] = item.children.as_mut_slice() {
+            item.children = std::mem::take(&mut para.children);
+        }
+    }
+}
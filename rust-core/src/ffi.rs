@@ -1,6 +1,7 @@
 use crate::*;
+use std::cell::RefCell;
 use std::ffi::{CStr, CString};
-use std::os::raw::c_char;
+use std::os::raw::{c_char, c_void};
 use std::ptr;
 
 /// FFI 错误类型
@@ -19,8 +20,11 @@ pub struct ParseResult {
 }
 
 /// 释放 CString
+///
+/// # Safety
+/// `ptr` 必须为 null 或由本库返回且尚未释放的字符串
 #[no_mangle]
-pub extern "C" fn free_string(ptr: *mut c_char) {
+pub unsafe extern "C" fn free_string(ptr: *mut c_char) {
     if !ptr.is_null() {
         unsafe {
             let _ = CString::from_raw(ptr);
@@ -29,8 +33,11 @@ pub extern "C" fn free_string(ptr: *mut c_char) {
 }
 
 /// 解析 Markdown 为 JSON AST
+///
+/// # Safety
+/// `input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
 #[no_mangle]
-pub extern "C" fn parse_markdown_to_json(input: *const c_char) -> *mut ParseResult {
+pub unsafe extern "C" fn parse_markdown_to_json(input: *const c_char) -> *mut ParseResult {
     let input_str = unsafe {
         if input.is_null() {
             return create_error_result("Input is null".to_string());
@@ -41,7 +48,7 @@ pub extern "C" fn parse_markdown_to_json(input: *const c_char) -> *mut ParseResu
         }
     };
 
-    match parse_markdown(input_str) {
+    match parse_markdown_shared(input_str) {
         Ok(ast) => {
             match serialize_ast(&ast) {
                 Ok(json) => {
@@ -67,8 +74,11 @@ pub extern "C" fn parse_markdown_to_json(input: *const c_char) -> *mut ParseResu
 }
 
 /// 解析 Delta 为 JSON AST
+///
+/// # Safety
+/// `input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
 #[no_mangle]
-pub extern "C" fn parse_delta_to_json(input: *const c_char) -> *mut ParseResult {
+pub unsafe extern "C" fn parse_delta_to_json(input: *const c_char) -> *mut ParseResult {
     let input_str = unsafe {
         if input.is_null() {
             return create_error_result("Input is null".to_string());
@@ -79,7 +89,7 @@ pub extern "C" fn parse_delta_to_json(input: *const c_char) -> *mut ParseResult
         }
     };
 
-    match parse_delta(input_str) {
+    match parse_delta_shared(input_str) {
         Ok(ast) => {
             match serialize_ast(&ast) {
                 Ok(json) => {
@@ -104,9 +114,362 @@ pub extern "C" fn parse_delta_to_json(input: *const c_char) -> *mut ParseResult
     }
 }
 
+/// 解析 HTML 为 JSON AST
+/// @param input HTML 字符串（邮件、剪贴板等），只保留安全的标签子集
+///
+/// # Safety
+/// `input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
+#[no_mangle]
+pub unsafe extern "C" fn parse_html_to_json(input: *const c_char) -> *mut ParseResult {
+    let Some(input_str) = c_str(input) else {
+        return create_error_result("Input is null or not valid UTF-8".to_string());
+    };
+    match parse_html_shared(input_str) {
+        Ok(ast) => match serialize_ast(&ast) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(format!("Serialization error: {}", e)),
+        },
+        Err(e) => create_error_result(format!("Parse error: {}", e)),
+    }
+}
+
+/// 批量解析消息
+/// @param items_json 消息数组 JSON：`[{"id": "...", "format": "markdown" | "delta" | "html", "content": "..."}]`
+/// @return 结果数组 JSON（`[{"id": "...", "ast": {...}}` 或 `{"id": "...", "error": "..."}]`），
+///         顺序与输入一致；仅当输入 JSON 本身不合法时返回失败
+///
+/// # Safety
+/// `items_json` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
+#[no_mangle]
+pub unsafe extern "C" fn parse_batch_to_json(items_json: *const c_char) -> *mut ParseResult {
+    let input_str = unsafe {
+        if items_json.is_null() {
+            return create_error_result("Input is null".to_string());
+        }
+        match CStr::from_ptr(items_json).to_str() {
+            Ok(s) => s,
+            Err(_) => return create_error_result("Invalid UTF-8 string".to_string()),
+        }
+    };
+
+    let items: Vec<BatchItem> = match serde_json::from_str(input_str) {
+        Ok(items) => items,
+        Err(e) => return create_error_result(format!("Failed to parse batch JSON: {}", e)),
+    };
+
+    match serde_json::to_string(&parse_batch(&items)) {
+        Ok(json) => {
+            let c_string = match CString::new(json) {
+                Ok(s) => s,
+                Err(_) => return create_error_result("Failed to create CString".to_string()),
+            };
+            Box::into_raw(Box::new(ParseResult {
+                success: true,
+                ast_json: c_string.into_raw(),
+                error: FFIError {
+                    code: 0,
+                    message: ptr::null(),
+                },
+            }))
+        }
+        Err(e) => create_error_result(format!("Serialization error: {}", e)),
+    }
+}
+
+/// 创建可复用的解析器句柄
+/// @param options_json 解析器配置 JSON（`ParserConfig`：markdown、normalize、limits、sanitize、style），
+///        为 null 时使用默认配置
+/// @param error_out 可为 null；创建失败时写入错误信息（需要调用 free_string 释放），成功时写入 null
+/// @return 解析器句柄，配置不合法时返回 null；需要调用 imparse_parser_free 释放。
+///         句柄不含内部可变状态，可在多个线程间共享
+///
+/// # Safety
+/// `options_json` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串；
+/// `error_out` 必须为 null 或指向可写的 `char *`
+#[no_mangle]
+pub unsafe extern "C" fn imparse_parser_new(
+    options_json: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut ConfiguredParser {
+    let result = if options_json.is_null() {
+        Ok(ConfiguredParser::default())
+    } else {
+        match unsafe { CStr::from_ptr(options_json) }.to_str() {
+            Ok(options_str) => {
+                ConfiguredParser::from_json(options_str).map_err(|e| format!("Invalid parser config: {}", e))
+            }
+            Err(_) => Err("Invalid UTF-8 string".to_string()),
+        }
+    };
+
+    let (parser, error) = match result {
+        Ok(parser) => (Box::into_raw(Box::new(parser)), ptr::null_mut()),
+        Err(message) => (ptr::null_mut(), CString::new(message).map_or(ptr::null_mut(), CString::into_raw)),
+    };
+    if !error_out.is_null() {
+        unsafe { *error_out = error };
+    } else if !error.is_null() {
+        unsafe { drop(CString::from_raw(error)) };
+    }
+    parser
+}
+
+/// 使用解析器句柄解析为 JSON AST
+/// @param handle imparse_parser_new 返回的句柄
+/// @param format 输入格式："markdown"、"delta" 或 "html"
+/// @param input 输入内容
+///
+/// # Safety
+/// `handle` 必须为 null 或 imparse_parser_new 返回且尚未释放的句柄；
+/// `format`、`input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
+#[no_mangle]
+pub unsafe extern "C" fn imparse_parse(
+    handle: *const ConfiguredParser,
+    format: *const c_char,
+    input: *const c_char,
+) -> *mut ParseResult {
+    let (parser, format, input_str) = match handle_arguments(handle, format, input) {
+        Ok(arguments) => arguments,
+        Err(result) => return result,
+    };
+
+    match parser.parse(format, input_str) {
+        Ok(ast) => match serialize_ast(&ast) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(format!("Serialization error: {}", e)),
+        },
+        Err(e) => create_error_result(format!("Parse error: {}", e)),
+    }
+}
+
+/// 使用解析器句柄解析并渲染为 HTML（样式取自句柄配置的 style）
+/// @param handle imparse_parser_new 返回的句柄
+/// @param format 输入格式："markdown"、"delta" 或 "html"
+/// @param input 输入内容
+///
+/// # Safety
+/// `handle` 必须为 null 或 imparse_parser_new 返回且尚未释放的句柄；
+/// `format`、`input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
+#[no_mangle]
+pub unsafe extern "C" fn imparse_render_html(
+    handle: *const ConfiguredParser,
+    format: *const c_char,
+    input: *const c_char,
+) -> *mut ParseResult {
+    let (parser, format, input_str) = match handle_arguments(handle, format, input) {
+        Ok(arguments) => arguments,
+        Err(result) => return result,
+    };
+
+    match parser.render_html(format, input_str) {
+        Ok(html) => create_success_result(html),
+        Err(e) => create_error_result(format!("Conversion error: {}", e)),
+    }
+}
+
+/// 释放解析器句柄
+///
+/// # Safety
+/// `handle` 必须为 null 或 imparse_parser_new 返回且尚未释放的句柄
+#[no_mangle]
+pub unsafe extern "C" fn imparse_parser_free(handle: *mut ConfiguredParser) {
+    if !handle.is_null() {
+        unsafe {
+            let _ = Box::from_raw(handle);
+        }
+    }
+}
+
+/// 校验句柄方法的参数
+///
+/// # Safety
+/// 要求同 imparse_parse
+unsafe fn handle_arguments<'a>(
+    handle: *const ConfiguredParser,
+    format: *const c_char,
+    input: *const c_char,
+) -> Result<(&'a ConfiguredParser, InputFormat, &'a str), *mut ParseResult> {
+    if handle.is_null() {
+        return Err(create_error_result("Parser handle is null".to_string()));
+    }
+    if format.is_null() || input.is_null() {
+        return Err(create_error_result("Input is null".to_string()));
+    }
+
+    let format = unsafe {
+        match CStr::from_ptr(format).to_str() {
+            Ok("markdown") => InputFormat::Markdown,
+            Ok("delta") => InputFormat::Delta,
+            Ok("html") => InputFormat::Html,
+            _ => return Err(create_error_result("Unknown input format".to_string())),
+        }
+    };
+    let input_str = unsafe {
+        match CStr::from_ptr(input).to_str() {
+            Ok(s) => s,
+            Err(_) => return Err(create_error_result("Invalid UTF-8 string".to_string())),
+        }
+    };
+
+    Ok((unsafe { &*handle }, format, input_str))
+}
+
+fn create_success_result(content: String) -> *mut ParseResult {
+    let c_string = match CString::new(content) {
+        Ok(s) => s,
+        Err(_) => return create_error_result("Failed to create CString".to_string()),
+    };
+    Box::into_raw(Box::new(ParseResult {
+        success: true,
+        ast_json: c_string.into_raw(),
+        error: FFIError {
+            code: 0,
+            message: ptr::null(),
+        },
+    }))
+}
+
+/// 提及查找回调
+/// @param context 调用方传入的上下文指针，原样传回
+/// @param kind 提及类型："user"、"channel" 或 "role"
+/// @param id 用户、频道或角色 ID
+/// @return 目标 JSON（`{"name": "Alice", "deactivated": false}`），找不到时返回 null。
+///         字符串由宿主持有，只需保持有效到回调返回（Rust 侧会立即复制）
+pub type MentionLookupCallback =
+    extern "C" fn(context: *mut c_void, kind: *const c_char, id: *const c_char) -> *const c_char;
+
+/// 使用查找表解析 JSON AST 中的提及
+/// @param ast_json JSON AST
+/// @param directory_json 通讯录 JSON：`{"users": {"U1": {"name": "Alice"}}, "channels": {...}}`
+/// @return 填入名称和状态后的 JSON AST
+///
+/// # Safety
+/// `ast_json`、`directory_json` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
+#[no_mangle]
+pub unsafe extern "C" fn imparse_resolve_mentions(ast_json: *const c_char, directory_json: *const c_char) -> *mut ParseResult {
+    let (ast_str, directory_str) = match (c_str(ast_json), c_str(directory_json)) {
+        (Some(ast), Some(directory)) => (ast, directory),
+        _ => return create_error_result("Input is null or not valid UTF-8".to_string()),
+    };
+    let directory = match MentionDirectory::from_json(directory_str) {
+        Ok(directory) => directory,
+        Err(e) => return create_error_result(format!("Failed to parse directory JSON: {}", e)),
+    };
+    resolve_mentions_json(ast_str, &directory)
+}
+
+/// 使用宿主回调解析 JSON AST 中的提及
+/// @param ast_json JSON AST
+/// @param lookup 查找回调，每个用户、频道或角色提及调用一次
+/// @param context 传给回调的上下文指针
+/// @return 填入名称和状态后的 JSON AST；回调返回的字符串不是合法的目标 JSON 时返回错误
+///
+/// # Safety
+/// `ast_json` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串；
+/// `lookup` 返回的指针必须为 null 或指向有效的 C 字符串；`context` 原样传给回调，Rust 侧不会访问
+#[no_mangle]
+pub unsafe extern "C" fn imparse_resolve_mentions_with_callback(
+    ast_json: *const c_char,
+    lookup: Option<MentionLookupCallback>,
+    context: *mut c_void,
+) -> *mut ParseResult {
+    let ast_str = match c_str(ast_json) {
+        Some(s) => s,
+        None => return create_error_result("Input is null or not valid UTF-8".to_string()),
+    };
+    let lookup = match lookup {
+        Some(lookup) => lookup,
+        None => return create_error_result("Lookup callback is null".to_string()),
+    };
+    // 回调返回的内容不是合法的目标 JSON 时记录第一个错误，解析结束后整体返回错误
+    let callback_error = RefCell::new(None);
+    let resolver = |kind: MentionKind, id: &str| -> Option<MentionTarget> {
+        let kind_name = match kind {
+            MentionKind::Channel => "channel",
+            MentionKind::Role => "role",
+            _ => "user",
+        };
+        let kind = CString::new(kind_name).ok()?;
+        let id_c = CString::new(id).ok()?;
+        let target = lookup(context, kind.as_ptr(), id_c.as_ptr());
+        if target.is_null() {
+            return None;
+        }
+        let parsed = unsafe { CStr::from_ptr(target) }
+            .to_str()
+            .map_err(|e| e.to_string())
+            .and_then(|target| serde_json::from_str(target).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(target) => Some(target),
+            Err(e) => {
+                callback_error
+                    .borrow_mut()
+                    .get_or_insert_with(|| format!("Invalid mention target JSON for {} {}: {}", kind_name, id, e));
+                None
+            }
+        }
+    };
+    let mut ast = match deserialize_ast(ast_str) {
+        Ok(ast) => ast,
+        Err(e) => return create_error_result(format!("Failed to parse AST JSON: {}", e)),
+    };
+    resolve_mentions(&mut ast, &resolver);
+    if let Some(message) = callback_error.into_inner() {
+        return create_error_result(message);
+    }
+    match serialize_ast(&ast) {
+        Ok(json) => create_success_result(json),
+        Err(e) => create_error_result(format!("Serialization error: {}", e)),
+    }
+}
+
+/// 判断消息是否提及了指定用户（直接提及或 @all / @here），用于通知角标
+/// @param ast_json JSON AST
+/// @param user_id 当前用户 ID
+/// @return 是否提及；参数为 null 或 AST 不合法时返回 false
+///
+/// # Safety
+/// `ast_json`、`user_id` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
+#[no_mangle]
+pub unsafe extern "C" fn imparse_mentions_user(ast_json: *const c_char, user_id: *const c_char) -> bool {
+    match (c_str(ast_json), c_str(user_id)) {
+        (Some(ast), Some(user_id)) => match deserialize_ast(ast) {
+            Ok(ast) => mentions_user(&ast, user_id),
+            Err(_) => false,
+        },
+        _ => false,
+    }
+}
+
+fn resolve_mentions_json<R: MentionResolver + ?Sized>(ast_json: &str, resolver: &R) -> *mut ParseResult {
+    let mut ast = match deserialize_ast(ast_json) {
+        Ok(ast) => ast,
+        Err(e) => return create_error_result(format!("Failed to parse AST JSON: {}", e)),
+    };
+    resolve_mentions(&mut ast, resolver);
+    match serialize_ast(&ast) {
+        Ok(json) => create_success_result(json),
+        Err(e) => create_error_result(format!("Serialization error: {}", e)),
+    }
+}
+
+/// 读取 C 字符串参数，为 null 或不是合法 UTF-8 时返回 `None`
+///
+/// # Safety
+/// `ptr` 必须为 null 或指向以 NUL 结尾的有效 C 字符串
+unsafe fn c_str<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    unsafe { CStr::from_ptr(ptr) }.to_str().ok()
+}
+
 /// 释放 ParseResult
+///
+/// # Safety
+/// `result` 必须为 null 或由本库返回且尚未释放的 ParseResult
 #[no_mangle]
-pub extern "C" fn free_parse_result(result: *mut ParseResult) {
+pub unsafe extern "C" fn free_parse_result(result: *mut ParseResult) {
     if !result.is_null() {
         unsafe {
             let result = Box::from_raw(result);
@@ -122,16 +485,22 @@ pub extern "C" fn free_parse_result(result: *mut ParseResult) {
 
 
 /// 将 Markdown 转换为 HTML
+///
+/// # Safety
+/// `input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
 #[no_mangle]
-pub extern "C" fn markdown_to_html(input: *const c_char) -> *mut ParseResult {
+pub unsafe extern "C" fn markdown_to_html(input: *const c_char) -> *mut ParseResult {
     markdown_to_html_with_config(input, ptr::null())
 }
 
 /// 将 Markdown 转换为 HTML（使用样式配置）
 /// @param input Markdown 字符串
 /// @param config_json 样式配置 JSON 字符串，如果为 null 则使用默认配置
+///
+/// # Safety
+/// `input`、`config_json` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
 #[no_mangle]
-pub extern "C" fn markdown_to_html_with_config(input: *const c_char, config_json: *const c_char) -> *mut ParseResult {
+pub unsafe extern "C" fn markdown_to_html_with_config(input: *const c_char, config_json: *const c_char) -> *mut ParseResult {
     let input_str = unsafe {
         if input.is_null() {
             return create_error_result("Input is null".to_string());
@@ -178,16 +547,22 @@ pub extern "C" fn markdown_to_html_with_config(input: *const c_char, config_json
 }
 
 /// 将 Delta 转换为 HTML
+///
+/// # Safety
+/// `input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
 #[no_mangle]
-pub extern "C" fn delta_to_html(input: *const c_char) -> *mut ParseResult {
+pub unsafe extern "C" fn delta_to_html(input: *const c_char) -> *mut ParseResult {
     delta_to_html_with_config(input, ptr::null())
 }
 
 /// 将 Delta 转换为 HTML（使用样式配置）
 /// @param input Delta JSON 字符串
 /// @param config_json 样式配置 JSON 字符串，如果为 null 则使用默认配置
+///
+/// # Safety
+/// `input`、`config_json` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
 #[no_mangle]
-pub extern "C" fn delta_to_html_with_config(input: *const c_char, config_json: *const c_char) -> *mut ParseResult {
+pub unsafe extern "C" fn delta_to_html_with_config(input: *const c_char, config_json: *const c_char) -> *mut ParseResult {
     let input_str = unsafe {
         if input.is_null() {
             return create_error_result("Input is null".to_string());
@@ -263,11 +638,51 @@ pub extern "C" fn get_dark_style_config() -> *mut c_char {
     }
 }
 
+/// 启用全局解析缓存（已启用时以新配置重建）
+/// @param max_entries 最多缓存的条目数
+/// @param max_bytes 缓存内容的估算总字节数上限
+#[no_mangle]
+pub extern "C" fn enable_parse_cache(max_entries: usize, max_bytes: usize) {
+    crate::enable_parse_cache(CacheConfig { max_entries, max_bytes });
+}
+
+/// 停用并释放全局解析缓存
+#[no_mangle]
+pub extern "C" fn disable_parse_cache() {
+    crate::disable_parse_cache();
+}
+
+/// 清空全局解析缓存
+#[no_mangle]
+pub extern "C" fn clear_parse_cache() {
+    crate::clear_parse_cache();
+}
+
+/// 获取全局解析缓存统计 JSON，未启用缓存时返回 null
+#[no_mangle]
+pub extern "C" fn get_parse_cache_stats() -> *mut c_char {
+    let Some(stats) = crate::parse_cache_stats() else {
+        return ptr::null_mut();
+    };
+    match serde_json::to_string(&stats) {
+        Ok(json) => {
+            match CString::new(json) {
+                Ok(c_string) => c_string.into_raw(),
+                Err(_) => ptr::null_mut(),
+            }
+        }
+        Err(_) => ptr::null_mut(),
+    }
+}
+
 /// 将数学公式转换为 HTML
 /// @param formula 数学公式字符串（LaTeX 格式）
 /// @param display 是否为块级公式（true 为块级，false 为行内）
+///
+/// # Safety
+/// `formula` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
 #[no_mangle]
-pub extern "C" fn math_to_html(formula: *const c_char, display: bool) -> *mut ParseResult {
+pub unsafe extern "C" fn math_to_html(formula: *const c_char, display: bool) -> *mut ParseResult {
     let formula_str = unsafe {
         if formula.is_null() {
             return create_error_result("Formula is null".to_string());
@@ -302,8 +717,11 @@ pub extern "C" fn math_to_html(formula: *const c_char, display: bool) -> *mut Pa
 /// @param mermaid_code Mermaid 语法代码
 /// @param text_color 文本颜色（十六进制，如 "#000000"）
 /// @param background_color 背景颜色（十六进制，如 "#ffffff"）
+///
+/// # Safety
+/// `mermaid_code`、`text_color`、`background_color` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
 #[no_mangle]
-pub extern "C" fn mermaid_to_html(
+pub unsafe extern "C" fn mermaid_to_html(
     mermaid_code: *const c_char,
     text_color: *const c_char,
     background_color: *const c_char,
@@ -358,6 +776,217 @@ pub extern "C" fn mermaid_to_html(
     }
 }
 
+/// 二进制结果（紧凑编码 / MessagePack / CBOR）
+///
+/// `data` 指向长度为 `len` 的字节缓冲区，需要调用 `free_binary_result` 释放。
+#[repr(C)]
+pub struct BinaryResult {
+    pub success: bool,
+    pub data: *const u8,
+    pub len: usize,
+    pub error: FFIError,
+}
+
+/// 解析 Markdown 为 MessagePack AST
+///
+/// # Safety
+/// `input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
+#[cfg(feature = "msgpack")]
+#[no_mangle]
+pub unsafe extern "C" fn parse_markdown_to_msgpack(input: *const c_char) -> *mut BinaryResult {
+    encode_parsed_ast(input, parse_markdown_shared, |ast| serialize_ast_msgpack(ast).map_err(|e| e.to_string()))
+}
+
+/// 解析 Delta 为 MessagePack AST
+///
+/// # Safety
+/// `input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
+#[cfg(feature = "msgpack")]
+#[no_mangle]
+pub unsafe extern "C" fn parse_delta_to_msgpack(input: *const c_char) -> *mut BinaryResult {
+    encode_parsed_ast(input, parse_delta_shared, |ast| serialize_ast_msgpack(ast).map_err(|e| e.to_string()))
+}
+
+/// 将 MessagePack AST 转换为 JSON AST
+/// @param data MessagePack 字节
+/// @param len 字节长度
+///
+/// # Safety
+/// `data` 必须为 null 或指向至少 `len` 个在调用期间有效的字节
+#[cfg(feature = "msgpack")]
+#[no_mangle]
+pub unsafe extern "C" fn msgpack_to_json(data: *const u8, len: usize) -> *mut ParseResult {
+    decode_to_json(data, len, |bytes| deserialize_ast_msgpack(bytes).map_err(|e| e.to_string()))
+}
+
+/// 解析 Markdown 为 CBOR AST
+///
+/// # Safety
+/// `input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
+#[cfg(feature = "cbor")]
+#[no_mangle]
+pub unsafe extern "C" fn parse_markdown_to_cbor(input: *const c_char) -> *mut BinaryResult {
+    encode_parsed_ast(input, parse_markdown_shared, |ast| serialize_ast_cbor(ast).map_err(|e| e.to_string()))
+}
+
+/// 解析 Delta 为 CBOR AST
+///
+/// # Safety
+/// `input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
+#[cfg(feature = "cbor")]
+#[no_mangle]
+pub unsafe extern "C" fn parse_delta_to_cbor(input: *const c_char) -> *mut BinaryResult {
+    encode_parsed_ast(input, parse_delta_shared, |ast| serialize_ast_cbor(ast).map_err(|e| e.to_string()))
+}
+
+/// 将 CBOR AST 转换为 JSON AST
+/// @param data CBOR 字节
+/// @param len 字节长度
+///
+/// # Safety
+/// `data` 必须为 null 或指向至少 `len` 个在调用期间有效的字节
+#[cfg(feature = "cbor")]
+#[no_mangle]
+pub unsafe extern "C" fn cbor_to_json(data: *const u8, len: usize) -> *mut ParseResult {
+    decode_to_json(data, len, |bytes| deserialize_ast_cbor(bytes).map_err(|e| e.to_string()))
+}
+
+/// 解析 Markdown 为紧凑编码 AST
+///
+/// # Safety
+/// `input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
+#[no_mangle]
+pub unsafe extern "C" fn parse_markdown_to_compact(input: *const c_char) -> *mut BinaryResult {
+    encode_parsed_ast(input, parse_markdown_shared, |ast| Ok(encode_compact(ast)))
+}
+
+/// 解析 Delta 为紧凑编码 AST
+///
+/// # Safety
+/// `input` 必须为 null 或指向以 NUL 结尾、在调用期间保持有效的 C 字符串
+#[no_mangle]
+pub unsafe extern "C" fn parse_delta_to_compact(input: *const c_char) -> *mut BinaryResult {
+    encode_parsed_ast(input, parse_delta_shared, |ast| Ok(encode_compact(ast)))
+}
+
+/// 将紧凑编码 AST 转换为 JSON AST
+/// @param data 紧凑编码字节
+/// @param len 字节长度
+///
+/// # Safety
+/// `data` 必须为 null 或指向至少 `len` 个在调用期间有效的字节
+#[no_mangle]
+pub unsafe extern "C" fn compact_to_json(data: *const u8, len: usize) -> *mut ParseResult {
+    decode_to_json(data, len, |bytes| decode_compact(bytes).map_err(|e| e.to_string()))
+}
+
+/// 释放 BinaryResult
+///
+/// # Safety
+/// `result` 必须为 null 或由本库返回且尚未释放的 BinaryResult
+#[no_mangle]
+pub unsafe extern "C" fn free_binary_result(result: *mut BinaryResult) {
+    if !result.is_null() {
+        unsafe {
+            let result = Box::from_raw(result);
+            if !result.data.is_null() {
+                let _ = Box::from_raw(ptr::slice_from_raw_parts_mut(result.data as *mut u8, result.len));
+            }
+            if !result.error.message.is_null() {
+                free_string(result.error.message as *mut c_char);
+            }
+        }
+    }
+}
+
+/// # Safety
+/// `input` 必须为 null 或指向以 NUL 结尾的有效 C 字符串
+unsafe fn encode_parsed_ast(
+    input: *const c_char,
+    parse: fn(&str) -> Result<Arc<RootNode>, ParseError>,
+    encode: impl FnOnce(&RootNode) -> Result<Vec<u8>, String>,
+) -> *mut BinaryResult {
+    let input_str = unsafe {
+        if input.is_null() {
+            return create_binary_error_result("Input is null".to_string());
+        }
+        match CStr::from_ptr(input).to_str() {
+            Ok(s) => s,
+            Err(_) => return create_binary_error_result("Invalid UTF-8 string".to_string()),
+        }
+    };
+
+    match parse(input_str) {
+        Ok(ast) => match encode(&ast) {
+            Ok(bytes) => {
+                let len = bytes.len();
+                let data = Box::into_raw(bytes.into_boxed_slice()) as *const u8;
+                Box::into_raw(Box::new(BinaryResult {
+                    success: true,
+                    data,
+                    len,
+                    error: FFIError {
+                        code: 0,
+                        message: ptr::null(),
+                    },
+                }))
+            }
+            Err(e) => create_binary_error_result(format!("Serialization error: {}", e)),
+        },
+        Err(e) => create_binary_error_result(format!("Parse error: {}", e)),
+    }
+}
+
+/// # Safety
+/// `data` 必须为 null 或指向至少 `len` 个有效字节
+unsafe fn decode_to_json(
+    data: *const u8,
+    len: usize,
+    decode: impl FnOnce(&[u8]) -> Result<RootNode, String>,
+) -> *mut ParseResult {
+    if data.is_null() {
+        return create_error_result("Input is null".to_string());
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+
+    match decode(bytes) {
+        Ok(ast) => match serialize_ast(&ast) {
+            Ok(json) => {
+                let c_string = match CString::new(json) {
+                    Ok(s) => s,
+                    Err(_) => return create_error_result("Failed to create CString".to_string()),
+                };
+                Box::into_raw(Box::new(ParseResult {
+                    success: true,
+                    ast_json: c_string.into_raw(),
+                    error: FFIError {
+                        code: 0,
+                        message: ptr::null(),
+                    },
+                }))
+            }
+            Err(e) => create_error_result(format!("Serialization error: {}", e)),
+        },
+        Err(e) => create_error_result(format!("Deserialization error: {}", e)),
+    }
+}
+
+fn create_binary_error_result(message: String) -> *mut BinaryResult {
+    let error_msg = match CString::new(message) {
+        Ok(s) => s.into_raw(),
+        Err(_) => ptr::null(),
+    };
+    Box::into_raw(Box::new(BinaryResult {
+        success: false,
+        data: ptr::null(),
+        len: 0,
+        error: FFIError {
+            code: 1,
+            message: error_msg,
+        },
+    }))
+}
+
 fn create_error_result(message: String) -> *mut ParseResult {
     let error_msg = match CString::new(message.clone()) {
         Ok(s) => s.into_raw(),
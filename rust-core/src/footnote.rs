@@ -0,0 +1,122 @@
+use crate::ast::*;
+use crate::visitor::*;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+/// 为脚注编号并关联引用与定义
+///
+/// - 按正文中首次引用的顺序从 1 开始编号，同一标签的多次引用共用编号
+/// - 脚注定义（包括嵌套在引用块、列表项中的）按编号顺序移到根节点末尾；未被引用的定义排在最后，
+///   同一标签只保留第一个定义
+/// - 没有对应定义的引用还原为 `[^label]` 文本
+///
+/// 标签比较不区分大小写，并忽略多余的空白（与 CommonMark 链接标签规则一致）。
+pub fn number_footnotes(root: &mut RootNode) {
+    let mut hoisting = FootnoteHoisting::default();
+    hoisting.take_definitions(&mut root.children);
+    hoisting.visit_root_mut(root);
+
+    let mut definitions: HashMap<String, FootnoteDefinitionNode> = HashMap::new();
+    let mut definition_order = Vec::new();
+    for definition in hoisting.definitions {
+        let key = normalize_label(&definition.label);
+        if let Entry::Vacant(entry) = definitions.entry(key.clone()) {
+            definition_order.push(key);
+            entry.insert(definition);
+        }
+    }
+
+    let mut numbering = FootnoteNumbering {
+        defined: &definitions,
+        order: Vec::new(),
+    };
+    numbering.visit_root_mut(root);
+
+    // 脚注内容中也可能引用其他脚注，编号过程中 order 会继续增长
+    let mut numbered = Vec::with_capacity(definitions.len());
+    let mut next = 0;
+    while next < numbering.order.len() {
+        let key = numbering.order[next].clone();
+        next += 1;
+        if let Some(mut definition) = definitions.get(&key).cloned() {
+            walk_footnote_definition_mut(&mut numbering, &mut definition);
+            numbered.push((key, definition));
+        }
+    }
+
+    let mut order = numbering.order;
+    for key in definition_order {
+        if !order.contains(&key) {
+            order.push(key.clone());
+            if let Some(definition) = definitions.get(&key).cloned() {
+                numbered.push((key, definition));
+            }
+        }
+    }
+
+    for (key, mut definition) in numbered {
+        definition.index = footnote_index(&order, &key);
+        root.children.push(ASTNode::FootnoteDefinition(definition));
+    }
+}
+
+/// 按文档顺序取出所有脚注定义
+#[derive(Default)]
+struct FootnoteHoisting {
+    definitions: Vec<FootnoteDefinitionNode>,
+}
+
+impl FootnoteHoisting {
+    fn take_definitions(&mut self, children: &mut Vec<ASTNode>) {
+        for child in std::mem::take(children) {
+            match child {
+                ASTNode::FootnoteDefinition(definition) => self.definitions.push(definition),
+                child => children.push(child),
+            }
+        }
+    }
+}
+
+impl VisitorMut for FootnoteHoisting {
+    fn visit_list_item_mut(&mut self, item: &mut ListItemNode) {
+        self.take_definitions(&mut item.children);
+        walk_list_item_mut(self, item);
+    }
+
+    fn visit_blockquote_mut(&mut self, blockquote: &mut BlockquoteNode) {
+        self.take_definitions(&mut blockquote.children);
+        walk_blockquote_mut(self, blockquote);
+    }
+}
+
+struct FootnoteNumbering<'a> {
+    defined: &'a HashMap<String, FootnoteDefinitionNode>,
+    /// 已编号的标签，下标 + 1 即编号
+    order: Vec<String>,
+}
+
+impl VisitorMut for FootnoteNumbering<'_> {
+    fn visit_node_mut(&mut self, node: &mut ASTNode) {
+        if let ASTNode::FootnoteReference(reference) = node {
+            let key = normalize_label(&reference.label);
+            if !self.defined.contains_key(&key) {
+                *node = ASTNode::Text(TextNode { content: format!("[^{}]", reference.label) });
+                return;
+            }
+            if !self.order.contains(&key) {
+                self.order.push(key.clone());
+            }
+            reference.index = footnote_index(&self.order, &key);
+            return;
+        }
+        walk_node_mut(self, node);
+    }
+}
+
+fn footnote_index(order: &[String], key: &str) -> u32 {
+    order.iter().position(|label| label == key).map_or(0, |position| position as u32 + 1)
+}
+
+fn normalize_label(label: &str) -> String {
+    label.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
@@ -0,0 +1,887 @@
+use crate::ast::*;
+use crate::html_renderer::is_block_node;
+use crate::normalize::normalize;
+use crate::sanitize::{sanitize, SanitizeOptions};
+use crate::ParseError;
+
+/// HTML 解析器
+///
+/// 将邮件、剪贴板、Matrix `formatted_body` 等来源的 HTML 转换为 AST。只映射安全的标签子集：
+/// `p`、`h1`-`h6`、`b`/`strong`、`i`/`em`、`u`、`s`、`code`、`pre`、`a`、`img`、`ul`/`ol`/`li`、
+/// `table`、`blockquote`、`hr`、`br`；其余标签去掉标签本身保留文本，`script`、`style` 等连同内容一起丢弃。
+/// 输入通常不可信，解析完成后总是按 [`SanitizeOptions`] 清理链接和图片地址。
+#[derive(Hash)]
+pub struct HtmlParser {
+    normalize: bool,
+    sanitize: SanitizeOptions,
+}
+
+impl HtmlParser {
+    pub fn new() -> Self {
+        Self { normalize: false, sanitize: SanitizeOptions::default() }
+    }
+
+    /// 设置是否在解析完成后自动执行 [`normalize`]
+    pub fn with_normalize(mut self, enabled: bool) -> Self {
+        self.normalize = enabled;
+        self
+    }
+
+    /// 设置清理链接和图片地址使用的选项
+    pub fn with_sanitize_options(mut self, options: SanitizeOptions) -> Self {
+        self.sanitize = options;
+        self
+    }
+
+    pub fn parse(&self, input: &str) -> Result<RootNode, ParseError> {
+        let dom = build_dom(input);
+        let mut root = RootNode::new();
+        convert_blocks(&dom, &mut root.children);
+        sanitize(&mut root, &self.sanitize);
+        if self.normalize {
+            normalize(&mut root);
+        }
+        Ok(root)
+    }
+}
+
+impl Default for HtmlParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 元素最大嵌套深度，更深的开始标签被忽略（内容归入外层元素），避免转换时栈溢出
+const MAX_DEPTH: usize = 128;
+
+/// 没有结束标签的空元素
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+];
+
+/// 内容按原始文本读取并整体丢弃的元素
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title", "xmp", "noembed", "noframes"];
+
+/// 连同内容一起丢弃的元素
+const DROPPED_ELEMENTS: &[&str] = &[
+    "head", "template", "iframe", "object", "svg", "math", "select", "button", "noscript", "canvas", "audio", "video",
+];
+
+/// 会结束未闭合 `<p>` 的块级元素
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "center", "details", "dd", "div", "dl", "dt", "fieldset",
+    "figcaption", "figure", "footer", "form", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "li", "main",
+    "nav", "ol", "p", "pre", "section", "summary", "table", "tbody", "td", "tfoot", "th", "thead", "tr", "ul",
+];
+
+#[derive(Debug)]
+enum Dom {
+    Element(Element),
+    Text(String),
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Dom>,
+}
+
+impl Element {
+    fn new(name: &str, attrs: Vec<(String, String)>) -> Self {
+        Self { name: name.to_string(), attrs, children: Vec::new() }
+    }
+
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// 容错地构建 DOM 树
+///
+/// 按 HTML 的常见规则隐式结束 `p`、`li`、`td`/`th`、`tr` 等元素；没有对应开始标签的结束标签被忽略。
+fn build_dom(input: &str) -> Vec<Dom> {
+    let mut stack = vec![Element::new("#root", Vec::new())];
+    let bytes = input.as_bytes();
+    let mut pos = 0;
+    let mut text_start = 0;
+
+    while pos < bytes.len() {
+        if bytes[pos] != b'<' {
+            pos += 1;
+            continue;
+        }
+        let rest = &input[pos..];
+        let next = bytes.get(pos + 1).copied().unwrap_or(0);
+        let markup_end = if let Some(comment) = rest.strip_prefix("<!--") {
+            Some(comment.find("-->").map_or(input.len(), |end| pos + 4 + end + 3))
+        } else if next == b'!' || next == b'?' {
+            Some(rest.find('>').map_or(input.len(), |end| pos + end + 1))
+        } else if next == b'/' && bytes.get(pos + 2).is_some_and(u8::is_ascii_alphabetic) {
+            Some(rest.find('>').map_or(input.len(), |end| pos + end + 1))
+        } else if next.is_ascii_alphabetic() {
+            Some(pos)
+        } else {
+            None
+        };
+        let Some(markup_end) = markup_end else {
+            pos += 1;
+            continue;
+        };
+
+        push_text(&mut stack, &input[text_start..pos]);
+        if next == b'/' {
+            let name = tag_name(&rest[2..]);
+            close_element(&mut stack, &name);
+            pos = markup_end;
+        } else if next.is_ascii_alphabetic() {
+            let (tag, end) = read_start_tag(rest);
+            pos += end;
+            if RAW_TEXT_ELEMENTS.contains(&tag.name.as_str()) {
+                pos = skip_raw_text(input, pos, &tag.name);
+            } else if !tag.self_closing || VOID_ELEMENTS.contains(&tag.name.as_str()) {
+                open_element(&mut stack, tag.name, tag.attrs);
+            }
+        } else {
+            pos = markup_end;
+        }
+        text_start = pos;
+    }
+    push_text(&mut stack, &input[text_start..]);
+
+    while stack.len() > 1 {
+        pop_element(&mut stack);
+    }
+    stack.pop().map(|root| root.children).unwrap_or_default()
+}
+
+fn push_text(stack: &mut [Element], raw: &str) {
+    if raw.is_empty() {
+        return;
+    }
+    let text = decode_entities(raw);
+    if let Some(current) = stack.last_mut() {
+        match current.children.last_mut() {
+            Some(Dom::Text(prev)) => prev.push_str(&text),
+            _ => current.children.push(Dom::Text(text)),
+        }
+    }
+}
+
+fn pop_element(stack: &mut Vec<Element>) {
+    if stack.len() > 1 {
+        if let Some(element) = stack.pop() {
+            if let Some(parent) = stack.last_mut() {
+                parent.children.push(Dom::Element(element));
+            }
+        }
+    }
+}
+
+/// 从栈顶向下查找 `names` 中的元素，遇到 `boundaries` 中的元素时停止
+fn find_open(stack: &[Element], names: &[&str], boundaries: &[&str]) -> Option<usize> {
+    for (index, element) in stack.iter().enumerate().rev() {
+        if names.contains(&element.name.as_str()) {
+            return Some(index);
+        }
+        if boundaries.contains(&element.name.as_str()) {
+            return None;
+        }
+    }
+    None
+}
+
+fn open_element(stack: &mut Vec<Element>, name: String, attrs: Vec<(String, String)>) {
+    let implicit_end = match name.as_str() {
+        "li" => find_open(stack, &["li"], &["ul", "ol", "table"]),
+        "td" | "th" => find_open(stack, &["td", "th"], &["tr", "table"]),
+        "tr" => find_open(stack, &["tr"], &["table"]),
+        "thead" | "tbody" | "tfoot" => find_open(stack, &["thead", "tbody", "tfoot"], &["table"]),
+        _ => None,
+    };
+    if let Some(index) = implicit_end {
+        while stack.len() > index {
+            pop_element(stack);
+        }
+    }
+    if BLOCK_ELEMENTS.contains(&name.as_str()) {
+        if let Some(index) = find_open(stack, &["p"], BLOCK_ELEMENTS) {
+            while stack.len() > index {
+                pop_element(stack);
+            }
+        }
+    }
+
+    let element = Element::new(&name, attrs);
+    if VOID_ELEMENTS.contains(&name.as_str()) || stack.len() > MAX_DEPTH {
+        if let Some(current) = stack.last_mut() {
+            current.children.push(Dom::Element(element));
+        }
+    } else {
+        stack.push(element);
+    }
+}
+
+fn close_element(stack: &mut Vec<Element>, name: &str) {
+    let boundaries: &[&str] = match name {
+        "li" => &["ul", "ol"],
+        "td" | "th" | "tr" | "thead" | "tbody" | "tfoot" => &["table"],
+        _ => &[],
+    };
+    if let Some(index) = find_open(stack, &[name], boundaries).filter(|&index| index > 0) {
+        while stack.len() > index {
+            pop_element(stack);
+        }
+    }
+}
+
+pub(crate) struct StartTag {
+    pub(crate) name: String,
+    pub(crate) attrs: Vec<(String, String)>,
+    pub(crate) self_closing: bool,
+}
+
+/// 读取标签名（小写），`input` 从标签名开始
+pub(crate) fn tag_name(input: &str) -> String {
+    input
+        .split(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// 解析从 `<` 开始的开始标签，返回标签和消耗的字节数
+pub(crate) fn read_start_tag(input: &str) -> (StartTag, usize) {
+    let bytes = input.as_bytes();
+    let name = tag_name(&input[1..]);
+    let mut pos = 1 + name.len();
+    let mut attrs: Vec<(String, String)> = Vec::new();
+    let mut self_closing = false;
+
+    loop {
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        match bytes.get(pos) {
+            None => break,
+            Some(b'>') => {
+                pos += 1;
+                break;
+            }
+            Some(b'/') => {
+                self_closing = bytes.get(pos + 1) == Some(&b'>');
+                pos += 1;
+                continue;
+            }
+            Some(_) => {}
+        }
+
+        let key_start = pos;
+        while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() && !matches!(bytes[pos], b'=' | b'>' | b'/') {
+            pos += 1;
+        }
+        if pos == key_start {
+            // 单独的 `=` 之类的无效字符
+            pos += 1;
+            continue;
+        }
+        let key = input[key_start..pos].to_ascii_lowercase();
+
+        let mut lookahead = pos;
+        while lookahead < bytes.len() && bytes[lookahead].is_ascii_whitespace() {
+            lookahead += 1;
+        }
+        let mut value = String::new();
+        if bytes.get(lookahead) == Some(&b'=') {
+            pos = lookahead + 1;
+            while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            match bytes.get(pos) {
+                Some(&quote @ (b'"' | b'\'')) => {
+                    let end = input[pos + 1..].find(quote as char).map_or(input.len(), |end| pos + 1 + end);
+                    value = decode_entities(&input[pos + 1..end]);
+                    pos = (end + 1).min(input.len());
+                }
+                _ => {
+                    let start = pos;
+                    while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() && bytes[pos] != b'>' {
+                        pos += 1;
+                    }
+                    value = decode_entities(&input[start..pos]);
+                }
+            }
+        }
+        if !attrs.iter().any(|(existing, _)| *existing == key) {
+            attrs.push((key, value));
+        }
+    }
+
+    (StartTag { name, attrs, self_closing }, pos)
+}
+
+/// 跳过原始文本元素的内容和结束标签，返回之后的位置
+fn skip_raw_text(input: &str, pos: usize, name: &str) -> usize {
+    let closing = format!("</{}", name);
+    let bytes = input.as_bytes();
+    let mut search = pos;
+    while let Some(offset) = input[search..].find("</") {
+        let start = search + offset;
+        let candidate = &bytes[start..];
+        if candidate.len() >= closing.len() && candidate[..closing.len()].eq_ignore_ascii_case(closing.as_bytes()) {
+            return input[start..].find('>').map_or(input.len(), |end| start + end + 1);
+        }
+        search = start + 2;
+    }
+    input.len()
+}
+
+/// 解码字符引用（`&amp;`、`&#39;`、`&#x4e2d;` 等），无法识别的保留原样
+fn decode_entities(input: &str) -> String {
+    if !input.contains('&') {
+        return input.to_string();
+    }
+
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(amp) = rest.find('&') {
+        result.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest[1..].find(';').filter(|&end| end > 0 && end <= 32).and_then(|end| {
+            let name = &rest[1..=end];
+            let c = match name.strip_prefix('#') {
+                Some(number) => {
+                    let code = match number.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => number.parse().ok(),
+                    };
+                    code.map(|code| char::from_u32(code).filter(|&c| c != '\0').unwrap_or('\u{fffd}'))
+                }
+                None => named_entity(name),
+            }?;
+            Some((c, end + 2))
+        });
+        match decoded {
+            Some((c, len)) => {
+                result.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn named_entity(name: &str) -> Option<char> {
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "ensp" => '\u{2002}',
+        "emsp" => '\u{2003}',
+        "thinsp" => '\u{2009}',
+        "zwnj" => '\u{200c}',
+        "zwj" => '\u{200d}',
+        "shy" => '\u{ad}',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "bull" => '•',
+        "middot" => '·',
+        "deg" => '°',
+        "plusmn" => '±',
+        "times" => '×',
+        "divide" => '÷',
+        "para" => '¶',
+        "sect" => '§',
+        "cent" => '¢',
+        "pound" => '£',
+        "yen" => '¥',
+        "euro" => '€',
+        "larr" => '←',
+        "rarr" => '→',
+        "uarr" => '↑',
+        "darr" => '↓',
+        _ => return None,
+    })
+}
+
+/// 将流式内容（根节点、引用块、列表项中的块级内容）转换为块级节点，散落的行内内容包装为段落
+fn convert_blocks(nodes: &[Dom], out: &mut Vec<ASTNode>) {
+    let mut inline = Vec::new();
+    for node in nodes {
+        let element = match node {
+            Dom::Element(element) if is_block(element) || contains_block(element) => element,
+            node => {
+                convert_inline(node, &mut inline);
+                continue;
+            }
+        };
+        flush_paragraph(&mut inline, out);
+
+        match element.name.as_str() {
+            "p" => {
+                let mut children = Vec::new();
+                convert_inlines(&element.children, &mut children);
+                flush_paragraph(&mut children, out);
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let mut children = Vec::new();
+                convert_inlines(&element.children, &mut children);
+                tidy_inline(&mut children);
+                if !children.is_empty() {
+                    let level = element.name[1..].parse().unwrap_or(1);
+                    out.push(ASTNode::Heading(HeadingNode { level, children }));
+                }
+            }
+            "pre" => out.push(convert_pre(element)),
+            "ul" | "ol" => {
+                if let Some(list) = convert_list(element) {
+                    out.push(list);
+                }
+            }
+            "table" => {
+                if let Some(table) = convert_table(element) {
+                    out.push(table);
+                }
+            }
+            "blockquote" => {
+                let mut children = Vec::new();
+                convert_blocks(&element.children, &mut children);
+                if !children.is_empty() {
+                    out.push(ASTNode::Blockquote(BlockquoteNode { children }));
+                }
+            }
+            "hr" => out.push(ASTNode::HorizontalRule(HorizontalRuleNode)),
+            // div、body 等其他容器去掉容器本身，内容按流式内容处理
+            _ => convert_blocks(&element.children, out),
+        }
+    }
+    flush_paragraph(&mut inline, out);
+}
+
+/// 把累积的行内内容作为段落输出，只有空白时丢弃
+fn flush_paragraph(inline: &mut Vec<ASTNode>, out: &mut Vec<ASTNode>) {
+    let mut children = std::mem::take(inline);
+    tidy_inline(&mut children);
+    if !children.is_empty() {
+        out.push(ASTNode::Paragraph(ParagraphNode { children }));
+    }
+}
+
+fn is_block(element: &Element) -> bool {
+    BLOCK_ELEMENTS.contains(&element.name.as_str())
+}
+
+/// 行内元素中是否嵌套了块级元素（如邮件中常见的 `<font><div>…</div></font>`）
+fn contains_block(element: &Element) -> bool {
+    !is_dropped(element)
+        && element.children.iter().any(|child| match child {
+            Dom::Element(child) => is_block(child) || contains_block(child),
+            Dom::Text(_) => false,
+        })
+}
+
+fn is_dropped(element: &Element) -> bool {
+    DROPPED_ELEMENTS.contains(&element.name.as_str())
+}
+
+fn convert_inlines(nodes: &[Dom], out: &mut Vec<ASTNode>) {
+    for node in nodes {
+        convert_inline(node, out);
+    }
+}
+
+/// 转换行内内容；行内位置出现的块级元素只保留其中的行内内容，前后补空白避免文字粘连
+fn convert_inline(node: &Dom, out: &mut Vec<ASTNode>) {
+    let element = match node {
+        Dom::Text(text) => {
+            push_inline_text(out, &collapse_whitespace(text));
+            return;
+        }
+        Dom::Element(element) => element,
+    };
+    if is_dropped(element) {
+        return;
+    }
+
+    let children = || {
+        let mut children = Vec::new();
+        convert_inlines(&element.children, &mut children);
+        children
+    };
+    match element.name.as_str() {
+        "b" | "strong" => out.push(ASTNode::Strong(StrongNode { children: children() })),
+        "i" | "em" => out.push(ASTNode::Em(EmNode { children: children() })),
+        "u" | "ins" => out.push(ASTNode::Underline(UnderlineNode { children: children() })),
+        "s" | "strike" | "del" => out.push(ASTNode::Strike(StrikeNode { children: children() })),
+        "code" => out.push(ASTNode::Code(CodeNode { content: collapse_whitespace(&text_content(element)) })),
+        "a" => match element.attr("href").map(str::trim).filter(|href| !href.is_empty()) {
+            Some(href) => out.push(ASTNode::Link(LinkNode { url: href.to_string(), children: children() })),
+            None => out.extend(children()),
+        },
+        "img" => {
+            if let Some(image) = convert_image(element) {
+                out.push(image);
+            }
+        }
+        "br" => {
+            if let Some(ASTNode::Text(prev)) = out.last_mut() {
+                prev.content.truncate(prev.content.trim_end_matches(' ').len());
+            }
+            push_inline_text(out, "\n");
+        }
+        "pre" => out.push(ASTNode::Code(CodeNode { content: collapse_whitespace(&text_content(element)) })),
+        "hr" | "input" => {}
+        _ if is_block(element) => {
+            push_inline_text(out, " ");
+            convert_inlines(&element.children, out);
+            push_inline_text(out, " ");
+        }
+        // 不认识的行内标签（span、font、sub 等）去掉标签保留内容
+        _ => convert_inlines(&element.children, out),
+    }
+}
+
+fn push_inline_text(out: &mut Vec<ASTNode>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match out.last_mut() {
+        Some(ASTNode::Text(prev)) if prev.content.ends_with([' ', '\n']) => {
+            prev.content.push_str(text.strip_prefix(' ').unwrap_or(text))
+        }
+        Some(ASTNode::Text(prev)) => prev.content.push_str(text),
+        _ => out.push(ASTNode::Text(TextNode { content: text.to_string() })),
+    }
+}
+
+fn convert_image(element: &Element) -> Option<ASTNode> {
+    let url = element.attr("src").map(str::trim).filter(|src| !src.is_empty())?;
+    let dimension = |name: &str| {
+        element
+            .attr(name)
+            .and_then(|value| value.trim().trim_end_matches("px").parse::<f32>().ok())
+            .filter(|value| value.is_finite() && *value > 0.0)
+    };
+    Some(ASTNode::Image(ImageNode {
+        url: url.to_string(),
+        width: dimension("width"),
+        height: dimension("height"),
+        alt: element.attr("alt").filter(|alt| !alt.is_empty()).map(str::to_string),
+    }))
+}
+
+/// `<pre>` 转换为代码块，语言取自 `pre` 或内部 `code` 的 `language-xxx`/`lang-xxx` class
+fn convert_pre(element: &Element) -> ASTNode {
+    let code = match element.children.as_slice() {
+        [Dom::Element(code)] if code.name == "code" => Some(code),
+        _ => None,
+    };
+    let language = code.and_then(language_class).or_else(|| language_class(element));
+    let content = text_content(element);
+    // 与浏览器一致，忽略紧跟在 `<pre>` 后的第一个换行
+    let mut content = content.strip_prefix('\n').unwrap_or(&content).to_string();
+    // 与 Markdown、Slack 代码块一致，非空内容以换行结尾
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    ASTNode::CodeBlock(CodeBlockNode { language, content })
+}
+
+fn language_class(element: &Element) -> Option<String> {
+    element.attr("class")?.split_ascii_whitespace().find_map(|class| {
+        class
+            .strip_prefix("language-")
+            .or_else(|| class.strip_prefix("lang-"))
+            .filter(|language| !language.is_empty())
+            .map(str::to_string)
+    })
+}
+
+/// 元素内的全部文本，`<br>` 视为换行
+fn text_content(element: &Element) -> String {
+    fn collect(nodes: &[Dom], out: &mut String) {
+        for node in nodes {
+            match node {
+                Dom::Text(text) => out.push_str(text),
+                Dom::Element(element) if element.name == "br" => out.push('\n'),
+                Dom::Element(element) if !is_dropped(element) => collect(&element.children, out),
+                Dom::Element(_) => {}
+            }
+        }
+    }
+    let mut out = String::new();
+    collect(&element.children, &mut out);
+    out
+}
+
+fn convert_list(element: &Element) -> Option<ASTNode> {
+    let list_type = if element.name == "ol" { ListType::Ordered } else { ListType::Bullet };
+    let mut items = Vec::new();
+    let mut stray = Vec::new();
+    for child in &element.children {
+        match child {
+            Dom::Element(item) if item.name == "li" => {
+                items.extend(convert_list_item(std::mem::take(&mut stray)));
+                items.extend(convert_list_item(item.children.iter().collect()));
+            }
+            Dom::Element(nested) if matches!(nested.name.as_str(), "ul" | "ol") => {
+                // 直接嵌套在列表中的子列表归入上一项
+                match items.last_mut() {
+                    Some(ListItemNode { children, .. }) => {
+                        if !children.iter().all(is_block_node) {
+                            let mut inline = std::mem::take(children);
+                            flush_paragraph(&mut inline, children);
+                        }
+                        children.extend(convert_list(nested));
+                    }
+                    None => stray.push(child),
+                }
+            }
+            child => stray.push(child),
+        }
+    }
+    items.extend(convert_list_item(stray));
+
+    (!items.is_empty()).then_some(ASTNode::List(ListNode { list_type, items }))
+}
+
+/// 只有行内内容的列表项直接以行内节点为子节点（与 Markdown 紧凑列表一致），否则按流式内容转换
+fn convert_list_item(nodes: Vec<&Dom>) -> Option<ListItemNode> {
+    let checked = nodes.iter().find_map(|node| match node {
+        Dom::Element(input) if input.name == "input" => input
+            .attr("type")
+            .filter(|kind| kind.eq_ignore_ascii_case("checkbox"))
+            .map(|_| input.attr("checked").is_some()),
+        Dom::Element(wrapper) if matches!(wrapper.name.as_str(), "p" | "label") => {
+            wrapper.children.iter().find_map(|node| match node {
+                Dom::Element(input) if input.name == "input" => input
+                    .attr("type")
+                    .filter(|kind| kind.eq_ignore_ascii_case("checkbox"))
+                    .map(|_| input.attr("checked").is_some()),
+                _ => None,
+            })
+        }
+        _ => None,
+    });
+
+    let has_block = nodes.iter().any(|node| matches!(node, Dom::Element(element) if is_block(element)));
+    let mut children = Vec::new();
+    if has_block {
+        let mut inline = Vec::new();
+        for node in &nodes {
+            match node {
+                Dom::Element(element) if is_block(element) => {
+                    flush_paragraph(&mut inline, &mut children);
+                    convert_blocks(std::slice::from_ref(*node), &mut children);
+                }
+                node => convert_inline(node, &mut inline),
+            }
+        }
+        flush_paragraph(&mut inline, &mut children);
+    } else {
+        for node in &nodes {
+            convert_inline(node, &mut children);
+        }
+        tidy_inline(&mut children);
+    }
+
+    (!children.is_empty() || checked.is_some()).then_some(ListItemNode { children, checked })
+}
+
+fn convert_table(element: &Element) -> Option<ASTNode> {
+    let mut rows = Vec::new();
+    collect_rows(&element.children, &mut rows);
+    (!rows.is_empty()).then_some(ASTNode::Table(TableNode { rows }))
+}
+
+fn collect_rows(nodes: &[Dom], rows: &mut Vec<TableRow>) {
+    for node in nodes {
+        let Dom::Element(element) = node else {
+            continue;
+        };
+        match element.name.as_str() {
+            "tr" => {
+                let cells: Vec<TableCell> = element
+                    .children
+                    .iter()
+                    .filter_map(|cell| match cell {
+                        Dom::Element(cell) if matches!(cell.name.as_str(), "td" | "th") => Some(convert_cell(cell)),
+                        _ => None,
+                    })
+                    .collect();
+                if !cells.is_empty() {
+                    rows.push(TableRow { cells });
+                }
+            }
+            "thead" | "tbody" | "tfoot" => collect_rows(&element.children, rows),
+            _ => {}
+        }
+    }
+}
+
+/// 单元格只包含行内内容，块级内容被展开；表头单元格不单独标记
+fn convert_cell(cell: &Element) -> TableCell {
+    let mut children = Vec::new();
+    convert_inlines(&cell.children, &mut children);
+    tidy_inline(&mut children);
+
+    let style_align = cell.attr("style").and_then(|style| {
+        style.split(';').find_map(|declaration| {
+            let (property, value) = declaration.split_once(':')?;
+            property.trim().eq_ignore_ascii_case("text-align").then(|| value.trim())
+        })
+    });
+    let align = match style_align.or(cell.attr("align")).map(str::to_ascii_lowercase).as_deref() {
+        Some("left") => Some(TextAlign::Left),
+        Some("center") => Some(TextAlign::Center),
+        Some("right") => Some(TextAlign::Right),
+        _ => None,
+    };
+    TableCell { children, align }
+}
+
+/// 把连续空白折叠为一个空格（不包括不换行空格）
+fn collapse_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0c') {
+            if !in_space {
+                result.push(' ');
+                in_space = true;
+            }
+        } else {
+            result.push(c);
+            in_space = false;
+        }
+    }
+    result
+}
+
+/// 整理一段行内内容的空白：样式节点首尾的空白移到节点外，跨节点折叠重复空格，去掉首尾空白，再移除空文本和空样式节点
+fn tidy_inline(nodes: &mut Vec<ASTNode>) {
+    hoist_whitespace(nodes);
+    let mut prev_space = true;
+    collapse_across(nodes, &mut prev_space);
+    trim_end(nodes);
+    prune_empty(nodes);
+}
+
+fn style_children_mut(node: &mut ASTNode) -> Option<&mut Vec<ASTNode>> {
+    match node {
+        ASTNode::Strong(StrongNode { children })
+        | ASTNode::Em(EmNode { children })
+        | ASTNode::Underline(UnderlineNode { children })
+        | ASTNode::Strike(StrikeNode { children })
+        | ASTNode::Link(LinkNode { children, .. }) => Some(children),
+        _ => None,
+    }
+}
+
+/// `a<i> x </i>b` 中的空白属于外层文本，移到样式节点外得到 `a *x* b`，而不是留在强调标记内侧
+fn hoist_whitespace(nodes: &mut Vec<ASTNode>) {
+    let mut result = Vec::with_capacity(nodes.len());
+    for mut node in std::mem::take(nodes) {
+        if let ASTNode::Text(text) = &node {
+            push_inline_text(&mut result, &text.content);
+            continue;
+        }
+        let Some(children) = style_children_mut(&mut node) else {
+            result.push(node);
+            continue;
+        };
+        hoist_whitespace(children);
+        let leading = take_edge_whitespace(children, true);
+        let trailing = take_edge_whitespace(children, false);
+        push_inline_text(&mut result, &leading);
+        result.push(node);
+        push_inline_text(&mut result, &trailing);
+    }
+    *nodes = result;
+}
+
+/// 取出首个（`leading`）或末尾文本节点开头或结尾的空白，文本只有空白时移除该节点
+fn take_edge_whitespace(children: &mut Vec<ASTNode>, leading: bool) -> String {
+    let index = if leading { 0 } else { children.len().saturating_sub(1) };
+    let Some(ASTNode::Text(text)) = children.get_mut(index) else {
+        return String::new();
+    };
+    let whitespace = if leading {
+        let kept = text.content.trim_start_matches([' ', '\n']).len();
+        text.content.drain(..text.content.len() - kept).collect()
+    } else {
+        let kept = text.content.trim_end_matches([' ', '\n']).len();
+        text.content.split_off(kept)
+    };
+    if text.content.is_empty() {
+        children.remove(index);
+    }
+    whitespace
+}
+
+fn collapse_across(nodes: &mut [ASTNode], prev_space: &mut bool) {
+    for node in nodes {
+        if let ASTNode::Text(text) = node {
+            if *prev_space && text.content.starts_with(' ') {
+                text.content.remove(0);
+            }
+            if let Some(last) = text.content.chars().last() {
+                *prev_space = matches!(last, ' ' | '\n');
+            }
+        } else if let Some(children) = style_children_mut(node) {
+            collapse_across(children, prev_space);
+        } else {
+            *prev_space = false;
+        }
+    }
+}
+
+/// 去掉末尾空白，遇到非空白内容时返回 `true`
+fn trim_end(nodes: &mut [ASTNode]) -> bool {
+    for node in nodes.iter_mut().rev() {
+        if let ASTNode::Text(text) = node {
+            text.content.truncate(text.content.trim_end_matches([' ', '\n']).len());
+            if !text.content.is_empty() {
+                return true;
+            }
+        } else if let Some(children) = style_children_mut(node) {
+            if trim_end(children) {
+                return true;
+            }
+        } else {
+            return true;
+        }
+    }
+    false
+}
+
+fn prune_empty(nodes: &mut Vec<ASTNode>) {
+    nodes.retain_mut(|node| match node {
+        ASTNode::Text(text) => !text.content.is_empty(),
+        node => match style_children_mut(node) {
+            Some(children) => {
+                prune_empty(children);
+                !children.is_empty()
+            }
+            None => true,
+        },
+    });
+}
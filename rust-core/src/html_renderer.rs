@@ -1,20 +1,38 @@
 use crate::ast::*;
+use crate::custom::CustomNodeRenderer;
 use crate::style_config::StyleConfig;
+use std::cell::RefCell;
+use std::collections::HashMap;
 
 /// HTML 渲染器
 pub struct HtmlRenderer {
     config: StyleConfig,
+    custom_renderers: HashMap<String, Box<dyn CustomNodeRenderer>>,
 }
 
 impl HtmlRenderer {
     pub fn new() -> Self {
-        Self {
-            config: StyleConfig::default(),
-        }
+        Self::with_config(StyleConfig::default())
     }
 
     pub fn with_config(config: StyleConfig) -> Self {
-        Self { config }
+        Self { config, custom_renderers: HashMap::new() }
+    }
+
+    /// 注册自定义节点的渲染钩子
+    ///
+    /// 没有注册渲染器的自定义节点渲染为带 `data-kind` 的 `<div class="custom">`（子节点含块级内容时）
+    /// 或 `<span class="custom">`，只输出子节点。
+    pub fn with_custom_renderer(mut self, kind: impl Into<String>, renderer: impl CustomNodeRenderer + 'static) -> Self {
+        self.custom_renderers.insert(kind.into(), Box::new(renderer));
+        self
+    }
+
+    /// 已注册渲染器的自定义节点类型名
+    pub fn custom_kinds(&self) -> Vec<&str> {
+        let mut kinds: Vec<&str> = self.custom_renderers.keys().map(String::as_str).collect();
+        kinds.sort_unstable();
+        kinds
     }
 
     /// 将 AST 渲染为 HTML
@@ -28,8 +46,19 @@ impl HtmlRenderer {
         html.push_str("\n</style>\n");
         html.push_str("</head>\n<body>\n<div class=\"content\">\n");
         
-        for child in &ast.children {
-            html.push_str(&self.render_node(child));
+        // 脚注定义集中渲染在正文末尾
+        let state = RenderState::default();
+        let (footnotes, body): (Vec<&ASTNode>, Vec<&ASTNode>) = ast.children.iter()
+            .partition(|child| matches!(child, ASTNode::FootnoteDefinition(_)));
+        for child in body {
+            html.push_str(&self.render_node(child, &state));
+        }
+        if !footnotes.is_empty() {
+            html.push_str("<section class=\"footnotes\">\n<ol>\n");
+            for footnote in footnotes {
+                html.push_str(&self.render_node(footnote, &state));
+            }
+            html.push_str("</ol>\n</section>\n");
         }
         
         html.push_str("</div>\n</body>\n</html>");
@@ -99,6 +128,43 @@ s {{
     text-decoration: line-through;
 }}
 
+mark {{
+    background-color: {};
+    color: {};
+    padding: 0 2px;
+    border-radius: 2px;
+}}
+
+.spoiler-toggle {{
+    position: absolute;
+    width: 1px;
+    height: 1px;
+    opacity: 0;
+}}
+
+.spoiler-content {{
+    background-color: {};
+    border-radius: 3px;
+    filter: blur(4px);
+    cursor: pointer;
+    transition: filter 0.2s;
+}}
+
+.spoiler-toggle:checked + .spoiler-content {{
+    background-color: transparent;
+    filter: none;
+}}
+
+.spoiler-toggle:focus-visible + .spoiler-content {{
+    outline: 2px solid {};
+}}
+
+details.spoiler > summary {{
+    cursor: pointer;
+    color: {};
+    margin-bottom: {}px;
+}}
+
 code {{
     background-color: {};
     padding: 2px 6px;
@@ -208,6 +274,15 @@ hr {{
     font-weight: 500;
 }}
 
+.mention-deactivated {{
+    text-decoration: line-through;
+    opacity: 0.6;
+}}
+
+.mention-unknown {{
+    opacity: 0.6;
+}}
+
 .card {{
     border: 1px solid {};
     border-radius: {}px;
@@ -215,6 +290,109 @@ hr {{
     margin: 1em 0;
     background-color: {};
 }}
+
+.card-header {{
+    display: flex;
+    align-items: center;
+    flex-wrap: wrap;
+    gap: 8px;
+    margin-bottom: 8px;
+}}
+
+.card-icon {{
+    width: 24px;
+    height: 24px;
+    margin: 0;
+}}
+
+.card-title {{
+    font-weight: 600;
+}}
+
+.card-subtitle {{
+    flex-basis: 100%;
+    font-size: 0.875em;
+    opacity: 0.7;
+}}
+
+.card-fields {{
+    display: flex;
+    flex-wrap: wrap;
+    margin: 8px 0;
+}}
+
+.card-field {{
+    flex-basis: 100%;
+    margin-bottom: 4px;
+}}
+
+.card-field-short {{
+    flex-basis: 50%;
+}}
+
+.card-field dt {{
+    font-size: 0.875em;
+    opacity: 0.7;
+}}
+
+.card-field dd {{
+    margin: 0;
+}}
+
+.card-actions {{
+    display: flex;
+    flex-wrap: wrap;
+    gap: 8px;
+    margin-top: 8px;
+}}
+
+.card-button {{
+    padding: 6px 12px;
+    border: 1px solid {};
+    border-radius: 6px;
+    background: transparent;
+    color: inherit;
+    font: inherit;
+    text-decoration: none;
+    cursor: pointer;
+}}
+
+.card-button-primary {{
+    background-color: {};
+    border-color: {};
+    color: #ffffff;
+}}
+
+.card-button-danger {{
+    color: #d93025;
+}}
+
+.card-select, .card-input {{
+    display: flex;
+    flex-direction: column;
+    gap: 4px;
+    flex-basis: 100%;
+}}
+
+.card-label {{
+    font-size: 0.875em;
+}}
+
+.footnote-ref a {{
+    color: {};
+    text-decoration: none;
+}}
+
+.footnotes {{
+    margin-top: 1.5em;
+    padding-top: 0.5em;
+    border-top: 1px solid {};
+    font-size: 0.875em;
+}}
+
+.footnotes ol {{
+    margin-left: 1.5em;
+}}
 "#,
             config.font_size,
             config.line_height,
@@ -222,13 +400,19 @@ hr {{
             config.background_color,
             config.content_padding,
             max_width,
-            config.heading_colors.get(0).unwrap_or(&config.text_color),
+            config.heading_colors.first().unwrap_or(&config.text_color),
             config.heading_colors.get(1).unwrap_or(&config.text_color),
             config.heading_colors.get(2).unwrap_or(&config.text_color),
             config.heading_colors.get(3).unwrap_or(&config.text_color),
             config.heading_colors.get(4).unwrap_or(&config.text_color),
             config.heading_colors.get(5).unwrap_or(&config.text_color),
             config.paragraph_spacing,
+            config.highlight_background,
+            config.highlight_text_color,
+            config.spoiler_background,
+            config.link_color,
+            config.blockquote_text_color,
+            config.paragraph_spacing,
             config.code_background_color,
             config.code_font_size,
             config.code_text_color,
@@ -256,25 +440,30 @@ hr {{
             config.card_border_radius,
             config.card_padding,
             config.card_background,
+            config.card_border_color,
+            config.link_color,
+            config.link_color,
+            config.link_color,
+            config.hr_color,
         )
     }
 
-    fn render_node(&self, node: &ASTNode) -> String {
+    fn render_node(&self, node: &ASTNode, state: &RenderState) -> String {
         match node {
             ASTNode::Root(root) => {
                 root.children.iter()
-                    .map(|child| self.render_node(child))
+                    .map(|child| self.render_node(child, state))
                     .collect()
             }
             ASTNode::Paragraph(para) => {
                 let content: String = para.children.iter()
-                    .map(|child| self.render_node(child))
+                    .map(|child| self.render_node(child, state))
                     .collect();
                 format!("<p>{}</p>\n", content)
             }
             ASTNode::Heading(heading) => {
                 let content: String = heading.children.iter()
-                    .map(|child| self.render_node(child))
+                    .map(|child| self.render_node(child, state))
                     .collect();
                 format!("<h{}>{}</h{}>\n", heading.level, content, heading.level)
             }
@@ -283,28 +472,48 @@ hr {{
             }
             ASTNode::Strong(strong) => {
                 let content: String = strong.children.iter()
-                    .map(|child| self.render_node(child))
+                    .map(|child| self.render_node(child, state))
                     .collect();
                 format!("<strong>{}</strong>", content)
             }
             ASTNode::Em(em) => {
                 let content: String = em.children.iter()
-                    .map(|child| self.render_node(child))
+                    .map(|child| self.render_node(child, state))
                     .collect();
                 format!("<em>{}</em>", content)
             }
             ASTNode::Underline(underline) => {
                 let content: String = underline.children.iter()
-                    .map(|child| self.render_node(child))
+                    .map(|child| self.render_node(child, state))
                     .collect();
                 format!("<u>{}</u>", content)
             }
             ASTNode::Strike(strike) => {
                 let content: String = strike.children.iter()
-                    .map(|child| self.render_node(child))
+                    .map(|child| self.render_node(child, state))
                     .collect();
                 format!("<s>{}</s>", content)
             }
+            ASTNode::Highlight(highlight) => {
+                let content: String = highlight.children.iter()
+                    .map(|child| self.render_node(child, state))
+                    .collect();
+                format!("<mark>{}</mark>", content)
+            }
+            ASTNode::Spoiler(spoiler) => {
+                let content: String = spoiler.children.iter()
+                    .map(|child| self.render_node(child, state))
+                    .collect();
+                // 块级剧透用原生可展开的 <details>，行内剧透用可聚焦的复选框切换模糊效果
+                if spoiler.children.iter().any(is_block_node) {
+                    format!("<details class=\"spoiler\"><summary>Spoiler</summary>\n{}</details>\n", content)
+                } else {
+                    format!(
+                        "<label class=\"spoiler\"><input type=\"checkbox\" class=\"spoiler-toggle\" aria-label=\"Reveal spoiler\"><span class=\"spoiler-content\">{}</span></label>",
+                        content
+                    )
+                }
+            }
             ASTNode::Code(code) => {
                 format!("<code>{}</code>", escape_html(&code.content))
             }
@@ -318,7 +527,7 @@ hr {{
             }
             ASTNode::Link(link) => {
                 let content: String = link.children.iter()
-                    .map(|child| self.render_node(child))
+                    .map(|child| self.render_node(child, state))
                     .collect();
                 format!("<a href=\"{}\">{}</a>", escape_html_attr(&link.url), content)
             }
@@ -337,25 +546,25 @@ hr {{
                     ListType::Ordered => "ol",
                 };
                 let items: String = list.items.iter()
-                    .map(|item| self.render_list_item(item))
+                    .map(|item| self.render_list_item(item, state))
                     .collect();
                 format!("<{}>\n{}</{}>\n", tag, items, tag)
             }
             ASTNode::ListItem(item) => {
-                self.render_list_item(item)
+                self.render_list_item(item, state)
             }
             ASTNode::Table(table) => {
                 let rows: String = table.rows.iter()
-                    .map(|row| self.render_table_row(row))
+                    .map(|row| self.render_table_row(row, state))
                     .collect();
                 format!("<table>\n{}</table>\n", rows)
             }
             ASTNode::TableRow(row) => {
-                self.render_table_row(row)
+                self.render_table_row(row, state)
             }
             ASTNode::TableCell(cell) => {
                 let content: String = cell.children.iter()
-                    .map(|child| self.render_node(child))
+                    .map(|child| self.render_node(child, state))
                     .collect();
                 let align_attr = cell.align.as_ref()
                     .map(|align| format!(" style=\"text-align: {};\"", match align {
@@ -389,29 +598,94 @@ hr {{
             ASTNode::Mermaid(mermaid) => {
                 format!("<div class=\"mermaid\">{}</div>\n", escape_html(&mermaid.content))
             }
-            ASTNode::Card(card) => {
-                format!("<div class=\"card\" data-subtype=\"{}\">{}</div>\n", 
-                    escape_html_attr(&card.subtype), escape_html(&card.content))
-            }
+            ASTNode::Card(card) => self.render_card(card),
             ASTNode::Mention(mention) => {
-                format!("<span class=\"mention\" data-id=\"{}\">@{}</span>", 
-                    escape_html_attr(&mention.id), escape_html(&mention.name))
+                let (kind, prefix) = match mention.kind {
+                    MentionKind::User => ("user", '@'),
+                    MentionKind::Channel => ("channel", '#'),
+                    MentionKind::All => ("all", '@'),
+                    MentionKind::Here => ("here", '@'),
+                    MentionKind::Role => ("role", '@'),
+                };
+                let status_class = match mention.status {
+                    MentionStatus::Deactivated => " mention-deactivated",
+                    MentionStatus::Unknown => " mention-unknown",
+                    MentionStatus::Unresolved | MentionStatus::Active => "",
+                };
+                let name = if mention.name.is_empty() { &mention.id } else { &mention.name };
+                format!("<span class=\"mention{}\" data-id=\"{}\" data-kind=\"{}\">{}{}</span>",
+                    status_class, escape_html_attr(&mention.id), kind, prefix, escape_html(name))
             }
             ASTNode::HorizontalRule(_) => {
                 "<hr/>\n".to_string()
             }
             ASTNode::Blockquote(blockquote) => {
                 let content: String = blockquote.children.iter()
-                    .map(|child| self.render_node(child))
+                    .map(|child| self.render_node(child, state))
                     .collect();
                 format!("<blockquote>{}</blockquote>\n", content)
             }
+            ASTNode::FootnoteReference(reference) => {
+                // 同一脚注被多次引用时，第一次引用的 id 为 `fnref-N`（返回链接指向它），之后依次为 `fnref-N-2`、`fnref-N-3`……
+                let occurrence = state.footnote_reference(reference.index);
+                let id = if occurrence == 1 {
+                    format!("fnref-{}", reference.index)
+                } else {
+                    format!("fnref-{}-{}", reference.index, occurrence)
+                };
+                format!("<sup class=\"footnote-ref\"><a href=\"#fn-{0}\" id=\"{1}\">{0}</a></sup>",
+                    reference.index, id)
+            }
+            ASTNode::FootnoteDefinition(definition) => {
+                let content: String = definition.children.iter()
+                    .map(|child| self.render_node(child, state))
+                    .collect();
+                format!("<li id=\"fn-{0}\" value=\"{0}\">{1}<a href=\"#fnref-{0}\" class=\"footnote-backref\">↩</a></li>\n",
+                    definition.index, content)
+            }
+            ASTNode::Custom(custom) => {
+                let content: String = custom.children.iter()
+                    .map(|child| self.render_node(child, state))
+                    .collect();
+                match self.custom_renderers.get(&custom.kind) {
+                    Some(renderer) => renderer.render(custom, &content),
+                    // Markdown 中允许的原始 HTML 标签（见 `HtmlAllowlist`）还原为同名标签
+                    None if matches!(custom.kind.as_str(), "sub" | "sup" | "kbd" | "summary") => {
+                        format!("<{0}>{1}</{0}>", custom.kind, content)
+                    }
+                    // Discord 方言的 `-# ` 小字行
+                    None if custom.kind == "subtext" => format!("<p class=\"subtext\"><small>{}</small></p>\n", content),
+                    None if custom.kind == "details" => {
+                        let open = custom.attrs.get("open").and_then(|open| open.as_bool()).unwrap_or(false);
+                        format!("<details{}>\n{}</details>\n", if open { " open" } else { "" }, content)
+                    }
+                    None if custom.children.iter().any(is_block_node) => {
+                        format!("<div class=\"custom\" data-kind=\"{}\">\n{}</div>\n", escape_html_attr(&custom.kind), content)
+                    }
+                    None => format!("<span class=\"custom\" data-kind=\"{}\">{}</span>", escape_html_attr(&custom.kind), content),
+                }
+            }
+            ASTNode::Timestamp(timestamp) => {
+                // 显示 UTC 时间，宿主页面可按 `datetime` 和 `data-style` 改写为本地格式
+                let iso = timestamp.iso8601();
+                let style = timestamp.style.as_deref().unwrap_or("f");
+                format!("<time class=\"timestamp\" datetime=\"{}\" data-style=\"{}\">{}</time>",
+                    iso, escape_html_attr(style), iso.replacen('T', " ", 1).replacen('Z', " UTC", 1))
+            }
+            ASTNode::CustomEmoji(emoji) => {
+                format!("<span class=\"custom-emoji\" data-id=\"{}\" data-animated=\"{}\">:{}:</span>",
+                    escape_html_attr(&emoji.id), emoji.animated, escape_html(&emoji.name))
+            }
+            ASTNode::Unknown(_) => {
+                // 未知节点类型不渲染
+                String::new()
+            }
         }
     }
 
-    fn render_list_item(&self, item: &ListItemNode) -> String {
+    fn render_list_item(&self, item: &ListItemNode, state: &RenderState) -> String {
         let content: String = item.children.iter()
-            .map(|child| self.render_node(child))
+            .map(|child| self.render_node(child, state))
             .collect();
         
         if let Some(checked) = item.checked {
@@ -423,20 +697,158 @@ hr {{
         }
     }
 
-    fn render_table_row(&self, row: &TableRow) -> String {
+    /// 渲染卡片；交互元素带有 `data-action`（元素 ID），由宿主页面绑定事件并回传
+    fn render_card(&self, card: &CardNode) -> String {
+        let subtype = escape_html_attr(&card.subtype);
+        if card.header.is_none() && card.fields.is_empty() && card.images.is_empty() && card.actions.is_empty() {
+            return format!("<div class=\"card\" data-subtype=\"{}\">{}</div>\n", subtype, escape_html(&card.content));
+        }
+
+        let mut html = format!("<div class=\"card\" data-subtype=\"{}\">\n", subtype);
+        if let Some(header) = &card.header {
+            html.push_str("<div class=\"card-header\">");
+            if let Some(icon) = &header.icon {
+                html.push_str(&format!("<img class=\"card-icon\" src=\"{}\" alt=\"\"/>", escape_html_attr(icon)));
+            }
+            html.push_str(&format!("<div class=\"card-title\">{}</div>", escape_html(&header.title)));
+            if let Some(subtitle) = &header.subtitle {
+                html.push_str(&format!("<div class=\"card-subtitle\">{}</div>", escape_html(subtitle)));
+            }
+            html.push_str("</div>\n");
+        }
+        if !card.content.is_empty() {
+            html.push_str(&format!("<div class=\"card-text\">{}</div>\n", escape_html(&card.content)));
+        }
+        if !card.fields.is_empty() {
+            html.push_str("<dl class=\"card-fields\">");
+            for field in &card.fields {
+                let class = if field.short { "card-field card-field-short" } else { "card-field" };
+                html.push_str(&format!(
+                    "<div class=\"{}\"><dt>{}</dt><dd>{}</dd></div>",
+                    class,
+                    escape_html(&field.label),
+                    escape_html(&field.value)
+                ));
+            }
+            html.push_str("</dl>\n");
+        }
+        for image in &card.images {
+            let alt = image.alt.as_deref().unwrap_or_default();
+            html.push_str(&format!(
+                "<img class=\"card-image\" src=\"{}\" alt=\"{}\"/>\n",
+                escape_html_attr(&image.url),
+                escape_html_attr(alt)
+            ));
+        }
+        if !card.actions.is_empty() {
+            html.push_str("<div class=\"card-actions\">");
+            for action in &card.actions {
+                html.push_str(&render_card_action(action));
+            }
+            html.push_str("</div>\n");
+        }
+        html.push_str("</div>\n");
+        html
+    }
+
+    fn render_table_row(&self, row: &TableRow, state: &RenderState) -> String {
         let cells: String = row.cells.iter()
-            .map(|cell| self.render_node(&ASTNode::TableCell(cell.clone())))
+            .map(|cell| self.render_node(&ASTNode::TableCell(cell.clone()), state))
             .collect();
         format!("<tr>{}</tr>\n", cells)
     }
 }
 
+/// 单次渲染的状态，每次调用 `render` 时新建，渲染器本身保持无状态以便跨线程共享
+#[derive(Default)]
+struct RenderState {
+    /// 每个脚注编号已渲染的引用次数
+    footnote_refs: RefCell<HashMap<u32, u32>>,
+}
+
+impl RenderState {
+    /// 记录一次脚注引用，返回它是该脚注的第几次引用（从 1 开始）
+    fn footnote_reference(&self, index: u32) -> u32 {
+        let mut refs = self.footnote_refs.borrow_mut();
+        let count = refs.entry(index).or_insert(0);
+        *count += 1;
+        *count
+    }
+}
+
 impl Default for HtmlRenderer {
     fn default() -> Self {
         Self::new()
     }
 }
 
+fn render_card_action(action: &CardAction) -> String {
+    let id = escape_html_attr(&action.id);
+    let label = escape_html(&action.label);
+    let value_attr = action.value.as_ref()
+        .map(|value| format!(" data-value=\"{}\"", escape_html_attr(value)))
+        .unwrap_or_default();
+    let labelled = |control: String| {
+        if action.label.is_empty() {
+            control
+        } else {
+            format!("<span class=\"card-label\">{}</span>{}", label, control)
+        }
+    };
+
+    match action.kind {
+        CardActionKind::Button => {
+            let style = match action.style {
+                CardActionStyle::Default => "card-button",
+                CardActionStyle::Primary => "card-button card-button-primary",
+                CardActionStyle::Danger => "card-button card-button-danger",
+            };
+            match &action.url {
+                Some(url) => format!(
+                    "<a class=\"{}\" href=\"{}\" data-action=\"{}\"{} target=\"_blank\" rel=\"noopener noreferrer\">{}</a>",
+                    style,
+                    escape_html_attr(url),
+                    id,
+                    value_attr,
+                    label
+                ),
+                None => format!(
+                    "<button type=\"button\" class=\"{}\" data-action=\"{}\"{}>{}</button>",
+                    style, id, value_attr, label
+                ),
+            }
+        }
+        CardActionKind::Select => {
+            let mut options = String::new();
+            if let Some(placeholder) = &action.placeholder {
+                let selected = if action.value.is_none() { " selected" } else { "" };
+                options.push_str(&format!("<option value=\"\" disabled{}>{}</option>", selected, escape_html(placeholder)));
+            }
+            for option in &action.options {
+                let selected = if action.value.as_ref() == Some(&option.value) { " selected" } else { "" };
+                options.push_str(&format!(
+                    "<option value=\"{}\"{}>{}</option>",
+                    escape_html_attr(&option.value),
+                    selected,
+                    escape_html(&option.label)
+                ));
+            }
+            let control = format!("<select name=\"{}\" data-action=\"{}\">{}</select>", id, id, options);
+            format!("<label class=\"card-select\">{}</label>", labelled(control))
+        }
+        CardActionKind::Input => {
+            let placeholder = action.placeholder.as_ref()
+                .map(|placeholder| format!(" placeholder=\"{}\"", escape_html_attr(placeholder)))
+                .unwrap_or_default();
+            let value = action.value.as_ref()
+                .map(|value| format!(" value=\"{}\"", escape_html_attr(value)))
+                .unwrap_or_default();
+            let control = format!("<input type=\"text\" name=\"{}\" data-action=\"{}\"{}{}/>", id, id, placeholder, value);
+            format!("<label class=\"card-input\">{}</label>", labelled(control))
+        }
+    }
+}
+
 /// 转义 HTML 特殊字符
 fn escape_html(text: &str) -> String {
     let mut result = String::with_capacity(text.len());
@@ -468,3 +880,18 @@ fn escape_html_attr(text: &str) -> String {
     result
 }
 
+/// 是否为块级节点；渲染剧透、自定义节点等容器时据此决定使用块级还是行内标签
+pub(crate) fn is_block_node(node: &ASTNode) -> bool {
+    matches!(
+        node,
+        ASTNode::Paragraph(_)
+            | ASTNode::Heading(_)
+            | ASTNode::CodeBlock(_)
+            | ASTNode::List(_)
+            | ASTNode::Table(_)
+            | ASTNode::Mermaid(_)
+            | ASTNode::Card(_)
+            | ASTNode::HorizontalRule(_)
+            | ASTNode::Blockquote(_)
+    )
+}
@@ -1,29 +1,125 @@
 pub mod ast;
 pub mod markdown_parser;
 pub mod delta_parser;
+pub mod html_parser;
 pub mod ast_builder;
 pub mod html_renderer;
+pub mod plain_text;
 pub mod style_config;
+pub mod normalize;
+pub mod footnote;
+pub mod mention;
+pub mod mention_resolver;
+pub mod raw_html;
+pub mod slack;
+pub mod telegram;
+pub mod card;
+pub mod custom;
+mod delimiter;
+mod block_markers;
+mod discord;
+pub mod equivalence;
+pub mod validation;
+pub mod compact;
+pub mod cache;
+pub mod batch;
+pub mod sanitize;
+pub mod parser_config;
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(feature = "schema")]
+pub mod codegen;
+pub mod visitor;
 
 pub mod ffi;
 
 pub use ast::*;
 pub use markdown_parser::*;
 pub use delta_parser::*;
+pub use html_parser::*;
 pub use ast_builder::*;
 pub use html_renderer::*;
+pub use plain_text::*;
 pub use style_config::*;
+pub use normalize::*;
+pub use footnote::*;
+pub use mention::*;
+pub use mention_resolver::*;
+pub use raw_html::*;
+pub use slack::*;
+pub use telegram::*;
+pub use card::*;
+pub use custom::*;
+pub use equivalence::*;
+pub use validation::*;
+pub use compact::*;
+pub use cache::*;
+pub use batch::*;
+pub use sanitize::*;
+pub use parser_config::*;
+#[cfg(feature = "schema")]
+pub use schema::*;
+#[cfg(feature = "schema")]
+pub use codegen::*;
+pub use visitor::*;
+
+use std::sync::Arc;
 
 /// 解析 Markdown 为 AST
+///
+/// 启用全局解析缓存（[`enable_parse_cache`]）时优先从缓存读取，命中时返回缓存 AST 的副本；
+/// 只读取 AST 时可以使用 [`parse_markdown_shared`] 避免复制。
 pub fn parse_markdown(input: &str) -> Result<RootNode, ParseError> {
+    parse_markdown_shared(input).map(unwrap_shared)
+}
+
+/// 解析 Markdown 为共享的 AST，缓存命中时直接返回缓存中的 AST
+pub fn parse_markdown_shared(input: &str) -> Result<Arc<RootNode>, ParseError> {
     let parser = MarkdownParser::new();
-    parser.parse(input)
+    match global_parse_cache() {
+        Some(cache) => cache.get_or_parse(CacheKey::ast(InputFormat::Markdown, input, &parser), || parser.parse(input)),
+        None => parser.parse(input).map(Arc::new),
+    }
 }
 
 /// 解析 Delta 为 AST
+///
+/// 启用全局解析缓存（[`enable_parse_cache`]）时优先从缓存读取，命中时返回缓存 AST 的副本；
+/// 只读取 AST 时可以使用 [`parse_delta_shared`] 避免复制。
 pub fn parse_delta(input: &str) -> Result<RootNode, ParseError> {
+    parse_delta_shared(input).map(unwrap_shared)
+}
+
+/// 解析 Delta 为共享的 AST，缓存命中时直接返回缓存中的 AST
+pub fn parse_delta_shared(input: &str) -> Result<Arc<RootNode>, ParseError> {
     let parser = DeltaParser::new();
-    parser.parse(input)
+    match global_parse_cache() {
+        Some(cache) => cache.get_or_parse(CacheKey::ast(InputFormat::Delta, input, &parser), || parser.parse(input)),
+        None => parser.parse(input).map(Arc::new),
+    }
+}
+
+/// 解析 HTML 为 AST
+///
+/// 只保留安全的标签子集，链接和图片地址按默认的 [`SanitizeOptions`] 清理，见 [`HtmlParser`]。
+/// 启用全局解析缓存（[`enable_parse_cache`]）时优先从缓存读取，命中时返回缓存 AST 的副本；
+/// 只读取 AST 时可以使用 [`parse_html_shared`] 避免复制。
+pub fn parse_html(input: &str) -> Result<RootNode, ParseError> {
+    parse_html_shared(input).map(unwrap_shared)
+}
+
+/// 解析 HTML 为共享的 AST，缓存命中时直接返回缓存中的 AST
+pub fn parse_html_shared(input: &str) -> Result<Arc<RootNode>, ParseError> {
+    let parser = HtmlParser::new();
+    match global_parse_cache() {
+        Some(cache) => cache.get_or_parse(CacheKey::ast(InputFormat::Html, input, &parser), || parser.parse(input)),
+        None => parser.parse(input).map(Arc::new),
+    }
+}
+
+/// 取出共享 AST，仍被缓存持有时复制一份
+fn unwrap_shared(ast: Arc<RootNode>) -> RootNode {
+    Arc::try_unwrap(ast).unwrap_or_else(|ast| (*ast).clone())
 }
 
 /// 将 AST 序列化为 JSON
@@ -32,10 +128,41 @@ pub fn serialize_ast(ast: &RootNode) -> Result<String, serde_json::Error> {
 }
 
 /// 从 JSON 反序列化为 AST
+///
+/// 不认识的节点类型会保留为 [`ASTNode::Unknown`]；已知类型但字段不合法的节点返回错误。
+/// 这一规则由 [`ASTNode`] 的 `Deserialize` 实现保证，直接使用 `serde_json::from_str::<ASTNode>` 等方式时同样生效。
 pub fn deserialize_ast(json: &str) -> Result<RootNode, serde_json::Error> {
     serde_json::from_str(json)
 }
 
+/// 将 AST 序列化为 MessagePack
+///
+/// 结构体以 map（带字段名）编码，与 JSON 的结构一一对应。
+#[cfg(feature = "msgpack")]
+pub fn serialize_ast_msgpack(ast: &RootNode) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    rmp_serde::to_vec_named(ast)
+}
+
+/// 从 MessagePack 反序列化为 AST（未知节点的处理与 [`deserialize_ast`] 相同）
+#[cfg(feature = "msgpack")]
+pub fn deserialize_ast_msgpack(bytes: &[u8]) -> Result<RootNode, rmp_serde::decode::Error> {
+    rmp_serde::from_slice(bytes)
+}
+
+/// 将 AST 序列化为 CBOR
+#[cfg(feature = "cbor")]
+pub fn serialize_ast_cbor(ast: &RootNode) -> Result<Vec<u8>, ciborium::ser::Error<std::io::Error>> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(ast, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// 从 CBOR 反序列化为 AST（未知节点的处理与 [`deserialize_ast`] 相同）
+#[cfg(feature = "cbor")]
+pub fn deserialize_ast_cbor(bytes: &[u8]) -> Result<RootNode, ciborium::de::Error<std::io::Error>> {
+    ciborium::from_reader(bytes)
+}
+
 /// 将 Markdown 转换为 HTML
 pub fn markdown_to_html(input: &str) -> Result<String, ParseError> {
     markdown_to_html_with_config(input, &StyleConfig::default())
@@ -43,9 +170,20 @@ pub fn markdown_to_html(input: &str) -> Result<String, ParseError> {
 
 /// 将 Markdown 转换为 HTML（使用自定义样式配置）
 pub fn markdown_to_html_with_config(input: &str, config: &StyleConfig) -> Result<String, ParseError> {
-    let ast = parse_markdown(input)?;
-    let renderer = HtmlRenderer::with_config(config.clone());
-    Ok(renderer.render(&ast))
+    let parser = MarkdownParser::new();
+    // HTML 缓存未命中时直接解析，不再经过 AST 缓存，每次调用只计一次命中或未命中
+    let render = || {
+        let ast = parser.parse(input)?;
+        let renderer = HtmlRenderer::with_config(config.clone());
+        Ok(renderer.render(&ast))
+    };
+    match global_parse_cache() {
+        Some(cache) => {
+            let key = CacheKey::html(InputFormat::Markdown, input, &parser, config);
+            cache.get_or_render(key, render).map(|html| html.to_string())
+        }
+        None => render(),
+    }
 }
 
 /// 将 Delta 转换为 HTML
@@ -55,9 +193,20 @@ pub fn delta_to_html(input: &str) -> Result<String, ParseError> {
 
 /// 将 Delta 转换为 HTML（使用自定义样式配置）
 pub fn delta_to_html_with_config(input: &str, config: &StyleConfig) -> Result<String, ParseError> {
-    let ast = parse_delta(input)?;
-    let renderer = HtmlRenderer::with_config(config.clone());
-    Ok(renderer.render(&ast))
+    let parser = DeltaParser::new();
+    // HTML 缓存未命中时直接解析，不再经过 AST 缓存，每次调用只计一次命中或未命中
+    let render = || {
+        let ast = parser.parse(input)?;
+        let renderer = HtmlRenderer::with_config(config.clone());
+        Ok(renderer.render(&ast))
+    };
+    match global_parse_cache() {
+        Some(cache) => {
+            let key = CacheKey::html(InputFormat::Delta, input, &parser, config);
+            cache.get_or_render(key, render).map(|html| html.to_string())
+        }
+        None => render(),
+    }
 }
 
 /// 将数学公式转换为 HTML（使用 KaTeX）
@@ -73,10 +222,12 @@ pub fn math_to_html(formula: &str, display: bool) -> Result<String, ParseError>
     let ctx = KatexContext::default();
     
     // 配置设置
-    let mut settings = Settings::default();
-    settings.display_mode = display;
-    // 使用纯 HTML 输出
-    settings.output = OutputFormat::Html;
+    let settings = Settings {
+        display_mode: display,
+        // 使用纯 HTML 输出
+        output: OutputFormat::Html,
+        ..Default::default()
+    };
     
     // 渲染为 HTML
     let html = render_to_string(&ctx, formula, &settings)
@@ -222,5 +373,9 @@ pub enum ParseError {
     MarkdownError(String),
     #[error("Delta parse error: {0}")]
     DeltaError(String),
+    #[error("Slack parse error: {0}")]
+    SlackError(String),
+    #[error("Limit exceeded: {0}")]
+    LimitExceeded(String),
 }
 
@@ -1,31 +1,666 @@
 use crate::ast::*;
 use crate::ast_builder::ASTBuilder;
+use crate::block_markers::{apply_block_markers, mark_blocks, BlockSyntax, CardBuilder, InlineParser, BLOCK_MARKER};
+use crate::card::{invalid_card, issues_at_line, parse_card_attributes, parse_fenced_card, CardIssue, CardSchemaRegistry};
+use crate::custom::{BlockNodeParser, CustomSyntax, InlineNodeParser};
+use crate::footnote::number_footnotes;
+use crate::delimiter::{apply_delimiter, escaped_placeholder, restore_escaped, DelimiterRule};
+use crate::discord::parse_discord_markup;
+use crate::normalize::normalize;
+use crate::validation::{card_issues, ValidationIssue};
 use crate::ParseError;
-use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
+use pulldown_cmark::{BrokenLink, CodeBlockKind, Event, Options, Parser, RefDefs, Tag};
+use crate::mention::{is_angle_mention, parse_mentions, MentionSyntax};
+use crate::raw_html::{html_tokens, HtmlAllowlist, HtmlToken};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Markdown 语法扩展选项
+///
+/// 缺省字段使用 [`Default`]（与 [`MarkdownParser::new`] 一致，只启用早期版本就支持的语法）。常用组合见
+/// [`MarkdownOptions::im_chat`]、[`MarkdownOptions::commonmark_strict`]、[`MarkdownOptions::docs`]
+/// 和 [`MarkdownOptions::discord`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkdownOptions {
+    /// 删除线 `~~text~~`
+    pub strikethrough: bool,
+    /// GFM 表格
+    pub tables: bool,
+    /// 脚注
+    pub footnotes: bool,
+    /// 任务列表 `- [ ]`
+    pub tasklists: bool,
+    /// 高亮 `==text==`
+    pub highlight: bool,
+    /// 剧透：行内 `||text||`，以及独占一行的 `||` 围栏包裹的块级内容
+    pub spoiler: bool,
+    /// 智能标点（弯引号、破折号、省略号）
+    pub smart_punctuation: bool,
+    /// 标题属性 `# 标题 {#id .class}`
+    pub heading_attributes: bool,
+    /// 数学公式 `$...$` 和 `$$...$$`
+    pub math: bool,
+    /// 语言为 `mermaid` 的代码块解析为 Mermaid 图表
+    pub mermaid: bool,
+    /// 卡片：语言为 `card:<子类型>` 的代码块（正文为 JSON 或 YAML），以及 `:::card{type=...}` 容器
+    pub cards: bool,
+    /// 提及语法，也可以写成布尔值（`true` 启用全部，`false` 全部关闭）
+    #[serde(deserialize_with = "deserialize_mention_syntax")]
+    pub mentions: MentionSyntax,
+    /// 允许转换为节点的原始 HTML 标签，其余标签保留为文本；也可以写成布尔值（`true` 允许全部，`false` 全部保留为文本）
+    #[serde(deserialize_with = "deserialize_html_allowlist")]
+    pub html: HtmlAllowlist,
+    /// 软换行保留为换行（聊天消息的习惯），关闭时按 CommonMark 渲染为空格
+    pub hard_wrap: bool,
+    /// 方言，决定 CommonMark 之外的平台专有语法
+    pub dialect: MarkdownDialect,
+}
+
+/// Markdown 方言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkdownDialect {
+    /// CommonMark 加上 [`MarkdownOptions`] 中启用的扩展
+    #[default]
+    CommonMark,
+    /// Discord：`__text__` 为下划线而不是粗体，独占一行的 `-# text` 为小字，
+    /// `<t:1618953630:R>` 为时间戳，`<:name:id>` / `<a:name:id>` 为自定义表情
+    Discord,
+}
+
+impl Default for MarkdownOptions {
+    /// 与早期版本的 `MarkdownParser::new` 保持一致：GFM 扩展、数学公式和 Mermaid，
+    /// 不转换弯引号；高亮、剧透、卡片、提及和原始 HTML 等扩展需要显式启用或使用预设
+    fn default() -> Self {
+        Self {
+            strikethrough: true,
+            tables: true,
+            footnotes: true,
+            tasklists: true,
+            highlight: false,
+            spoiler: false,
+            smart_punctuation: false,
+            heading_attributes: false,
+            math: true,
+            mermaid: true,
+            cards: false,
+            mentions: MentionSyntax::none(),
+            html: HtmlAllowlist::none(),
+            hard_wrap: false,
+            dialect: MarkdownDialect::CommonMark,
+        }
+    }
+}
+
+impl MarkdownOptions {
+    /// 即时通讯消息：启用聊天扩展（高亮、剧透、卡片、提及、原始 HTML），保留用户输入的引号和换行，
+    /// 不启用脚注和标题属性
+    pub fn im_chat() -> Self {
+        Self {
+            footnotes: false,
+            hard_wrap: true,
+            ..Self::extended()
+        }
+    }
+
+    /// 严格 CommonMark：关闭所有扩展
+    pub fn commonmark_strict() -> Self {
+        Self {
+            strikethrough: false,
+            tables: false,
+            footnotes: false,
+            tasklists: false,
+            highlight: false,
+            spoiler: false,
+            smart_punctuation: false,
+            heading_attributes: false,
+            math: false,
+            mermaid: false,
+            cards: false,
+            mentions: MentionSyntax::none(),
+            html: HtmlAllowlist::none(),
+            hard_wrap: false,
+            dialect: MarkdownDialect::CommonMark,
+        }
+    }
+
+    /// 长文档：启用全部扩展，包括智能标点和标题属性
+    pub fn docs() -> Self {
+        Self {
+            smart_punctuation: true,
+            heading_attributes: true,
+            ..Self::extended()
+        }
+    }
+
+    /// Discord 消息：Discord 方言，只保留 Discord 支持的语法（没有表格、脚注、任务列表、高亮、
+    /// 数学公式、Mermaid、卡片和原始 HTML），提及只识别尖括号形式和 `@everyone` / `@here`
+    pub fn discord() -> Self {
+        Self {
+            tables: false,
+            footnotes: false,
+            tasklists: false,
+            highlight: false,
+            smart_punctuation: false,
+            math: false,
+            mermaid: false,
+            cards: false,
+            mentions: MentionSyntax { links: false, ..MentionSyntax::all() },
+            html: HtmlAllowlist::none(),
+            hard_wrap: true,
+            dialect: MarkdownDialect::Discord,
+            ..Self::extended()
+        }
+    }
+
+    /// 按名称获取预设：`im_chat`、`commonmark_strict`、`docs`、`discord`、`default`
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "im_chat" => Some(Self::im_chat()),
+            "commonmark_strict" => Some(Self::commonmark_strict()),
+            "docs" => Some(Self::docs()),
+            "discord" => Some(Self::discord()),
+            "default" => Some(Self::default()),
+            _ => None,
+        }
+    }
+
+    /// 默认选项加上高亮、剧透、卡片、提及和原始 HTML，各预设在此基础上调整
+    fn extended() -> Self {
+        Self {
+            highlight: true,
+            spoiler: true,
+            cards: true,
+            mentions: MentionSyntax::all(),
+            html: HtmlAllowlist::all(),
+            ..Self::default()
+        }
+    }
+
+    fn pulldown_options(&self) -> Options {
+        let mut options = Options::empty();
+        options.set(Options::ENABLE_STRIKETHROUGH, self.strikethrough);
+        options.set(Options::ENABLE_TABLES, self.tables);
+        options.set(Options::ENABLE_FOOTNOTES, self.footnotes);
+        options.set(Options::ENABLE_TASKLISTS, self.tasklists);
+        options.set(Options::ENABLE_SMART_PUNCTUATION, self.smart_punctuation);
+        options.set(Options::ENABLE_HEADING_ATTRIBUTES, self.heading_attributes);
+        options
+    }
+
+    /// 启用的定界符规则使用的字符，这些字符被反斜杠转义时不参与配对
+    fn delimiter_chars(&self) -> Vec<char> {
+        let mut chars = Vec::new();
+        if self.highlight {
+            chars.push('=');
+        }
+        if self.spoiler {
+            chars.push('|');
+        }
+        if self.dialect == MarkdownDialect::Discord {
+            chars.push('_');
+        }
+        chars
+    }
+
+    /// 软换行对应的文本
+    fn soft_break(&self) -> &'static str {
+        if self.hard_wrap {
+            "\n"
+        } else {
+            " "
+        }
+    }
+}
+
+const HIGHLIGHT: DelimiterRule = DelimiterRule {
+    marker: "==",
+    wrap: |children| ASTNode::Highlight(HighlightNode { children }),
+};
+
+fn deserialize_mention_syntax<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MentionSyntax, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum FlagOrSyntax {
+        Flag(bool),
+        Syntax(MentionSyntax),
+    }
+
+    Ok(match FlagOrSyntax::deserialize(deserializer)? {
+        FlagOrSyntax::Flag(true) => MentionSyntax::all(),
+        FlagOrSyntax::Flag(false) => MentionSyntax::none(),
+        FlagOrSyntax::Syntax(syntax) => syntax,
+    })
+}
+
+fn deserialize_html_allowlist<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HtmlAllowlist, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum FlagOrAllowlist {
+        Flag(bool),
+        Allowlist(HtmlAllowlist),
+    }
+
+    Ok(match FlagOrAllowlist::deserialize(deserializer)? {
+        FlagOrAllowlist::Flag(true) => HtmlAllowlist::all(),
+        FlagOrAllowlist::Flag(false) => HtmlAllowlist::none(),
+        FlagOrAllowlist::Allowlist(allowlist) => allowlist,
+    })
+}
+
+const SPOILER: DelimiterRule = DelimiterRule {
+    marker: "||",
+    wrap: |children| ASTNode::Spoiler(SpoilerNode { children }),
+};
+
+const UNDERLINE: DelimiterRule = DelimiterRule {
+    marker: "__",
+    wrap: |children| ASTNode::Underline(UnderlineNode { children }),
+};
+
+/// 逐行遍历输入，跳过围栏代码块（含围栏行本身），返回 (行的字节范围, 去掉首尾空白的行)
+fn lines_outside_code(input: &str) -> Vec<(Range<usize>, &str)> {
+    let mut lines = Vec::new();
+    // 当前所在围栏代码块的围栏字符和长度
+    let mut code_fence: Option<(char, usize)> = None;
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        let range = offset..offset + line.len();
+        offset += line.len();
+        let trimmed = line.trim();
+
+        let fence_char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~');
+        if let Some(c) = fence_char {
+            let run = trimmed.chars().take_while(|x| *x == c).count();
+            if run >= 3 {
+                match code_fence {
+                    None => code_fence = Some((c, run)),
+                    Some((open_char, open_run)) if open_char == c && run >= open_run && trimmed.len() == run => {
+                        code_fence = None;
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+        }
+
+        if code_fence.is_none() {
+            lines.push((range, trimmed));
+        }
+    }
+    lines
+}
+
+/// 把反斜杠转义的定界符字符（如 `\=`）换成占位字符，使其不参与解析完成后的定界符配对
+///
+/// pulldown-cmark 把转义的字符作为单独的文本事件输出，事件的源码范围紧跟在反斜杠之后；代码块中的文本不处理。
+struct EscapedMarkers<'a, I: Iterator<Item = (Event<'a>, Range<usize>)>> {
+    input: &'a str,
+    events: I,
+    markers: Vec<char>,
+    in_code_block: bool,
+}
+
+impl<'a, I: Iterator<Item = (Event<'a>, Range<usize>)>> EscapedMarkers<'a, I> {
+    fn new(input: &'a str, events: I, markers: Vec<char>) -> Self {
+        Self { input, events, markers, in_code_block: false }
+    }
+}
+
+impl<'a, I: Iterator<Item = (Event<'a>, Range<usize>)>> Iterator for EscapedMarkers<'a, I> {
+    type Item = (Event<'a>, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (event, range) = self.events.next()?;
+        match &event {
+            Event::Start(Tag::CodeBlock(_)) => self.in_code_block = true,
+            Event::End(Tag::CodeBlock(_)) => self.in_code_block = false,
+            Event::Text(text) if !self.in_code_block && is_escaped(self.input, range.start) => {
+                let mut chars = text.chars();
+                let placeholder = chars.next().filter(|c| self.markers.contains(c)).and_then(escaped_placeholder);
+                if let Some(placeholder) = placeholder {
+                    let text = format!("{placeholder}{}", chars.as_str());
+                    return Some((Event::Text(text.into()), range));
+                }
+            }
+            _ => {}
+        }
+        Some((event, range))
+    }
+}
+
+/// 源码位置前是否紧跟一个本身没有被转义的反斜杠
+fn is_escaped(input: &str, offset: usize) -> bool {
+    input.as_bytes()[..offset].iter().rev().take_while(|b| **b == b'\\').count() % 2 == 1
+}
+
+/// Discord 方言中 `__` 表示下划线：把由 `__` 构成的粗体还原为文本定界符，解析完成后按 [`UNDERLINE`] 规则配对
+///
+/// 相邻文本事件合并为一个，使 `___text___` 中的定界符与内容留在同一个斜体节点中。未启用时原样输出事件。
+struct UnderlineMarkers<'a, I: Iterator<Item = (Event<'a>, Range<usize>)>> {
+    input: &'a str,
+    events: I,
+    enabled: bool,
+    /// 未结束的粗体是否由 `__` 构成
+    strong: Vec<bool>,
+    pending: Option<Event<'a>>,
+}
+
+impl<'a, I: Iterator<Item = (Event<'a>, Range<usize>)>> UnderlineMarkers<'a, I> {
+    fn new(input: &'a str, events: I, enabled: bool) -> Self {
+        Self { input, events, enabled, strong: Vec::new(), pending: None }
+    }
+
+    fn next_event(&mut self) -> Option<Event<'a>> {
+        let (event, range) = self.events.next()?;
+        Some(match event {
+            Event::Start(Tag::Strong) => {
+                let marker = self.input[range].starts_with("__");
+                self.strong.push(marker);
+                if marker { Event::Text("__".into()) } else { Event::Start(Tag::Strong) }
+            }
+            Event::End(Tag::Strong) if self.strong.pop() == Some(true) => Event::Text("__".into()),
+            event => event,
+        })
+    }
+}
+
+impl<'a, I: Iterator<Item = (Event<'a>, Range<usize>)>> Iterator for UnderlineMarkers<'a, I> {
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Event<'a>> {
+        if !self.enabled {
+            return self.events.next().map(|(event, _)| event);
+        }
+        let event = match self.pending.take() {
+            Some(event) => event,
+            None => self.next_event()?,
+        };
+        let Event::Text(text) = event else {
+            return Some(event);
+        };
+        let mut merged = text.into_string();
+        while let Some(next) = self.next_event() {
+            match next {
+                Event::Text(text) => merged.push_str(&text),
+                next => {
+                    self.pending = Some(next);
+                    break;
+                }
+            }
+        }
+        Some(Event::Text(merged.into()))
+    }
+}
 
 /// Markdown 解析器
+///
+/// 实现 `Hash` 以便作为解析缓存键的一部分，解析选项不同的结果不会互相命中。
+#[derive(Hash)]
 pub struct MarkdownParser {
-    options: Options,
+    options: MarkdownOptions,
+    normalize: bool,
+    card_schemas: CardSchemaRegistry,
+    custom: CustomSyntax,
 }
 
 impl MarkdownParser {
     pub fn new() -> Self {
-        let mut options = Options::empty();
-        options.insert(Options::ENABLE_STRIKETHROUGH);
-        options.insert(Options::ENABLE_TABLES);
-        options.insert(Options::ENABLE_FOOTNOTES);
-        options.insert(Options::ENABLE_TASKLISTS);
-        options.insert(Options::ENABLE_SMART_PUNCTUATION);
+        Self::with_options(MarkdownOptions::default())
+    }
+
+    pub fn with_options(options: MarkdownOptions) -> Self {
+        Self {
+            options,
+            normalize: false,
+            card_schemas: CardSchemaRegistry::new(),
+            custom: CustomSyntax::default(),
+        }
+    }
+
+    pub fn options(&self) -> &MarkdownOptions {
+        &self.options
+    }
+
+    /// 设置是否在解析完成后自动执行 [`normalize`]
+    pub fn with_normalize(mut self, enabled: bool) -> Self {
+        self.normalize = enabled;
+        self
+    }
+
+    /// 设置卡片子类型的 schema
+    ///
+    /// 结构不合法（见 [`validate`](crate::validate)）或不符合 schema 的卡片按未启用卡片语法时的方式解析（围栏卡片保留为代码块，容器卡片保留为普通文本），内容不会丢失。
+    /// 这些卡片的具体问题可以通过 [`validate_cards`](Self::validate_cards) 获取。
+    pub fn with_card_schemas(mut self, schemas: CardSchemaRegistry) -> Self {
+        self.card_schemas = schemas;
+        self
+    }
+
+    pub fn card_schemas(&self) -> &CardSchemaRegistry {
+        &self.card_schemas
+    }
+
+    /// 注册行内自定义语法，匹配的片段生成 [`CustomNode`]（节点没有类型名时使用 `kind`）
+    ///
+    /// 在提及、高亮等扩展语法之前识别，多个钩子按注册顺序优先。类型名参与解析缓存键，不同的实现应使用不同的类型名。
+    pub fn with_inline_node(mut self, kind: impl Into<String>, parser: impl InlineNodeParser + 'static) -> Self {
+        self.custom.add_inline(kind.into(), Box::new(parser));
+        self
+    }
 
-        Self { options }
+    /// 注册块级自定义语法（如 `:::location` 容器），在每个行首尝试匹配，围栏代码块和卡片容器中的内容不参与匹配
+    ///
+    /// 自定义块之间的内容分段解析，链接引用定义在整篇输入中共享。
+    pub fn with_block_node(mut self, kind: impl Into<String>, parser: impl BlockNodeParser + 'static) -> Self {
+        self.custom.add_block(kind.into(), Box::new(parser));
+        self
     }
 
     pub fn parse(&self, input: &str) -> Result<RootNode, ParseError> {
-        let parser = Parser::new_ext(input, self.options);
+        let mut root = self.parse_blocks(input);
+        number_footnotes(&mut root);
+        self.custom.apply_inline(&mut root);
+        parse_mentions(&mut root, &self.options.mentions);
+        if self.options.dialect == MarkdownDialect::Discord {
+            parse_discord_markup(&mut root);
+            apply_delimiter(&mut root, &UNDERLINE);
+        }
+        if self.options.highlight {
+            apply_delimiter(&mut root, &HIGHLIGHT);
+        }
+        if self.options.spoiler {
+            apply_delimiter(&mut root, &SPOILER);
+        }
+        if !self.options.delimiter_chars().is_empty() {
+            restore_escaped(&mut root);
+        }
+        if self.normalize {
+            normalize(&mut root);
+        }
+        Ok(root)
+    }
+
+    /// 解析单行内容，段落展开为其中的行内节点
+    fn parse_inline(&self, input: &str) -> Vec<ASTNode> {
+        self.parse_blocks(input)
+            .children
+            .into_iter()
+            .flat_map(|node| match node {
+                ASTNode::Paragraph(para) => para.children,
+                node => vec![node],
+            })
+            .collect()
+    }
+
+    /// 解析块级结构，不含解析后的扩展语法处理：拆出块级自定义语法后用 pulldown-cmark 解析其余内容
+    fn parse_blocks(&self, input: &str) -> RootNode {
+        if !self.custom.has_block() {
+            return self.parse_commonmark(input, None);
+        }
+        // 各段分别解析，链接引用定义从整篇输入中收集，段内找不到的引用到其中查找
+        let document = Parser::new_ext(input, self.options.pulldown_options());
+        self.parse_custom_blocks(input, document.reference_definitions())
+    }
+
+    /// 在行首匹配块级自定义语法，其间的内容用 pulldown-cmark 解析，自定义块的正文递归解析
+    fn parse_custom_blocks(&self, input: &str, references: &RefDefs) -> RootNode {
+        let mut root = self.parse_commonmark("", None);
+        let mut rest = 0;
+        for (line, _) in lines_outside_code(input) {
+            if line.start < rest {
+                continue;
+            }
+            let Some(block) = self.custom.parse_block(&input[line.start..]) else {
+                continue;
+            };
+            let mut end = line.start + block.len;
+            // 块没有在行尾结束时延伸到行尾
+            if !input[..end].ends_with('\n') {
+                end = input[end..].find('\n').map_or(input.len(), |offset| end + offset + 1);
+            }
+            let mut node = block.node;
+            if let Some(content) = block.content {
+                let content = input[line.start..end].get(content).unwrap_or_default();
+                node.children.extend(self.parse_custom_blocks(content, references).children);
+            }
+            root.children.extend(self.parse_commonmark(&input[rest..line.start], Some(references)).children);
+            root.children.push(ASTNode::Custom(node));
+            rest = end;
+        }
+        root.children.extend(self.parse_commonmark(&input[rest..], Some(references)).children);
+        root
+    }
+
+    /// 检查输入中未能生成卡片的卡片语法
+    ///
+    /// 格式错误或不符合 schema 的卡片解析时按普通 Markdown 处理（见 [`with_card_schemas`](Self::with_card_schemas)），
+    /// 该方法返回这些卡片的全部问题，按开始行排序。未启用卡片语法时返回空列表。
+    pub fn validate_cards(&self, input: &str) -> Vec<CardIssue> {
+        if !self.options.cards {
+            return Vec::new();
+        }
+        let line_of = |offset: usize| input[..offset].matches('\n').count() + 1;
+        let events: Vec<_> = Parser::new_ext(input, self.options.pulldown_options()).into_offset_iter().collect();
+
+        // 容器卡片：与解析时相同的配对规则，合法容器的正文不再检查其中的围栏卡片
+        let mut issues = Vec::new();
+        let containers = RefCell::new(Vec::new());
+        let card = |range: Range<usize>, attributes: &str, body: &str| {
+            let result = self.container_card(attributes, body);
+            containers.borrow_mut().push((range, attributes.to_string(), result.clone()));
+            result.ok()
+        };
+        let syntax = BlockSyntax { spoiler: self.options.spoiler, cards: Some(&card), details: None, subtext: false };
+        mark_blocks(input, events.clone(), &syntax);
+        let mut cards = Vec::new();
+        for (range, attributes, result) in containers.into_inner() {
+            match result {
+                Ok(_) => cards.push(range),
+                Err(found) => {
+                    let subtype = parse_card_attributes(&attributes).map(|(subtype, _)| subtype).unwrap_or_default();
+                    issues.extend(issues_at_line(line_of(range.start), &subtype, found));
+                }
+            }
+        }
+
+        // 围栏卡片：任意嵌套层级中的 ```` ```card:<子类型> ```` 代码块
+        let mut events = events.into_iter();
+        while let Some((event, range)) = events.next() {
+            let Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(language))) = event else {
+                continue;
+            };
+            let Some(subtype) = language.strip_prefix("card:").and_then(|rest| rest.split_whitespace().next()) else {
+                continue;
+            };
+            let mut content = String::new();
+            for (event, _) in events.by_ref() {
+                match event {
+                    Event::Text(text) => content.push_str(&text),
+                    Event::End(Tag::CodeBlock(_)) => break,
+                    _ => {}
+                }
+            }
+            if cards.iter().any(|card| card.contains(&range.start)) {
+                continue;
+            }
+            if let Err(found) = self.fenced_card(subtype, &content) {
+                issues.extend(issues_at_line(line_of(range.start), subtype, found));
+            }
+        }
+        issues.sort_by_key(|issue| issue.line);
+        issues
+    }
+
+    /// `:::card{...}` 容器卡片：开始行属性作为子类型和 metadata，容器正文原样作为卡片正文
+    fn container_card(&self, attributes: &str, body: &str) -> Result<CardNode, Vec<ValidationIssue>> {
+        let (subtype, metadata) = parse_card_attributes(attributes).ok_or_else(|| {
+            vec![invalid_card("", format!("invalid card attributes `{}`, expected `{{type=<subtype> key=value ...}}`", attributes.trim()))]
+        })?;
+        self.checked_card(CardNode { subtype, content: body.trim_end().to_string(), metadata, ..CardNode::default() })
+    }
+
+    /// ```` ```card:<子类型> ```` 围栏卡片
+    fn fenced_card(&self, subtype: &str, body: &str) -> Result<CardNode, Vec<ValidationIssue>> {
+        self.checked_card(parse_fenced_card(subtype, body).map_err(|issue| vec![issue])?)
+    }
+
+    /// 检查卡片结构及其子类型的 schema，返回的问题路径相对于卡片节点
+    fn checked_card(&self, card: CardNode) -> Result<CardNode, Vec<ValidationIssue>> {
+        let mut issues = card_issues(&card, "");
+        issues.extend(self.card_schemas.check_card(&card, ""));
+        if issues.is_empty() {
+            Ok(card)
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// 代码块节点：`mermaid` 和 `card:<子类型>` 代码块按选项转换为对应节点
+    fn code_block_node(&self, language: Option<String>, content: String) -> ASTNode {
+        if let Some(lang) = &language {
+            if self.options.mermaid && lang.to_lowercase() == "mermaid" {
+                return ASTNode::Mermaid(MermaidNode { content });
+            }
+            let subtype = lang.strip_prefix("card:").and_then(|rest| rest.split_whitespace().next());
+            if let Some(subtype) = subtype.filter(|_| self.options.cards) {
+                if let Ok(card) = self.fenced_card(subtype, &content) {
+                    return ASTNode::Card(Box::new(card));
+                }
+            }
+        }
+        ASTNode::CodeBlock(CodeBlockNode { language, content })
+    }
+
+    /// 用 pulldown-cmark 解析块级结构，`references` 是输入之外可供引用的链接引用定义
+    fn parse_commonmark(&self, input: &str, references: Option<&RefDefs>) -> RootNode {
+        let mut resolve = |link: BrokenLink<'_>| {
+            let definition = references?.get(&link.reference)?;
+            let title = definition.title.as_deref().unwrap_or_default();
+            Some((definition.dest.to_string().into(), title.to_string().into()))
+        };
+        let parser = Parser::new_with_broken_link_callback(input, self.options.pulldown_options(), Some(&mut resolve));
         let mut builder = ASTBuilder::new();
         builder.start_document();
 
-        let mut events = parser.into_iter().peekable();
+        let underline = self.options.dialect == MarkdownDialect::Discord;
+        let card = |_: Range<usize>, attributes: &str, body: &str| self.container_card(attributes, body).ok();
+        let summary = |title: &str| self.parse_inline(title);
+        let syntax = BlockSyntax {
+            spoiler: self.options.spoiler,
+            cards: self.options.cards.then_some(&card as CardBuilder<'_>),
+            details: self.options.html.details.then_some(&summary as InlineParser<'_>),
+            subtext: self.options.dialect == MarkdownDialect::Discord,
+        };
+        let (events, markers) = mark_blocks(input, parser.into_offset_iter().collect(), &syntax);
+        // 每个块级标记之前已完成的顶层块数量
+        let mut positions = Vec::new();
+        let events = EscapedMarkers::new(input, events.into_iter(), self.options.delimiter_chars());
+        let mut events = UnderlineMarkers::new(input, events, underline).peekable();
         let mut current_inline_styles: Vec<InlineStyle> = Vec::new();
         let mut in_paragraph = false;
 
@@ -63,15 +698,12 @@ impl MarkdownParser {
                             // 收集代码块内容
                             let content = self.collect_code_block_content(&mut events);
                             
-                            // 检查是否是 Mermaid
-                            if let Some(ref lang) = language {
-                                if lang.to_lowercase() == "mermaid" {
-                                    builder.add_mermaid(content);
-                                } else {
-                                    builder.add_code_block(language, content);
-                                }
-                            } else {
-                                builder.add_code_block(language, content);
+                            // 检查是否是 Mermaid 或卡片
+                            match self.code_block_node(language, content) {
+                                ASTNode::Mermaid(mermaid) => builder.add_mermaid(mermaid.content),
+                                ASTNode::Card(card) => builder.add_card_node(*card),
+                                ASTNode::CodeBlock(block) => builder.add_code_block(block.language, block.content),
+                                _ => {}
                             }
                         }
                         Tag::List(Some(1)) => {
@@ -118,7 +750,7 @@ impl MarkdownParser {
                                         break;
                                     }
                                     Event::Text(text) => {
-                                        alt_text.push_str(&text);
+                                        alt_text.push_str(text);
                                         events.next();
                                     }
                                     _ => {
@@ -134,12 +766,19 @@ impl MarkdownParser {
                         Tag::Strikethrough => {
                             current_inline_styles.push(InlineStyle::Strike);
                         }
+                        Tag::FootnoteDefinition(label) => {
+                            let mut children = Vec::new();
+                            self.collect_block_content(&mut events, &mut children);
+                            builder.add_footnote_definition(label.to_string(), children);
+                        }
                         _ => {}
                     }
                 }
                 Event::End(tag) => {
                     match tag {
                         Tag::Paragraph => {
+                            // 段落内的 Markdown 样式都已结束，剩下的是未闭合的 HTML 标签
+                            current_inline_styles.clear();
                             // 检查当前段落是否只包含块级公式
                             // 需要收集所有文本节点的内容，因为 pulldown-cmark 可能会将公式拆分成多个节点
                             let should_convert_to_block_math = if let Some(para) = &builder.current_paragraph {
@@ -160,7 +799,7 @@ impl MarkdownParser {
                                 }
                                 
                                 // 如果只包含文本节点，检查是否是块级公式
-                                if only_text_nodes {
+                                if self.options.math && only_text_nodes {
                                     let trimmed = full_text.trim();
                                     if trimmed.starts_with("$$") && trimmed.ends_with("$$") && trimmed.len() > 4 {
                                         let inner = trimmed[2..trimmed.len()-2].trim();
@@ -243,11 +882,43 @@ impl MarkdownParser {
                 Event::Code(text) => {
                     builder.add_code(text.to_string());
                 }
-                Event::Html(_) => {
-                    // 忽略 HTML 标签（安全考虑）
+                Event::FootnoteReference(label) => {
+                    builder.add_footnote_reference(label.to_string());
+                }
+                Event::Html(html) if html.as_ref() == BLOCK_MARKER => {
+                    positions.push(builder.block_count());
+                }
+                Event::Html(html) if self.options.mentions.angle_brackets && is_angle_mention(&html) => {
+                    // `<!here>` 等提及保留为文本，解析完成后转换为提及
+                    builder.add_text(html.to_string());
+                }
+                Event::Html(html) if in_paragraph => {
+                    for node in self.raw_html_nodes(&html, &mut current_inline_styles) {
+                        builder.add_inline_node(node);
+                    }
+                }
+                Event::Html(html) => {
+                    // HTML 块：pulldown-cmark 逐行给出，合并后单独成段，内容不按 Markdown 解析；块内的样式不延续到块外
+                    let mut html = html.to_string();
+                    while let Some(Event::Html(next)) = events.peek() {
+                        if next.as_ref() == BLOCK_MARKER {
+                            break;
+                        }
+                        html.push_str(next);
+                        events.next();
+                    }
+                    let nodes = self.raw_html_nodes(html.trim_end(), &mut Vec::new());
+                    let blank = nodes.iter().all(|node| matches!(node, ASTNode::Text(text) if text.content.trim().is_empty()));
+                    if !blank {
+                        builder.start_paragraph();
+                        for node in nodes {
+                            builder.add_inline_node(node);
+                        }
+                        builder.end_paragraph();
+                    }
                 }
                 Event::SoftBreak => {
-                    builder.add_text(" ".to_string());
+                    builder.add_text(self.options.soft_break().to_string());
                 }
                 Event::HardBreak => {
                     builder.add_text("\n".to_string());
@@ -258,11 +929,65 @@ impl MarkdownParser {
                 Event::TaskListMarker(_checked) => {
                     // 任务列表标记，在 ListItem 中处理
                 }
-                _ => {}
             }
         }
 
-        Ok(builder.end_document())
+        let mut root = builder.end_document();
+        apply_block_markers(&mut root, markers, &positions);
+        root
+    }
+
+    /// 允许列表中的行内 HTML 标签对应的样式
+    fn html_style(&self, name: &str) -> Option<InlineStyle> {
+        let allowlist = &self.options.html;
+        match name {
+            "b" | "strong" if allowlist.emphasis => Some(InlineStyle::Strong),
+            "i" | "em" if allowlist.emphasis => Some(InlineStyle::Em),
+            "s" | "del" | "strike" if allowlist.emphasis => Some(InlineStyle::Strike),
+            "mark" if allowlist.emphasis => Some(InlineStyle::Highlight),
+            "u" | "ins" if allowlist.underline => Some(InlineStyle::Underline),
+            "sub" if allowlist.sub_sup => Some(InlineStyle::Custom("sub")),
+            "sup" if allowlist.sub_sup => Some(InlineStyle::Custom("sup")),
+            "kbd" if allowlist.kbd => Some(InlineStyle::Custom("kbd")),
+            _ => None,
+        }
+    }
+
+    /// 按允许列表转换原始 HTML：允许的开始/结束标签改变当前样式，`<br>` 转换为换行，
+    /// 其余标签（包括没有对应开始标签的结束标签）和文本按原文保留为文本，HTML 注释被忽略
+    fn raw_html_nodes(&self, html: &str, styles: &mut Vec<InlineStyle>) -> Vec<ASTNode> {
+        let mut nodes = Vec::new();
+        for token in html_tokens(html) {
+            let literal = match token {
+                HtmlToken::Comment => continue,
+                HtmlToken::Open { name, .. } if name == "br" && self.options.html.line_break => {
+                    nodes.push(ASTNode::Text(TextNode { content: "\n".to_string() }));
+                    continue;
+                }
+                HtmlToken::Open { name, self_closing: false, raw } => match self.html_style(&name) {
+                    Some(style) => {
+                        styles.push(style);
+                        continue;
+                    }
+                    None => raw,
+                },
+                HtmlToken::Close { name, raw } => {
+                    let open = self.html_style(&name).and_then(|style| styles.iter().rposition(|s| *s == style));
+                    match open {
+                        Some(pos) => {
+                            styles.remove(pos);
+                            continue;
+                        }
+                        None => raw,
+                    }
+                }
+                HtmlToken::Open { raw, .. } | HtmlToken::Text(raw) => raw,
+            };
+            if !literal.is_empty() {
+                nodes.extend(self.build_styled_nodes(literal.to_string(), styles));
+            }
+        }
+        nodes
     }
 
     fn collect_inline_content<'a>(
@@ -271,6 +996,7 @@ impl MarkdownParser {
         children: &mut Vec<ASTNode>,
         current_styles: &mut Vec<InlineStyle>,
     ) {
+        let outer_styles = current_styles.len();
         while let Some(event) = events.peek() {
             match event {
                 Event::End(Tag::Heading(_, _, _))
@@ -292,8 +1018,25 @@ impl MarkdownParser {
                                     content: code.to_string(),
                                 }));
                             }
-                            Event::Html(_) => {
-                                // 忽略 HTML
+                            Event::FootnoteReference(label) => {
+                                children.push(ASTNode::FootnoteReference(FootnoteReferenceNode {
+                                    label: label.to_string(),
+                                    index: 0,
+                                }));
+                            }
+                            Event::Html(html) if self.options.mentions.angle_brackets && is_angle_mention(&html) => {
+                                children.push(ASTNode::Text(TextNode { content: html.to_string() }));
+                            }
+                            Event::Html(html) => {
+                                children.extend(self.raw_html_nodes(&html, current_styles));
+                            }
+                            Event::SoftBreak => {
+                                children.push(ASTNode::Text(TextNode {
+                                    content: self.options.soft_break().to_string(),
+                                }));
+                            }
+                            Event::HardBreak => {
+                                children.push(ASTNode::Text(TextNode { content: "\n".to_string() }));
                             }
                             Event::Start(Tag::Strong) => {
                                 current_styles.push(InlineStyle::Strong);
@@ -333,6 +1076,8 @@ impl MarkdownParser {
                 }
             }
         }
+        // 未闭合的 HTML 标签不延续到下一个块
+        current_styles.truncate(outer_styles);
     }
 
     fn collect_block_content<'a>(
@@ -344,7 +1089,7 @@ impl MarkdownParser {
         
         while let Some(event) = events.peek() {
             match event {
-                Event::End(Tag::BlockQuote) => {
+                Event::End(Tag::BlockQuote) | Event::End(Tag::FootnoteDefinition(_)) => {
                     events.next(); // 消费 End 事件
                     break;
                 }
@@ -352,6 +1097,9 @@ impl MarkdownParser {
                     events.next();
                     let mut para_children = Vec::new();
                     self.collect_inline_content(events, &mut para_children, &mut current_styles);
+                    if matches!(events.peek(), Some(Event::End(Tag::Paragraph))) {
+                        events.next();
+                    }
                     
                     // 检查是否是块级公式
                     let should_convert_to_block_math = {
@@ -370,7 +1118,7 @@ impl MarkdownParser {
                             }
                         }
                         
-                        if only_text_nodes {
+                        if self.options.math && only_text_nodes {
                             let trimmed = full_text.trim();
                             trimmed.starts_with("$$") && trimmed.ends_with("$$") && trimmed.len() > 4
                                 && !trimmed[2..trimmed.len()-2].trim().contains("$$")
@@ -461,21 +1209,16 @@ impl MarkdownParser {
                     events.next();
                     let content = self.collect_code_block_content(events);
                     
-                    if let Some(ref lang) = language {
-                        if lang.to_lowercase() == "mermaid" {
-                            children.push(ASTNode::Mermaid(MermaidNode { content }));
-                        } else {
-                            children.push(ASTNode::CodeBlock(CodeBlockNode { language, content }));
-                        }
-                    } else {
-                        children.push(ASTNode::CodeBlock(CodeBlockNode { language, content }));
-                    }
+                    children.push(self.code_block_node(language, content));
                 }
                 Event::Start(Tag::Heading(level, _, _)) => {
                     let heading_level = *level as u8; // 先复制 level 的值
                     events.next();
                     let mut heading_children = Vec::new();
                     self.collect_inline_content(events, &mut heading_children, &mut current_styles);
+                    if matches!(events.peek(), Some(Event::End(Tag::Heading(..)))) {
+                        events.next();
+                    }
                     children.push(ASTNode::Heading(HeadingNode {
                         level: heading_level,
                         children: heading_children,
@@ -487,6 +1230,18 @@ impl MarkdownParser {
                     self.collect_block_content(events, &mut nested_children);
                     children.push(ASTNode::Blockquote(BlockquoteNode { children: nested_children }));
                 }
+                Event::Start(Tag::FootnoteDefinition(label)) => {
+                    // 嵌套在引用块、列表项中的脚注定义先原地保留，由 number_footnotes 移到末尾
+                    let label = label.to_string();
+                    events.next();
+                    let mut definition_children = Vec::new();
+                    self.collect_block_content(events, &mut definition_children);
+                    children.push(ASTNode::FootnoteDefinition(FootnoteDefinitionNode {
+                        label,
+                        index: 0,
+                        children: definition_children,
+                    }));
+                }
                 Event::Rule => {
                     events.next();
                     children.push(ASTNode::HorizontalRule(HorizontalRuleNode {}));
@@ -552,7 +1307,7 @@ impl MarkdownParser {
                             }
                         }
                         
-                        if only_text_nodes {
+                        if self.options.math && only_text_nodes {
                             let trimmed = full_text.trim();
                             trimmed.starts_with("$$") && trimmed.ends_with("$$") && trimmed.len() > 4
                                 && !trimmed[2..trimmed.len()-2].trim().contains("$$")
@@ -648,15 +1403,7 @@ impl MarkdownParser {
                     events.next();
                     let content = self.collect_code_block_content(events);
                     
-                    if let Some(ref lang) = language {
-                        if lang.to_lowercase() == "mermaid" {
-                            children.push(ASTNode::Mermaid(MermaidNode { content }));
-                        } else {
-                            children.push(ASTNode::CodeBlock(CodeBlockNode { language, content }));
-                        }
-                    } else {
-                        children.push(ASTNode::CodeBlock(CodeBlockNode { language, content }));
-                    }
+                    children.push(self.code_block_node(language, content));
                 }
                 Event::Start(Tag::BlockQuote) => {
                     events.next();
@@ -664,6 +1411,18 @@ impl MarkdownParser {
                     self.collect_block_content(events, &mut blockquote_children);
                     children.push(ASTNode::Blockquote(BlockquoteNode { children: blockquote_children }));
                 }
+                Event::Start(Tag::FootnoteDefinition(label)) => {
+                    // 嵌套在引用块、列表项中的脚注定义先原地保留，由 number_footnotes 移到末尾
+                    let label = label.to_string();
+                    events.next();
+                    let mut definition_children = Vec::new();
+                    self.collect_block_content(events, &mut definition_children);
+                    children.push(ASTNode::FootnoteDefinition(FootnoteDefinitionNode {
+                        label,
+                        index: 0,
+                        children: definition_children,
+                    }));
+                }
                 _ => {
                     // 处理内联内容
                     self.collect_inline_content(events, children, current_styles);
@@ -684,7 +1443,7 @@ impl MarkdownParser {
                     break;
                 }
                 Event::Text(text) => {
-                    content.push_str(&text);
+                    content.push_str(text);
                     content.push('\n');
                     events.next();
                 }
@@ -777,6 +1536,9 @@ impl MarkdownParser {
 
     /// 分割块级数学公式 $$...$$
     fn split_block_math(&self, text: &str) -> Option<Vec<TextPart>> {
+        if !self.options.math {
+            return None;
+        }
         let mut parts = Vec::new();
         let mut last_end = 0;
         let mut i = 0;
@@ -835,6 +1597,9 @@ impl MarkdownParser {
 
     /// 分割行内数学公式 $...$
     fn split_inline_math(&self, text: &str) -> Vec<TextPart> {
+        if !self.options.math {
+            return vec![TextPart::Text(text.to_string())];
+        }
         let mut parts = Vec::new();
         let mut last_end = 0;
         let chars: Vec<(usize, char)> = text.char_indices().collect();
@@ -993,6 +1758,17 @@ impl MarkdownParser {
                     url: url.clone(),
                     children: vec![current],
                 }),
+                InlineStyle::Underline => ASTNode::Underline(UnderlineNode {
+                    children: vec![current],
+                }),
+                InlineStyle::Highlight => ASTNode::Highlight(HighlightNode {
+                    children: vec![current],
+                }),
+                InlineStyle::Custom(kind) => ASTNode::Custom(CustomNode {
+                    kind: kind.to_string(),
+                    attrs: HashMap::new(),
+                    children: vec![current],
+                }),
             };
         }
 
@@ -1000,12 +1776,16 @@ impl MarkdownParser {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 enum InlineStyle {
     Strong,
     Em,
     Strike,
     Link(String),
+    Underline,
+    Highlight,
+    /// 没有专用节点的 HTML 标签（`sub`、`sup`、`kbd`），转换为同名自定义节点
+    Custom(&'static str),
 }
 
 /// 文本部分（用于数学公式解析）
@@ -0,0 +1,308 @@
+use crate::ast::*;
+use crate::delimiter::merge_text;
+use crate::visitor::*;
+use serde::{Deserialize, Serialize};
+
+/// 提及语法开关
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MentionSyntax {
+    /// 尖括号形式：`<@U123>`、`<@U123|alice>`、`<@&R123>`（角色）、`<#C123>`、`<!here>`、`<!channel>`（Slack / Discord 机器人消息）
+    pub angle_brackets: bool,
+    /// 链接形式：`@[Alice](user:123)`、`#[general](channel:C1)`
+    pub links: bool,
+    /// 纯文本广播：`@all`、`@everyone`、`@channel`、`@here`
+    pub broadcast: bool,
+}
+
+impl Default for MentionSyntax {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl MentionSyntax {
+    /// 启用全部提及语法
+    pub fn all() -> Self {
+        Self {
+            angle_brackets: true,
+            links: true,
+            broadcast: true,
+        }
+    }
+
+    /// 关闭全部提及语法
+    pub fn none() -> Self {
+        Self {
+            angle_brackets: false,
+            links: false,
+            broadcast: false,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.angle_brackets || self.links || self.broadcast
+    }
+}
+
+/// 识别文本中的提及语法并替换为 [`MentionNode`]
+///
+/// 提及只在同一容器的文本节点中识别：行内代码和代码块是独立节点，不会被处理；
+/// 链接文本中也不识别，避免出现嵌套的可点击元素。
+pub(crate) fn parse_mentions(root: &mut RootNode, syntax: &MentionSyntax) {
+    if syntax.is_enabled() {
+        MentionPass { syntax }.visit_root_mut(root);
+    }
+}
+
+/// 整段 HTML 是否为尖括号形式的广播提及（如 `<!here>`）
+///
+/// pulldown-cmark 把 `<!here>` 当作 HTML 声明，解析器据此将其保留为文本。
+pub(crate) fn is_angle_mention(html: &str) -> bool {
+    html.starts_with("<!") && matches!(parse_angle(html), Some((len, _)) if len == html.len())
+}
+
+struct MentionPass<'a> {
+    syntax: &'a MentionSyntax,
+}
+
+impl MentionPass<'_> {
+    /// pulldown-cmark 可能把 `<` 拆成单独的文本节点，识别前先合并相邻文本；没有识别到提及时保持原样
+    fn apply(&self, children: &mut Vec<ASTNode>) {
+        let has_candidate = children.iter().any(|child| match child {
+            ASTNode::Text(text) => text.content.contains(['<', '@']),
+            ASTNode::Link(_) => true,
+            _ => false,
+        });
+        if !has_candidate {
+            return;
+        }
+
+        let mut found = false;
+        let mut result: Vec<ASTNode> = Vec::with_capacity(children.len());
+        for child in merge_text(children.clone()) {
+            match child {
+                ASTNode::Link(link) => match self.link_mention(&link, result.last()) {
+                    Some((mention, strip_prefix)) => {
+                        if strip_prefix {
+                            if let Some(ASTNode::Text(prev)) = result.last_mut() {
+                                prev.content.pop();
+                                if prev.content.is_empty() {
+                                    result.pop();
+                                }
+                            }
+                        }
+                        result.push(ASTNode::Mention(mention));
+                        found = true;
+                    }
+                    None => result.push(ASTNode::Link(link)),
+                },
+                ASTNode::Text(text) => match split_mentions(&text.content, self.syntax) {
+                    Some(nodes) => {
+                        result.extend(nodes);
+                        found = true;
+                    }
+                    None => result.push(ASTNode::Text(text)),
+                },
+                child => result.push(child),
+            }
+        }
+        if found {
+            *children = result;
+        }
+    }
+
+    /// 链接形式的提及，返回提及以及是否需要去掉前一个文本末尾的 `@` / `#`
+    ///
+    /// pulldown-cmark 会把 `<@U123>` 解析为自动链接，这里一并还原为提及。
+    fn link_mention(&self, link: &LinkNode, before: Option<&ASTNode>) -> Option<(MentionNode, bool)> {
+        let text = link_text(link);
+        if self.syntax.angle_brackets && text == link.url {
+            let angle = format!("<{}>", link.url);
+            if let Some((len, mention)) = parse_angle(&angle) {
+                if len == angle.len() {
+                    return Some((mention, false));
+                }
+            }
+        }
+
+        if !self.syntax.links {
+            return None;
+        }
+        let before = match before {
+            Some(ASTNode::Text(prev)) => prev.content.chars().last()?,
+            _ => return None,
+        };
+        let (kind, id) = match before {
+            '@' => (MentionKind::User, link.url.strip_prefix("user:")?),
+            '#' => (MentionKind::Channel, link.url.strip_prefix("channel:")?),
+            _ => return None,
+        };
+        id_mention(kind, id, &text).map(|mention| (mention, true))
+    }
+}
+
+fn link_text(link: &LinkNode) -> String {
+    link.children.iter()
+        .map(|child| match child {
+            ASTNode::Text(text) => text.content.as_str(),
+            _ => "",
+        })
+        .collect()
+}
+
+impl VisitorMut for MentionPass<'_> {
+    fn visit_paragraph_mut(&mut self, para: &mut ParagraphNode) {
+        walk_paragraph_mut(self, para);
+        self.apply(&mut para.children);
+    }
+
+    fn visit_heading_mut(&mut self, heading: &mut HeadingNode) {
+        walk_heading_mut(self, heading);
+        self.apply(&mut heading.children);
+    }
+
+    fn visit_strong_mut(&mut self, strong: &mut StrongNode) {
+        walk_strong_mut(self, strong);
+        self.apply(&mut strong.children);
+    }
+
+    fn visit_em_mut(&mut self, em: &mut EmNode) {
+        walk_em_mut(self, em);
+        self.apply(&mut em.children);
+    }
+
+    fn visit_underline_mut(&mut self, underline: &mut UnderlineNode) {
+        walk_underline_mut(self, underline);
+        self.apply(&mut underline.children);
+    }
+
+    fn visit_strike_mut(&mut self, strike: &mut StrikeNode) {
+        walk_strike_mut(self, strike);
+        self.apply(&mut strike.children);
+    }
+
+    fn visit_highlight_mut(&mut self, highlight: &mut HighlightNode) {
+        walk_highlight_mut(self, highlight);
+        self.apply(&mut highlight.children);
+    }
+
+    fn visit_spoiler_mut(&mut self, spoiler: &mut SpoilerNode) {
+        walk_spoiler_mut(self, spoiler);
+        self.apply(&mut spoiler.children);
+    }
+
+    fn visit_custom_mut(&mut self, custom: &mut CustomNode) {
+        walk_custom_mut(self, custom);
+        self.apply(&mut custom.children);
+    }
+
+    fn visit_link_mut(&mut self, _link: &mut LinkNode) {}
+
+    fn visit_list_item_mut(&mut self, item: &mut ListItemNode) {
+        walk_list_item_mut(self, item);
+        self.apply(&mut item.children);
+    }
+
+    fn visit_table_cell_mut(&mut self, cell: &mut TableCell) {
+        walk_table_cell_mut(self, cell);
+        self.apply(&mut cell.children);
+    }
+}
+
+/// 拆分文本中的尖括号提及和广播提及；没有提及时返回 `None`
+fn split_mentions(content: &str, syntax: &MentionSyntax) -> Option<Vec<ASTNode>> {
+    if !(syntax.angle_brackets || syntax.broadcast) {
+        return None;
+    }
+    let bytes = content.as_bytes();
+    let mut nodes = Vec::new();
+    let mut pending = 0;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let matched = match bytes[index] {
+            b'<' if syntax.angle_brackets => parse_angle(&content[index..]),
+            b'@' if syntax.broadcast && word_boundary_before(content, index) => parse_broadcast(&content[index..]),
+            _ => None,
+        };
+        match matched {
+            Some((len, mention)) => {
+                if pending < index {
+                    nodes.push(ASTNode::Text(TextNode { content: content[pending..index].to_string() }));
+                }
+                nodes.push(ASTNode::Mention(mention));
+                index += len;
+                pending = index;
+            }
+            None => index += 1,
+        }
+    }
+
+    if nodes.is_empty() {
+        return None;
+    }
+    if pending < content.len() {
+        nodes.push(ASTNode::Text(TextNode { content: content[pending..].to_string() }));
+    }
+    Some(nodes)
+}
+
+/// 解析 `<@id>`、`<@!id>`、`<@&id>`、`<@id|name>`、`<#id>`、`<#id|name>`、`<!here>` 等，返回消耗的字节数
+pub(crate) fn parse_angle(input: &str) -> Option<(usize, MentionNode)> {
+    let end = input.find('>')?;
+    let inner = &input[1..end];
+    let (target, label) = match inner.split_once('|') {
+        Some((target, label)) => (target, label),
+        None => (inner, ""),
+    };
+    if label.contains(['<', '\n']) {
+        return None;
+    }
+
+    let mention = match *target.as_bytes().first()? {
+        b'@' => match target[1..].strip_prefix('&') {
+            Some(id) => id_mention(MentionKind::Role, id, label)?,
+            None => {
+                let id = target[1..].strip_prefix('!').unwrap_or(&target[1..]);
+                id_mention(MentionKind::User, id, label)?
+            }
+        },
+        b'#' => id_mention(MentionKind::Channel, &target[1..], label)?,
+        b'!' => broadcast_mention(&target[1..])?,
+        _ => return None,
+    };
+    Some((end + 1, mention))
+}
+
+fn id_mention(kind: MentionKind, id: &str, name: &str) -> Option<MentionNode> {
+    if id.is_empty() || !id.chars().all(is_id_char) {
+        return None;
+    }
+    Some(MentionNode { id: id.to_string(), name: name.to_string(), kind, status: MentionStatus::default() })
+}
+
+/// 解析 `@all`、`@here` 等，返回消耗的字节数
+fn parse_broadcast(input: &str) -> Option<(usize, MentionNode)> {
+    let word_len = input[1..].find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(input.len() - 1);
+    let mention = broadcast_mention(&input[1..1 + word_len])?;
+    Some((1 + word_len, mention))
+}
+
+pub(crate) fn broadcast_mention(keyword: &str) -> Option<MentionNode> {
+    let (kind, id) = match keyword {
+        "all" | "everyone" | "channel" => (MentionKind::All, "all"),
+        "here" => (MentionKind::Here, "here"),
+        _ => return None,
+    };
+    Some(MentionNode { id: id.to_string(), name: keyword.to_string(), kind, status: MentionStatus::default() })
+}
+
+/// `@` 前不是单词字符，避免把邮箱地址等识别为提及
+fn word_boundary_before(content: &str, index: usize) -> bool {
+    content[..index].chars().last().is_none_or(|c| !(c.is_alphanumeric() || c == '_'))
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
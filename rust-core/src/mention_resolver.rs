@@ -0,0 +1,123 @@
+use crate::ast::*;
+use crate::visitor::*;
+use crate::ParseError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 提及目标（用户、频道或角色）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MentionTarget {
+    /// 当前显示名称
+    pub name: String,
+    /// 是否已停用（离职、注销、归档的频道等）
+    #[serde(default)]
+    pub deactivated: bool,
+}
+
+/// 提及解析器
+///
+/// 由宿主提供通讯录，在解析完成后通过 [`resolve_mentions`] 为提及填入当前显示名称。
+/// 闭包 `Fn(MentionKind, &str) -> Option<MentionTarget>` 也实现了该 trait。
+pub trait MentionResolver {
+    /// 按类型和 ID 查找用户、频道或角色，找不到时返回 `None`
+    fn resolve(&self, kind: MentionKind, id: &str) -> Option<MentionTarget>;
+}
+
+impl<F> MentionResolver for F
+where
+    F: Fn(MentionKind, &str) -> Option<MentionTarget>,
+{
+    fn resolve(&self, kind: MentionKind, id: &str) -> Option<MentionTarget> {
+        self(kind, id)
+    }
+}
+
+/// 基于查找表的提及解析器
+///
+/// JSON 格式：`{"users": {"U1": {"name": "Alice"}}, "channels": {"C1": {"name": "general", "deactivated": true}}, "roles": {...}}`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MentionDirectory {
+    #[serde(default)]
+    pub users: HashMap<String, MentionTarget>,
+    #[serde(default)]
+    pub channels: HashMap<String, MentionTarget>,
+    #[serde(default)]
+    pub roles: HashMap<String, MentionTarget>,
+}
+
+impl MentionDirectory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ParseError> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+impl MentionResolver for MentionDirectory {
+    fn resolve(&self, kind: MentionKind, id: &str) -> Option<MentionTarget> {
+        match kind {
+            MentionKind::User => self.users.get(id).cloned(),
+            MentionKind::Channel => self.channels.get(id).cloned(),
+            MentionKind::Role => self.roles.get(id).cloned(),
+            MentionKind::All | MentionKind::Here => None,
+        }
+    }
+}
+
+/// 解析 AST 中的用户、频道和角色提及
+///
+/// 找到目标时用当前名称替换 `name`（目录中名称为空时保留原名称），并将 `status` 设为
+/// `active` 或 `deactivated`；找不到时设为 `unknown`。`@all` / `@here` 不需要解析，保持不变。
+pub fn resolve_mentions<R: MentionResolver + ?Sized>(root: &mut RootNode, resolver: &R) {
+    MentionResolution { resolver }.visit_root_mut(root);
+}
+
+/// 消息是否提及了指定用户（直接提及，或 `@all` / `@here` 广播），用于计算通知角标
+pub fn mentions_user(root: &RootNode, user_id: &str) -> bool {
+    let mut finder = MentionFinder { user_id, found: false };
+    finder.visit_root(root);
+    finder.found
+}
+
+struct MentionResolution<'a, R: ?Sized> {
+    resolver: &'a R,
+}
+
+impl<R: MentionResolver + ?Sized> VisitorMut for MentionResolution<'_, R> {
+    fn visit_mention_mut(&mut self, mention: &mut MentionNode) {
+        if matches!(mention.kind, MentionKind::All | MentionKind::Here) {
+            return;
+        }
+        match self.resolver.resolve(mention.kind, &mention.id) {
+            Some(target) => {
+                if !target.name.is_empty() {
+                    mention.name = target.name;
+                }
+                mention.status = if target.deactivated {
+                    MentionStatus::Deactivated
+                } else {
+                    MentionStatus::Active
+                };
+            }
+            None => mention.status = MentionStatus::Unknown,
+        }
+    }
+}
+
+struct MentionFinder<'a> {
+    user_id: &'a str,
+    found: bool,
+}
+
+impl Visitor for MentionFinder<'_> {
+    fn visit_mention(&mut self, mention: &MentionNode) {
+        self.found |= match mention.kind {
+            MentionKind::User => mention.id == self.user_id,
+            MentionKind::All | MentionKind::Here => true,
+            // 是否属于某个角色由宿主判断，这里不计入
+            MentionKind::Channel | MentionKind::Role => false,
+        };
+    }
+}
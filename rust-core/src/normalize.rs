@@ -0,0 +1,248 @@
+use crate::ast::*;
+use crate::visitor::*;
+
+/// 规范化 AST
+///
+/// 依次执行：
+/// - 合并相邻的文本节点（如 Markdown 的 SoftBreak 产生的独立 `" "` 节点）
+/// - 移除空文本节点和没有子节点的行内容器（粗体、斜体、下划线、删除线、高亮、剧透、链接）
+/// - 合并相邻的同类样式节点，并将单子节点的样式嵌套链统一为
+///   `strong > em > underline > strike > highlight > link` 的顺序
+/// - 移除根节点末尾的空段落
+pub fn normalize(root: &mut RootNode) {
+    Normalizer.visit_root_mut(root);
+    while matches!(root.children.last(), Some(ASTNode::Paragraph(para)) if para.children.is_empty()) {
+        root.children.pop();
+    }
+}
+
+struct Normalizer;
+
+impl VisitorMut for Normalizer {
+    fn visit_root_mut(&mut self, root: &mut RootNode) {
+        walk_root_mut(self, root);
+        normalize_children(&mut root.children);
+    }
+
+    fn visit_paragraph_mut(&mut self, para: &mut ParagraphNode) {
+        walk_paragraph_mut(self, para);
+        normalize_children(&mut para.children);
+    }
+
+    fn visit_heading_mut(&mut self, heading: &mut HeadingNode) {
+        walk_heading_mut(self, heading);
+        normalize_children(&mut heading.children);
+    }
+
+    fn visit_strong_mut(&mut self, strong: &mut StrongNode) {
+        walk_strong_mut(self, strong);
+        normalize_children(&mut strong.children);
+    }
+
+    fn visit_em_mut(&mut self, em: &mut EmNode) {
+        walk_em_mut(self, em);
+        normalize_children(&mut em.children);
+    }
+
+    fn visit_underline_mut(&mut self, underline: &mut UnderlineNode) {
+        walk_underline_mut(self, underline);
+        normalize_children(&mut underline.children);
+    }
+
+    fn visit_strike_mut(&mut self, strike: &mut StrikeNode) {
+        walk_strike_mut(self, strike);
+        normalize_children(&mut strike.children);
+    }
+
+    fn visit_link_mut(&mut self, link: &mut LinkNode) {
+        walk_link_mut(self, link);
+        normalize_children(&mut link.children);
+    }
+
+    fn visit_list_item_mut(&mut self, item: &mut ListItemNode) {
+        walk_list_item_mut(self, item);
+        normalize_children(&mut item.children);
+    }
+
+    fn visit_table_cell_mut(&mut self, cell: &mut TableCell) {
+        walk_table_cell_mut(self, cell);
+        normalize_children(&mut cell.children);
+    }
+
+    fn visit_blockquote_mut(&mut self, blockquote: &mut BlockquoteNode) {
+        walk_blockquote_mut(self, blockquote);
+        normalize_children(&mut blockquote.children);
+    }
+
+    fn visit_footnote_definition_mut(&mut self, definition: &mut FootnoteDefinitionNode) {
+        walk_footnote_definition_mut(self, definition);
+        normalize_children(&mut definition.children);
+    }
+
+    fn visit_spoiler_mut(&mut self, spoiler: &mut SpoilerNode) {
+        walk_spoiler_mut(self, spoiler);
+        normalize_children(&mut spoiler.children);
+    }
+
+    fn visit_highlight_mut(&mut self, highlight: &mut HighlightNode) {
+        walk_highlight_mut(self, highlight);
+        normalize_children(&mut highlight.children);
+    }
+
+    fn visit_custom_mut(&mut self, custom: &mut CustomNode) {
+        walk_custom_mut(self, custom);
+        normalize_children(&mut custom.children);
+    }
+}
+
+/// 规范化一组已经规范化过子树的兄弟节点
+fn normalize_children(children: &mut Vec<ASTNode>) {
+    let mut result: Vec<ASTNode> = Vec::with_capacity(children.len());
+
+    for child in std::mem::take(children) {
+        let child = canonicalize_style_chain(child);
+        if is_empty_inline(&child) {
+            continue;
+        }
+
+        match (result.last_mut(), child) {
+            (Some(ASTNode::Text(prev)), ASTNode::Text(text)) => {
+                prev.content.push_str(&text.content);
+            }
+            (Some(prev), child) if same_style(prev, &child) => {
+                if let (Some(prev_children), Some(mut next_children)) =
+                    (style_children_mut(prev), style_children(child))
+                {
+                    prev_children.append(&mut next_children);
+                    normalize_children(prev_children);
+                }
+            }
+            (_, child) => result.push(child),
+        }
+    }
+
+    *children = result;
+}
+
+/// 空文本节点或没有子节点的行内容器
+fn is_empty_inline(node: &ASTNode) -> bool {
+    match node {
+        ASTNode::Text(text) => text.content.is_empty(),
+        ASTNode::Strong(StrongNode { children })
+        | ASTNode::Em(EmNode { children })
+        | ASTNode::Underline(UnderlineNode { children })
+        | ASTNode::Strike(StrikeNode { children })
+        | ASTNode::Highlight(HighlightNode { children })
+        | ASTNode::Spoiler(SpoilerNode { children })
+        | ASTNode::Link(LinkNode { children, .. }) => children.is_empty(),
+        _ => false,
+    }
+}
+
+/// 样式节点的嵌套顺序（数值越小越靠外）
+fn style_rank(node: &ASTNode) -> Option<u8> {
+    match node {
+        ASTNode::Strong(_) => Some(0),
+        ASTNode::Em(_) => Some(1),
+        ASTNode::Underline(_) => Some(2),
+        ASTNode::Strike(_) => Some(3),
+        ASTNode::Highlight(_) => Some(4),
+        ASTNode::Link(_) => Some(5),
+        _ => None,
+    }
+}
+
+/// 两个节点是否是可以合并的同类样式节点
+fn same_style(a: &ASTNode, b: &ASTNode) -> bool {
+    match (a, b) {
+        (ASTNode::Strong(_), ASTNode::Strong(_))
+        | (ASTNode::Em(_), ASTNode::Em(_))
+        | (ASTNode::Underline(_), ASTNode::Underline(_))
+        | (ASTNode::Strike(_), ASTNode::Strike(_))
+        | (ASTNode::Highlight(_), ASTNode::Highlight(_)) => true,
+        (ASTNode::Link(a), ASTNode::Link(b)) => a.url == b.url,
+        _ => false,
+    }
+}
+
+fn style_children(node: ASTNode) -> Option<Vec<ASTNode>> {
+    match node {
+        ASTNode::Strong(StrongNode { children })
+        | ASTNode::Em(EmNode { children })
+        | ASTNode::Underline(UnderlineNode { children })
+        | ASTNode::Strike(StrikeNode { children })
+        | ASTNode::Highlight(HighlightNode { children })
+        | ASTNode::Link(LinkNode { children, .. }) => Some(children),
+        _ => None,
+    }
+}
+
+fn style_children_mut(node: &mut ASTNode) -> Option<&mut Vec<ASTNode>> {
+    match node {
+        ASTNode::Strong(StrongNode { children })
+        | ASTNode::Em(EmNode { children })
+        | ASTNode::Underline(UnderlineNode { children })
+        | ASTNode::Strike(StrikeNode { children })
+        | ASTNode::Highlight(HighlightNode { children })
+        | ASTNode::Link(LinkNode { children, .. }) => Some(children),
+        _ => None,
+    }
+}
+
+/// 用给定的样式节点（子节点会被替换）包裹 children
+fn rewrap(style: ASTNode, children: Vec<ASTNode>) -> ASTNode {
+    match style {
+        ASTNode::Strong(_) => ASTNode::Strong(StrongNode { children }),
+        ASTNode::Em(_) => ASTNode::Em(EmNode { children }),
+        ASTNode::Underline(_) => ASTNode::Underline(UnderlineNode { children }),
+        ASTNode::Strike(_) => ASTNode::Strike(StrikeNode { children }),
+        ASTNode::Highlight(_) => ASTNode::Highlight(HighlightNode { children }),
+        ASTNode::Link(link) => ASTNode::Link(LinkNode { url: link.url, children }),
+        other => other,
+    }
+}
+
+/// 统一单子节点样式链的嵌套顺序，并去掉重复的样式层
+///
+/// 例如 Delta 产生的 `em > strong > text` 会变为 `strong > em > text`，
+/// `strong > strong > text` 会变为 `strong > text`。
+fn canonicalize_style_chain(node: ASTNode) -> ASTNode {
+    if style_rank(&node).is_none() {
+        return node;
+    }
+
+    // 拆出样式链：每一层都只有一个子节点，且子节点也是样式节点
+    let mut chain: Vec<ASTNode> = Vec::new();
+    let mut current = node;
+    let payload = loop {
+        let single_styled_child = matches!(
+            style_children_mut(&mut current),
+            Some(children) if children.len() == 1 && style_rank(&children[0]).is_some()
+        );
+        if single_styled_child {
+            let mut children = style_children_mut(&mut current).map(std::mem::take).unwrap_or_default();
+            chain.push(current);
+            current = children.remove(0);
+        } else {
+            let children = style_children_mut(&mut current).map(std::mem::take).unwrap_or_default();
+            chain.push(current);
+            break children;
+        }
+    };
+
+    if chain.len() == 1 {
+        return rewrap(chain.remove(0), payload);
+    }
+
+    chain.sort_by_key(|style| style_rank(style).unwrap_or(u8::MAX));
+    chain.dedup_by(|inner, outer| {
+        // 同一 URL 的链接重复嵌套才去重；不同 URL 时以内层为准保持原样
+        same_style(outer, inner)
+    });
+
+    let mut result = payload;
+    for style in chain.into_iter().rev() {
+        result = vec![rewrap(style, result)];
+    }
+    result.remove(0)
+}
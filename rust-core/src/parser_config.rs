@@ -0,0 +1,250 @@
+use crate::ast::*;
+use crate::cache::{global_parse_cache, CacheKey, InputFormat};
+use crate::card::{CardIssue, CardSchemaRegistry};
+use crate::custom::{BlockNodeParser, CustomNodeRenderer, InlineNodeParser};
+use crate::delta_parser::{DeltaOptions, DeltaParser};
+use crate::html_parser::HtmlParser;
+use crate::html_renderer::HtmlRenderer;
+use crate::markdown_parser::{MarkdownOptions, MarkdownParser};
+use crate::sanitize::{sanitize, SanitizeOptions};
+use crate::style_config::StyleConfig;
+use crate::visitor::*;
+use crate::ParseError;
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+/// 解析限制，超出时返回 [`ParseError::LimitExceeded`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ParseLimits {
+    /// 输入的最大字节数，`None` 表示不限制
+    #[serde(default)]
+    pub max_input_bytes: Option<usize>,
+    /// AST 的最大嵌套深度（根节点的子节点深度为 1），`None` 表示不限制
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+}
+
+/// 解析器配置
+///
+/// 一次性描述解析和渲染所需的全部设置，由 [`ConfiguredParser`] 持有并复用。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParserConfig {
+    /// Markdown 语法扩展，也可以是预设名称（如 `"im_chat"`）
+    #[serde(default, deserialize_with = "deserialize_markdown_options")]
+    pub markdown: MarkdownOptions,
+    /// Delta 属性映射
+    #[serde(default)]
+    pub delta: DeltaOptions,
+    /// Markdown 卡片子类型的 schema
+    #[serde(default)]
+    pub card_schemas: CardSchemaRegistry,
+    /// 解析后是否执行 [`normalize`](crate::normalize)
+    #[serde(default)]
+    pub normalize: bool,
+    #[serde(default)]
+    pub limits: ParseLimits,
+    /// URL 清理，`None` 表示不清理（HTML 输入仍按默认选项清理）
+    #[serde(default = "default_sanitize")]
+    pub sanitize: Option<SanitizeOptions>,
+    /// HTML 渲染样式
+    #[serde(default)]
+    pub style: StyleConfig,
+}
+
+fn deserialize_markdown_options<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MarkdownOptions, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OptionsOrPreset {
+        Preset(String),
+        Options(MarkdownOptions),
+    }
+
+    match OptionsOrPreset::deserialize(deserializer)? {
+        OptionsOrPreset::Options(options) => Ok(options),
+        OptionsOrPreset::Preset(name) => MarkdownOptions::preset(&name)
+            .ok_or_else(|| de::Error::custom(format!("unknown markdown preset: {}", name))),
+    }
+}
+
+fn default_sanitize() -> Option<SanitizeOptions> {
+    Some(SanitizeOptions::default())
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            markdown: MarkdownOptions::default(),
+            delta: DeltaOptions::default(),
+            card_schemas: CardSchemaRegistry::default(),
+            normalize: false,
+            limits: ParseLimits::default(),
+            sanitize: default_sanitize(),
+            style: StyleConfig::default(),
+        }
+    }
+}
+
+/// 按配置创建、可重复使用的解析器
+///
+/// 不含内部可变状态，可以在多个线程间共享。启用全局解析缓存时，缓存键包含解析相关的配置。
+pub struct ConfiguredParser {
+    config: ParserConfig,
+    markdown: MarkdownParser,
+    delta: DeltaParser,
+    html: HtmlParser,
+    renderer: HtmlRenderer,
+}
+
+impl ConfiguredParser {
+    pub fn new(config: ParserConfig) -> Self {
+        Self {
+            markdown: MarkdownParser::with_options(config.markdown)
+                .with_normalize(config.normalize)
+                .with_card_schemas(config.card_schemas.clone()),
+            delta: DeltaParser::with_options(config.delta).with_normalize(config.normalize),
+            // HTML 输入总是清理 URL，配置关闭 `sanitize` 时使用默认选项
+            html: HtmlParser::new()
+                .with_normalize(config.normalize)
+                .with_sanitize_options(config.sanitize.clone().unwrap_or_default()),
+            renderer: HtmlRenderer::with_config(config.style.clone()),
+            config,
+        }
+    }
+
+    /// 从 JSON 配置创建，缺省字段使用默认值
+    pub fn from_json(json: &str) -> Result<Self, ParseError> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    pub fn config(&self) -> &ParserConfig {
+        &self.config
+    }
+
+    /// 注册 Markdown 行内自定义语法，见 [`MarkdownParser::with_inline_node`]
+    pub fn with_inline_node(mut self, kind: impl Into<String>, parser: impl InlineNodeParser + 'static) -> Self {
+        self.markdown = self.markdown.with_inline_node(kind, parser);
+        self
+    }
+
+    /// 注册 Markdown 块级自定义语法，见 [`MarkdownParser::with_block_node`]
+    pub fn with_block_node(mut self, kind: impl Into<String>, parser: impl BlockNodeParser + 'static) -> Self {
+        self.markdown = self.markdown.with_block_node(kind, parser);
+        self
+    }
+
+    /// 注册自定义节点的 HTML 渲染钩子，见 [`HtmlRenderer::with_custom_renderer`]
+    pub fn with_custom_renderer(mut self, kind: impl Into<String>, renderer: impl CustomNodeRenderer + 'static) -> Self {
+        self.renderer = self.renderer.with_custom_renderer(kind, renderer);
+        self
+    }
+
+    /// 解析为 AST
+    pub fn parse(&self, format: InputFormat, input: &str) -> Result<RootNode, ParseError> {
+        match global_parse_cache() {
+            Some(cache) => cache
+                .get_or_parse(CacheKey::ast(format, input, &self.cache_options()), || self.parse_uncached(format, input))
+                .map(|ast| (*ast).clone()),
+            None => self.parse_uncached(format, input),
+        }
+    }
+
+    /// 检查 Markdown 输入中未能生成卡片的卡片语法，见 [`MarkdownParser::validate_cards`]
+    pub fn validate_cards(&self, input: &str) -> Vec<CardIssue> {
+        self.markdown.validate_cards(input)
+    }
+
+    /// 解析并渲染为 HTML
+    pub fn render_html(&self, format: InputFormat, input: &str) -> Result<String, ParseError> {
+        let render = || Ok(self.renderer.render(&self.parse(format, input)?));
+        match global_parse_cache() {
+            Some(cache) => {
+                let options = (self.cache_options(), self.renderer.custom_kinds());
+                let key = CacheKey::html(format, input, &options, &self.config.style);
+                cache.get_or_render(key, render).map(|html| html.to_string())
+            }
+            None => render(),
+        }
+    }
+
+    fn parse_uncached(&self, format: InputFormat, input: &str) -> Result<RootNode, ParseError> {
+        let limits = &self.config.limits;
+        if let Some(max) = limits.max_input_bytes {
+            if input.len() > max {
+                return Err(ParseError::LimitExceeded(format!(
+                    "input is {} bytes, limit is {}",
+                    input.len(),
+                    max
+                )));
+            }
+        }
+
+        let mut ast = match format {
+            InputFormat::Markdown => self.markdown.parse(input)?,
+            InputFormat::Delta => self.delta.parse(input)?,
+            InputFormat::Html => self.html.parse(input)?,
+        };
+
+        if let Some(max) = limits.max_depth {
+            let depth = ast_depth(&ast);
+            if depth > max {
+                return Err(ParseError::LimitExceeded(format!("AST depth is {}, limit is {}", depth, max)));
+            }
+        }
+        if let Some(options) = &self.config.sanitize {
+            sanitize(&mut ast, options);
+        }
+        Ok(ast)
+    }
+
+    /// 影响解析结果的配置（不含样式）
+    fn cache_options(&self) -> (&MarkdownParser, &DeltaParser, &HtmlParser, &ParseLimits, &Option<SanitizeOptions>) {
+        (&self.markdown, &self.delta, &self.html, &self.config.limits, &self.config.sanitize)
+    }
+}
+
+impl Default for ConfiguredParser {
+    fn default() -> Self {
+        Self::new(ParserConfig::default())
+    }
+}
+
+/// 计算 AST 的最大嵌套深度
+fn ast_depth(root: &RootNode) -> usize {
+    let mut counter = DepthCounter { depth: 0, max: 0 };
+    counter.visit_root(root);
+    counter.max
+}
+
+struct DepthCounter {
+    depth: usize,
+    max: usize,
+}
+
+impl Visitor for DepthCounter {
+    fn visit_node(&mut self, node: &ASTNode) {
+        self.depth += 1;
+        self.max = self.max.max(self.depth);
+        walk_node(self, node);
+        self.depth -= 1;
+    }
+
+    fn visit_list_item(&mut self, item: &ListItemNode) {
+        self.depth += 1;
+        self.max = self.max.max(self.depth);
+        walk_list_item(self, item);
+        self.depth -= 1;
+    }
+
+    fn visit_table_row(&mut self, row: &TableRow) {
+        self.depth += 1;
+        self.max = self.max.max(self.depth);
+        walk_table_row(self, row);
+        self.depth -= 1;
+    }
+
+    fn visit_table_cell(&mut self, cell: &TableCell) {
+        self.depth += 1;
+        self.max = self.max.max(self.depth);
+        walk_table_cell(self, cell);
+        self.depth -= 1;
+    }
+}
@@ -0,0 +1,125 @@
+use crate::ast::*;
+use crate::html_renderer::is_block_node;
+
+/// 纯文本渲染器
+///
+/// 用于通知预览、搜索索引、复制等场景。块级节点按行分隔，剧透内容替换为占位符，不会泄露隐藏的文本。
+pub struct PlainTextRenderer {
+    spoiler_placeholder: String,
+}
+
+impl PlainTextRenderer {
+    pub fn new() -> Self {
+        Self {
+            spoiler_placeholder: "[spoiler]".to_string(),
+        }
+    }
+
+    /// 设置剧透内容的占位符
+    pub fn with_spoiler_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.spoiler_placeholder = placeholder.into();
+        self
+    }
+
+    /// 将 AST 渲染为纯文本
+    pub fn render(&self, ast: &RootNode) -> String {
+        self.render_blocks(&ast.children)
+    }
+
+    fn render_blocks(&self, nodes: &[ASTNode]) -> String {
+        let lines: Vec<String> = nodes.iter()
+            .map(|node| self.render_node(node))
+            .filter(|line| !line.is_empty())
+            .collect();
+        lines.join("\n")
+    }
+
+    fn render_inline(&self, nodes: &[ASTNode]) -> String {
+        nodes.iter().map(|node| self.render_node(node)).collect()
+    }
+
+    fn render_node(&self, node: &ASTNode) -> String {
+        match node {
+            ASTNode::Root(root) => self.render_blocks(&root.children),
+            ASTNode::Paragraph(ParagraphNode { children })
+            | ASTNode::Heading(HeadingNode { children, .. })
+            | ASTNode::Strong(StrongNode { children })
+            | ASTNode::Em(EmNode { children })
+            | ASTNode::Underline(UnderlineNode { children })
+            | ASTNode::Strike(StrikeNode { children })
+            | ASTNode::Highlight(HighlightNode { children })
+            | ASTNode::Link(LinkNode { children, .. })
+            | ASTNode::TableCell(TableCell { children, .. }) => self.render_inline(children),
+            ASTNode::Blockquote(BlockquoteNode { children }) | ASTNode::ListItem(ListItemNode { children, .. }) => {
+                self.render_blocks(children)
+            }
+            ASTNode::Text(TextNode { content }) | ASTNode::Code(CodeNode { content }) => content.clone(),
+            ASTNode::CodeBlock(CodeBlockNode { content, .. }) => content.trim_end().to_string(),
+            ASTNode::Math(math) => math.content.clone(),
+            ASTNode::Image(image) => image.alt.clone().unwrap_or_default(),
+            ASTNode::Mention(mention) => {
+                let name = if mention.name.is_empty() { &mention.id } else { &mention.name };
+                let prefix = if mention.kind == MentionKind::Channel { '#' } else { '@' };
+                format!("{}{}", prefix, name)
+            }
+            ASTNode::Card(card) => self.render_card(card),
+            ASTNode::List(list) => {
+                let items: Vec<String> = list.items.iter().enumerate()
+                    .map(|(index, item)| {
+                        let marker = match list.list_type {
+                            ListType::Bullet => "-".to_string(),
+                            ListType::Ordered => format!("{}.", index + 1),
+                        };
+                        format!("{} {}", marker, self.render_blocks(&item.children))
+                    })
+                    .collect();
+                items.join("\n")
+            }
+            ASTNode::Table(table) => {
+                let rows: Vec<String> = table.rows.iter().map(|row| self.render_row(row)).collect();
+                rows.join("\n")
+            }
+            ASTNode::TableRow(row) => self.render_row(row),
+            ASTNode::FootnoteReference(reference) => format!("[{}]", reference.index),
+            ASTNode::FootnoteDefinition(definition) => {
+                format!("[{}] {}", definition.index, self.render_blocks(&definition.children))
+            }
+            ASTNode::Spoiler(_) => self.spoiler_placeholder.clone(),
+            ASTNode::Timestamp(timestamp) => timestamp.iso8601().replacen('T', " ", 1).replacen('Z', " UTC", 1),
+            ASTNode::CustomEmoji(emoji) => format!(":{}:", emoji.name),
+            // 没有子节点的自定义节点（如贴纸）输出 `[类型名]`
+            ASTNode::Custom(custom) if custom.children.is_empty() => format!("[{}]", custom.kind),
+            ASTNode::Custom(custom) if custom.children.iter().any(is_block_node) => self.render_blocks(&custom.children),
+            ASTNode::Custom(custom) => self.render_inline(&custom.children),
+            ASTNode::Mermaid(_) | ASTNode::HorizontalRule(_) | ASTNode::Unknown(_) => String::new(),
+        }
+    }
+
+    /// 卡片：标题、正文和字段各占一行，交互元素不输出
+    fn render_card(&self, card: &CardNode) -> String {
+        let mut lines = Vec::new();
+        if let Some(header) = &card.header {
+            lines.push(header.title.clone());
+        }
+        if !card.content.is_empty() {
+            lines.push(card.content.clone());
+        }
+        for field in &card.fields {
+            lines.push(format!("{}: {}", field.label, field.value));
+        }
+        lines.join("\n")
+    }
+
+    fn render_row(&self, row: &TableRow) -> String {
+        let cells: Vec<String> = row.cells.iter()
+            .map(|cell| self.render_inline(&cell.children))
+            .collect();
+        cells.join(" | ")
+    }
+}
+
+impl Default for PlainTextRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,135 @@
+use crate::html_parser::{read_start_tag, tag_name};
+use serde::{Deserialize, Serialize};
+
+/// Markdown 中允许转换为 AST 的原始 HTML 标签
+///
+/// 允许的标签转换为对应节点；不允许或不认识的标签按原文保留为文本（渲染时被转义），内容不会丢失，HTML 注释被忽略。
+/// `<sub>`、`<sup>`、`<kbd>`、`<details>` 没有专用节点，转换为同名 `kind` 的 [`CustomNode`](crate::CustomNode)，
+/// `<details>` 的标题是其中 `kind` 为 `summary` 的第一个子节点。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HtmlAllowlist {
+    /// `<br>` 换行
+    pub line_break: bool,
+    /// `<b>`/`<strong>`、`<i>`/`<em>`、`<s>`/`<del>`/`<strike>`、`<mark>`
+    pub emphasis: bool,
+    /// `<u>`、`<ins>` 下划线
+    pub underline: bool,
+    /// `<sub>`、`<sup>` 上下标
+    pub sub_sup: bool,
+    /// `<kbd>` 按键
+    pub kbd: bool,
+    /// 独占一行的 `<details>` 与 `</details>` 之间的折叠块，标题 `<summary>…</summary>` 写在开始标签同一行或下一行
+    pub details: bool,
+}
+
+impl Default for HtmlAllowlist {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl HtmlAllowlist {
+    /// 允许全部支持的标签
+    pub fn all() -> Self {
+        Self {
+            line_break: true,
+            emphasis: true,
+            underline: true,
+            sub_sup: true,
+            kbd: true,
+            details: true,
+        }
+    }
+
+    /// 不允许任何标签，原始 HTML 全部保留为文本
+    pub fn none() -> Self {
+        Self {
+            line_break: false,
+            emphasis: false,
+            underline: false,
+            sub_sup: false,
+            kbd: false,
+            details: false,
+        }
+    }
+}
+
+/// 原始 HTML 片段中的一段，`raw` 是原文
+pub(crate) enum HtmlToken<'a> {
+    Open { name: String, self_closing: bool, raw: &'a str },
+    Close { name: String, raw: &'a str },
+    Comment,
+    Text(&'a str),
+}
+
+/// 把 pulldown-cmark 交给的原始 HTML（单个行内标签或整个 HTML 块）拆分为标签和文本
+pub(crate) fn html_tokens(html: &str) -> Vec<HtmlToken<'_>> {
+    let bytes = html.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut text_start = 0;
+
+    while pos < bytes.len() {
+        if bytes[pos] != b'<' {
+            pos += 1;
+            continue;
+        }
+        let rest = &html[pos..];
+        let next = bytes.get(pos + 1).copied().unwrap_or(0);
+        let (token, len) = if let Some(comment) = rest.strip_prefix("<!--") {
+            let len = comment.find("-->").map_or(rest.len(), |end| 4 + end + 3);
+            (HtmlToken::Comment, len)
+        } else if next == b'/' && bytes.get(pos + 2).is_some_and(u8::is_ascii_alphabetic) {
+            let Some(end) = rest.find('>') else {
+                pos += 1;
+                continue;
+            };
+            (HtmlToken::Close { name: tag_name(&rest[2..]), raw: &rest[..=end] }, end + 1)
+        } else if next.is_ascii_alphabetic() {
+            let (tag, len) = read_start_tag(rest);
+            if !rest[..len].ends_with('>') {
+                pos += 1;
+                continue;
+            }
+            (HtmlToken::Open { name: tag.name, self_closing: tag.self_closing, raw: &rest[..len] }, len)
+        } else {
+            pos += 1;
+            continue;
+        };
+
+        if text_start < pos {
+            tokens.push(HtmlToken::Text(&html[text_start..pos]));
+        }
+        tokens.push(token);
+        pos += len;
+        text_start = pos;
+    }
+    if text_start < html.len() {
+        tokens.push(HtmlToken::Text(&html[text_start..]));
+    }
+    tokens
+}
+
+/// 独占一行的 `<details>` 开始标签（可以带 `open` 属性，后面可以紧跟 `<summary>…</summary>`），返回 (是否展开, 标题)
+pub(crate) fn details_open_line(line: &str) -> Option<(bool, Option<&str>)> {
+    if !line.starts_with('<') || tag_name(&line[1..]) != "details" {
+        return None;
+    }
+    let (tag, len) = read_start_tag(line);
+    if !line[..len].ends_with('>') || tag.self_closing {
+        return None;
+    }
+    let expanded = tag.attrs.iter().any(|(name, _)| name == "open");
+    let rest = line[len..].trim();
+    if rest.is_empty() {
+        return Some((expanded, None));
+    }
+    summary_line(rest).map(|summary| (expanded, Some(summary)))
+}
+
+/// 独占一行的 `<summary>标题</summary>`，返回标题
+pub(crate) fn summary_line(line: &str) -> Option<&str> {
+    let title = line.strip_prefix("<summary>")?.strip_suffix("</summary>")?;
+    Some(title.trim())
+}
@@ -0,0 +1,187 @@
+use crate::ast::*;
+use crate::visitor::*;
+use serde::{Deserialize, Serialize};
+
+/// URL 清理选项
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SanitizeOptions {
+    /// 允许的 URL scheme（小写，不含冒号）
+    #[serde(default = "default_allowed_url_schemes")]
+    pub allowed_url_schemes: Vec<String>,
+    /// 是否允许没有 scheme 的相对 URL
+    #[serde(default = "default_allow_relative_urls")]
+    pub allow_relative_urls: bool,
+}
+
+fn default_allowed_url_schemes() -> Vec<String> {
+    ["http", "https", "mailto", "tel"].iter().map(|scheme| scheme.to_string()).collect()
+}
+
+fn default_allow_relative_urls() -> bool {
+    true
+}
+
+impl Default for SanitizeOptions {
+    fn default() -> Self {
+        Self {
+            allowed_url_schemes: default_allowed_url_schemes(),
+            allow_relative_urls: default_allow_relative_urls(),
+        }
+    }
+}
+
+impl SanitizeOptions {
+    /// URL 是否允许保留
+    pub fn is_url_allowed(&self, url: &str) -> bool {
+        match url_scheme(url) {
+            Some(scheme) => self
+                .allowed_url_schemes
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(&scheme)),
+            None => self.allow_relative_urls,
+        }
+    }
+}
+
+/// 清理 AST 中不安全的 URL
+///
+/// scheme 不在允许列表中的链接展开为其子节点，图片替换为 alt 文本（没有 alt 时移除）。
+/// 卡片中不安全的图片被移除，图标和链接按钮的地址被清除（按钮改为回传动作）。
+/// 自定义节点只清理子节点，属性由对应的渲染器负责检查。
+pub fn sanitize(root: &mut RootNode, options: &SanitizeOptions) {
+    Sanitizer { options }.visit_root_mut(root);
+}
+
+/// 提取 URL 的 scheme（小写）
+///
+/// 与浏览器一致，忽略首尾空白和中间的制表符、换行符，避免 `java\tscript:` 之类的绕过。
+fn url_scheme(url: &str) -> Option<String> {
+    let cleaned: String = url
+        .trim_matches(|c: char| c.is_ascii_whitespace() || c.is_ascii_control())
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+    let colon = cleaned.find(':')?;
+    let scheme = &cleaned[..colon];
+    if scheme.contains(['/', '?', '#']) {
+        return None;
+    }
+
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    // 不是合法 scheme 的冒号（如 `a b:c`）仍视为可疑，返回原样以便被拒绝
+    Some(if valid { scheme.to_ascii_lowercase() } else { scheme.to_string() })
+}
+
+struct Sanitizer<'a> {
+    options: &'a SanitizeOptions,
+}
+
+impl Sanitizer<'_> {
+    fn sanitize_children(&self, children: &mut Vec<ASTNode>) {
+        let mut result = Vec::with_capacity(children.len());
+        for child in std::mem::take(children) {
+            match child {
+                ASTNode::Link(link) if !self.options.is_url_allowed(&link.url) => {
+                    result.extend(link.children);
+                }
+                ASTNode::Image(image) if !self.options.is_url_allowed(&image.url) => {
+                    if let Some(alt) = image.alt.filter(|alt| !alt.is_empty()) {
+                        result.push(ASTNode::Text(TextNode { content: alt }));
+                    }
+                }
+                child => result.push(child),
+            }
+        }
+        *children = result;
+    }
+}
+
+impl VisitorMut for Sanitizer<'_> {
+    fn visit_root_mut(&mut self, root: &mut RootNode) {
+        walk_root_mut(self, root);
+        self.sanitize_children(&mut root.children);
+    }
+
+    fn visit_paragraph_mut(&mut self, para: &mut ParagraphNode) {
+        walk_paragraph_mut(self, para);
+        self.sanitize_children(&mut para.children);
+    }
+
+    fn visit_heading_mut(&mut self, heading: &mut HeadingNode) {
+        walk_heading_mut(self, heading);
+        self.sanitize_children(&mut heading.children);
+    }
+
+    fn visit_strong_mut(&mut self, strong: &mut StrongNode) {
+        walk_strong_mut(self, strong);
+        self.sanitize_children(&mut strong.children);
+    }
+
+    fn visit_em_mut(&mut self, em: &mut EmNode) {
+        walk_em_mut(self, em);
+        self.sanitize_children(&mut em.children);
+    }
+
+    fn visit_underline_mut(&mut self, underline: &mut UnderlineNode) {
+        walk_underline_mut(self, underline);
+        self.sanitize_children(&mut underline.children);
+    }
+
+    fn visit_strike_mut(&mut self, strike: &mut StrikeNode) {
+        walk_strike_mut(self, strike);
+        self.sanitize_children(&mut strike.children);
+    }
+
+    fn visit_link_mut(&mut self, link: &mut LinkNode) {
+        walk_link_mut(self, link);
+        self.sanitize_children(&mut link.children);
+    }
+
+    fn visit_list_item_mut(&mut self, item: &mut ListItemNode) {
+        walk_list_item_mut(self, item);
+        self.sanitize_children(&mut item.children);
+    }
+
+    fn visit_table_cell_mut(&mut self, cell: &mut TableCell) {
+        walk_table_cell_mut(self, cell);
+        self.sanitize_children(&mut cell.children);
+    }
+
+    fn visit_blockquote_mut(&mut self, blockquote: &mut BlockquoteNode) {
+        walk_blockquote_mut(self, blockquote);
+        self.sanitize_children(&mut blockquote.children);
+    }
+
+    fn visit_footnote_definition_mut(&mut self, definition: &mut FootnoteDefinitionNode) {
+        walk_footnote_definition_mut(self, definition);
+        self.sanitize_children(&mut definition.children);
+    }
+
+    fn visit_spoiler_mut(&mut self, spoiler: &mut SpoilerNode) {
+        walk_spoiler_mut(self, spoiler);
+        self.sanitize_children(&mut spoiler.children);
+    }
+
+    fn visit_highlight_mut(&mut self, highlight: &mut HighlightNode) {
+        walk_highlight_mut(self, highlight);
+        self.sanitize_children(&mut highlight.children);
+    }
+
+    fn visit_custom_mut(&mut self, custom: &mut CustomNode) {
+        walk_custom_mut(self, custom);
+        self.sanitize_children(&mut custom.children);
+    }
+
+    fn visit_card_mut(&mut self, card: &mut CardNode) {
+        let options = self.options;
+        if let Some(header) = &mut card.header {
+            header.icon = header.icon.take().filter(|icon| options.is_url_allowed(icon));
+        }
+        card.images.retain(|image| options.is_url_allowed(&image.url));
+        for action in &mut card.actions {
+            action.url = action.url.take().filter(|url| options.is_url_allowed(url));
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::ast::{RootNode, UnknownNode, KNOWN_NODE_TYPES};
+use schemars::{json_schema, Schema, SchemaGenerator};
+use std::borrow::Cow;
+
+/// 生成整棵 AST（以 [`RootNode`] 为根）的 JSON Schema
+///
+/// 由 `ast.rs` 中的类型定义和 serde 属性直接生成，与序列化格式保持一致。
+/// 未知节点（[`crate::ASTNode::Unknown`]）在 Schema 中表现为任意 JSON 值。
+pub fn ast_json_schema() -> serde_json::Value {
+    let schema = schemars::schema_for!(RootNode);
+    serde_json::to_value(schema).unwrap_or(serde_json::Value::Null)
+}
+
+/// 未知节点的 Schema：`type` 为任意非已知类型名的对象
+///
+/// 手写而非派生，保证与已知节点类型互斥（`ASTNode` 的 Schema 使用 `oneOf`）。
+impl schemars::JsonSchema for UnknownNode {
+    fn schema_name() -> Cow<'static, str> {
+        "UnknownNode".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "未知节点（前向兼容），原始 JSON 原样保留",
+            "type": "object",
+            "properties": {
+                "type": {
+                    "type": "string",
+                    "not": { "enum": KNOWN_NODE_TYPES }
+                }
+            },
+            "required": ["type"]
+        })
+    }
+}
@@ -0,0 +1,787 @@
+use crate::ast::*;
+use crate::delimiter::merge_text;
+use crate::html_renderer::is_block_node;
+use crate::mention::{broadcast_mention, parse_angle};
+use crate::normalize::normalize;
+use crate::sanitize::{sanitize, SanitizeOptions};
+use crate::ParseError;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Slack 消息解析器
+///
+/// 支持两种输入：mrkdwn 文本（`*粗体*`、`_斜体_`、`~删除线~`、`` `代码` ``、```` ``` ```` 代码块、`>` 引用、
+/// `<url|label>` 链接、`<@U123>` / `<#C123>` / `<!here>` 提及）和 Block Kit JSON。
+/// `section`、`context` 转换为段落，`header` 转换为标题，`rich_text` 按结构转换；
+/// `actions`、`input` 等没有对应节点的块转换为子类型为 `slack.<块类型>` 的 [`CardNode`]，原始字段保存在 `metadata` 中。
+/// 输入来自外部，解析完成后总是按 [`SanitizeOptions`] 清理链接和图片地址。
+#[derive(Hash)]
+pub struct SlackParser {
+    normalize: bool,
+    sanitize: SanitizeOptions,
+}
+
+impl SlackParser {
+    pub fn new() -> Self {
+        Self { normalize: false, sanitize: SanitizeOptions::default() }
+    }
+
+    /// 设置是否在解析完成后自动执行 [`normalize`]
+    pub fn with_normalize(mut self, enabled: bool) -> Self {
+        self.normalize = enabled;
+        self
+    }
+
+    /// 设置清理链接和图片地址使用的选项
+    pub fn with_sanitize_options(mut self, options: SanitizeOptions) -> Self {
+        self.sanitize = options;
+        self
+    }
+
+    /// 解析 mrkdwn 文本（Slack API 中的 `text` 字段，`&`、`<`、`>` 已转义为实体）
+    pub fn parse_mrkdwn(&self, input: &str) -> Result<RootNode, ParseError> {
+        let mut root = RootNode::new();
+        root.children = mrkdwn_blocks(input);
+        Ok(self.finish(root))
+    }
+
+    /// 解析 Block Kit JSON：块数组、单个块，或带 `blocks` 的消息对象
+    ///
+    /// 消息对象没有块时使用其中的 `text` 字段按 mrkdwn 解析。
+    /// 块不是对象或没有字符串类型的 `type` 字段时返回 [`ParseError::SlackError`]。
+    pub fn parse_blocks(&self, input: &str) -> Result<RootNode, ParseError> {
+        let value: Value = serde_json::from_str(input)?;
+        let blocks = match &value {
+            Value::Array(blocks) => blocks.as_slice(),
+            Value::Object(message) => match message.get("blocks") {
+                Some(Value::Array(blocks)) => blocks.as_slice(),
+                Some(_) => return Err(ParseError::SlackError("`blocks` must be an array".to_string())),
+                None if message.contains_key("type") => std::slice::from_ref(&value),
+                None => &[],
+            },
+            _ => return Err(ParseError::SlackError("expected a block array or a message object".to_string())),
+        };
+
+        let mut root = RootNode::new();
+        for (index, block) in blocks.iter().enumerate() {
+            let Some(kind) = str_field(block, "type") else {
+                return Err(ParseError::SlackError(format!("block {} must be an object with a string `type`", index)));
+            };
+            convert_block(kind, block, &mut root.children);
+        }
+        if blocks.is_empty() {
+            if let Some(text) = str_field(&value, "text") {
+                root.children = mrkdwn_blocks(text);
+            }
+        }
+        Ok(self.finish(root))
+    }
+
+    fn finish(&self, mut root: RootNode) -> RootNode {
+        sanitize(&mut root, &self.sanitize);
+        if self.normalize {
+            normalize(&mut root);
+        }
+        root
+    }
+}
+
+impl Default for SlackParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 解析 mrkdwn 的块级结构：代码块、引用和以空行分隔的段落
+fn mrkdwn_blocks(input: &str) -> Vec<ASTNode> {
+    let mut blocks = Vec::new();
+    let mut rest = input;
+    while let Some(start) = rest.find("```") {
+        let Some(len) = rest[start + 3..].find("```") else {
+            break;
+        };
+        text_blocks(&rest[..start], &mut blocks);
+        let code = &rest[start + 3..start + 3 + len];
+        let mut content = decode_entities(code.strip_prefix('\n').unwrap_or(code));
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+        blocks.push(ASTNode::CodeBlock(CodeBlockNode { language: None, content }));
+        rest = &rest[start + 3 + len + 3..];
+    }
+    text_blocks(rest, &mut blocks);
+    blocks
+}
+
+fn text_blocks(text: &str, blocks: &mut Vec<ASTNode>) {
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut quote: Vec<&str> = Vec::new();
+    for line in text.lines() {
+        let quoted = line.strip_prefix("&gt;").or_else(|| line.strip_prefix('>'));
+        if let Some(quoted) = quoted {
+            flush_paragraph(&mut paragraph, blocks);
+            quote.push(quoted.strip_prefix(' ').unwrap_or(quoted));
+            continue;
+        }
+        flush_quote(&mut quote, blocks);
+        if line.trim().is_empty() {
+            flush_paragraph(&mut paragraph, blocks);
+        } else {
+            paragraph.push(line);
+        }
+    }
+    flush_paragraph(&mut paragraph, blocks);
+    flush_quote(&mut quote, blocks);
+}
+
+fn flush_paragraph(lines: &mut Vec<&str>, blocks: &mut Vec<ASTNode>) {
+    if !lines.is_empty() {
+        let children = mrkdwn_inline(&lines.join("\n"));
+        blocks.push(ASTNode::Paragraph(ParagraphNode { children }));
+        lines.clear();
+    }
+}
+
+fn flush_quote(lines: &mut Vec<&str>, blocks: &mut Vec<ASTNode>) {
+    if !lines.is_empty() {
+        let mut children = Vec::new();
+        text_blocks(&lines.join("\n"), &mut children);
+        blocks.push(ASTNode::Blockquote(BlockquoteNode { children }));
+        lines.clear();
+    }
+}
+
+/// 解析 mrkdwn 的行内格式，样式不跨行
+fn mrkdwn_inline(input: &str) -> Vec<ASTNode> {
+    let bytes = input.as_bytes();
+    let mut nodes = Vec::new();
+    let mut pending = 0;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let matched = match bytes[index] {
+            b'<' => parse_control(&input[index..]),
+            b'`' => parse_inline_code(&input[index..]),
+            b'*' | b'_' | b'~' if can_open(input, index) => parse_emphasis(&input[index..]),
+            _ => None,
+        };
+        match matched {
+            Some((len, node)) => {
+                if pending < index {
+                    nodes.push(text_node(decode_entities(&input[pending..index])));
+                }
+                nodes.push(node);
+                index += len;
+                pending = index;
+            }
+            None => index += 1,
+        }
+    }
+    if pending < input.len() {
+        nodes.push(text_node(decode_entities(&input[pending..])));
+    }
+    merge_text(nodes)
+}
+
+/// 尖括号控制序列：提及、链接、用户组和日期
+fn parse_control(input: &str) -> Option<(usize, ASTNode)> {
+    let end = input.find(['>', '\n'])?;
+    if input.as_bytes()[end] != b'>' || end == 1 {
+        return None;
+    }
+    if let Some((len, mention)) = parse_angle(input) {
+        if len == end + 1 {
+            return Some((len, ASTNode::Mention(mention)));
+        }
+    }
+
+    let inner = &input[1..end];
+    let (target, label) = inner.split_once('|').unwrap_or((inner, ""));
+    if let Some(command) = target.strip_prefix('!') {
+        // `<!subteam^ID|@team>`、`<!date^1392734382^{date}|Feb 18>` 等没有对应节点，保留显示文字
+        let text = match (command.split_once('^'), label) {
+            (_, label) if !label.is_empty() => label.to_string(),
+            (Some(("subteam", id)), _) => format!("@{}", id),
+            _ => return None,
+        };
+        return Some((end + 1, text_node(decode_entities(&text))));
+    }
+
+    if !target.contains(':') || target.contains(char::is_whitespace) {
+        return None;
+    }
+    let url = decode_entities(target);
+    let text = if label.is_empty() { url.clone() } else { decode_entities(label) };
+    Some((end + 1, ASTNode::Link(LinkNode { url, children: vec![text_node(text)] })))
+}
+
+fn parse_inline_code(input: &str) -> Option<(usize, ASTNode)> {
+    let len = input[1..].find(['`', '\n'])?;
+    if len == 0 || input.as_bytes()[1 + len] != b'`' {
+        return None;
+    }
+    let content = decode_entities(&input[1..1 + len]);
+    Some((len + 2, ASTNode::Code(CodeNode { content })))
+}
+
+/// 标记前是行首、空白或标点，后面不是空白
+fn can_open(input: &str, index: usize) -> bool {
+    let before = input[..index].chars().last();
+    let after = input[index + 1..].chars().next();
+    before.is_none_or(|c| !c.is_alphanumeric()) && after.is_some_and(|c| !c.is_whitespace())
+}
+
+fn parse_emphasis(input: &str) -> Option<(usize, ASTNode)> {
+    let marker = input.as_bytes()[0];
+    let bytes = input.as_bytes();
+    let mut index = 2;
+    while index < bytes.len() && bytes[index] != b'\n' {
+        let closes = bytes[index] == marker
+            && !input[..index].ends_with(char::is_whitespace)
+            && input[index + 1..].chars().next().is_none_or(|c| !c.is_alphanumeric());
+        if closes {
+            let children = mrkdwn_inline(&input[1..index]);
+            let node = match marker {
+                b'*' => ASTNode::Strong(StrongNode { children }),
+                b'_' => ASTNode::Em(EmNode { children }),
+                _ => ASTNode::Strike(StrikeNode { children }),
+            };
+            return Some((index + 1, node));
+        }
+        index += 1;
+    }
+    None
+}
+
+/// Slack 只转义 `&`、`<`、`>`
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")
+}
+
+fn text_node(content: impl Into<String>) -> ASTNode {
+    ASTNode::Text(TextNode { content: content.into() })
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+fn array_field<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value.get(key).and_then(Value::as_array).map_or(&[], Vec::as_slice)
+}
+
+fn convert_block(kind: &str, block: &Value, out: &mut Vec<ASTNode>) {
+    match kind {
+        "section" => {
+            if let Some(text) = block.get("text") {
+                out.extend(text_object_blocks(text));
+            }
+            for field in array_field(block, "fields") {
+                out.extend(text_object_blocks(field));
+            }
+            if let Some(accessory) = block.get("accessory") {
+                match str_field(accessory, "type") {
+                    Some("image") => out.push(ASTNode::Paragraph(ParagraphNode { children: vec![image_node(accessory)] })),
+                    Some(kind) => out.push(ASTNode::Card(Box::new(slack_card(kind, accessory, true)))),
+                    None => {}
+                }
+            }
+        }
+        "context" => {
+            let mut children = Vec::new();
+            for element in array_field(block, "elements") {
+                let nodes = match str_field(element, "type") {
+                    Some("image") => vec![image_node(element)],
+                    _ => inline_content(text_object_blocks(element)),
+                };
+                if nodes.is_empty() {
+                    continue;
+                }
+                if !children.is_empty() {
+                    children.push(text_node(" "));
+                }
+                children.extend(nodes);
+            }
+            if !children.is_empty() {
+                out.push(ASTNode::Paragraph(ParagraphNode { children: merge_text(children) }));
+            }
+        }
+        "header" => {
+            let children = block.get("text").map(|text| inline_content(text_object_blocks(text))).unwrap_or_default();
+            out.push(ASTNode::Heading(HeadingNode { level: 1, children }));
+        }
+        "divider" => out.push(ASTNode::HorizontalRule(HorizontalRuleNode)),
+        "image" => out.push(ASTNode::Paragraph(ParagraphNode { children: vec![image_node(block)] })),
+        "rich_text" => rich_text_blocks(block, out),
+        kind => out.push(ASTNode::Card(Box::new(slack_card(kind, block, false)))),
+    }
+}
+
+/// 文本对象（`{"type": "mrkdwn" | "plain_text", "text": ...}`）
+fn text_object_blocks(object: &Value) -> Vec<ASTNode> {
+    let text = str_field(object, "text").unwrap_or_default();
+    match str_field(object, "type") {
+        Some("mrkdwn") => mrkdwn_blocks(text),
+        _ if text.is_empty() => Vec::new(),
+        _ => vec![ASTNode::Paragraph(ParagraphNode { children: vec![text_node(text)] })],
+    }
+}
+
+/// 把块级内容压平为行内内容，块之间用换行分隔
+fn inline_content(blocks: Vec<ASTNode>) -> Vec<ASTNode> {
+    let mut nodes = Vec::new();
+    for block in blocks {
+        if !nodes.is_empty() {
+            nodes.push(text_node("\n"));
+        }
+        match block {
+            ASTNode::Paragraph(ParagraphNode { children }) => nodes.extend(children),
+            ASTNode::Blockquote(BlockquoteNode { children }) => nodes.extend(inline_content(children)),
+            ASTNode::CodeBlock(code_block) => {
+                nodes.push(ASTNode::Code(CodeNode { content: code_block.content.trim_end().to_string() }))
+            }
+            other => nodes.push(other),
+        }
+    }
+    merge_text(nodes)
+}
+
+fn image_node(value: &Value) -> ASTNode {
+    ASTNode::Image(ImageNode {
+        url: str_field(value, "image_url").unwrap_or_default().to_string(),
+        width: None,
+        height: None,
+        alt: str_field(value, "alt_text").map(str::to_string),
+    })
+}
+
+/// `rich_text` 块；缩进更深的列表嵌套到前一个列表的最后一项中
+fn rich_text_blocks(block: &Value, out: &mut Vec<ASTNode>) {
+    let mut lists: Vec<(u64, ListNode)> = Vec::new();
+    for element in array_field(block, "elements") {
+        let kind = str_field(element, "type").unwrap_or_default();
+        if kind == "rich_text_list" {
+            let indent = element.get("indent").and_then(Value::as_u64).unwrap_or(0);
+            let list_type = match str_field(element, "style") {
+                Some("ordered") => ListType::Ordered,
+                _ => ListType::Bullet,
+            };
+            let items = array_field(element, "elements").iter()
+                .map(|section| ListItemNode { children: rich_text_inline(array_field(section, "elements")), checked: None })
+                .collect();
+            close_lists(&mut lists, indent, out);
+            lists.push((indent, ListNode { list_type, items }));
+            continue;
+        }
+
+        close_lists(&mut lists, 0, out);
+        let children = rich_text_inline(array_field(element, "elements"));
+        match kind {
+            "rich_text_section" if !children.is_empty() => {
+                out.push(ASTNode::Paragraph(ParagraphNode { children }));
+            }
+            "rich_text_preformatted" => {
+                let mut content: String = array_field(element, "elements").iter()
+                    .filter_map(|item| str_field(item, "text").or_else(|| str_field(item, "url")))
+                    .collect();
+                if !content.ends_with('\n') {
+                    content.push('\n');
+                }
+                let language = str_field(element, "language").map(str::to_string);
+                out.push(ASTNode::CodeBlock(CodeBlockNode { language, content }));
+            }
+            "rich_text_quote" => {
+                let children = vec![ASTNode::Paragraph(ParagraphNode { children })];
+                out.push(ASTNode::Blockquote(BlockquoteNode { children }));
+            }
+            _ => {}
+        }
+    }
+    close_lists(&mut lists, 0, out);
+}
+
+/// 结束缩进不小于 `indent` 的列表
+fn close_lists(lists: &mut Vec<(u64, ListNode)>, indent: u64, out: &mut Vec<ASTNode>) {
+    while lists.last().is_some_and(|(level, _)| *level >= indent) {
+        let Some((_, list)) = lists.pop() else { break };
+        match lists.last_mut().and_then(|(_, parent)| parent.items.last_mut()) {
+            Some(item) => item.children.push(ASTNode::List(list)),
+            None => out.push(ASTNode::List(list)),
+        }
+    }
+}
+
+fn rich_text_inline(elements: &[Value]) -> Vec<ASTNode> {
+    let mut nodes = Vec::new();
+    for element in elements {
+        let text = str_field(element, "text");
+        let node = match str_field(element, "type").unwrap_or_default() {
+            "text" => {
+                let content = text.unwrap_or_default().to_string();
+                if has_style(element, "code") {
+                    ASTNode::Code(CodeNode { content })
+                } else {
+                    text_node(content)
+                }
+            }
+            "link" => {
+                let url = str_field(element, "url").unwrap_or_default();
+                let label = text.filter(|text| !text.is_empty()).unwrap_or(url);
+                ASTNode::Link(LinkNode { url: url.to_string(), children: vec![text_node(label)] })
+            }
+            "user" => id_mention(MentionKind::User, str_field(element, "user_id")),
+            "channel" => id_mention(MentionKind::Channel, str_field(element, "channel_id")),
+            "usergroup" => text_node(format!("@{}", str_field(element, "usergroup_id").unwrap_or_default())),
+            "broadcast" => match str_field(element, "range").and_then(broadcast_mention) {
+                Some(mention) => ASTNode::Mention(mention),
+                None => continue,
+            },
+            "emoji" => text_node(emoji_text(element)),
+            "date" => text_node(str_field(element, "fallback").map_or_else(
+                || element.get("timestamp").map(Value::to_string).unwrap_or_default(),
+                str::to_string,
+            )),
+            "color" => text_node(str_field(element, "value").unwrap_or_default()),
+            _ => match text {
+                Some(text) => text_node(text),
+                None => continue,
+            },
+        };
+        nodes.push(apply_styles(element, node));
+    }
+    merge_text(nodes)
+}
+
+fn has_style(element: &Value, name: &str) -> bool {
+    element.get("style").and_then(|style| style.get(name)).and_then(Value::as_bool).unwrap_or(false)
+}
+
+fn apply_styles(element: &Value, mut node: ASTNode) -> ASTNode {
+    if has_style(element, "strike") {
+        node = ASTNode::Strike(StrikeNode { children: vec![node] });
+    }
+    if has_style(element, "italic") {
+        node = ASTNode::Em(EmNode { children: vec![node] });
+    }
+    if has_style(element, "bold") {
+        node = ASTNode::Strong(StrongNode { children: vec![node] });
+    }
+    node
+}
+
+fn id_mention(kind: MentionKind, id: Option<&str>) -> ASTNode {
+    ASTNode::Mention(MentionNode {
+        id: id.unwrap_or_default().to_string(),
+        name: String::new(),
+        kind,
+        status: MentionStatus::default(),
+    })
+}
+
+/// 有 `unicode` 码点（如 `1f44d-1f3fb`）时输出字符，否则输出 `:name:`
+fn emoji_text(element: &Value) -> String {
+    let unicode = str_field(element, "unicode").and_then(|code| {
+        code.split('-').map(|part| u32::from_str_radix(part, 16).ok().and_then(char::from_u32)).collect()
+    });
+    unicode.unwrap_or_else(|| format!(":{}:", str_field(element, "name").unwrap_or_default()))
+}
+
+/// 没有对应节点的块或 `section` 附带的交互元素（`element` 为 `true`），原始字段（除 `type` 外）保存在 `metadata` 中
+fn slack_card(kind: &str, value: &Value, element: bool) -> CardNode {
+    let metadata: HashMap<String, Value> = value.as_object()
+        .map(|object| object.iter().filter(|(key, _)| *key != "type").map(|(key, value)| (key.clone(), value.clone())).collect())
+        .unwrap_or_default();
+    let plain = |key: &str| value.get(key).and_then(|text| str_field(text, "text")).map(str::to_string);
+
+    let mut actions = Vec::new();
+    for element in array_field(value, "elements") {
+        actions.extend(card_action(element, None, actions.len()));
+    }
+    if let Some(element) = value.get("element") {
+        actions.extend(card_action(element, plain("label"), actions.len()));
+    }
+    if element {
+        actions.extend(card_action(value, None, 0));
+    }
+
+    let images = ["image_url", "thumbnail_url"].iter()
+        .filter_map(|key| str_field(value, key))
+        .map(|url| CardImage { url: url.to_string(), alt: str_field(value, "alt_text").map(str::to_string) })
+        .collect();
+
+    CardNode {
+        subtype: format!("slack.{}", kind),
+        content: if element { String::new() } else { plain("text").or_else(|| plain("description")).unwrap_or_default() },
+        metadata,
+        header: plain("title").map(|title| CardHeader { title, subtitle: None, icon: None }),
+        fields: Vec::new(),
+        images,
+        actions,
+    }
+}
+
+/// 交互元素转换为卡片动作；没有静态选项的下拉框（用户、频道、外部数据源）转换为输入框
+fn card_action(element: &Value, label: Option<String>, index: usize) -> Option<CardAction> {
+    let kind = str_field(element, "type")?;
+    let text = |key: &str| element.get(key).and_then(|text| str_field(text, "text")).map(str::to_string);
+    let options: Vec<CardSelectOption> = array_field(element, "options").iter()
+        .chain(array_field(element, "option_groups").iter().flat_map(|group| array_field(group, "options")))
+        .filter_map(|option| {
+            let value = str_field(option, "value")?;
+            Some(CardSelectOption { label: option_label(option).unwrap_or_else(|| value.to_string()), value: value.to_string() })
+        })
+        .collect();
+
+    let mut action = CardAction {
+        id: str_field(element, "action_id").map_or_else(|| format!("action-{}", index), str::to_string),
+        label: label.unwrap_or_default(),
+        placeholder: text("placeholder"),
+        ..CardAction::default()
+    };
+    match kind {
+        "button" => {
+            action.label = text("text").unwrap_or_default();
+            action.style = match str_field(element, "style") {
+                Some("primary") => CardActionStyle::Primary,
+                Some("danger") => CardActionStyle::Danger,
+                _ => CardActionStyle::Default,
+            };
+            action.value = str_field(element, "value").map(str::to_string);
+            action.url = str_field(element, "url").map(str::to_string);
+        }
+        _ if kind.ends_with("select") || matches!(kind, "overflow" | "radio_buttons" | "checkboxes") => {
+            if options.is_empty() {
+                action.kind = CardActionKind::Input;
+            } else {
+                action.kind = CardActionKind::Select;
+                action.value = element.get("initial_option").and_then(|option| str_field(option, "value")).map(str::to_string);
+                action.options = options;
+            }
+        }
+        _ if kind.ends_with("input") || kind.ends_with("picker") => {
+            action.kind = CardActionKind::Input;
+            action.value = ["initial_value", "initial_date", "initial_time"].iter()
+                .find_map(|key| str_field(element, key))
+                .map(str::to_string);
+        }
+        _ => return None,
+    }
+    Some(action)
+}
+
+fn option_label(option: &Value) -> Option<String> {
+    option.get("text").and_then(|text| str_field(text, "text")).map(str::to_string)
+}
+
+/// Slack mrkdwn 渲染器
+///
+/// 把 AST 序列化为发往 Slack 的 mrkdwn 文本。mrkdwn 没有标题、下划线、图片和表格，
+/// 标题输出为粗体，下划线、高亮和剧透只保留文字，图片输出为链接，表格按行输出。
+/// 时间戳输出为 `<!date^…>`，由 Slack 按查看者的时区显示。
+/// 文本中的 `&`、`<`、`>` 转义为实体；mrkdwn 没有转义格式标记的语法，文本中的 `*`、`_`、`~` 原样输出。
+pub struct MrkdwnRenderer;
+
+impl MrkdwnRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 将 AST 渲染为 mrkdwn
+    pub fn render(&self, ast: &RootNode) -> String {
+        self.render_blocks(&ast.children, "\n\n")
+    }
+
+    fn render_blocks(&self, nodes: &[ASTNode], separator: &str) -> String {
+        let mut parts = Vec::new();
+        let mut inline = String::new();
+        for node in nodes {
+            if is_block_node(node) {
+                if !inline.is_empty() {
+                    parts.push(std::mem::take(&mut inline));
+                }
+                parts.push(self.render_block(node));
+            } else {
+                inline.push_str(&self.render_inline_node(node));
+            }
+        }
+        if !inline.is_empty() {
+            parts.push(inline);
+        }
+        parts.retain(|part| !part.is_empty());
+        parts.join(separator)
+    }
+
+    fn render_block(&self, node: &ASTNode) -> String {
+        match node {
+            ASTNode::Paragraph(ParagraphNode { children }) => self.render_inline(children),
+            ASTNode::Heading(HeadingNode { children, .. }) => wrap("*", &self.render_inline(children)),
+            ASTNode::CodeBlock(CodeBlockNode { content, .. }) | ASTNode::Mermaid(MermaidNode { content }) => {
+                format!("```\n{}\n```", escape(content.trim_end_matches('\n')))
+            }
+            ASTNode::Blockquote(BlockquoteNode { children }) => {
+                let lines: Vec<String> = self.render_blocks(children, "\n\n").lines()
+                    .map(|line| if line.is_empty() { ">".to_string() } else { format!("> {}", line) })
+                    .collect();
+                lines.join("\n")
+            }
+            ASTNode::List(list) => self.render_list(list),
+            ASTNode::Table(table) => {
+                let rows: Vec<String> = table.rows.iter()
+                    .map(|row| {
+                        let cells: Vec<String> = row.cells.iter().map(|cell| self.render_inline(&cell.children)).collect();
+                        cells.join(" | ")
+                    })
+                    .collect();
+                rows.join("\n")
+            }
+            ASTNode::HorizontalRule(_) => "---".to_string(),
+            ASTNode::Card(card) => self.render_card(card),
+            _ => self.render_inline_node(node),
+        }
+    }
+
+    fn render_inline(&self, nodes: &[ASTNode]) -> String {
+        nodes.iter().map(|node| self.render_inline_node(node)).collect()
+    }
+
+    fn render_inline_node(&self, node: &ASTNode) -> String {
+        match node {
+            ASTNode::Text(TextNode { content }) => escape(content),
+            ASTNode::Strong(StrongNode { children }) => wrap("*", &self.render_inline(children)),
+            ASTNode::Em(EmNode { children }) => wrap("_", &self.render_inline(children)),
+            ASTNode::Strike(StrikeNode { children }) => wrap("~", &self.render_inline(children)),
+            ASTNode::Underline(UnderlineNode { children })
+            | ASTNode::Highlight(HighlightNode { children })
+            | ASTNode::Spoiler(SpoilerNode { children }) => self.render_inline(children),
+            ASTNode::Code(CodeNode { content }) | ASTNode::Math(MathNode { content, .. }) => {
+                format!("`{}`", escape(content))
+            }
+            ASTNode::Link(link) => link_markup(&link.url, &label_text(&link.children)),
+            ASTNode::Image(image) => link_markup(&image.url, image.alt.as_deref().unwrap_or_default()),
+            ASTNode::Mention(mention) => match mention.kind {
+                MentionKind::User => format!("<@{}>", mention.id),
+                MentionKind::Channel => format!("<#{}>", mention.id),
+                MentionKind::All if mention.name == "everyone" => "<!everyone>".to_string(),
+                MentionKind::All => "<!channel>".to_string(),
+                MentionKind::Here => "<!here>".to_string(),
+                MentionKind::Role => format!("<!subteam^{}>", mention.id),
+            },
+            ASTNode::Timestamp(timestamp) => {
+                // Slack 按查看者的时区格式化 `<!date^…>`，客户端不支持时显示 `|` 后的 UTC 时间
+                let token = match timestamp.style.as_deref() {
+                    Some("t") => "{time}",
+                    Some("T") => "{time_secs}",
+                    Some("d") => "{date_num}",
+                    Some("D") => "{date_long}",
+                    Some("F") => "{date_long} {time}",
+                    Some("R") => "{ago}",
+                    _ => "{date_short} {time}",
+                };
+                format!("<!date^{}^{}|{}>", timestamp.timestamp, token, timestamp.iso8601())
+            }
+            ASTNode::CustomEmoji(emoji) => format!(":{}:", emoji.name),
+            ASTNode::FootnoteReference(reference) => format!("[{}]", reference.index),
+            ASTNode::FootnoteDefinition(definition) => {
+                format!("[{}] {}", definition.index, self.render_blocks(&definition.children, "\n"))
+            }
+            ASTNode::Custom(custom) if custom.children.is_empty() => escape(&format!("[{}]", custom.kind)),
+            ASTNode::Custom(custom) => self.render_blocks(&custom.children, "\n"),
+            ASTNode::Root(root) => self.render_blocks(&root.children, "\n\n"),
+            ASTNode::ListItem(item) => self.render_blocks(&item.children, "\n"),
+            ASTNode::TableRow(_) | ASTNode::TableCell(_) | ASTNode::Unknown(_) => String::new(),
+            _ => self.render_block(node),
+        }
+    }
+
+    /// 列表项用 `•` 或序号开头，任务项用 `☑`/`☐`，续行和嵌套列表缩进四个空格
+    fn render_list(&self, list: &ListNode) -> String {
+        let mut lines = Vec::new();
+        for (index, item) in list.items.iter().enumerate() {
+            let marker = match (item.checked, &list.list_type) {
+                (Some(true), _) => "☑".to_string(),
+                (Some(false), _) => "☐".to_string(),
+                (None, ListType::Bullet) => "•".to_string(),
+                (None, ListType::Ordered) => format!("{}.", index + 1),
+            };
+            let body = self.render_blocks(&item.children, "\n");
+            let mut body_lines = body.lines();
+            lines.push(format!("{} {}", marker, body_lines.next().unwrap_or_default()));
+            lines.extend(body_lines.map(|line| format!("    {}", line)));
+        }
+        lines.join("\n")
+    }
+
+    /// 卡片：标题、正文、字段、图片链接和链接按钮各占一行
+    fn render_card(&self, card: &CardNode) -> String {
+        let mut lines = Vec::new();
+        if let Some(header) = &card.header {
+            lines.push(wrap("*", &escape(&header.title)));
+        }
+        if !card.content.is_empty() {
+            lines.push(escape(&card.content));
+        }
+        for field in &card.fields {
+            lines.push(format!("{}: {}", wrap("*", &escape(&field.label)), escape(&field.value)));
+        }
+        for image in &card.images {
+            lines.push(link_markup(&image.url, image.alt.as_deref().unwrap_or_default()));
+        }
+        for action in &card.actions {
+            if let Some(url) = &action.url {
+                lines.push(link_markup(url, &action.label));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl Default for MrkdwnRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// 加上格式标记；标记不能紧挨空白，也不能跨行，首尾空白移到标记外，多行内容逐行加标记
+fn wrap(marker: &str, inner: &str) -> String {
+    let lines: Vec<String> = inner.split('\n')
+        .map(|line| {
+            let core = line.trim();
+            if core.is_empty() {
+                return line.to_string();
+            }
+            let start = line.len() - line.trim_start().len();
+            let end = start + core.len();
+            format!("{}{}{}{}{}", &line[..start], marker, core, marker, &line[end..])
+        })
+        .collect();
+    lines.join("\n")
+}
+
+fn link_markup(url: &str, label: &str) -> String {
+    if label.is_empty() || label == url {
+        format!("<{}>", escape(url))
+    } else {
+        format!("<{}|{}>", escape(url), escape(label))
+    }
+}
+
+/// 链接文字：mrkdwn 的链接文字不支持格式，只取文本内容
+fn label_text(nodes: &[ASTNode]) -> String {
+    nodes.iter()
+        .map(|node| match node {
+            ASTNode::Text(TextNode { content }) | ASTNode::Code(CodeNode { content }) => content.clone(),
+            ASTNode::Strong(StrongNode { children })
+            | ASTNode::Em(EmNode { children })
+            | ASTNode::Underline(UnderlineNode { children })
+            | ASTNode::Strike(StrikeNode { children })
+            | ASTNode::Highlight(HighlightNode { children })
+            | ASTNode::Spoiler(SpoilerNode { children }) => label_text(children),
+            ASTNode::Mention(mention) => mention.name.clone(),
+            _ => String::new(),
+        })
+        .collect()
+}
@@ -92,6 +92,18 @@ pub struct StyleConfig {
     #[serde(default = "default_mention_text_color")]
     pub mention_text_color: String,
     
+    /// 高亮背景颜色（十六进制）
+    #[serde(default = "default_highlight_background")]
+    pub highlight_background: String,
+    
+    /// 高亮文本颜色（十六进制）
+    #[serde(default = "default_highlight_text_color")]
+    pub highlight_text_color: String,
+    
+    /// 剧透遮罩颜色（十六进制）
+    #[serde(default = "default_spoiler_background")]
+    pub spoiler_background: String,
+    
     /// 卡片背景颜色（十六进制）
     #[serde(default = "default_card_background")]
     pub card_background: String,
@@ -150,6 +162,9 @@ impl Default for StyleConfig {
             image_margin: default_image_margin(),
             mention_background: default_mention_background(),
             mention_text_color: default_mention_text_color(),
+            highlight_background: default_highlight_background(),
+            highlight_text_color: default_highlight_text_color(),
+            spoiler_background: default_spoiler_background(),
             card_background: default_card_background(),
             card_border_color: default_card_border_color(),
             card_padding: default_card_padding(),
@@ -194,6 +209,9 @@ fn default_image_border_radius() -> f32 { 8.0 }
 fn default_image_margin() -> f32 { 16.0 }
 fn default_mention_background() -> String { "#E3F2FD".to_string() }
 fn default_mention_text_color() -> String { "#1976D2".to_string() }
+fn default_highlight_background() -> String { "#FFF3A3".to_string() }
+fn default_highlight_text_color() -> String { "inherit".to_string() }
+fn default_spoiler_background() -> String { "#d1d1d6".to_string() }
 fn default_card_background() -> String { "#f9f9f9".to_string() }
 fn default_card_border_color() -> String { "#dddddd".to_string() }
 fn default_card_padding() -> f32 { 16.0 }
@@ -225,6 +243,8 @@ impl StyleConfig {
             blockquote_text_color: "#a1a1a6".to_string(),
             mention_background: "#1e3a5f".to_string(),
             mention_text_color: "#64b5f6".to_string(),
+            highlight_background: "#5c4b00".to_string(),
+            spoiler_background: "#48484a".to_string(),
             card_background: "#2c2c2e".to_string(),
             card_border_color: "#3a3a3c".to_string(),
             hr_color: "#3a3a3c".to_string(),
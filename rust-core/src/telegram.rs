@@ -0,0 +1,467 @@
+use crate::ast::*;
+use crate::delimiter::merge_text;
+use crate::html_renderer::is_block_node;
+use crate::normalize::normalize;
+use crate::sanitize::{sanitize, SanitizeOptions};
+use crate::ParseError;
+use serde::{Deserialize, Serialize};
+
+/// Telegram 消息实体（Bot API 的 `MessageEntity`），`offset` 和 `length` 以 UTF-16 码元计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TelegramEntity {
+    /// 实体类型，如 `bold`、`text_link`、`pre`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub offset: usize,
+    pub length: usize,
+    /// `text_link` 的地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// `text_mention` 提及的用户
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<TelegramUser>,
+    /// `pre` 的代码语言
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/// `text_mention` 实体中的用户
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TelegramUser {
+    pub id: i64,
+    #[serde(default)]
+    pub first_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+/// Telegram 消息对象中与格式有关的字段，图片等消息的文字在 `caption` 中
+#[derive(Deserialize)]
+struct TelegramMessage {
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    entities: Vec<TelegramEntity>,
+    #[serde(default)]
+    caption: Option<String>,
+    #[serde(default)]
+    caption_entities: Vec<TelegramEntity>,
+}
+
+/// Telegram 消息解析器
+///
+/// 把 `text` + `entities` 转换为 AST：`bold`、`italic`、`underline`、`strikethrough`、`spoiler` 转换为对应的样式节点，
+/// `code` / `pre` 转换为行内代码和代码块，`text_link`、`url`、`email`、`phone_number` 转换为链接，
+/// `mention`（`@username`）和 `text_mention` 转换为 [`MentionNode`]，`blockquote` / `expandable_blockquote` 转换为引用块。
+/// 其余实体（话题标签、机器人命令、自定义表情等）只保留文字。空行分隔段落，单个换行为 `\n` 文本。
+/// 输入来自外部，解析完成后总是按 [`SanitizeOptions`] 清理链接地址。
+#[derive(Hash)]
+pub struct TelegramParser {
+    normalize: bool,
+    sanitize: SanitizeOptions,
+}
+
+impl TelegramParser {
+    pub fn new() -> Self {
+        Self { normalize: false, sanitize: SanitizeOptions::default() }
+    }
+
+    /// 设置是否在解析完成后自动执行 [`normalize`]
+    pub fn with_normalize(mut self, enabled: bool) -> Self {
+        self.normalize = enabled;
+        self
+    }
+
+    /// 设置清理链接地址使用的选项
+    pub fn with_sanitize_options(mut self, options: SanitizeOptions) -> Self {
+        self.sanitize = options;
+        self
+    }
+
+    /// 解析 Bot API 的消息对象 JSON，使用 `text` + `entities`，没有 `text` 时使用 `caption` + `caption_entities`
+    pub fn parse(&self, input: &str) -> Result<RootNode, ParseError> {
+        let message: TelegramMessage = serde_json::from_str(input)?;
+        match message.text {
+            Some(text) => self.parse_entities(&text, &message.entities),
+            None => self.parse_entities(message.caption.as_deref().unwrap_or_default(), &message.caption_entities),
+        }
+    }
+
+    /// 解析文本和实体列表
+    ///
+    /// 越界的实体被截断到文本末尾；与前面的实体部分重叠的实体被截断到前面实体的末尾，按嵌套实体处理。
+    pub fn parse_entities(&self, text: &str, entities: &[TelegramEntity]) -> Result<RootNode, ParseError> {
+        let offsets = utf16_offsets(text);
+        let mut spans: Vec<Span> = entities.iter()
+            .filter_map(|entity| {
+                let start = *offsets.get(entity.offset)?;
+                let end = offsets[entity.offset.saturating_add(entity.length).min(offsets.len() - 1)];
+                (start < end).then_some(Span { start, end, entity })
+            })
+            .collect();
+        // 外层实体排在内层实体前面
+        spans.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+
+        let mut root = RootNode::new();
+        root.children = into_blocks(build_nodes(text, 0, text.len(), &spans));
+        sanitize(&mut root, &self.sanitize);
+        if self.normalize {
+            normalize(&mut root);
+        }
+        Ok(root)
+    }
+}
+
+impl Default for TelegramParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Span<'a> {
+    start: usize,
+    end: usize,
+    entity: &'a TelegramEntity,
+}
+
+/// UTF-16 偏移到字节偏移的映射，代理对中间的偏移指向下一个字符
+fn utf16_offsets(text: &str) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(text.len() + 1);
+    for (index, c) in text.char_indices() {
+        offsets.push(index);
+        if c.len_utf16() == 2 {
+            offsets.push(index + c.len_utf8());
+        }
+    }
+    offsets.push(text.len());
+    offsets
+}
+
+/// 把 `start..end` 范围内的文本按实体（已排序）构建为节点
+fn build_nodes(text: &str, start: usize, end: usize, spans: &[Span]) -> Vec<ASTNode> {
+    let mut nodes = Vec::new();
+    let mut pos = start;
+    let mut index = 0;
+    while index < spans.len() {
+        let span = spans[index];
+        if span.start < pos {
+            index += 1;
+            continue;
+        }
+        if pos < span.start {
+            nodes.push(text_node(&text[pos..span.start]));
+        }
+        let mut next = index + 1;
+        while next < spans.len() && spans[next].start < span.end {
+            next += 1;
+        }
+        let inner: Vec<Span> = spans[index + 1..next].iter()
+            .map(|inner| Span { end: inner.end.min(span.end), ..*inner })
+            .collect();
+        nodes.extend(entity_nodes(text, span, build_nodes(text, span.start, span.end, &inner)));
+        pos = span.end;
+        index = next;
+    }
+    if pos < end {
+        nodes.push(text_node(&text[pos..end]));
+    }
+    merge_text(nodes)
+}
+
+/// 实体对应的节点；只保留文字的实体直接返回子节点
+fn entity_nodes(text: &str, span: Span, children: Vec<ASTNode>) -> Vec<ASTNode> {
+    let entity = span.entity;
+    let content = &text[span.start..span.end];
+    let link = |url: String| ASTNode::Link(LinkNode { url, children: children.clone() });
+    let node = match entity.kind.as_str() {
+        "bold" => ASTNode::Strong(StrongNode { children }),
+        "italic" => ASTNode::Em(EmNode { children }),
+        "underline" => ASTNode::Underline(UnderlineNode { children }),
+        "strikethrough" => ASTNode::Strike(StrikeNode { children }),
+        "spoiler" => ASTNode::Spoiler(SpoilerNode { children }),
+        "code" => ASTNode::Code(CodeNode { content: content.to_string() }),
+        "pre" => {
+            let mut content = content.to_string();
+            if !content.ends_with('\n') {
+                content.push('\n');
+            }
+            let language = entity.language.clone().filter(|language| !language.is_empty());
+            ASTNode::CodeBlock(CodeBlockNode { language, content })
+        }
+        "text_link" => link(entity.url.clone().unwrap_or_default()),
+        "url" if content.contains("://") => link(content.to_string()),
+        "url" => link(format!("http://{}", content)),
+        "email" => link(format!("mailto:{}", content)),
+        "phone_number" => link(format!("tel:{}", content.replace([' ', '(', ')', '-'], ""))),
+        "mention" => {
+            let username = content.trim_start_matches('@');
+            mention(username.to_string(), username.to_string())
+        }
+        "text_mention" => match &entity.user {
+            Some(user) => mention(user.id.to_string(), content.to_string()),
+            None => return children,
+        },
+        "blockquote" | "expandable_blockquote" => ASTNode::Blockquote(BlockquoteNode { children: into_blocks(children) }),
+        _ => return children,
+    };
+    vec![node]
+}
+
+fn mention(id: String, name: String) -> ASTNode {
+    ASTNode::Mention(MentionNode { id, name, kind: MentionKind::User, status: MentionStatus::default() })
+}
+
+fn text_node(content: &str) -> ASTNode {
+    ASTNode::Text(TextNode { content: content.to_string() })
+}
+
+/// 把行内节点和代码块、引用块组织为块级内容：空行分隔段落，块级节点前后的换行被去掉
+fn into_blocks(nodes: Vec<ASTNode>) -> Vec<ASTNode> {
+    let mut blocks = Vec::new();
+    let mut paragraph = Vec::new();
+    for node in nodes {
+        match node {
+            node if is_block_node(&node) => {
+                flush_paragraph(&mut paragraph, &mut blocks);
+                blocks.push(node);
+            }
+            ASTNode::Text(TextNode { content }) if content.contains("\n\n") => {
+                let mut parts = content.split("\n\n").peekable();
+                while let Some(part) = parts.next() {
+                    if !part.is_empty() {
+                        paragraph.push(text_node(part));
+                    }
+                    if parts.peek().is_some() {
+                        flush_paragraph(&mut paragraph, &mut blocks);
+                    }
+                }
+            }
+            node => paragraph.push(node),
+        }
+    }
+    flush_paragraph(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn flush_paragraph(paragraph: &mut Vec<ASTNode>, blocks: &mut Vec<ASTNode>) {
+    let mut children = merge_text(std::mem::take(paragraph));
+    if let Some(ASTNode::Text(first)) = children.first_mut() {
+        first.content = first.content.trim_start_matches('\n').to_string();
+    }
+    if let Some(ASTNode::Text(last)) = children.last_mut() {
+        last.content.truncate(last.content.trim_end_matches('\n').len());
+    }
+    children.retain(|child| !matches!(child, ASTNode::Text(text) if text.content.is_empty()));
+    if !children.is_empty() {
+        blocks.push(ASTNode::Paragraph(ParagraphNode { children }));
+    }
+}
+
+/// Telegram MarkdownV2 渲染器
+///
+/// 把 AST 序列化为 `parse_mode: "MarkdownV2"` 的消息文本，按 Bot API 的规则转义：普通文本中的
+/// `` _*[]()~`>#+-=|{}.!\ `` 前加 `\`，代码中只转义 `` ` `` 和 `\`，链接地址中只转义 `)` 和 `\`。
+/// MarkdownV2 没有标题、表格、图片和频道提及，标题输出为粗体，表格按行输出，图片输出为链接；
+/// 数字 id 的用户提及输出为 `tg://user?id=` 链接，其余提及输出为 `@名称` 文本。
+pub struct MarkdownV2Renderer;
+
+impl MarkdownV2Renderer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 将 AST 渲染为 MarkdownV2
+    pub fn render(&self, ast: &RootNode) -> String {
+        self.render_blocks(&ast.children, "\n\n")
+    }
+
+    fn render_blocks(&self, nodes: &[ASTNode], separator: &str) -> String {
+        let mut parts = Vec::new();
+        let mut inline = Vec::new();
+        for node in nodes {
+            if is_block_node(node) {
+                if !inline.is_empty() {
+                    parts.push(self.render_inline(&std::mem::take(&mut inline)));
+                }
+                parts.push(self.render_block(node));
+            } else {
+                inline.push(node.clone());
+            }
+        }
+        if !inline.is_empty() {
+            parts.push(self.render_inline(&inline));
+        }
+        parts.retain(|part| !part.is_empty());
+        parts.join(separator)
+    }
+
+    fn render_block(&self, node: &ASTNode) -> String {
+        match node {
+            ASTNode::Paragraph(ParagraphNode { children }) => self.render_inline(children),
+            ASTNode::Heading(HeadingNode { children, .. }) => wrap("*", &self.render_inline(children)),
+            ASTNode::CodeBlock(CodeBlockNode { content, language }) => {
+                let language = language.as_deref().unwrap_or_default();
+                format!("```{}\n{}\n```", escape_code(language), escape_code(content.trim_end_matches('\n')))
+            }
+            ASTNode::Mermaid(MermaidNode { content }) => {
+                format!("```mermaid\n{}\n```", escape_code(content.trim_end_matches('\n')))
+            }
+            ASTNode::Blockquote(BlockquoteNode { children }) => {
+                let lines: Vec<String> = self.render_blocks(children, "\n\n").lines()
+                    .map(|line| format!(">{}", line))
+                    .collect();
+                lines.join("\n")
+            }
+            ASTNode::List(list) => self.render_list(list),
+            ASTNode::Table(table) => {
+                let rows: Vec<String> = table.rows.iter()
+                    .map(|row| {
+                        let cells: Vec<String> = row.cells.iter().map(|cell| self.render_inline(&cell.children)).collect();
+                        cells.join(" \\| ")
+                    })
+                    .collect();
+                rows.join("\n")
+            }
+            ASTNode::HorizontalRule(_) => "———".to_string(),
+            ASTNode::Card(card) => self.render_card(card),
+            _ => self.render_inline_node(node),
+        }
+    }
+
+    /// 相邻的 `_` 标记会被当作下划线的 `__`，中间插入 Telegram 会忽略的 `\r`
+    fn render_inline(&self, nodes: &[ASTNode]) -> String {
+        let mut result = String::new();
+        for node in nodes {
+            let part = self.render_inline_node(node);
+            if result.ends_with('_') && part.starts_with('_') {
+                result.push('\r');
+            }
+            result.push_str(&part);
+        }
+        result
+    }
+
+    fn render_inline_node(&self, node: &ASTNode) -> String {
+        match node {
+            ASTNode::Text(TextNode { content }) => escape(content),
+            ASTNode::Strong(StrongNode { children }) => wrap("*", &self.render_inline(children)),
+            ASTNode::Em(EmNode { children }) => wrap("_", &self.render_inline(children)),
+            ASTNode::Underline(UnderlineNode { children }) => wrap("__", &self.render_inline(children)),
+            ASTNode::Strike(StrikeNode { children }) => wrap("~", &self.render_inline(children)),
+            ASTNode::Spoiler(SpoilerNode { children }) => wrap("||", &self.render_inline(children)),
+            ASTNode::Highlight(HighlightNode { children }) => self.render_inline(children),
+            ASTNode::Code(CodeNode { content }) | ASTNode::Math(MathNode { content, .. }) => {
+                format!("`{}`", escape_code(content))
+            }
+            ASTNode::Link(link) => link_markup(&self.render_inline(&link.children), &link.url),
+            ASTNode::Image(image) => link_markup(&escape(image.alt.as_deref().unwrap_or(&image.url)), &image.url),
+            ASTNode::Mention(mention) => {
+                let name = if mention.name.is_empty() { &mention.id } else { &mention.name };
+                match mention.kind {
+                    MentionKind::User if mention.id.parse::<i64>().is_ok() => {
+                        link_markup(&escape(name), &format!("tg://user?id={}", mention.id))
+                    }
+                    MentionKind::Channel => escape(&format!("#{}", name)),
+                    _ => escape(&format!("@{}", name)),
+                }
+            }
+            ASTNode::Timestamp(timestamp) => escape(&timestamp.iso8601()),
+            ASTNode::CustomEmoji(emoji) => escape(&format!(":{}:", emoji.name)),
+            ASTNode::FootnoteReference(reference) => escape(&format!("[{}]", reference.index)),
+            ASTNode::FootnoteDefinition(definition) => format!(
+                "{} {}",
+                escape(&format!("[{}]", definition.index)),
+                self.render_blocks(&definition.children, "\n")
+            ),
+            ASTNode::Custom(custom) if custom.children.is_empty() => escape(&format!("[{}]", custom.kind)),
+            ASTNode::Custom(custom) => self.render_blocks(&custom.children, "\n"),
+            ASTNode::Root(root) => self.render_blocks(&root.children, "\n\n"),
+            ASTNode::ListItem(item) => self.render_blocks(&item.children, "\n"),
+            ASTNode::TableRow(_) | ASTNode::TableCell(_) | ASTNode::Unknown(_) => String::new(),
+            _ => self.render_block(node),
+        }
+    }
+
+    /// 列表项用 `•` 或序号开头，任务项用 `☑`/`☐`，续行和嵌套列表缩进四个空格
+    fn render_list(&self, list: &ListNode) -> String {
+        let mut lines = Vec::new();
+        for (index, item) in list.items.iter().enumerate() {
+            let marker = match (item.checked, &list.list_type) {
+                (Some(true), _) => "☑".to_string(),
+                (Some(false), _) => "☐".to_string(),
+                (None, ListType::Bullet) => "•".to_string(),
+                (None, ListType::Ordered) => format!("{}\\.", index + 1),
+            };
+            let body = self.render_blocks(&item.children, "\n");
+            let mut body_lines = body.lines();
+            lines.push(format!("{} {}", marker, body_lines.next().unwrap_or_default()));
+            lines.extend(body_lines.map(|line| format!("    {}", line)));
+        }
+        lines.join("\n")
+    }
+
+    /// 卡片：标题、正文、字段、图片链接和链接按钮各占一行
+    fn render_card(&self, card: &CardNode) -> String {
+        let mut lines = Vec::new();
+        if let Some(header) = &card.header {
+            lines.push(wrap("*", &escape(&header.title)));
+        }
+        if !card.content.is_empty() {
+            lines.push(escape(&card.content));
+        }
+        for field in &card.fields {
+            lines.push(format!("{}: {}", wrap("*", &escape(&field.label)), escape(&field.value)));
+        }
+        for image in &card.images {
+            lines.push(link_markup(&escape(image.alt.as_deref().unwrap_or(&image.url)), &image.url));
+        }
+        for action in &card.actions {
+            if let Some(url) = &action.url {
+                lines.push(link_markup(&escape(&action.label), url));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl Default for MarkdownV2Renderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 普通文本的转义
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '_' | '*' | '[' | ']' | '(' | ')' | '~' | '`' | '>' | '#' | '+' | '-' | '=' | '|' | '{' | '}' | '.' | '!' | '\\'
+        ) {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// 代码和代码块中的转义
+fn escape_code(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('`', "\\`")
+}
+
+fn link_markup(label: &str, url: &str) -> String {
+    let label = if label.is_empty() { escape(url) } else { label.to_string() };
+    format!("[{}]({})", label, url.replace('\\', "\\\\").replace(')', "\\)"))
+}
+
+/// 加上格式标记；内容为空时不加，与内容相邻的 `_` 之间插入 `\r` 避免与下划线混淆
+fn wrap(marker: &str, inner: &str) -> String {
+    if inner.is_empty() {
+        return String::new();
+    }
+    let open = if marker.ends_with('_') && inner.starts_with('_') { "\r" } else { "" };
+    let close = if marker.starts_with('_') && inner.ends_with('_') { "\r" } else { "" };
+    format!("{}{}{}{}{}", marker, open, inner, close, marker)
+}
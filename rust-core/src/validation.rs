@@ -0,0 +1,563 @@
+use crate::ast::*;
+use serde::Serialize;
+
+/// 校验问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationIssueKind {
+    /// 标题级别不在 1-6 之间
+    InvalidHeadingLevel,
+    /// 结构节点（root / listItem / tableRow / tableCell）出现在 children 中
+    MisplacedNode,
+    /// 块级节点出现在只允许行内内容的容器中
+    BlockInInline,
+    /// 表格行的单元格数量与其他行不一致
+    RaggedTableRow,
+    /// 链接或图片的 URL 为空
+    EmptyUrl,
+    /// 卡片结构不合法（如交互元素 ID 为空或重复、下拉框没有选项），或 metadata 不符合其子类型注册的 schema
+    /// （见 [`CardSchemaRegistry`](crate::CardSchemaRegistry)）
+    InvalidCard,
+}
+
+/// 校验问题
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationIssue {
+    /// 问题节点的 JSON Pointer 路径，如 `/children/2/rows/1`
+    pub path: String,
+    pub kind: ValidationIssueKind,
+    pub message: String,
+}
+
+/// 校验 AST 是否符合内容模型
+///
+/// 检查块级/行内嵌套关系、标题级别、表格行宽度、链接和图片 URL 以及卡片结构，不修改 AST。
+pub fn validate(root: &RootNode) -> Vec<ValidationIssue> {
+    let mut validator = Validator { issues: Vec::new() };
+    validator.check_children(&root.children, ContentModel::Flow, "/children");
+    validator.issues
+}
+
+/// 校验并原地修复 AST，返回发现（并已修复）的问题
+///
+/// 修复规则：
+/// - 标题级别截断到 1-6
+/// - 游离的 listItem / tableRow 包装进列表 / 表格，游离的 root / tableCell 展开其子节点
+/// - 行内容器中的块级节点展开为行内内容（代码块变为行内代码，分割线被移除，相邻的块之间以换行分隔）
+/// - 表格中较短的行用空单元格补齐
+/// - 空 URL 的链接展开为其子节点，空 URL 的图片替换为 alt 文本
+/// - 卡片中移除空 URL 的图片、ID 为空或重复的交互元素，清除空的图标和按钮链接
+///
+/// 修复后的路径以修复前的结构为准。
+pub fn repair(root: &mut RootNode) -> Vec<ValidationIssue> {
+    let issues = validate(root);
+    if !issues.is_empty() {
+        repair_children(&mut root.children, ContentModel::Flow);
+    }
+    issues
+}
+
+/// 校验单个卡片的结构（不修改卡片），`path` 是卡片节点的 JSON Pointer 路径
+pub(crate) fn card_issues(card: &CardNode, path: &str) -> Vec<ValidationIssue> {
+    let mut validator = Validator { issues: Vec::new() };
+    validator.check_card(card, path);
+    validator.issues
+}
+
+/// 容器允许的内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentModel {
+    /// 块级和行内节点均可
+    Flow,
+    /// 只允许行内节点
+    Phrasing,
+}
+
+/// 节点分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeCategory {
+    Inline,
+    Block,
+    /// 既可作为块级也可作为行内节点（图片、公式、剧透、自定义节点、未知节点）
+    Either,
+    /// 只能出现在特定父节点的专用字段中
+    Structural,
+}
+
+fn node_category(node: &ASTNode) -> NodeCategory {
+    match node {
+        ASTNode::Text(_)
+        | ASTNode::Strong(_)
+        | ASTNode::Em(_)
+        | ASTNode::Underline(_)
+        | ASTNode::Strike(_)
+        | ASTNode::Code(_)
+        | ASTNode::Link(_)
+        | ASTNode::Mention(_)
+        | ASTNode::FootnoteReference(_)
+        | ASTNode::Highlight(_)
+        | ASTNode::Timestamp(_)
+        | ASTNode::CustomEmoji(_) => NodeCategory::Inline,
+        ASTNode::Image(_)
+        | ASTNode::Math(_)
+        | ASTNode::Spoiler(_)
+        | ASTNode::Custom(_)
+        | ASTNode::Unknown(_) => NodeCategory::Either,
+        ASTNode::Paragraph(_)
+        | ASTNode::Heading(_)
+        | ASTNode::CodeBlock(_)
+        | ASTNode::List(_)
+        | ASTNode::Table(_)
+        | ASTNode::Mermaid(_)
+        | ASTNode::Card(_)
+        | ASTNode::HorizontalRule(_)
+        | ASTNode::Blockquote(_)
+        | ASTNode::FootnoteDefinition(_) => NodeCategory::Block,
+        ASTNode::Root(_) | ASTNode::ListItem(_) | ASTNode::TableRow(_) | ASTNode::TableCell(_) => {
+            NodeCategory::Structural
+        }
+    }
+}
+
+struct Validator {
+    issues: Vec<ValidationIssue>,
+}
+
+impl Validator {
+    fn report(&mut self, path: String, kind: ValidationIssueKind, message: String) {
+        self.issues.push(ValidationIssue { path, kind, message });
+    }
+
+    /// 先递归校验每个子节点，再检查本层的包含关系
+    fn check_children(&mut self, children: &[ASTNode], model: ContentModel, path: &str) {
+        for (index, child) in children.iter().enumerate() {
+            self.check_node(child, model, &format!("{}/{}", path, index));
+        }
+
+        for (index, child) in children.iter().enumerate() {
+            let child_path = format!("{}/{}", path, index);
+            match node_category(child) {
+                NodeCategory::Structural => self.report(
+                    child_path,
+                    ValidationIssueKind::MisplacedNode,
+                    format!("`{}` node cannot appear in children", child.type_name()),
+                ),
+                NodeCategory::Block if model == ContentModel::Phrasing => self.report(
+                    child_path,
+                    ValidationIssueKind::BlockInInline,
+                    format!("block node `{}` inside inline content", child.type_name()),
+                ),
+                _ => match child {
+                    ASTNode::Link(link) if link.url.trim().is_empty() => {
+                        self.report(child_path, ValidationIssueKind::EmptyUrl, "link has an empty url".to_string());
+                    }
+                    ASTNode::Image(image) if image.url.trim().is_empty() => {
+                        self.report(child_path, ValidationIssueKind::EmptyUrl, "image has an empty url".to_string());
+                    }
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    /// `model` 是父容器的内容模型，剧透节点和自定义节点的子节点沿用它
+    fn check_node(&mut self, node: &ASTNode, model: ContentModel, path: &str) {
+        match node {
+            ASTNode::Root(root) => {
+                self.check_children(&root.children, ContentModel::Flow, &format!("{}/children", path));
+            }
+            ASTNode::Heading(heading) => {
+                if !(1..=6).contains(&heading.level) {
+                    self.report(
+                        path.to_string(),
+                        ValidationIssueKind::InvalidHeadingLevel,
+                        format!("heading level {} is outside 1-6", heading.level),
+                    );
+                }
+                self.check_children(&heading.children, ContentModel::Phrasing, &format!("{}/children", path));
+            }
+            ASTNode::Paragraph(ParagraphNode { children })
+            | ASTNode::Strong(StrongNode { children })
+            | ASTNode::Em(EmNode { children })
+            | ASTNode::Underline(UnderlineNode { children })
+            | ASTNode::Strike(StrikeNode { children })
+            | ASTNode::Highlight(HighlightNode { children })
+            | ASTNode::Link(LinkNode { children, .. }) => {
+                self.check_children(children, ContentModel::Phrasing, &format!("{}/children", path));
+            }
+            ASTNode::Blockquote(BlockquoteNode { children })
+            | ASTNode::FootnoteDefinition(FootnoteDefinitionNode { children, .. }) => {
+                self.check_children(children, ContentModel::Flow, &format!("{}/children", path));
+            }
+            ASTNode::Spoiler(SpoilerNode { children }) | ASTNode::Custom(CustomNode { children, .. }) => {
+                self.check_children(children, model, &format!("{}/children", path));
+            }
+            ASTNode::List(list) => {
+                for (index, item) in list.items.iter().enumerate() {
+                    self.check_list_item(item, &format!("{}/items/{}", path, index));
+                }
+            }
+            ASTNode::ListItem(item) => self.check_list_item(item, path),
+            ASTNode::Table(table) => self.check_table(table, path),
+            ASTNode::TableRow(row) => self.check_table_row(row, path),
+            ASTNode::TableCell(cell) => self.check_table_cell(cell, path),
+            ASTNode::Text(_)
+            | ASTNode::Code(_)
+            | ASTNode::CodeBlock(_)
+            | ASTNode::Image(_)
+            | ASTNode::Math(_)
+            | ASTNode::Mermaid(_)
+            | ASTNode::Mention(_)
+            | ASTNode::HorizontalRule(_)
+            | ASTNode::FootnoteReference(_)
+            | ASTNode::Timestamp(_)
+            | ASTNode::CustomEmoji(_)
+            | ASTNode::Unknown(_) => {}
+            ASTNode::Card(card) => self.check_card(card, path),
+        }
+    }
+
+    fn check_card(&mut self, card: &CardNode, path: &str) {
+        if let Some(header) = &card.header {
+            if header.title.trim().is_empty() {
+                self.report(
+                    format!("{}/header/title", path),
+                    ValidationIssueKind::InvalidCard,
+                    "card header has an empty title".to_string(),
+                );
+            }
+            if header.icon.as_ref().is_some_and(|icon| icon.trim().is_empty()) {
+                self.report(
+                    format!("{}/header/icon", path),
+                    ValidationIssueKind::EmptyUrl,
+                    "card icon has an empty url".to_string(),
+                );
+            }
+        }
+
+        for (index, field) in card.fields.iter().enumerate() {
+            if field.label.trim().is_empty() {
+                self.report(
+                    format!("{}/fields/{}/label", path, index),
+                    ValidationIssueKind::InvalidCard,
+                    "card field has an empty label".to_string(),
+                );
+            }
+        }
+
+        for (index, image) in card.images.iter().enumerate() {
+            if image.url.trim().is_empty() {
+                self.report(
+                    format!("{}/images/{}/url", path, index),
+                    ValidationIssueKind::EmptyUrl,
+                    "card image has an empty url".to_string(),
+                );
+            }
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for (index, action) in card.actions.iter().enumerate() {
+            let action_path = format!("{}/actions/{}", path, index);
+            let id_error = if action.id.trim().is_empty() {
+                Some("action has an empty id".to_string())
+            } else if !seen.insert(action.id.as_str()) {
+                Some(format!("duplicate action id `{}`", action.id))
+            } else {
+                None
+            };
+            if let Some(message) = id_error {
+                self.report(format!("{}/id", action_path), ValidationIssueKind::InvalidCard, message);
+            }
+            self.check_card_action(action, &action_path);
+        }
+    }
+
+    fn check_card_action(&mut self, action: &CardAction, path: &str) {
+        match action.kind {
+            CardActionKind::Button => {
+                if action.label.trim().is_empty() {
+                    self.report(
+                        format!("{}/label", path),
+                        ValidationIssueKind::InvalidCard,
+                        "button has an empty label".to_string(),
+                    );
+                }
+                if action.url.as_ref().is_some_and(|url| url.trim().is_empty()) {
+                    self.report(
+                        format!("{}/url", path),
+                        ValidationIssueKind::EmptyUrl,
+                        "button has an empty url".to_string(),
+                    );
+                }
+            }
+            CardActionKind::Select | CardActionKind::Input => {
+                if action.url.is_some() {
+                    self.report(
+                        format!("{}/url", path),
+                        ValidationIssueKind::InvalidCard,
+                        "only buttons can have a url".to_string(),
+                    );
+                }
+            }
+        }
+
+        if action.kind != CardActionKind::Select {
+            if !action.options.is_empty() {
+                self.report(
+                    format!("{}/options", path),
+                    ValidationIssueKind::InvalidCard,
+                    "only selects can have options".to_string(),
+                );
+            }
+            return;
+        }
+
+        if action.options.is_empty() {
+            self.report(
+                format!("{}/options", path),
+                ValidationIssueKind::InvalidCard,
+                "select has no options".to_string(),
+            );
+        }
+        let mut values = std::collections::HashSet::new();
+        for (index, option) in action.options.iter().enumerate() {
+            if !values.insert(option.value.as_str()) {
+                self.report(
+                    format!("{}/options/{}/value", path, index),
+                    ValidationIssueKind::InvalidCard,
+                    format!("duplicate option value `{}`", option.value),
+                );
+            }
+        }
+        if let Some(value) = &action.value {
+            if !values.contains(value.as_str()) {
+                self.report(
+                    format!("{}/value", path),
+                    ValidationIssueKind::InvalidCard,
+                    format!("initial value `{}` is not one of the options", value),
+                );
+            }
+        }
+    }
+
+    fn check_list_item(&mut self, item: &ListItemNode, path: &str) {
+        self.check_children(&item.children, ContentModel::Flow, &format!("{}/children", path));
+    }
+
+    fn check_table(&mut self, table: &TableNode, path: &str) {
+        let width = table.rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+
+        for (index, row) in table.rows.iter().enumerate() {
+            let row_path = format!("{}/rows/{}", path, index);
+            if row.cells.len() != width {
+                self.report(
+                    row_path.clone(),
+                    ValidationIssueKind::RaggedTableRow,
+                    format!("row has {} cells, expected {}", row.cells.len(), width),
+                );
+            }
+            self.check_table_row(row, &row_path);
+        }
+    }
+
+    fn check_table_row(&mut self, row: &TableRow, path: &str) {
+        for (index, cell) in row.cells.iter().enumerate() {
+            self.check_table_cell(cell, &format!("{}/cells/{}", path, index));
+        }
+    }
+
+    fn check_table_cell(&mut self, cell: &TableCell, path: &str) {
+        self.check_children(&cell.children, ContentModel::Phrasing, &format!("{}/children", path));
+    }
+}
+
+/// 修复子节点列表：先递归修复每个子节点，再处理本层的包含关系（与 [`Validator::check_children`] 对应）
+fn repair_children(children: &mut Vec<ASTNode>, model: ContentModel) {
+    for child in children.iter_mut() {
+        repair_node(child, model);
+    }
+
+    let mut pending = std::mem::take(children);
+    if model == ContentModel::Phrasing
+        && pending
+            .iter()
+            .any(|child| matches!(node_category(child), NodeCategory::Block | NodeCategory::Structural))
+    {
+        // 行内容器中的块级节点逐个展开，与相邻内容之间用换行分隔
+        pending = join_flattened(pending, "\n");
+    }
+
+    let mut result = Vec::with_capacity(pending.len());
+    for child in pending {
+        match node_category(&child) {
+            NodeCategory::Structural => place_structural(child, model, &mut result),
+            _ => match child {
+                ASTNode::Link(link) if link.url.trim().is_empty() => result.extend(link.children),
+                ASTNode::Image(image) if image.url.trim().is_empty() => {
+                    if let Some(alt) = image.alt.filter(|alt| !alt.is_empty()) {
+                        result.push(ASTNode::Text(TextNode { content: alt }));
+                    }
+                }
+                child => result.push(child),
+            },
+        }
+    }
+
+    *children = result;
+}
+
+fn repair_node(node: &mut ASTNode, model: ContentModel) {
+    match node {
+        ASTNode::Root(RootNode { children, .. })
+        | ASTNode::Blockquote(BlockquoteNode { children })
+        | ASTNode::FootnoteDefinition(FootnoteDefinitionNode { children, .. })
+        | ASTNode::ListItem(ListItemNode { children, .. }) => repair_children(children, ContentModel::Flow),
+        ASTNode::Heading(heading) => {
+            heading.level = heading.level.clamp(1, 6);
+            repair_children(&mut heading.children, ContentModel::Phrasing);
+        }
+        ASTNode::Paragraph(ParagraphNode { children })
+        | ASTNode::Strong(StrongNode { children })
+        | ASTNode::Em(EmNode { children })
+        | ASTNode::Underline(UnderlineNode { children })
+        | ASTNode::Strike(StrikeNode { children })
+        | ASTNode::Highlight(HighlightNode { children })
+        | ASTNode::Link(LinkNode { children, .. })
+        | ASTNode::TableCell(TableCell { children, .. }) => repair_children(children, ContentModel::Phrasing),
+        ASTNode::Spoiler(SpoilerNode { children }) | ASTNode::Custom(CustomNode { children, .. }) => {
+            repair_children(children, model);
+        }
+        ASTNode::List(list) => {
+            for item in &mut list.items {
+                repair_children(&mut item.children, ContentModel::Flow);
+            }
+        }
+        ASTNode::Table(table) => {
+            let width = table.rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+            for row in &mut table.rows {
+                row.cells.resize_with(width, || TableCell { children: Vec::new(), align: None });
+                repair_table_row(row);
+            }
+        }
+        ASTNode::TableRow(row) => repair_table_row(row),
+        ASTNode::Card(card) => repair_card(card),
+        ASTNode::Text(_)
+        | ASTNode::Code(_)
+        | ASTNode::CodeBlock(_)
+        | ASTNode::Image(_)
+        | ASTNode::Math(_)
+        | ASTNode::Mermaid(_)
+        | ASTNode::Mention(_)
+        | ASTNode::HorizontalRule(_)
+        | ASTNode::FootnoteReference(_)
+        | ASTNode::Timestamp(_)
+        | ASTNode::CustomEmoji(_)
+        | ASTNode::Unknown(_) => {}
+    }
+}
+
+fn repair_table_row(row: &mut TableRow) {
+    for cell in &mut row.cells {
+        repair_children(&mut cell.children, ContentModel::Phrasing);
+    }
+}
+
+fn repair_card(card: &mut CardNode) {
+    if let Some(header) = &mut card.header {
+        if header.icon.as_ref().is_some_and(|icon| icon.trim().is_empty()) {
+            header.icon = None;
+        }
+    }
+
+    card.images.retain(|image| !image.url.trim().is_empty());
+
+    let mut seen = std::collections::HashSet::new();
+    card.actions
+        .retain(|action| !action.id.trim().is_empty() && seen.insert(action.id.clone()));
+    for action in &mut card.actions {
+        if action.kind == CardActionKind::Button && action.url.as_ref().is_some_and(|url| url.trim().is_empty()) {
+            action.url = None;
+        }
+    }
+}
+
+/// 将游离的结构节点放到合适的位置
+fn place_structural(node: ASTNode, model: ContentModel, result: &mut Vec<ASTNode>) {
+    if model == ContentModel::Phrasing {
+        result.extend(flatten_to_inline(node));
+        return;
+    }
+
+    match node {
+        ASTNode::ListItem(item) => {
+            if let Some(ASTNode::List(list)) = result.last_mut() {
+                list.items.push(item);
+            } else {
+                result.push(ASTNode::List(ListNode {
+                    list_type: ListType::Bullet,
+                    items: vec![item],
+                }));
+            }
+        }
+        ASTNode::TableRow(row) => {
+            if let Some(ASTNode::Table(table)) = result.last_mut() {
+                table.rows.push(row);
+            } else {
+                result.push(ASTNode::Table(TableNode { rows: vec![row] }));
+            }
+        }
+        ASTNode::Root(RootNode { children, .. }) | ASTNode::TableCell(TableCell { children, .. }) => {
+            result.extend(children);
+        }
+        other => result.push(other),
+    }
+}
+
+/// 将节点展开为行内内容
+fn flatten_to_inline(node: ASTNode) -> Vec<ASTNode> {
+    match node {
+        ASTNode::Paragraph(ParagraphNode { children }) | ASTNode::Heading(HeadingNode { children, .. }) => {
+            children.into_iter().flat_map(flatten_to_inline).collect()
+        }
+        ASTNode::Root(RootNode { children, .. })
+        | ASTNode::Blockquote(BlockquoteNode { children })
+        | ASTNode::FootnoteDefinition(FootnoteDefinitionNode { children, .. })
+        | ASTNode::ListItem(ListItemNode { children, .. })
+        | ASTNode::TableCell(TableCell { children, .. }) => join_flattened(children, "\n"),
+        ASTNode::List(list) => join_flattened(list.items.into_iter().map(ASTNode::ListItem).collect(), "\n"),
+        ASTNode::Table(table) => join_flattened(table.rows.into_iter().map(ASTNode::TableRow).collect(), "\n"),
+        ASTNode::TableRow(row) => join_flattened(row.cells.into_iter().map(ASTNode::TableCell).collect(), " "),
+        ASTNode::CodeBlock(CodeBlockNode { content, .. }) | ASTNode::Mermaid(MermaidNode { content }) => {
+            vec![ASTNode::Code(CodeNode { content })]
+        }
+        ASTNode::Spoiler(SpoilerNode { children }) => vec![ASTNode::Spoiler(SpoilerNode {
+            children: children.into_iter().flat_map(flatten_to_inline).collect(),
+        })],
+        ASTNode::Custom(custom) => vec![ASTNode::Custom(CustomNode {
+            children: custom.children.into_iter().flat_map(flatten_to_inline).collect(),
+            ..custom
+        })],
+        ASTNode::Card(card) => vec![ASTNode::Text(TextNode { content: card.content })],
+        ASTNode::HorizontalRule(_) => Vec::new(),
+        inline => vec![inline],
+    }
+}
+
+/// 展开一组子节点，块级和结构节点各自成行，与相邻内容之间插入 `separator`
+fn join_flattened(children: Vec<ASTNode>, separator: &str) -> Vec<ASTNode> {
+    let mut result = Vec::new();
+    let mut previous_was_block = false;
+    for child in children {
+        let is_block = matches!(node_category(&child), NodeCategory::Block | NodeCategory::Structural);
+        let flattened = flatten_to_inline(child);
+        if flattened.is_empty() {
+            continue;
+        }
+        if !result.is_empty() && (is_block || previous_was_block) {
+            result.push(ASTNode::Text(TextNode { content: separator.to_string() }));
+        }
+        previous_was_block = is_block;
+        result.extend(flattened);
+    }
+    result
+}
@@ -0,0 +1,461 @@
+use crate::ast::*;
+
+/// 只读 AST 访问器
+///
+/// 每个节点类型都有对应的 `visit_*` 方法，默认实现调用同名的 `walk_*` 函数继续遍历子节点。
+/// 实现者只需覆盖关心的节点类型；如果覆盖后仍需遍历子节点，手动调用对应的 `walk_*` 函数。
+pub trait Visitor {
+    fn visit_root(&mut self, root: &RootNode) {
+        walk_root(self, root);
+    }
+
+    fn visit_node(&mut self, node: &ASTNode) {
+        walk_node(self, node);
+    }
+
+    fn visit_paragraph(&mut self, para: &ParagraphNode) {
+        walk_paragraph(self, para);
+    }
+
+    fn visit_heading(&mut self, heading: &HeadingNode) {
+        walk_heading(self, heading);
+    }
+
+    fn visit_text(&mut self, _text: &TextNode) {}
+
+    fn visit_strong(&mut self, strong: &StrongNode) {
+        walk_strong(self, strong);
+    }
+
+    fn visit_em(&mut self, em: &EmNode) {
+        walk_em(self, em);
+    }
+
+    fn visit_underline(&mut self, underline: &UnderlineNode) {
+        walk_underline(self, underline);
+    }
+
+    fn visit_strike(&mut self, strike: &StrikeNode) {
+        walk_strike(self, strike);
+    }
+
+    fn visit_code(&mut self, _code: &CodeNode) {}
+
+    fn visit_code_block(&mut self, _code_block: &CodeBlockNode) {}
+
+    fn visit_link(&mut self, link: &LinkNode) {
+        walk_link(self, link);
+    }
+
+    fn visit_image(&mut self, _image: &ImageNode) {}
+
+    fn visit_list(&mut self, list: &ListNode) {
+        walk_list(self, list);
+    }
+
+    fn visit_list_item(&mut self, item: &ListItemNode) {
+        walk_list_item(self, item);
+    }
+
+    fn visit_table(&mut self, table: &TableNode) {
+        walk_table(self, table);
+    }
+
+    fn visit_table_row(&mut self, row: &TableRow) {
+        walk_table_row(self, row);
+    }
+
+    fn visit_table_cell(&mut self, cell: &TableCell) {
+        walk_table_cell(self, cell);
+    }
+
+    fn visit_math(&mut self, _math: &MathNode) {}
+
+    fn visit_mermaid(&mut self, _mermaid: &MermaidNode) {}
+
+    fn visit_card(&mut self, _card: &CardNode) {}
+
+    fn visit_mention(&mut self, _mention: &MentionNode) {}
+
+    fn visit_horizontal_rule(&mut self, _hr: &HorizontalRuleNode) {}
+
+    fn visit_blockquote(&mut self, blockquote: &BlockquoteNode) {
+        walk_blockquote(self, blockquote);
+    }
+
+    fn visit_footnote_reference(&mut self, _reference: &FootnoteReferenceNode) {}
+
+    fn visit_footnote_definition(&mut self, definition: &FootnoteDefinitionNode) {
+        walk_footnote_definition(self, definition);
+    }
+
+    fn visit_highlight(&mut self, highlight: &HighlightNode) {
+        walk_highlight(self, highlight);
+    }
+
+    fn visit_spoiler(&mut self, spoiler: &SpoilerNode) {
+        walk_spoiler(self, spoiler);
+    }
+
+    fn visit_custom(&mut self, custom: &CustomNode) {
+        walk_custom(self, custom);
+    }
+
+    fn visit_timestamp(&mut self, _timestamp: &TimestampNode) {}
+
+    fn visit_custom_emoji(&mut self, _emoji: &CustomEmojiNode) {}
+
+    fn visit_unknown(&mut self, _unknown: &UnknownNode) {}
+}
+
+/// 根据节点类型分派到对应的 `visit_*` 方法
+pub fn walk_node<V: Visitor + ?Sized>(visitor: &mut V, node: &ASTNode) {
+    match node {
+        ASTNode::Root(root) => visitor.visit_root(root),
+        ASTNode::Paragraph(para) => visitor.visit_paragraph(para),
+        ASTNode::Heading(heading) => visitor.visit_heading(heading),
+        ASTNode::Text(text) => visitor.visit_text(text),
+        ASTNode::Strong(strong) => visitor.visit_strong(strong),
+        ASTNode::Em(em) => visitor.visit_em(em),
+        ASTNode::Underline(underline) => visitor.visit_underline(underline),
+        ASTNode::Strike(strike) => visitor.visit_strike(strike),
+        ASTNode::Code(code) => visitor.visit_code(code),
+        ASTNode::CodeBlock(code_block) => visitor.visit_code_block(code_block),
+        ASTNode::Link(link) => visitor.visit_link(link),
+        ASTNode::Image(image) => visitor.visit_image(image),
+        ASTNode::List(list) => visitor.visit_list(list),
+        ASTNode::ListItem(item) => visitor.visit_list_item(item),
+        ASTNode::Table(table) => visitor.visit_table(table),
+        ASTNode::TableRow(row) => visitor.visit_table_row(row),
+        ASTNode::TableCell(cell) => visitor.visit_table_cell(cell),
+        ASTNode::Math(math) => visitor.visit_math(math),
+        ASTNode::Mermaid(mermaid) => visitor.visit_mermaid(mermaid),
+        ASTNode::Card(card) => visitor.visit_card(card),
+        ASTNode::Mention(mention) => visitor.visit_mention(mention),
+        ASTNode::HorizontalRule(hr) => visitor.visit_horizontal_rule(hr),
+        ASTNode::Blockquote(blockquote) => visitor.visit_blockquote(blockquote),
+        ASTNode::FootnoteReference(reference) => visitor.visit_footnote_reference(reference),
+        ASTNode::FootnoteDefinition(definition) => visitor.visit_footnote_definition(definition),
+        ASTNode::Highlight(highlight) => visitor.visit_highlight(highlight),
+        ASTNode::Spoiler(spoiler) => visitor.visit_spoiler(spoiler),
+        ASTNode::Custom(custom) => visitor.visit_custom(custom),
+        ASTNode::Timestamp(timestamp) => visitor.visit_timestamp(timestamp),
+        ASTNode::CustomEmoji(emoji) => visitor.visit_custom_emoji(emoji),
+        ASTNode::Unknown(unknown) => visitor.visit_unknown(unknown),
+    }
+}
+
+/// 依次访问一组子节点
+pub fn walk_children<V: Visitor + ?Sized>(visitor: &mut V, children: &[ASTNode]) {
+    for child in children {
+        visitor.visit_node(child);
+    }
+}
+
+pub fn walk_root<V: Visitor + ?Sized>(visitor: &mut V, root: &RootNode) {
+    walk_children(visitor, &root.children);
+}
+
+pub fn walk_paragraph<V: Visitor + ?Sized>(visitor: &mut V, para: &ParagraphNode) {
+    walk_children(visitor, &para.children);
+}
+
+pub fn walk_heading<V: Visitor + ?Sized>(visitor: &mut V, heading: &HeadingNode) {
+    walk_children(visitor, &heading.children);
+}
+
+pub fn walk_strong<V: Visitor + ?Sized>(visitor: &mut V, strong: &StrongNode) {
+    walk_children(visitor, &strong.children);
+}
+
+pub fn walk_em<V: Visitor + ?Sized>(visitor: &mut V, em: &EmNode) {
+    walk_children(visitor, &em.children);
+}
+
+pub fn walk_underline<V: Visitor + ?Sized>(visitor: &mut V, underline: &UnderlineNode) {
+    walk_children(visitor, &underline.children);
+}
+
+pub fn walk_strike<V: Visitor + ?Sized>(visitor: &mut V, strike: &StrikeNode) {
+    walk_children(visitor, &strike.children);
+}
+
+pub fn walk_link<V: Visitor + ?Sized>(visitor: &mut V, link: &LinkNode) {
+    walk_children(visitor, &link.children);
+}
+
+pub fn walk_list<V: Visitor + ?Sized>(visitor: &mut V, list: &ListNode) {
+    for item in &list.items {
+        visitor.visit_list_item(item);
+    }
+}
+
+pub fn walk_list_item<V: Visitor + ?Sized>(visitor: &mut V, item: &ListItemNode) {
+    walk_children(visitor, &item.children);
+}
+
+pub fn walk_table<V: Visitor + ?Sized>(visitor: &mut V, table: &TableNode) {
+    for row in &table.rows {
+        visitor.visit_table_row(row);
+    }
+}
+
+pub fn walk_table_row<V: Visitor + ?Sized>(visitor: &mut V, row: &TableRow) {
+    for cell in &row.cells {
+        visitor.visit_table_cell(cell);
+    }
+}
+
+pub fn walk_table_cell<V: Visitor + ?Sized>(visitor: &mut V, cell: &TableCell) {
+    walk_children(visitor, &cell.children);
+}
+
+pub fn walk_blockquote<V: Visitor + ?Sized>(visitor: &mut V, blockquote: &BlockquoteNode) {
+    walk_children(visitor, &blockquote.children);
+}
+
+pub fn walk_footnote_definition<V: Visitor + ?Sized>(visitor: &mut V, definition: &FootnoteDefinitionNode) {
+    walk_children(visitor, &definition.children);
+}
+
+pub fn walk_highlight<V: Visitor + ?Sized>(visitor: &mut V, highlight: &HighlightNode) {
+    walk_children(visitor, &highlight.children);
+}
+
+pub fn walk_spoiler<V: Visitor + ?Sized>(visitor: &mut V, spoiler: &SpoilerNode) {
+    walk_children(visitor, &spoiler.children);
+}
+
+pub fn walk_custom<V: Visitor + ?Sized>(visitor: &mut V, custom: &CustomNode) {
+    walk_children(visitor, &custom.children);
+}
+
+/// 可变 AST 访问器
+///
+/// 与 [`Visitor`] 结构相同，但以可变引用遍历，用于原地改写节点（如补全提及名称、改写链接）。
+/// 需要增删子节点时，在父节点的 `visit_*` 方法中直接修改其 `children`。
+pub trait VisitorMut {
+    fn visit_root_mut(&mut self, root: &mut RootNode) {
+        walk_root_mut(self, root);
+    }
+
+    fn visit_node_mut(&mut self, node: &mut ASTNode) {
+        walk_node_mut(self, node);
+    }
+
+    fn visit_paragraph_mut(&mut self, para: &mut ParagraphNode) {
+        walk_paragraph_mut(self, para);
+    }
+
+    fn visit_heading_mut(&mut self, heading: &mut HeadingNode) {
+        walk_heading_mut(self, heading);
+    }
+
+    fn visit_text_mut(&mut self, _text: &mut TextNode) {}
+
+    fn visit_strong_mut(&mut self, strong: &mut StrongNode) {
+        walk_strong_mut(self, strong);
+    }
+
+    fn visit_em_mut(&mut self, em: &mut EmNode) {
+        walk_em_mut(self, em);
+    }
+
+    fn visit_underline_mut(&mut self, underline: &mut UnderlineNode) {
+        walk_underline_mut(self, underline);
+    }
+
+    fn visit_strike_mut(&mut self, strike: &mut StrikeNode) {
+        walk_strike_mut(self, strike);
+    }
+
+    fn visit_code_mut(&mut self, _code: &mut CodeNode) {}
+
+    fn visit_code_block_mut(&mut self, _code_block: &mut CodeBlockNode) {}
+
+    fn visit_link_mut(&mut self, link: &mut LinkNode) {
+        walk_link_mut(self, link);
+    }
+
+    fn visit_image_mut(&mut self, _image: &mut ImageNode) {}
+
+    fn visit_list_mut(&mut self, list: &mut ListNode) {
+        walk_list_mut(self, list);
+    }
+
+    fn visit_list_item_mut(&mut self, item: &mut ListItemNode) {
+        walk_list_item_mut(self, item);
+    }
+
+    fn visit_table_mut(&mut self, table: &mut TableNode) {
+        walk_table_mut(self, table);
+    }
+
+    fn visit_table_row_mut(&mut self, row: &mut TableRow) {
+        walk_table_row_mut(self, row);
+    }
+
+    fn visit_table_cell_mut(&mut self, cell: &mut TableCell) {
+        walk_table_cell_mut(self, cell);
+    }
+
+    fn visit_math_mut(&mut self, _math: &mut MathNode) {}
+
+    fn visit_mermaid_mut(&mut self, _mermaid: &mut MermaidNode) {}
+
+    fn visit_card_mut(&mut self, _card: &mut CardNode) {}
+
+    fn visit_mention_mut(&mut self, _mention: &mut MentionNode) {}
+
+    fn visit_horizontal_rule_mut(&mut self, _hr: &mut HorizontalRuleNode) {}
+
+    fn visit_blockquote_mut(&mut self, blockquote: &mut BlockquoteNode) {
+        walk_blockquote_mut(self, blockquote);
+    }
+
+    fn visit_footnote_reference_mut(&mut self, _reference: &mut FootnoteReferenceNode) {}
+
+    fn visit_footnote_definition_mut(&mut self, definition: &mut FootnoteDefinitionNode) {
+        walk_footnote_definition_mut(self, definition);
+    }
+
+    fn visit_highlight_mut(&mut self, highlight: &mut HighlightNode) {
+        walk_highlight_mut(self, highlight);
+    }
+
+    fn visit_spoiler_mut(&mut self, spoiler: &mut SpoilerNode) {
+        walk_spoiler_mut(self, spoiler);
+    }
+
+    fn visit_custom_mut(&mut self, custom: &mut CustomNode) {
+        walk_custom_mut(self, custom);
+    }
+
+    fn visit_timestamp_mut(&mut self, _timestamp: &mut TimestampNode) {}
+
+    fn visit_custom_emoji_mut(&mut self, _emoji: &mut CustomEmojiNode) {}
+
+    fn visit_unknown_mut(&mut self, _unknown: &mut UnknownNode) {}
+}
+
+/// 根据节点类型分派到对应的 `visit_*_mut` 方法
+pub fn walk_node_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ASTNode) {
+    match node {
+        ASTNode::Root(root) => visitor.visit_root_mut(root),
+        ASTNode::Paragraph(para) => visitor.visit_paragraph_mut(para),
+        ASTNode::Heading(heading) => visitor.visit_heading_mut(heading),
+        ASTNode::Text(text) => visitor.visit_text_mut(text),
+        ASTNode::Strong(strong) => visitor.visit_strong_mut(strong),
+        ASTNode::Em(em) => visitor.visit_em_mut(em),
+        ASTNode::Underline(underline) => visitor.visit_underline_mut(underline),
+        ASTNode::Strike(strike) => visitor.visit_strike_mut(strike),
+        ASTNode::Code(code) => visitor.visit_code_mut(code),
+        ASTNode::CodeBlock(code_block) => visitor.visit_code_block_mut(code_block),
+        ASTNode::Link(link) => visitor.visit_link_mut(link),
+        ASTNode::Image(image) => visitor.visit_image_mut(image),
+        ASTNode::List(list) => visitor.visit_list_mut(list),
+        ASTNode::ListItem(item) => visitor.visit_list_item_mut(item),
+        ASTNode::Table(table) => visitor.visit_table_mut(table),
+        ASTNode::TableRow(row) => visitor.visit_table_row_mut(row),
+        ASTNode::TableCell(cell) => visitor.visit_table_cell_mut(cell),
+        ASTNode::Math(math) => visitor.visit_math_mut(math),
+        ASTNode::Mermaid(mermaid) => visitor.visit_mermaid_mut(mermaid),
+        ASTNode::Card(card) => visitor.visit_card_mut(card),
+        ASTNode::Mention(mention) => visitor.visit_mention_mut(mention),
+        ASTNode::HorizontalRule(hr) => visitor.visit_horizontal_rule_mut(hr),
+        ASTNode::Blockquote(blockquote) => visitor.visit_blockquote_mut(blockquote),
+        ASTNode::FootnoteReference(reference) => visitor.visit_footnote_reference_mut(reference),
+        ASTNode::FootnoteDefinition(definition) => visitor.visit_footnote_definition_mut(definition),
+        ASTNode::Highlight(highlight) => visitor.visit_highlight_mut(highlight),
+        ASTNode::Spoiler(spoiler) => visitor.visit_spoiler_mut(spoiler),
+        ASTNode::Custom(custom) => visitor.visit_custom_mut(custom),
+        ASTNode::Timestamp(timestamp) => visitor.visit_timestamp_mut(timestamp),
+        ASTNode::CustomEmoji(emoji) => visitor.visit_custom_emoji_mut(emoji),
+        ASTNode::Unknown(unknown) => visitor.visit_unknown_mut(unknown),
+    }
+}
+
+/// 依次可变访问一组子节点
+pub fn walk_children_mut<V: VisitorMut + ?Sized>(visitor: &mut V, children: &mut [ASTNode]) {
+    for child in children {
+        visitor.visit_node_mut(child);
+    }
+}
+
+pub fn walk_root_mut<V: VisitorMut + ?Sized>(visitor: &mut V, root: &mut RootNode) {
+    walk_children_mut(visitor, &mut root.children);
+}
+
+pub fn walk_paragraph_mut<V: VisitorMut + ?Sized>(visitor: &mut V, para: &mut ParagraphNode) {
+    walk_children_mut(visitor, &mut para.children);
+}
+
+pub fn walk_heading_mut<V: VisitorMut + ?Sized>(visitor: &mut V, heading: &mut HeadingNode) {
+    walk_children_mut(visitor, &mut heading.children);
+}
+
+pub fn walk_strong_mut<V: VisitorMut + ?Sized>(visitor: &mut V, strong: &mut StrongNode) {
+    walk_children_mut(visitor, &mut strong.children);
+}
+
+pub fn walk_em_mut<V: VisitorMut + ?Sized>(visitor: &mut V, em: &mut EmNode) {
+    walk_children_mut(visitor, &mut em.children);
+}
+
+pub fn walk_underline_mut<V: VisitorMut + ?Sized>(visitor: &mut V, underline: &mut UnderlineNode) {
+    walk_children_mut(visitor, &mut underline.children);
+}
+
+pub fn walk_strike_mut<V: VisitorMut + ?Sized>(visitor: &mut V, strike: &mut StrikeNode) {
+    walk_children_mut(visitor, &mut strike.children);
+}
+
+pub fn walk_link_mut<V: VisitorMut + ?Sized>(visitor: &mut V, link: &mut LinkNode) {
+    walk_children_mut(visitor, &mut link.children);
+}
+
+pub fn walk_list_mut<V: VisitorMut + ?Sized>(visitor: &mut V, list: &mut ListNode) {
+    for item in &mut list.items {
+        visitor.visit_list_item_mut(item);
+    }
+}
+
+pub fn walk_list_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut ListItemNode) {
+    walk_children_mut(visitor, &mut item.children);
+}
+
+pub fn walk_table_mut<V: VisitorMut + ?Sized>(visitor: &mut V, table: &mut TableNode) {
+    for row in &mut table.rows {
+        visitor.visit_table_row_mut(row);
+    }
+}
+
+pub fn walk_table_row_mut<V: VisitorMut + ?Sized>(visitor: &mut V, row: &mut TableRow) {
+    for cell in &mut row.cells {
+        visitor.visit_table_cell_mut(cell);
+    }
+}
+
+pub fn walk_table_cell_mut<V: VisitorMut + ?Sized>(visitor: &mut V, cell: &mut TableCell) {
+    walk_children_mut(visitor, &mut cell.children);
+}
+
+pub fn walk_blockquote_mut<V: VisitorMut + ?Sized>(visitor: &mut V, blockquote: &mut BlockquoteNode) {
+    walk_children_mut(visitor, &mut blockquote.children);
+}
+
+pub fn walk_footnote_definition_mut<V: VisitorMut + ?Sized>(visitor: &mut V, definition: &mut FootnoteDefinitionNode) {
+    walk_children_mut(visitor, &mut definition.children);
+}
+
+pub fn walk_highlight_mut<V: VisitorMut + ?Sized>(visitor: &mut V, highlight: &mut HighlightNode) {
+    walk_children_mut(visitor, &mut highlight.children);
+}
+
+pub fn walk_spoiler_mut<V: VisitorMut + ?Sized>(visitor: &mut V, spoiler: &mut SpoilerNode) {
+    walk_children_mut(visitor, &mut spoiler.children);
+}
+
+pub fn walk_custom_mut<V: VisitorMut + ?Sized>(visitor: &mut V, custom: &mut CustomNode) {
+    walk_children_mut(visitor, &mut custom.children);
+}
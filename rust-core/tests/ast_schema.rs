@@ -0,0 +1,109 @@
+//! AST 节点类型名与前向兼容规则测试
+
+use im_parse_core::*;
+use serde_json::json;
+
+/// 每个已知变体的一个样例（不含 Unknown）
+fn samples() -> Vec<ASTNode> {
+    let values = vec![
+        json!({"type": "root", "children": []}),
+        json!({"type": "paragraph", "children": []}),
+        json!({"type": "heading", "level": 1, "children": []}),
+        json!({"type": "text", "content": ""}),
+        json!({"type": "strong", "children": []}),
+        json!({"type": "em", "children": []}),
+        json!({"type": "underline", "children": []}),
+        json!({"type": "strike", "children": []}),
+        json!({"type": "code", "content": ""}),
+        json!({"type": "codeBlock", "language": null, "content": ""}),
+        json!({"type": "link", "url": "https://x.y", "children": []}),
+        json!({"type": "image", "url": "https://x.y/a.png"}),
+        json!({"type": "list", "listType": "bullet", "items": []}),
+        json!({"type": "listItem", "children": [], "checked": null}),
+        json!({"type": "table", "rows": []}),
+        json!({"type": "tableRow", "cells": []}),
+        json!({"type": "tableCell", "children": [], "align": null}),
+        json!({"type": "math", "content": "x", "display": false}),
+        json!({"type": "mermaid", "content": ""}),
+        json!({"type": "card", "subtype": "info", "content": "", "metadata": {}}),
+        json!({"type": "mention", "id": "U1", "name": "Alice"}),
+        json!({"type": "horizontalRule"}),
+        json!({"type": "blockquote", "children": []}),
+        json!({"type": "footnoteReference", "label": "a", "index": 1}),
+        json!({"type": "footnoteDefinition", "label": "a", "index": 1, "children": []}),
+        json!({"type": "highlight", "children": []}),
+        json!({"type": "spoiler", "children": []}),
+        json!({"type": "custom", "kind": "x"}),
+        json!({"type": "timestamp", "timestamp": 0}),
+        json!({"type": "customEmoji", "id": "1", "name": "e"}),
+    ];
+    values.into_iter().map(|value| serde_json::from_value(value).unwrap()).collect()
+}
+
+/// 变体序号；新增变体时这里无法编译，提醒同步更新样例和 `KNOWN_NODE_TYPES`
+fn variant_index(node: &ASTNode) -> usize {
+    match node {
+        ASTNode::Root(_) => 0,
+        ASTNode::Paragraph(_) => 1,
+        ASTNode::Heading(_) => 2,
+        ASTNode::Text(_) => 3,
+        ASTNode::Strong(_) => 4,
+        ASTNode::Em(_) => 5,
+        ASTNode::Underline(_) => 6,
+        ASTNode::Strike(_) => 7,
+        ASTNode::Code(_) => 8,
+        ASTNode::CodeBlock(_) => 9,
+        ASTNode::Link(_) => 10,
+        ASTNode::Image(_) => 11,
+        ASTNode::List(_) => 12,
+        ASTNode::ListItem(_) => 13,
+        ASTNode::Table(_) => 14,
+        ASTNode::TableRow(_) => 15,
+        ASTNode::TableCell(_) => 16,
+        ASTNode::Math(_) => 17,
+        ASTNode::Mermaid(_) => 18,
+        ASTNode::Card(_) => 19,
+        ASTNode::Mention(_) => 20,
+        ASTNode::HorizontalRule(_) => 21,
+        ASTNode::Blockquote(_) => 22,
+        ASTNode::FootnoteReference(_) => 23,
+        ASTNode::FootnoteDefinition(_) => 24,
+        ASTNode::Highlight(_) => 25,
+        ASTNode::Spoiler(_) => 26,
+        ASTNode::Custom(_) => 27,
+        ASTNode::Timestamp(_) => 28,
+        ASTNode::CustomEmoji(_) => 29,
+        ASTNode::Unknown(_) => usize::MAX,
+    }
+}
+
+#[test]
+fn known_node_types_cover_every_variant() {
+    let samples = samples();
+    let indices: Vec<usize> = samples.iter().map(variant_index).collect();
+    assert_eq!(indices, (0..samples.len()).collect::<Vec<_>>(), "one sample per known variant, in order");
+
+    let names: Vec<&str> = samples.iter().map(|node| node.type_name()).collect();
+    assert_eq!(names, KNOWN_NODE_TYPES);
+
+    for node in &samples {
+        let value = serde_json::to_value(node).unwrap();
+        assert_eq!(value["type"], node.type_name(), "type_name must match the serde tag");
+    }
+}
+
+#[test]
+fn unknown_node_type_is_preserved() {
+    let raw = json!({"type": "poll", "question": "?", "options": ["a", "b"]});
+    let node: ASTNode = serde_json::from_value(raw.clone()).unwrap();
+    assert!(matches!(&node, ASTNode::Unknown(unknown) if unknown.node_type() == Some("poll")));
+    assert_eq!(serde_json::to_value(&node).unwrap(), raw);
+}
+
+#[test]
+fn malformed_known_node_is_rejected_by_plain_serde() {
+    assert!(serde_json::from_str::<ASTNode>(r#"{"type":"heading","level":"one","children":[]}"#).is_err());
+    assert!(serde_json::from_str::<ASTNode>(r#"{"children":[]}"#).is_err());
+    assert!(serde_json::from_str::<RootNode>(r#"{"children":[{"type":"text"}]}"#).is_err());
+    assert!(deserialize_ast(r#"{"children":[{"type":"paragraph","children":[{"type":"link","url":1}]}]}"#).is_err());
+}
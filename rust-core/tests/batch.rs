@@ -0,0 +1,127 @@
+//! 批量解析测试
+
+use im_parse_core::ffi::{free_parse_result, parse_batch_to_json};
+use im_parse_core::*;
+use serde_json::Value;
+use std::ffi::{CStr, CString};
+
+fn item(id: &str, format: InputFormat, content: &str) -> BatchItem {
+    BatchItem {
+        id: id.to_string(),
+        format,
+        content: content.to_string(),
+    }
+}
+
+/// 调用 parse_batch_to_json，返回是否成功以及结果 JSON 或错误信息
+fn batch_to_json(input: &str) -> (bool, String) {
+    let input = CString::new(input).unwrap();
+    unsafe {
+        let result = parse_batch_to_json(input.as_ptr());
+        let success = (*result).success;
+        let text = if success {
+            CStr::from_ptr((*result).ast_json).to_str().unwrap().to_string()
+        } else {
+            CStr::from_ptr((*result).error.message).to_str().unwrap().to_string()
+        };
+        free_parse_result(result);
+        (success, text)
+    }
+}
+
+#[test]
+fn results_keep_input_order_and_ids() {
+    let items = vec![
+        item("m1", InputFormat::Markdown, "**bold**"),
+        item("d1", InputFormat::Delta, r#"{"ops":[{"insert":"delta\n"}]}"#),
+        item("h1", InputFormat::Html, "<p><em>html</em></p>"),
+        item("m2", InputFormat::Markdown, "# title"),
+    ];
+    let results = parse_batch(&items);
+
+    let ids: Vec<&str> = results.iter().map(|result| result.id.as_str()).collect();
+    assert_eq!(ids, vec!["m1", "d1", "h1", "m2"]);
+    assert_eq!(results[0].ast, Some(parse_markdown("**bold**").unwrap()));
+    assert_eq!(results[1].ast, Some(parse_delta(r#"{"ops":[{"insert":"delta\n"}]}"#).unwrap()));
+    assert_eq!(results[2].ast, Some(parse_html("<p><em>html</em></p>").unwrap()));
+    assert!(matches!(results[3].ast.as_ref().unwrap().children[0], ASTNode::Heading(_)));
+    assert!(results.iter().all(|result| result.error.is_none()));
+}
+
+#[test]
+fn item_errors_do_not_affect_other_items() {
+    let items = vec![
+        item("ok", InputFormat::Markdown, "fine"),
+        item("bad", InputFormat::Delta, "not json"),
+        item("also-ok", InputFormat::Delta, r#"{"ops":[{"insert":"x\n"}]}"#),
+    ];
+    let results = parse_batch(&items);
+
+    assert!(results[0].ast.is_some() && results[0].error.is_none());
+    assert_eq!(results[1].id, "bad");
+    assert!(results[1].ast.is_none());
+    assert!(results[1].error.as_deref().is_some_and(|error| !error.is_empty()));
+    assert!(results[2].ast.is_some() && results[2].error.is_none());
+}
+
+#[test]
+fn empty_batch_returns_no_results() {
+    assert!(parse_batch(&[]).is_empty());
+}
+
+#[test]
+fn batch_json_passes_ids_through_and_omits_missing_fields() {
+    let (success, json) = batch_to_json(
+        r#"[
+            {"id": "a", "format": "markdown", "content": "*x*"},
+            {"id": "b", "format": "delta", "content": "{"},
+            {"id": "c", "format": "html", "content": "<b>y</b>"}
+        ]"#,
+    );
+    assert!(success, "{}", json);
+
+    let results: Vec<Value> = serde_json::from_str(&json).unwrap();
+    let ids: Vec<&str> = results.iter().map(|result| result["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["a", "b", "c"]);
+
+    assert!(results[0].get("ast").is_some() && results[0].get("error").is_none());
+    assert!(results[1].get("ast").is_none() && results[1]["error"].is_string());
+    assert!(results[2].get("ast").is_some() && results[2].get("error").is_none());
+
+    let ast: RootNode = serde_json::from_value(results[0]["ast"].clone()).unwrap();
+    assert_eq!(ast, parse_markdown("*x*").unwrap());
+}
+
+#[test]
+fn batch_json_rejects_malformed_input() {
+    let (success, message) = batch_to_json(r#"{"id": "a"}"#);
+    assert!(!success);
+    assert!(message.contains("batch JSON"), "{}", message);
+
+    let (success, _) = batch_to_json(r#"[{"id": "a", "format": "rtf", "content": "x"}]"#);
+    assert!(!success);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_batch_matches_sequential_parsing() {
+    let items: Vec<BatchItem> = (0..500)
+        .map(|index| match index % 3 {
+            0 => item(&index.to_string(), InputFormat::Markdown, &format!("**{}** _x_", index)),
+            1 => item(&index.to_string(), InputFormat::Delta, &format!(r#"{{"ops":[{{"insert":"{}\n"}}]}}"#, index)),
+            _ => item(&index.to_string(), InputFormat::Html, &format!("<p>{}</p>", index)),
+        })
+        .collect();
+
+    let results = parse_batch(&items);
+    assert_eq!(results.len(), items.len());
+    for (item, result) in items.iter().zip(&results) {
+        assert_eq!(result.id, item.id);
+        let expected = match item.format {
+            InputFormat::Markdown => parse_markdown(&item.content),
+            InputFormat::Delta => parse_delta(&item.content),
+            InputFormat::Html => parse_html(&item.content),
+        };
+        assert_eq!(result.ast, Some(expected.unwrap()));
+    }
+}
@@ -0,0 +1,161 @@
+//! 解析缓存测试
+
+use im_parse_core::*;
+use std::cell::Cell;
+use std::ffi::CStr;
+use std::sync::Arc;
+
+fn markdown_key(input: &str) -> CacheKey {
+    CacheKey::ast(InputFormat::Markdown, input, &MarkdownParser::new())
+}
+
+fn parse(input: &str) -> Result<RootNode, ParseError> {
+    MarkdownParser::new().parse(input)
+}
+
+#[test]
+fn hits_share_the_cached_ast() {
+    let cache = ParseCache::default();
+    let first = cache.get_or_parse(markdown_key("**a**"), || parse("**a**")).unwrap();
+    let second = cache
+        .get_or_parse(markdown_key("**a**"), || panic!("cached input must not be parsed again"))
+        .unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+    assert_eq!(stats.hit_rate, 0.5);
+}
+
+#[test]
+fn keys_distinguish_input_format_output_and_options() {
+    let cache = ParseCache::default();
+    let config = StyleConfig::default();
+    let ast = cache.get_or_parse(markdown_key("a"), || parse("a")).unwrap();
+    let other = cache.get_or_parse(markdown_key("b"), || parse("b")).unwrap();
+    assert_ne!(ast, other);
+
+    let delta_key = CacheKey::ast(InputFormat::Delta, "a", &MarkdownParser::new());
+    let parsed = Cell::new(false);
+    cache
+        .get_or_parse(delta_key, || {
+            parsed.set(true);
+            parse("a")
+        })
+        .unwrap();
+    assert!(parsed.get());
+
+    let html = cache
+        .get_or_render(CacheKey::html(InputFormat::Markdown, "a", &MarkdownParser::new(), &config), || {
+            Ok("<p>a</p>".to_string())
+        })
+        .unwrap();
+    assert_eq!(&*html, "<p>a</p>");
+
+    let options = MarkdownOptions {
+        smart_punctuation: !MarkdownOptions::default().smart_punctuation,
+        ..Default::default()
+    };
+    let parser = MarkdownParser::with_options(options);
+    assert_ne!(markdown_key("a"), CacheKey::ast(InputFormat::Markdown, "a", &parser));
+    assert_eq!(cache.stats().misses, 4);
+}
+
+#[test]
+fn evicts_least_recently_used_by_entry_count() {
+    let cache = ParseCache::new(CacheConfig { max_entries: 2, max_bytes: usize::MAX });
+    for input in ["a", "b"] {
+        cache.get_or_parse(markdown_key(input), || parse(input)).unwrap();
+    }
+    // 访问 a 使 b 成为最久未使用的条目
+    cache.get_or_parse(markdown_key("a"), || panic!("a should be cached")).unwrap();
+    cache.get_or_parse(markdown_key("c"), || parse("c")).unwrap();
+
+    let stats = cache.stats();
+    assert_eq!((stats.entries, stats.evictions), (2, 1));
+    cache.get_or_parse(markdown_key("a"), || panic!("a should still be cached")).unwrap();
+    let parsed = Cell::new(false);
+    cache
+        .get_or_parse(markdown_key("b"), || {
+            parsed.set(true);
+            parse("b")
+        })
+        .unwrap();
+    assert!(parsed.get());
+}
+
+#[test]
+fn evicts_by_byte_budget_and_skips_oversized_entries() {
+    let small = "x".repeat(10);
+    let large = "y".repeat(4096);
+    let budget = {
+        let probe = ParseCache::default();
+        probe.get_or_parse(markdown_key(&small), || parse(&small)).unwrap();
+        probe.stats().bytes
+    };
+
+    let cache = ParseCache::new(CacheConfig { max_entries: 100, max_bytes: budget });
+    cache.get_or_parse(markdown_key(&large), || parse(&large)).unwrap();
+    assert_eq!(cache.stats().entries, 0);
+    assert_eq!(cache.stats().evictions, 0);
+
+    cache.get_or_parse(markdown_key(&small), || parse(&small)).unwrap();
+    assert_eq!(cache.stats().bytes, budget);
+    cache.get_or_render(markdown_key("html"), || Ok("z".repeat(16))).unwrap();
+    let stats = cache.stats();
+    assert_eq!(stats.entries, 1);
+    assert_eq!(stats.evictions, 1);
+    assert!(stats.bytes <= budget);
+}
+
+#[test]
+fn failures_are_not_cached_and_clear_resets_stats() {
+    let cache = ParseCache::default();
+    let result = cache.get_or_render(markdown_key("bad"), || Err(ParseError::MarkdownError("bad".to_string())));
+    assert!(result.is_err());
+    assert_eq!(cache.stats().entries, 0);
+
+    cache.get_or_parse(markdown_key("a"), || parse("a")).unwrap();
+    cache.clear();
+    assert_eq!(cache.stats(), CacheStats::default());
+}
+
+fn ffi_stats() -> Option<serde_json::Value> {
+    let ptr = ffi::get_parse_cache_stats();
+    if ptr.is_null() {
+        return None;
+    }
+    let json = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string();
+    unsafe { ffi::free_string(ptr) };
+    Some(serde_json::from_str(&json).unwrap())
+}
+
+/// 全局缓存是进程级状态，相关断言放在同一个测试中顺序执行
+#[test]
+fn global_cache_and_ffi() {
+    ffi::enable_parse_cache(100, 1 << 20);
+
+    let first = markdown_to_html("*hi*").unwrap();
+    assert_eq!(markdown_to_html("*hi*").unwrap(), first);
+    // HTML 缓存未命中不会再经过 AST 缓存重复计数
+    let stats = parse_cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+
+    let shared = parse_markdown_shared("*hi*").unwrap();
+    assert!(Arc::ptr_eq(&shared, &parse_markdown_shared("*hi*").unwrap()));
+    assert_eq!(parse_markdown("*hi*").unwrap(), *shared);
+
+    let stats = ffi_stats().unwrap();
+    assert_eq!(stats["hits"], 3);
+    assert_eq!(stats["misses"], 2);
+    assert_eq!(stats["entries"], 2);
+
+    ffi::clear_parse_cache();
+    let stats = ffi_stats().unwrap();
+    assert_eq!((stats["hits"].as_u64(), stats["entries"].as_u64()), (Some(0), Some(0)));
+
+    ffi::disable_parse_cache();
+    assert!(ffi_stats().is_none());
+    assert!(parse_cache_stats().is_none());
+    assert_eq!(markdown_to_html("*hi*").unwrap(), first);
+}
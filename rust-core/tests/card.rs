@@ -0,0 +1,183 @@
+//! 卡片语法测试
+
+use im_parse_core::*;
+use serde_json::json;
+
+fn parse(input: &str) -> RootNode {
+    MarkdownParser::with_options(MarkdownOptions::im_chat()).parse(input).unwrap()
+}
+
+fn only_card(input: &str) -> CardNode {
+    match parse(input).children.as_slice() {
+        [ASTNode::Card(card)] => (**card).clone(),
+        other => panic!("expected a single card for {input:?}, got {other:?}"),
+    }
+}
+
+fn fenced(body: &str) -> String {
+    format!("```card:poll\n{body}\n```")
+}
+
+#[test]
+fn container_attributes_and_body() {
+    let card = only_card(":::card{type=order amount=120 title=\"团队 聚餐\" tags=['a,b',\"c\"] ok=true}\n**正文**\n\n第二段\n:::");
+    assert_eq!(card.subtype, "order");
+    assert_eq!(card.content, "**正文**\n\n第二段");
+    assert_eq!(card.metadata["amount"], json!(120));
+    assert_eq!(card.metadata["title"], json!("团队 聚餐"));
+    assert_eq!(card.metadata["tags"], json!(["a,b", "c"]));
+    assert_eq!(card.metadata["ok"], json!(true));
+}
+
+#[test]
+fn container_shares_the_document() {
+    let ast = parse("[a][r]\n:::card{type=note}\nbody\n:::\nafter [a][r]\n\n[r]: https://example.com");
+    let types: Vec<_> = ast.children.iter().map(ASTNode::type_name).collect();
+    assert_eq!(types, ["paragraph", "card", "paragraph"]);
+    for block in [&ast.children[0], &ast.children[2]] {
+        assert!(serde_json::to_string(block).unwrap().contains(r#""url":"https://example.com""#));
+    }
+}
+
+#[test]
+fn container_lines_must_be_top_level_paragraph_lines() {
+    for input in [
+        "    :::card{type=note}\n    body\n    :::",
+        "- :::card{type=note}\n- body\n- :::",
+        "```\n:::card{type=note}\nbody\n:::\n```",
+    ] {
+        assert!(!serde_json::to_string(&parse(input)).unwrap().contains("\"card\""), "card in {input:?}");
+    }
+}
+
+#[test]
+fn invalid_containers_stay_text() {
+    for input in [":::card{amount=1}\nbody\n:::", ":::card{type=note\nbody\n:::", ":::card{type=note}\nno close"] {
+        let ast = parse(input);
+        let json = serde_json::to_string(&ast).unwrap();
+        assert!(!json.contains("\"card\""), "card in {input:?}");
+        assert!(json.contains(":::card{"), "lost open line of {input:?}");
+    }
+}
+
+#[test]
+fn fenced_yaml_subset_accepted_forms() {
+    let card = only_card(&fenced(concat!(
+        "content: 正文 # 注释\n",
+        "title: 'It''s # not a comment'\n",
+        "quoted: \"a \\\"b\\\", c\"\n",
+        "options: [\"a,b\", 'c, d', [1, 2], it's, 3.5, null]\n",
+        "empty: []\n",
+        "note: |\n  line1\n    line2\n",
+        "items:\n  - one\n  - label: x\n    value: 2\n",
+        "meta:\n  on: true\n",
+    )));
+    assert_eq!(card.content, "正文");
+    assert_eq!(card.metadata["title"], json!("It's # not a comment"));
+    assert_eq!(card.metadata["quoted"], json!("a \"b\", c"));
+    assert_eq!(card.metadata["options"], json!(["a,b", "c, d", [1, 2], "it's", 3.5, null]));
+    assert_eq!(card.metadata["empty"], json!([]));
+    assert_eq!(card.metadata["note"], json!("line1\n  line2"));
+    assert_eq!(card.metadata["items"], json!(["one", {"label": "x", "value": 2}]));
+    assert_eq!(card.metadata["meta"], json!({"on": true}));
+}
+
+#[test]
+fn fenced_yaml_subset_rejected_forms() {
+    for body in [
+        "options: [\"a,b]",
+        "options: ['a, b]",
+        "options: [1, 2",
+        "  indented: 1",
+        "key:value",
+        "scalar: 1\n  continued",
+        "list:\n  - a\n - b",
+        "images: not-a-list",
+    ] {
+        let ast = parse(&fenced(body));
+        assert!(matches!(ast.children.as_slice(), [ASTNode::CodeBlock(_)]), "accepted {body:?}");
+    }
+}
+
+fn approval_parser() -> MarkdownParser {
+    let schemas = CardSchemaRegistry::new().with_schema(
+        "approval",
+        CardSchema::new()
+            .with_required_field("amount", CardFieldType::Number)
+            .with_enum_field("status", &["pending", "done"], false),
+    );
+    MarkdownParser::with_options(MarkdownOptions::im_chat()).with_card_schemas(schemas)
+}
+
+fn issue(line: usize, subtype: &str, path: &str) -> (usize, String, String) {
+    (line, subtype.to_string(), path.to_string())
+}
+
+fn located(issues: &[CardIssue]) -> Vec<(usize, String, String)> {
+    issues.iter().map(|issue| (issue.line, issue.subtype.clone(), issue.path.clone())).collect()
+}
+
+#[test]
+fn schema_violations_fall_back_and_are_reported() {
+    let parser = approval_parser();
+    let input = "intro\n\n```card:approval\namount: abc\nstatus: lost\nextra: 1\n```\n\n```card:approval\namount: 1\n```";
+
+    let ast = parser.parse(input).unwrap();
+    assert!(matches!(ast.children.as_slice(), [ASTNode::Paragraph(_), ASTNode::CodeBlock(_), ASTNode::Card(_)]));
+
+    let issues = parser.validate_cards(input);
+    assert_eq!(
+        located(&issues),
+        vec![
+            issue(3, "approval", "/metadata/amount"),
+            issue(3, "approval", "/metadata/status"),
+            issue(3, "approval", "/metadata/extra"),
+        ]
+    );
+    assert_eq!(issues[0].message, "field `amount` must be number, found string");
+    assert!(issues[1].message.contains("pending, done"), "{}", issues[1].message);
+}
+
+#[test]
+fn malformed_fenced_cards_are_reported() {
+    let parser = approval_parser();
+    let input = "```card:poll\noptions: [1, 2\n```\n\n- item\n\n  ```card:poll\n  images: not-a-list\n  ```\n\n```card:poll\n{\"actions\": [{\"id\": \"a\"}, 3]}\n```";
+
+    let issues = parser.validate_cards(input);
+    assert_eq!(
+        located(&issues),
+        vec![issue(1, "poll", ""), issue(7, "poll", "/images"), issue(11, "poll", "/actions/1")]
+    );
+    assert_eq!(issues[1].message, "`images` must be a list, found string");
+}
+
+#[test]
+fn structural_card_problems_are_reported() {
+    let input = "```card:poll\nactions:\n  - id: a\n    label: A\n  - id: a\n    label: B\n```";
+    let issues = approval_parser().validate_cards(input);
+    assert_eq!(issues.len(), 1, "{issues:?}");
+    assert_eq!((issues[0].line, issues[0].path.as_str()), (1, "/actions/1/id"));
+}
+
+#[test]
+fn invalid_containers_are_reported() {
+    let parser = approval_parser();
+    let input = ":::card{amount=1}\nbody\n:::\n\n:::card{type=approval amount=\"x\"}\nbody\n:::\n\n:::card{type=approval amount=2}\n```card:approval\nnot checked\n```\n:::";
+
+    let issues = parser.validate_cards(input);
+    assert_eq!(located(&issues), vec![issue(1, "", ""), issue(5, "approval", "/metadata/amount")]);
+    assert!(issues[0].message.contains("{amount=1}"), "{}", issues[0].message);
+}
+
+#[test]
+fn valid_or_disabled_cards_have_no_issues() {
+    let parser = approval_parser();
+    assert!(parser.validate_cards("```card:approval\namount: 1\nstatus: done\n```\n\n```card:note\ncontent: hi\n```").is_empty());
+    assert!(parser.validate_cards("no cards here").is_empty());
+
+    let plain = MarkdownParser::new();
+    assert!(plain.validate_cards("```card:approval\namount: abc\n```").is_empty());
+
+    let configured = ConfiguredParser::from_json(r#"{"markdown": "im_chat", "card_schemas": {"approval": {"fields": {"amount": {"type": "number", "required": true}}}}}"#).unwrap();
+    assert_eq!(located(&configured.validate_cards("```card:approval\n```")), vec![issue(1, "approval", "/metadata/amount")]);
+}
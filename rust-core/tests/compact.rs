@@ -0,0 +1,244 @@
+//! 紧凑二进制编码测试
+
+use im_parse_core::*;
+use std::collections::BTreeSet;
+
+/// 覆盖全部节点类型及其可选字段的 AST
+fn every_node_type() -> RootNode {
+    serde_json::from_str(
+        r##"{"schemaVersion":2,"children":[
+            {"type":"heading","level":2,"children":[{"type":"text","content":"标题"}]},
+            {"type":"paragraph","children":[
+                {"type":"strong","children":[{"type":"text","content":"b"}]},
+                {"type":"em","children":[{"type":"text","content":"i"}]},
+                {"type":"underline","children":[{"type":"text","content":"u"}]},
+                {"type":"strike","children":[{"type":"text","content":"s"}]},
+                {"type":"code","content":"x < y"},
+                {"type":"link","url":"https://example.com","children":[{"type":"text","content":"link"}]},
+                {"type":"image","url":"a.png","width":120.5,"height":null,"alt":"alt"},
+                {"type":"image","url":"b.png","width":null,"height":-3.25,"alt":null},
+                {"type":"mention","id":"u1","name":"Alice","kind":"user","status":"active"},
+                {"type":"mention","id":"c1","name":"general","kind":"channel","status":"deactivated"},
+                {"type":"mention","id":"","name":"all","kind":"all","status":"unresolved"},
+                {"type":"mention","id":"","name":"here","kind":"here","status":"unknown"},
+                {"type":"highlight","children":[{"type":"text","content":"h"}]},
+                {"type":"spoiler","children":[{"type":"text","content":"secret"}]},
+                {"type":"footnoteReference","label":"n","index":1},
+                {"type":"timestamp","timestamp":-8589934593,"style":"R"},
+                {"type":"timestamp","timestamp":1700000000,"style":null},
+                {"type":"customEmoji","id":"42","name":"party","animated":true},
+                {"type":"customEmoji","id":"43","name":"wave","animated":false},
+                {"type":"math","content":"x^2","display":false},
+                {"type":"mention","id":"r1","name":"mods","kind":"role","status":"active"}
+            ]},
+            {"type":"codeBlock","language":"rust","content":"fn main() {}\n"},
+            {"type":"codeBlock","language":null,"content":""},
+            {"type":"list","listType":"ordered","items":[
+                {"children":[{"type":"text","content":"one"}],"checked":null},
+                {"children":[{"type":"text","content":"two"}],"checked":true}
+            ]},
+            {"type":"list","listType":"bullet","items":[
+                {"children":[],"checked":false}
+            ]},
+            {"type":"listItem","children":[{"type":"text","content":"loose"}],"checked":null},
+            {"type":"table","rows":[
+                {"cells":[
+                    {"children":[{"type":"text","content":"a"}],"align":"left"},
+                    {"children":[],"align":"center"},
+                    {"children":[],"align":"right"},
+                    {"children":[],"align":null}
+                ]}
+            ]},
+            {"type":"tableRow","cells":[{"children":[],"align":null}]},
+            {"type":"tableCell","children":[],"align":"center"},
+            {"type":"math","content":"\\sum","display":true},
+            {"type":"mermaid","content":"graph TD; A-->B"},
+            {"type":"card","subtype":"plain","content":"body","metadata":{}},
+            {"type":"card","subtype":"order","content":"订单","metadata":{"id":7,"tags":["a","b"],"title":"t"},
+                "header":{"title":"Order","subtitle":"#7","icon":"box"},
+                "fields":[{"label":"Total","value":"$5","short":true}],
+                "images":[{"url":"i.png","alt":null}],
+                "actions":[
+                    {"id":"ok","kind":"button","label":"OK","style":"primary","value":"1"},
+                    {"id":"pick","kind":"select","label":"Pick","options":[{"label":"A","value":"a"}]},
+                    {"id":"note","kind":"input","placeholder":"...","style":"danger","url":"https://x.y"}
+                ]},
+            {"type":"horizontalRule"},
+            {"type":"blockquote","children":[{"type":"paragraph","children":[{"type":"text","content":"q"}]}]},
+            {"type":"footnoteDefinition","label":"n","index":1,"children":[{"type":"paragraph","children":[]}]},
+            {"type":"custom","kind":"poll","attrs":{"question":"?","options":[1,2]},"children":[{"type":"text","content":"c"}]},
+            {"type":"custom","kind":"empty","attrs":{},"children":[]},
+            {"type":"root","schemaVersion":1,"children":[{"type":"text","content":"nested"}]},
+            {"type":"futureWidget","payload":{"n":1,"list":[true,null]}}
+        ]}"##,
+    )
+    .unwrap()
+}
+
+fn collect_types(value: &serde_json::Value, out: &mut BTreeSet<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::String(name)) = map.get("type") {
+                out.insert(name.clone());
+            }
+            map.values().for_each(|v| collect_types(v, out));
+        }
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_types(v, out)),
+        _ => {}
+    }
+}
+
+/// 遍历借用视图的每个节点与字段，确保解码成功的缓冲区可以安全访问
+fn walk(node: CompactNode<'_>) -> usize {
+    let _ = node.type_name();
+    let children = match node.view() {
+        CompactNodeView::Card { metadata, .. } => {
+            metadata.for_each(|(key, value)| {
+                let _ = (key.len(), value.len());
+            });
+            return 1;
+        }
+        CompactNodeView::Text { content } | CompactNodeView::Code { content } => {
+            let _ = content.len();
+            return 1;
+        }
+        _ => node.children(),
+    };
+    1 + children.map(walk).sum::<usize>()
+}
+
+fn sample_buffer() -> Vec<u8> {
+    encode_compact(&every_node_type())
+}
+
+#[test]
+fn fixture_covers_every_known_node_type() {
+    let mut seen = BTreeSet::new();
+    collect_types(&serde_json::to_value(every_node_type()).unwrap(), &mut seen);
+    for name in KNOWN_NODE_TYPES {
+        // list / table 的子项不带 type 字段，由独立的 listItem / tableRow / tableCell 节点覆盖
+        assert!(seen.contains(*name), "fixture is missing {name}");
+    }
+    assert!(seen.contains("futureWidget"));
+}
+
+#[test]
+fn every_node_type_round_trips() {
+    let ast = every_node_type();
+    let bytes = encode_compact(&ast);
+    assert_eq!(decode_compact(&bytes).unwrap(), ast);
+
+    let view = CompactAst::from_bytes(&bytes).unwrap();
+    assert_eq!(view.schema_version(), 2);
+    assert_eq!(view.to_root_node().unwrap(), ast);
+    assert_eq!(walk(view.root()), view.node_count());
+}
+
+#[test]
+fn parsed_messages_round_trip() {
+    for input in [
+        "",
+        "# Title\n\nHello **world** and *you*, `code` ~~gone~~",
+        "- [x] done\n- [ ] todo\n\n1. one\n2. two",
+        "| a | b |\n|:--|--:|\n| 1 | 2 |",
+        "> quote\n\n```rust\nfn main() {}\n```\n\n---\n\n![img](a.png) [l](https://x.y)",
+        "text[^1]\n\n[^1]: note",
+    ] {
+        let ast = parse_markdown(input).unwrap();
+        assert_eq!(decode_compact(&encode_compact(&ast)).unwrap(), ast, "input: {input:?}");
+    }
+}
+
+#[test]
+fn borrowed_view_exposes_fields() {
+    let bytes = sample_buffer();
+    let view = CompactAst::from_bytes(&bytes).unwrap();
+    let paragraph = view.root().children().nth(1).unwrap();
+    let inline: Vec<_> = paragraph.children().map(|node| node.view()).collect();
+
+    assert!(matches!(inline[4], CompactNodeView::Code { content: "x < y" }));
+    assert!(matches!(
+        inline[6],
+        CompactNodeView::Image { url: "a.png", width: Some(w), height: None, alt: Some("alt") } if w == 120.5
+    ));
+    assert!(matches!(
+        inline[9],
+        CompactNodeView::Mention { name: "general", kind: MentionKind::Channel, status: MentionStatus::Deactivated, .. }
+    ));
+    assert!(matches!(inline[15], CompactNodeView::Timestamp { timestamp: -8589934593, style: Some("R") }));
+
+    let card = view.root().children().find(|node| {
+        matches!(node.view(), CompactNodeView::Card { subtype: "order", .. })
+    });
+    let CompactNodeView::Card { metadata, parts_json, .. } = card.unwrap().view() else {
+        unreachable!()
+    };
+    let keys: Vec<_> = metadata.map(|(key, _)| key).collect();
+    assert_eq!(keys, ["id", "tags", "title"]);
+    assert!(parts_json.contains("\"header\""));
+}
+
+#[test]
+fn rejects_bad_header() {
+    let bytes = sample_buffer();
+    assert_eq!(decode_compact(b"").unwrap_err(), CompactError::Truncated);
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] = b'X';
+    assert_eq!(decode_compact(&bad_magic).unwrap_err(), CompactError::BadMagic);
+
+    let mut bad_version = bytes.clone();
+    bad_version[4..6].copy_from_slice(&99u16.to_le_bytes());
+    assert_eq!(decode_compact(&bad_version).unwrap_err(), CompactError::UnsupportedVersion(99));
+}
+
+#[test]
+fn rejects_every_truncation() {
+    let bytes = sample_buffer();
+    for len in 0..bytes.len() {
+        assert!(decode_compact(&bytes[..len]).is_err(), "prefix of {len} bytes decoded");
+        assert!(CompactAst::from_bytes(&bytes[..len]).is_err(), "prefix of {len} bytes accepted");
+    }
+}
+
+#[test]
+fn rejects_invalid_utf8_strings() {
+    let ast: RootNode =
+        serde_json::from_str(r#"{"children":[{"type":"text","content":"marker-string"}]}"#).unwrap();
+    let mut bytes = encode_compact(&ast);
+    let at = bytes.windows(13).position(|w| w == b"marker-string").unwrap();
+    bytes[at] = 0xFF;
+    assert!(matches!(decode_compact(&bytes), Err(CompactError::InvalidString(_))));
+}
+
+#[test]
+fn rejects_excessive_nesting() {
+    let mut node = ASTNode::Text(TextNode { content: "deep".to_string() });
+    for _ in 0..200 {
+        node = ASTNode::Strong(StrongNode { children: vec![node] });
+    }
+    let mut ast = RootNode::new();
+    ast.children.push(node);
+    let bytes = encode_compact(&ast);
+    assert!(matches!(
+        decode_compact(&bytes),
+        Err(CompactError::InvalidNode { reason: "nesting too deep", .. })
+    ));
+}
+
+#[test]
+fn corrupted_bytes_never_panic() {
+    let bytes = sample_buffer();
+    for position in 0..bytes.len() {
+        let original = bytes[position];
+        for value in [0x00, 0xFF, original ^ 0x01, original ^ 0x80, original.wrapping_add(1)] {
+            let mut corrupted = bytes.clone();
+            corrupted[position] = value;
+            let _ = decode_compact(&corrupted);
+            if let Ok(view) = CompactAst::from_bytes(&corrupted) {
+                walk(view.root());
+                let _ = view.to_root_node();
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Markdown 与 Delta 跨格式一致性测试
+//!
+//! `tests/fixtures/conformance` 下每对同名的 `.md` / `.json` 文件描述同一段内容，
+//! 两者解析得到的 AST 必须语义等价。
+
+use im_parse_core::*;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn markdown_and_delta_fixtures_are_equivalent() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/conformance");
+    let mut markdown_files: Vec<_> = fs::read_dir(&dir)
+        .expect("conformance fixture directory")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    markdown_files.sort();
+    assert!(!markdown_files.is_empty(), "no conformance fixtures found");
+
+    let mut failures = Vec::new();
+    for md_path in &markdown_files {
+        let delta_path = md_path.with_extension("json");
+        let markdown = fs::read_to_string(md_path).unwrap();
+        let delta = fs::read_to_string(&delta_path)
+            .unwrap_or_else(|_| panic!("missing Delta fixture for {}", md_path.display()));
+
+        let md_ast = parse_markdown(&markdown).unwrap();
+        let delta_ast = parse_delta(&delta).unwrap();
+        if !ast_equivalent(&md_ast, &delta_ast) {
+            failures.push(format!(
+                "{}\n  markdown: {}\n  delta:    {}",
+                md_path.file_stem().unwrap().to_string_lossy(),
+                serialize_ast(&md_ast).unwrap(),
+                serialize_ast(&delta_ast).unwrap(),
+            ));
+        }
+    }
+
+    assert!(failures.is_empty(), "non-equivalent fixtures:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn different_content_is_not_equivalent() {
+    let md_ast = parse_markdown("Hello **world**").unwrap();
+    let delta_ast = parse_delta(r#"{"ops":[{"insert":"Hello world\n"}]}"#).unwrap();
+    assert!(!ast_equivalent(&md_ast, &delta_ast));
+}
@@ -0,0 +1,31 @@
+//! Delta 行属性（列表）解析测试
+
+use im_parse_core::*;
+
+fn list_items(root: &RootNode) -> Vec<&ListItemNode> {
+    match root.children.as_slice() {
+        [ASTNode::List(list)] => list.items.iter().collect(),
+        other => panic!("expected a single list, got {:?}", other),
+    }
+}
+
+#[test]
+fn empty_list_line_produces_empty_item() {
+    let root = parse_delta(r#"{"ops":[{"insert":"\n","attributes":{"list":"bullet"}}]}"#).unwrap();
+    let items = list_items(&root);
+    assert_eq!(items.len(), 1);
+    assert!(items[0].children.is_empty());
+}
+
+#[test]
+fn empty_line_keeps_its_position_in_list() {
+    let delta = r#"{"ops":[
+        {"insert":"one"},{"insert":"\n","attributes":{"list":"ordered"}},
+        {"insert":"\n","attributes":{"list":"ordered"}},
+        {"insert":"three"},{"insert":"\n","attributes":{"list":"ordered"}}
+    ]}"#;
+    let root = parse_delta(delta).unwrap();
+    let items = list_items(&root);
+    assert_eq!(items.len(), 3);
+    assert!(items[1].children.is_empty());
+}
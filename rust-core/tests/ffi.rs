@@ -0,0 +1,58 @@
+//! FFI 解析器句柄测试
+
+use im_parse_core::ffi::*;
+use im_parse_core::ConfiguredParser;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+/// 调用 imparse_parser_new，返回句柄和错误信息
+fn parser_new(options: *const c_char) -> (*mut ConfiguredParser, Option<String>) {
+    let mut error: *mut c_char = ptr::null_mut();
+    let handle = unsafe { imparse_parser_new(options, &mut error) };
+    let message = (!error.is_null()).then(|| {
+        let message = unsafe { CStr::from_ptr(error) }.to_str().unwrap().to_string();
+        unsafe { free_string(error) };
+        message
+    });
+    (handle, message)
+}
+
+#[test]
+fn parser_new_accepts_default_and_json_config() {
+    let (handle, error) = parser_new(ptr::null());
+    assert!(!handle.is_null());
+    assert_eq!(error, None);
+
+    let format = CString::new("markdown").unwrap();
+    let input = CString::new("**a**").unwrap();
+    let result = unsafe { imparse_parse(handle, format.as_ptr(), input.as_ptr()) };
+    assert!(unsafe { (*result).success });
+    unsafe {
+        free_parse_result(result);
+        imparse_parser_free(handle);
+    }
+
+    let options = CString::new(r#"{"markdown":{"tables":false}}"#).unwrap();
+    let (handle, error) = parser_new(options.as_ptr());
+    assert!(!handle.is_null());
+    assert_eq!(error, None);
+    unsafe { imparse_parser_free(handle) };
+}
+
+#[test]
+fn parser_new_reports_errors() {
+    let options = CString::new(r#"{"markdown":{"tables":"yes"}}"#).unwrap();
+    let (handle, error) = parser_new(options.as_ptr());
+    assert!(handle.is_null());
+    assert!(error.unwrap().starts_with("Invalid parser config"));
+
+    let invalid_utf8 = [0xFFu8, 0xFE, 0];
+    let (handle, error) = parser_new(invalid_utf8.as_ptr() as *const c_char);
+    assert!(handle.is_null());
+    assert_eq!(error.as_deref(), Some("Invalid UTF-8 string"));
+
+    // 不关心错误信息时可以传 null
+    let handle = unsafe { imparse_parser_new(options.as_ptr(), ptr::null_mut()) };
+    assert!(handle.is_null());
+}
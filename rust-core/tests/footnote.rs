@@ -0,0 +1,126 @@
+//! 脚注编号与渲染测试
+
+use im_parse_core::*;
+
+fn parse(input: &str) -> RootNode {
+    MarkdownParser::new().parse(input).unwrap()
+}
+
+fn text(content: &str) -> ASTNode {
+    ASTNode::Text(TextNode { content: content.to_string() })
+}
+
+fn paragraph(children: Vec<ASTNode>) -> ASTNode {
+    ASTNode::Paragraph(ParagraphNode { children })
+}
+
+fn reference(label: &str, index: u32) -> ASTNode {
+    ASTNode::FootnoteReference(FootnoteReferenceNode { label: label.to_string(), index })
+}
+
+/// 根节点末尾的脚注定义：(label, index)
+fn definitions(ast: &RootNode) -> Vec<(&str, u32)> {
+    ast.children
+        .iter()
+        .filter_map(|child| match child {
+            ASTNode::FootnoteDefinition(definition) => Some((definition.label.as_str(), definition.index)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 渲染结果中 `<div class="content">` 之后的部分
+fn render_body(ast: &RootNode) -> String {
+    let html = HtmlRenderer::new().render(ast);
+    let start = html.find("<div class=\"content\">").unwrap();
+    html[start..].to_string()
+}
+
+#[test]
+fn references_are_numbered_by_first_use() {
+    let ast = parse("one[^b] two[^a]\n\n[^a]: A\n\n[^b]: B\n\n[^unused]: U");
+
+    assert_eq!(ast.children[0], paragraph(vec![text("one"), reference("b", 1), text(" two"), reference("a", 2)]));
+    assert_eq!(definitions(&ast), vec![("b", 1), ("a", 2), ("unused", 3)]);
+    assert!(matches!(ast.children.last(), Some(ASTNode::FootnoteDefinition(_))));
+}
+
+#[test]
+fn repeated_references_share_an_index() {
+    let ast = parse("a[^note] b[^Note] c[^note]\n\n[^note]: N");
+
+    assert_eq!(
+        ast.children[0],
+        paragraph(vec![
+            text("a"),
+            reference("note", 1),
+            text(" b"),
+            reference("Note", 1),
+            text(" c"),
+            reference("note", 1),
+        ])
+    );
+    assert_eq!(definitions(&ast), vec![("note", 1)]);
+}
+
+#[test]
+fn undefined_references_stay_literal() {
+    let ast = parse("see[^missing]");
+    assert_eq!(ast.children, vec![paragraph(vec![text("see"), text("[^missing]")])]);
+}
+
+#[test]
+fn references_inside_definitions_are_numbered_after_the_body() {
+    let ast = parse("x[^a]\n\n[^a]: see[^b]\n\n[^b]: B");
+
+    assert_eq!(definitions(&ast), vec![("a", 1), ("b", 2)]);
+    match &ast.children[1] {
+        ASTNode::FootnoteDefinition(definition) => {
+            assert_eq!(definition.children, vec![paragraph(vec![text("see"), reference("b", 2)])]);
+        }
+        other => panic!("expected footnote definition, got {:?}", other),
+    }
+}
+
+#[test]
+fn definitions_are_hoisted_out_of_blockquotes() {
+    let ast = parse("> quote[^q]\n>\n> [^q]: inside\n\nafter");
+
+    assert_eq!(ast.children.len(), 3);
+    match &ast.children[0] {
+        ASTNode::Blockquote(blockquote) => {
+            assert_eq!(blockquote.children, vec![paragraph(vec![text("quote"), reference("q", 1)])]);
+        }
+        other => panic!("expected blockquote, got {:?}", other),
+    }
+    assert_eq!(ast.children[1], paragraph(vec![text("after")]));
+    match &ast.children[2] {
+        ASTNode::FootnoteDefinition(definition) => {
+            assert_eq!((definition.label.as_str(), definition.index), ("q", 1));
+            assert_eq!(definition.children, vec![paragraph(vec![text("inside")])]);
+        }
+        other => panic!("expected footnote definition, got {:?}", other),
+    }
+}
+
+#[test]
+fn html_gives_each_reference_a_unique_id() {
+    let body = render_body(&parse("a[^1] b[^2] c[^1]\n\n[^1]: one\n\n[^2]: two"));
+
+    assert!(body.contains("<sup class=\"footnote-ref\"><a href=\"#fn-1\" id=\"fnref-1\">1</a></sup>"), "{}", body);
+    assert!(body.contains("<sup class=\"footnote-ref\"><a href=\"#fn-2\" id=\"fnref-2\">2</a></sup>"), "{}", body);
+    assert!(body.contains("<sup class=\"footnote-ref\"><a href=\"#fn-1\" id=\"fnref-1-2\">1</a></sup>"), "{}", body);
+    assert_eq!(body.matches("id=\"fnref-1\"").count(), 1);
+
+    assert!(body.contains("<section class=\"footnotes\">\n<ol>\n<li id=\"fn-1\" value=\"1\"><p>one</p>"), "{}", body);
+    assert!(body.contains("<a href=\"#fnref-1\" class=\"footnote-backref\">↩</a></li>"), "{}", body);
+    assert!(body.contains("<li id=\"fn-2\" value=\"2\"><p>two</p>"), "{}", body);
+    assert!(body.find("id=\"fn-1\"").unwrap() < body.find("id=\"fn-2\"").unwrap());
+}
+
+#[test]
+fn reference_ids_restart_for_each_render() {
+    let ast = parse("a[^1] b[^1]\n\n[^1]: one");
+    let renderer = HtmlRenderer::new();
+    assert_eq!(renderer.render(&ast), renderer.render(&ast));
+}
@@ -0,0 +1,196 @@
+//! HTML 解析测试
+
+use im_parse_core::*;
+
+fn parse(input: &str) -> Vec<ASTNode> {
+    HtmlParser::new().parse(input).unwrap().children
+}
+
+fn text(content: &str) -> ASTNode {
+    ASTNode::Text(TextNode { content: content.to_string() })
+}
+
+fn paragraph(children: Vec<ASTNode>) -> ASTNode {
+    ASTNode::Paragraph(ParagraphNode { children })
+}
+
+fn em(children: Vec<ASTNode>) -> ASTNode {
+    ASTNode::Em(EmNode { children })
+}
+
+fn strong(children: Vec<ASTNode>) -> ASTNode {
+    ASTNode::Strong(StrongNode { children })
+}
+
+fn item(children: Vec<ASTNode>) -> ListItemNode {
+    ListItemNode { children, checked: None }
+}
+
+/// 段落中的全部文本
+fn plain_text(nodes: &[ASTNode]) -> String {
+    nodes
+        .iter()
+        .map(|node| match node {
+            ASTNode::Text(text) => text.content.clone(),
+            ASTNode::Paragraph(ParagraphNode { children }) => plain_text(children),
+            _ => String::new(),
+        })
+        .collect()
+}
+
+#[test]
+fn tags_map_to_nodes() {
+    let nodes = parse(
+        "<h2>T</h2><p><b>b</b><strong>s</strong><i>i</i><em>e</em><u>u</u><s>s</s><del>d</del>\
+         <code>c  d</code><span>sp</span></p><hr><blockquote><p>q</p></blockquote>",
+    );
+    assert_eq!(
+        nodes,
+        vec![
+            ASTNode::Heading(HeadingNode { level: 2, children: vec![text("T")] }),
+            paragraph(vec![
+                strong(vec![text("b")]),
+                strong(vec![text("s")]),
+                em(vec![text("i")]),
+                em(vec![text("e")]),
+                ASTNode::Underline(UnderlineNode { children: vec![text("u")] }),
+                ASTNode::Strike(StrikeNode { children: vec![text("s")] }),
+                ASTNode::Strike(StrikeNode { children: vec![text("d")] }),
+                ASTNode::Code(CodeNode { content: "c d".to_string() }),
+                text("sp"),
+            ]),
+            ASTNode::HorizontalRule(HorizontalRuleNode),
+            ASTNode::Blockquote(BlockquoteNode { children: vec![paragraph(vec![text("q")])] }),
+        ]
+    );
+}
+
+#[test]
+fn scripts_and_styles_are_dropped_with_their_content() {
+    assert_eq!(
+        parse("<p>a<script>alert('</p>')</script><style>p { color: red }</style>b</p><script>x</script>"),
+        vec![paragraph(vec![text("ab")])]
+    );
+    assert_eq!(parse("<noscript>n</noscript><template><p>t</p></template>"), vec![]);
+}
+
+#[test]
+fn unsafe_urls_are_removed() {
+    let nodes = parse(
+        "<p><a href=\"javascript:alert(1)\">x</a> <a href=\" JavaScript:alert(1)\">z</a> <a href=\"https://a.b\">y</a>\
+         <img src=\"javascript:1\"><img src=\"https://a.b/i.png\" width=\"10px\" alt=\"A\"></p>",
+    );
+    let json = serde_json::to_string(&nodes).unwrap();
+    assert!(!json.to_lowercase().contains("javascript"), "{json}");
+    assert_eq!(plain_text(&nodes), "x z ");
+
+    let ASTNode::Paragraph(paragraph) = &nodes[0] else { panic!("expected paragraph, got {nodes:?}") };
+    assert!(paragraph.children.iter().any(|node| matches!(node, ASTNode::Link(link) if link.url == "https://a.b")));
+    assert!(paragraph.children.iter().any(|node| matches!(
+        node,
+        ASTNode::Image(image) if image.url == "https://a.b/i.png" && image.width == Some(10.0) && image.alt.as_deref() == Some("A")
+    )));
+}
+
+#[test]
+fn entities_are_decoded() {
+    assert_eq!(
+        parse("<p>&lt;&amp;&gt; &quot;&#39;&#x4E2D;&#25991; &nbsp;&copy; &unknown; &#xZZ;</p>"),
+        vec![paragraph(vec![text("<&> \"'中文 \u{a0}© &unknown; &#xZZ;")])]
+    );
+    assert_eq!(
+        parse("<a href=\"https://a.b/?x=1&amp;y=2\">l</a>"),
+        vec![paragraph(vec![ASTNode::Link(LinkNode { url: "https://a.b/?x=1&y=2".to_string(), children: vec![text("l")] })])]
+    );
+}
+
+#[test]
+fn tables_keep_rows_and_alignment() {
+    let nodes = parse(
+        "<table><thead><tr><th>A</th><th style=\"text-align: right\">B</th></tr></thead>\
+         <tbody><tr><td align=center>1</td><td><p>2</p></td></tr></tbody></table>",
+    );
+    let cell = |content: &str, align| TableCell { children: vec![text(content)], align };
+    assert_eq!(
+        nodes,
+        vec![ASTNode::Table(TableNode {
+            rows: vec![
+                TableRow { cells: vec![cell("A", None), cell("B", Some(TextAlign::Right))] },
+                TableRow { cells: vec![cell("1", Some(TextAlign::Center)), cell("2", None)] },
+            ],
+        })]
+    );
+}
+
+#[test]
+fn nested_lists_and_task_items() {
+    let nodes = parse("<ul><li>a<ul><li>b</li></ul></li><li><input type=checkbox checked> c</li></ul><ol><li>x</li><ol><li>y</li></ol></ol>");
+    assert_eq!(
+        nodes,
+        vec![
+            ASTNode::List(ListNode {
+                list_type: ListType::Bullet,
+                items: vec![
+                    item(vec![
+                        paragraph(vec![text("a")]),
+                        ASTNode::List(ListNode { list_type: ListType::Bullet, items: vec![item(vec![text("b")])] }),
+                    ]),
+                    ListItemNode { children: vec![text("c")], checked: Some(true) },
+                ],
+            }),
+            // 直接嵌套在列表中的子列表归入上一项
+            ASTNode::List(ListNode {
+                list_type: ListType::Ordered,
+                items: vec![item(vec![
+                    paragraph(vec![text("x")]),
+                    ASTNode::List(ListNode { list_type: ListType::Ordered, items: vec![item(vec![text("y")])] }),
+                ])],
+            }),
+        ]
+    );
+}
+
+#[test]
+fn unclosed_tags_are_closed_at_their_container() {
+    assert_eq!(
+        parse("<p><b>bold <i>both</p><p>after"),
+        vec![paragraph(vec![strong(vec![text("bold "), em(vec![text("both")])])]), paragraph(vec![text("after")])]
+    );
+    assert_eq!(
+        parse("<ul><li>one<li>two</ul>"),
+        vec![ASTNode::List(ListNode { list_type: ListType::Bullet, items: vec![item(vec![text("one")]), item(vec![text("two")])] })]
+    );
+    assert_eq!(parse("<div><b>x</div>y"), vec![paragraph(vec![strong(vec![text("x")])]), paragraph(vec![text("y")])]);
+    assert_eq!(parse("a</b></p>b"), vec![paragraph(vec![text("ab")])]);
+}
+
+#[test]
+fn code_blocks_end_with_a_newline() {
+    assert_eq!(
+        parse("<pre><code class=\"language-rust\">fn main() {}</code></pre>"),
+        vec![ASTNode::CodeBlock(CodeBlockNode { language: Some("rust".to_string()), content: "fn main() {}\n".to_string() })]
+    );
+    // 紧跟在 `<pre>` 后的换行被忽略，已有的结尾换行不重复添加
+    assert_eq!(
+        parse("<pre>\na\n  b<br>c\n</pre>"),
+        vec![ASTNode::CodeBlock(CodeBlockNode { language: None, content: "a\n  b\nc\n".to_string() })]
+    );
+    let slack = SlackParser::new().parse_mrkdwn("```fn main() {}```").unwrap();
+    assert_eq!(parse("<pre><code>fn main() {}</code></pre>"), slack.children);
+    assert_eq!(parse("<pre></pre>"), vec![ASTNode::CodeBlock(CodeBlockNode { language: None, content: String::new() })]);
+}
+
+#[test]
+fn whitespace_moves_outside_styles() {
+    assert_eq!(parse("<p>a<i> x </i>b</p>"), vec![paragraph(vec![text("a "), em(vec![text("x")]), text(" b")])]);
+    assert_eq!(parse("<p>a <i> x </i> b</p>"), vec![paragraph(vec![text("a "), em(vec![text("x")]), text(" b")])]);
+    assert_eq!(
+        parse("<p>a<b><i> x </i></b>b</p>"),
+        vec![paragraph(vec![text("a "), strong(vec![em(vec![text("x")])]), text(" b")])]
+    );
+    assert_eq!(parse("<p><i> x </i></p>"), vec![paragraph(vec![em(vec![text("x")])])]);
+    assert_eq!(
+        parse("<p><a href=\"https://a.b\"> link </a>end</p>"),
+        vec![paragraph(vec![ASTNode::Link(LinkNode { url: "https://a.b".to_string(), children: vec![text("link")] }), text(" end")])]
+    );
+}
@@ -0,0 +1,243 @@
+//! Markdown 解析测试
+
+use im_parse_core::*;
+use std::collections::HashMap;
+
+fn parse_with(options: MarkdownOptions, input: &str) -> RootNode {
+    MarkdownParser::with_options(options).parse(input).unwrap()
+}
+
+fn text_of(nodes: &[ASTNode]) -> String {
+    nodes
+        .iter()
+        .map(|node| match node {
+            ASTNode::Text(text) => text.content.clone(),
+            ASTNode::Paragraph(ParagraphNode { children }) => text_of(children),
+            _ => String::new(),
+        })
+        .collect()
+}
+
+fn has_node(nodes: &[ASTNode], type_name: &str) -> bool {
+    serde_json::to_string(nodes).unwrap().contains(&format!("\"type\":\"{type_name}\""))
+}
+
+#[test]
+fn default_options_keep_legacy_behavior() {
+    let ast = parse_markdown(r#"run "echo 'hi'" -- now..."#).unwrap();
+    assert_eq!(text_of(&ast.children), r#"run "echo 'hi'" -- now..."#);
+
+    let ast = parse_markdown("==mark== ||hidden|| <@U123> @all\n\n:::card{type=a}\nx\n:::").unwrap();
+    for type_name in ["highlight", "spoiler", "mention", "card"] {
+        assert!(!has_node(&ast.children, type_name), "{type_name} enabled by default");
+    }
+
+    let ast = parse_markdown("~~gone~~\n\n| a |\n|---|\n| 1 |\n\n- [x] done\n\n$$x^2$$").unwrap();
+    for type_name in ["strike", "table", "math"] {
+        assert!(has_node(&ast.children, type_name), "{type_name} disabled by default");
+    }
+    assert!(serde_json::to_string(&ast).unwrap().contains("\"checked\":true"));
+}
+
+#[test]
+fn presets_enable_chat_extensions() {
+    let ast = parse_with(MarkdownOptions::im_chat(), "==mark== ||hidden|| <@U123> \"quoted\"");
+    for type_name in ["highlight", "spoiler", "mention"] {
+        assert!(has_node(&ast.children, type_name), "{type_name} missing in im_chat");
+    }
+    assert!(text_of(&ast.children).contains("\"quoted\""));
+
+    let ast = parse_with(MarkdownOptions::docs(), "\"quoted\"");
+    assert_eq!(text_of(&ast.children), "\u{201c}quoted\u{201d}");
+
+    let ast = parse_with(MarkdownOptions::commonmark_strict(), "~~a~~ ==b==");
+    assert_eq!(text_of(&ast.children), "~~a~~ ==b==");
+}
+
+#[test]
+fn escaped_delimiters_are_not_paired() {
+    let ast = parse_with(MarkdownOptions::im_chat(), r"\=\=not\=\= ==yes== \|\|no\|\| a\\==pair==");
+    let paragraph = &ast.children[0];
+    assert_eq!(
+        serde_json::to_value(paragraph).unwrap(),
+        serde_json::json!({"type": "paragraph", "children": [
+            {"type": "text", "content": "==not== "},
+            {"type": "highlight", "children": [{"type": "text", "content": "yes"}]},
+            {"type": "text", "content": " ||no|| a\\"},
+            {"type": "highlight", "children": [{"type": "text", "content": "pair"}]}
+        ]})
+    );
+
+    let ast = parse_with(MarkdownOptions::im_chat(), "[\\=\\=a\\=\\=](https://x.y) ![\\|\\|b\\|\\|](i.png)");
+    assert!(!has_node(&ast.children, "highlight") && !has_node(&ast.children, "spoiler"));
+    let ASTNode::Paragraph(paragraph) = &ast.children[0] else { panic!("expected paragraph") };
+    let link_text: String = paragraph
+        .children
+        .iter()
+        .filter_map(|node| match node {
+            ASTNode::Link(link) => Some(text_of(&link.children)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(link_text, "==a==");
+    assert!(matches!(&ast.children[1], ASTNode::Image(image) if image.alt.as_deref() == Some("||b||")));
+
+    let ast = parse_with(MarkdownOptions::discord(), r"\_\_plain\_\_ __underlined__");
+    assert_eq!(
+        serde_json::to_value(&ast.children[0]).unwrap(),
+        serde_json::json!({"type": "paragraph", "children": [
+            {"type": "text", "content": "__plain__ "},
+            {"type": "underline", "children": [{"type": "text", "content": "underlined"}]}
+        ]})
+    );
+
+    let ast = parse_with(MarkdownOptions::im_chat(), "```\n\\=\\=x\\=\\=\n```");
+    assert_eq!(
+        ast.children,
+        vec![ASTNode::CodeBlock(CodeBlockNode { language: None, content: "\\=\\=x\\=\\=".to_string() })]
+    );
+}
+
+#[test]
+fn block_spoiler_shares_the_document() {
+    let ast = parse_with(MarkdownOptions::im_chat(), "[link][ref]\n\n||\nsecret [link][ref]\n||\n\n[ref]: https://example.com");
+    assert_eq!(ast.children.len(), 2);
+    let ASTNode::Spoiler(spoiler) = &ast.children[1] else { panic!("expected block spoiler") };
+    for nodes in [&ast.children[..1], &spoiler.children[..]] {
+        let json = serde_json::to_string(nodes).unwrap();
+        assert!(json.contains(r#""url":"https://example.com""#), "unresolved link in {json}");
+    }
+
+    let ast = parse_with(MarkdownOptions::im_chat(), "before\n||\nsecret\n||\nafter");
+    let types: Vec<_> = ast.children.iter().map(ASTNode::type_name).collect();
+    assert_eq!(types, ["paragraph", "spoiler", "paragraph"]);
+    assert_eq!(text_of(&ast.children[..1]), "before");
+}
+
+#[test]
+fn block_spoiler_fences_must_be_top_level_paragraph_lines() {
+    let ast = parse_with(MarkdownOptions::im_chat(), "    ||\n    code\n    ||\n");
+    assert_eq!(ast.children.len(), 1);
+    assert!(matches!(&ast.children[0], ASTNode::CodeBlock(block) if block.content.contains("code")));
+
+    let ast = parse_with(MarkdownOptions::im_chat(), "- ||\n- a\n- ||\n\n**x\n||\ny**");
+    assert!(!has_node(&ast.children, "spoiler"));
+
+    let ast = parse_with(MarkdownOptions::im_chat(), "||\nopen only");
+    assert!(!has_node(&ast.children, "spoiler"));
+    assert_eq!(text_of(&ast.children), "||\nopen only");
+}
+
+fn custom_nodes<'a>(nodes: &'a [ASTNode], kind: &str) -> Vec<&'a CustomNode> {
+    nodes.iter().filter_map(|node| match node {
+        ASTNode::Custom(custom) if custom.kind == kind => Some(custom),
+        _ => None,
+    }).collect()
+}
+
+#[test]
+fn details_blocks_follow_html_block_boundaries() {
+    let ast = parse_with(MarkdownOptions::im_chat(), "<details>\n<summary>Title\n\nbody [l][r]\n\n</details>\n\n[r]: http://x");
+    let [details] = custom_nodes(&ast.children, "details")[..] else { panic!("expected one details block") };
+    assert!(serde_json::to_string(&details.children).unwrap().contains(r#""url":"http://x""#));
+
+    let ast = parse_with(
+        MarkdownOptions::im_chat(),
+        "<details open>\n<summary>**Outer**</summary>\n\nbefore\n\n<details><summary>Inner</summary>\n\n||\nsecret\n||\n\n</details>\n\nafter\n\n</details>",
+    );
+    let [outer] = custom_nodes(&ast.children, "details")[..] else { panic!("expected one details block") };
+    assert_eq!(outer.attrs.get("open"), Some(&serde_json::Value::Bool(true)));
+    let [summary] = custom_nodes(&outer.children[..1], "summary")[..] else { panic!("summary must come first") };
+    assert!(matches!(summary.children.as_slice(), [ASTNode::Strong(_)]));
+    let types: Vec<_> = outer.children.iter().map(ASTNode::type_name).collect();
+    assert_eq!(types, ["custom", "paragraph", "custom", "paragraph"]);
+    let [inner] = custom_nodes(&outer.children[2..], "details")[..] else { panic!("expected nested details") };
+    assert!(matches!(inner.children.as_slice(), [ASTNode::Custom(_), ASTNode::Spoiler(_)]));
+}
+
+#[test]
+fn details_lines_outside_top_level_html_blocks_stay_literal() {
+    for input in ["```\n<details>\nbody\n</details>\n```", "- <details>\n- body\n- </details>", "<details>\n\nno close"] {
+        let ast = parse_with(MarkdownOptions::im_chat(), input);
+        assert!(!serde_json::to_string(&ast).unwrap().contains(r#""kind":"details""#), "details in {input:?}");
+    }
+}
+
+#[test]
+fn subtext_lines_are_unindented_top_level_paragraph_lines() {
+    let ast = parse_with(MarkdownOptions::discord(), "- item\n  -# sub\n- two");
+    let [ASTNode::List(list)] = ast.children.as_slice() else { panic!("expected a single list") };
+    assert_eq!(list.items.len(), 2);
+    assert!(custom_nodes(&ast.children, "subtext").is_empty());
+
+    let ast = parse_with(MarkdownOptions::discord(), "[a][r]\n-# **small** [a][r]\nnext\n\n  -# indented\n\n[r]: http://x");
+    let types: Vec<_> = ast.children.iter().map(ASTNode::type_name).collect();
+    assert_eq!(types, ["paragraph", "custom", "paragraph", "paragraph"]);
+    let [subtext] = custom_nodes(&ast.children, "subtext")[..] else { panic!("expected one subtext line") };
+    assert!(matches!(subtext.children.first(), Some(ASTNode::Strong(_))));
+    for nodes in [&ast.children[..1], &subtext.children[..]] {
+        assert!(serde_json::to_string(nodes).unwrap().contains(r#""url":"http://x""#));
+    }
+    assert_eq!(text_of(&ast.children[2..3]), "next");
+    assert_eq!(text_of(&ast.children[3..]), "-# indented");
+}
+
+#[test]
+fn angle_mentions_include_roles() {
+    let ast = parse_with(MarkdownOptions::discord(), "<@&123> <@!42> <@7|bob>");
+    let ASTNode::Paragraph(paragraph) = &ast.children[0] else { panic!("expected paragraph") };
+    let mentions: Vec<_> = paragraph
+        .children
+        .iter()
+        .filter_map(|node| match node {
+            ASTNode::Mention(mention) => Some((mention.kind, mention.id.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(mentions, [(MentionKind::Role, "123"), (MentionKind::User, "42"), (MentionKind::User, "7")]);
+    assert_eq!(MrkdwnRenderer::new().render(&ast), "<!subteam^123> <@42> <@7>");
+
+    let directory = MentionDirectory::from_json(r#"{"roles": {"123": {"name": "mods"}}}"#).unwrap();
+    let mut ast = ast;
+    resolve_mentions(&mut ast, &directory);
+    assert!(serde_json::to_string(&ast).unwrap().contains(r#""name":"mods","kind":"role","status":"active""#));
+}
+
+/// `::loc` 独占一行的自定义块；`::box` 开始、`::` 结束的自定义块，其间内容按 Markdown 解析
+fn custom_block_parser() -> MarkdownParser {
+    MarkdownParser::new()
+        .with_block_node("location", |input: &str| {
+            let line = input.lines().next()?;
+            (line == "::loc").then(|| CustomBlock::new(line.len(), CustomNode { kind: String::new(), attrs: HashMap::new(), children: vec![] }))
+        })
+        .with_block_node("box", |input: &str| {
+            let body = input.strip_prefix("::box\n")?;
+            let end = body.find("\n::\n").map(|offset| offset + 1).or_else(|| body.ends_with("\n::").then(|| body.len() - 2))?;
+            let content = "::box\n".len().."::box\n".len() + end;
+            Some(CustomBlock::new(content.end + 2, CustomNode { kind: String::new(), attrs: HashMap::new(), children: vec![] }).with_content(content))
+        })
+}
+
+#[test]
+fn custom_blocks_share_link_reference_definitions() {
+    let ast = custom_block_parser().parse("see [x]\n\n::loc\n\n[x]: https://a.b").unwrap();
+    let types: Vec<_> = ast.children.iter().map(ASTNode::type_name).collect();
+    assert_eq!(types, ["paragraph", "custom"]);
+    assert_eq!(custom_nodes(&ast.children, "location").len(), 1);
+    let ASTNode::Paragraph(paragraph) = &ast.children[0] else { panic!("expected paragraph") };
+    assert!(
+        matches!(&paragraph.children[1], ASTNode::Link(link) if link.url == "https://a.b" && link.children == vec![ASTNode::Text(TextNode { content: "x".to_string() })]),
+        "unresolved link in {:?}",
+        paragraph.children
+    );
+
+    // 自定义块正文与外部互相引用
+    let ast = custom_block_parser().parse("::box\n[outer] and [Inner][]\n\n[inner]: https://in.side \"t\"\n::\nafter [inner]\n\n[outer]: https://out.side").unwrap();
+    let json = serde_json::to_string(&ast).unwrap();
+    assert_eq!(json.matches(r#""url":"https://in.side""#).count(), 2, "{json}");
+    assert_eq!(json.matches(r#""url":"https://out.side""#).count(), 1, "{json}");
+    assert_eq!(custom_nodes(&ast.children, "box").len(), 1);
+
+    let ast = custom_block_parser().parse("see [missing]\n\n::loc").unwrap();
+    assert_eq!(text_of(&ast.children[..1]), "see [missing]");
+}
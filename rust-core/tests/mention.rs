@@ -0,0 +1,190 @@
+//! 提及解析测试
+
+use im_parse_core::ffi::{free_parse_result, imparse_mentions_user, imparse_resolve_mentions_with_callback, MentionLookupCallback};
+use im_parse_core::*;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+
+const MESSAGE: &str = r#"{"children":[{"type":"paragraph","children":[
+    {"type":"mention","id":"U1","name":"U1","kind":"user"},
+    {"type":"mention","id":"U2","name":"old name","kind":"user"},
+    {"type":"mention","id":"U3","name":"U3","kind":"user"},
+    {"type":"mention","id":"C1","name":"C1","kind":"channel"},
+    {"type":"mention","id":"R1","name":"R1","kind":"role"},
+    {"type":"mention","id":"all","name":"all","kind":"all"}
+]}]}"#;
+
+const DIRECTORY: &str = r#"{
+    "users": {"U1": {"name": "Alice"}, "U2": {"name": "", "deactivated": true}},
+    "channels": {"C1": {"name": "general"}},
+    "roles": {"R1": {"name": "admins"}}
+}"#;
+
+fn message() -> RootNode {
+    serde_json::from_str(MESSAGE).unwrap()
+}
+
+fn mentions(ast: &RootNode) -> Vec<(String, MentionStatus)> {
+    match &ast.children[0] {
+        ASTNode::Paragraph(paragraph) => paragraph
+            .children
+            .iter()
+            .map(|child| match child {
+                ASTNode::Mention(mention) => (mention.name.clone(), mention.status),
+                other => panic!("expected mention, got {:?}", other),
+            })
+            .collect(),
+        other => panic!("expected paragraph, got {:?}", other),
+    }
+}
+
+fn mention(kind: MentionKind, id: &str) -> RootNode {
+    let mut root = RootNode::new();
+    root.children.push(ASTNode::Paragraph(ParagraphNode {
+        children: vec![ASTNode::Mention(MentionNode {
+            id: id.to_string(),
+            name: id.to_string(),
+            kind,
+            status: MentionStatus::Unresolved,
+        })],
+    }));
+    root
+}
+
+#[test]
+fn directory_resolves_names_and_statuses() {
+    let directory = MentionDirectory::from_json(DIRECTORY).unwrap();
+    let mut ast = message();
+    resolve_mentions(&mut ast, &directory);
+
+    assert_eq!(
+        mentions(&ast),
+        vec![
+            ("Alice".to_string(), MentionStatus::Active),
+            // 目录中名称为空时保留原名称
+            ("old name".to_string(), MentionStatus::Deactivated),
+            ("U3".to_string(), MentionStatus::Unknown),
+            ("general".to_string(), MentionStatus::Active),
+            ("admins".to_string(), MentionStatus::Active),
+            ("all".to_string(), MentionStatus::Unresolved),
+        ]
+    );
+}
+
+#[test]
+fn closures_act_as_resolvers() {
+    let resolver = |kind: MentionKind, id: &str| {
+        (kind == MentionKind::User && id == "U3").then(|| MentionTarget { name: "Carol".to_string(), deactivated: false })
+    };
+    let mut ast = message();
+    resolve_mentions(&mut ast, &resolver);
+
+    let resolved = mentions(&ast);
+    assert_eq!(resolved[0], ("U1".to_string(), MentionStatus::Unknown));
+    assert_eq!(resolved[2], ("Carol".to_string(), MentionStatus::Active));
+    assert_eq!(resolved[5].1, MentionStatus::Unresolved);
+}
+
+#[test]
+fn directory_json_sections_are_optional() {
+    let directory = MentionDirectory::from_json(r#"{"users": {"U1": {"name": "Alice"}}}"#).unwrap();
+    assert_eq!(directory.users["U1"], MentionTarget { name: "Alice".to_string(), deactivated: false });
+    assert!(directory.channels.is_empty() && directory.roles.is_empty());
+
+    assert!(MentionDirectory::from_json("{}").unwrap().users.is_empty());
+    assert!(MentionDirectory::from_json(r#"{"users": {"U1": {}}}"#).is_err());
+    assert!(MentionDirectory::from_json(r#"{"users": ["U1"]}"#).is_err());
+}
+
+#[test]
+fn mentions_user_matches_direct_and_broadcast_mentions() {
+    assert!(mentions_user(&mention(MentionKind::User, "U1"), "U1"));
+    assert!(!mentions_user(&mention(MentionKind::User, "U2"), "U1"));
+    assert!(mentions_user(&mention(MentionKind::All, "all"), "U1"));
+    assert!(mentions_user(&mention(MentionKind::Here, "here"), "U1"));
+    // 是否属于某个角色或频道由宿主判断
+    assert!(!mentions_user(&mention(MentionKind::Role, "U1"), "U1"));
+    assert!(!mentions_user(&mention(MentionKind::Channel, "U1"), "U1"));
+    assert!(!mentions_user(&RootNode::new(), "U1"));
+
+    let ast = CString::new(serde_json::to_string(&mention(MentionKind::Here, "here")).unwrap()).unwrap();
+    let user = CString::new("U1").unwrap();
+    assert!(unsafe { imparse_mentions_user(ast.as_ptr(), user.as_ptr()) });
+    assert!(!unsafe { imparse_mentions_user(ptr::null(), user.as_ptr()) });
+}
+
+/// 回调：`context` 指向以 `(kind, id, 返回值)` 描述的查找表
+extern "C" fn lookup(context: *mut c_void, kind: *const c_char, id: *const c_char) -> *const c_char {
+    let table = unsafe { &*(context as *const Vec<(&str, &str, CString)>) };
+    let kind = unsafe { CStr::from_ptr(kind) }.to_str().unwrap();
+    let id = unsafe { CStr::from_ptr(id) }.to_str().unwrap();
+    table
+        .iter()
+        .find(|(k, i, _)| *k == kind && *i == id)
+        .map_or(ptr::null(), |(_, _, target)| target.as_ptr())
+}
+
+/// 调用 imparse_resolve_mentions_with_callback，返回是否成功以及结果 JSON 或错误信息
+fn resolve_with_callback(table: &[(&str, &str, &str)], callback: Option<MentionLookupCallback>) -> (bool, String) {
+    let table: Vec<(&str, &str, CString)> = table
+        .iter()
+        .map(|(kind, id, target)| (*kind, *id, CString::new(*target).unwrap()))
+        .collect();
+    let ast = CString::new(MESSAGE).unwrap();
+    unsafe {
+        let result = imparse_resolve_mentions_with_callback(ast.as_ptr(), callback, &table as *const _ as *mut c_void);
+        let success = (*result).success;
+        let text = if success {
+            CStr::from_ptr((*result).ast_json).to_str().unwrap().to_string()
+        } else {
+            CStr::from_ptr((*result).error.message).to_str().unwrap().to_string()
+        };
+        free_parse_result(result);
+        (success, text)
+    }
+}
+
+#[test]
+fn callback_resolves_by_kind() {
+    let (success, json) = resolve_with_callback(
+        &[
+            ("user", "U1", r#"{"name": "Alice"}"#),
+            ("channel", "C1", r#"{"name": "general", "deactivated": true}"#),
+            ("role", "R1", r#"{"name": "admins"}"#),
+        ],
+        Some(lookup),
+    );
+    assert!(success, "{}", json);
+
+    let ast: RootNode = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        mentions(&ast),
+        vec![
+            ("Alice".to_string(), MentionStatus::Active),
+            ("old name".to_string(), MentionStatus::Unknown),
+            ("U3".to_string(), MentionStatus::Unknown),
+            ("general".to_string(), MentionStatus::Deactivated),
+            ("admins".to_string(), MentionStatus::Active),
+            ("all".to_string(), MentionStatus::Unresolved),
+        ]
+    );
+}
+
+#[test]
+fn callback_returning_invalid_json_is_an_error() {
+    let (success, message) = resolve_with_callback(
+        &[("user", "U1", r#"{"name": "Alice"}"#), ("user", "U2", "Bob")],
+        Some(lookup),
+    );
+    assert!(!success);
+    assert!(message.starts_with("Invalid mention target JSON for user U2"), "{}", message);
+
+    let (success, message) = resolve_with_callback(&[("channel", "C1", r#"{"title": "general"}"#)], Some(lookup));
+    assert!(!success);
+    assert!(message.contains("channel C1"), "{}", message);
+
+    let (success, message) = resolve_with_callback(&[], None);
+    assert!(!success);
+    assert_eq!(message, "Lookup callback is null");
+}
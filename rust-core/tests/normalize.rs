@@ -0,0 +1,152 @@
+//! AST 规范化测试
+
+use im_parse_core::*;
+
+fn root(json: &str) -> RootNode {
+    serde_json::from_str(json).unwrap()
+}
+
+fn normalized(json: &str) -> RootNode {
+    let mut ast = root(json);
+    normalize(&mut ast);
+    ast
+}
+
+fn text(content: &str) -> ASTNode {
+    ASTNode::Text(TextNode { content: content.to_string() })
+}
+
+fn strong(children: Vec<ASTNode>) -> ASTNode {
+    ASTNode::Strong(StrongNode { children })
+}
+
+fn em(children: Vec<ASTNode>) -> ASTNode {
+    ASTNode::Em(EmNode { children })
+}
+
+fn paragraph(children: Vec<ASTNode>) -> ASTNode {
+    ASTNode::Paragraph(ParagraphNode { children })
+}
+
+#[test]
+fn adjacent_text_nodes_are_merged() {
+    let ast = normalized(
+        r#"{"children":[{"type":"paragraph","children":[
+            {"type":"text","content":"a"},{"type":"text","content":" "},{"type":"text","content":"b"},
+            {"type":"code","content":"x"},
+            {"type":"text","content":"c"},{"type":"text","content":"d"}
+        ]}]}"#,
+    );
+    assert_eq!(
+        ast.children,
+        vec![paragraph(vec![text("a b"), ASTNode::Code(CodeNode { content: "x".to_string() }), text("cd")])]
+    );
+}
+
+#[test]
+fn empty_inline_nodes_are_removed() {
+    let ast = normalized(
+        r#"{"children":[{"type":"paragraph","children":[
+            {"type":"text","content":""},
+            {"type":"strong","children":[]},
+            {"type":"em","children":[{"type":"text","content":""}]},
+            {"type":"link","url":"https://a.b","title":null,"children":[]},
+            {"type":"spoiler","children":[]},
+            {"type":"text","content":"kept"},
+            {"type":"mention","id":"U1","name":"U1","kind":"user","status":"unknown"}
+        ]}]}"#,
+    );
+    match &ast.children[0] {
+        ASTNode::Paragraph(para) => {
+            assert_eq!(para.children.len(), 2);
+            assert_eq!(para.children[0], text("kept"));
+            assert!(matches!(&para.children[1], ASTNode::Mention(_)));
+        }
+        other => panic!("expected paragraph, got {:?}", other),
+    }
+}
+
+#[test]
+fn style_chains_use_canonical_order() {
+    let ast = normalized(
+        r#"{"children":[{"type":"paragraph","children":[
+            {"type":"em","children":[{"type":"strong","children":[{"type":"text","content":"a"}]}]},
+            {"type":"text","content":" "},
+            {"type":"strong","children":[{"type":"strong","children":[{"type":"text","content":"b"}]}]}
+        ]}]}"#,
+    );
+    assert_eq!(
+        ast.children,
+        vec![paragraph(vec![
+            strong(vec![em(vec![text("a")])]),
+            text(" "),
+            strong(vec![text("b")]),
+        ])]
+    );
+}
+
+#[test]
+fn adjacent_same_styles_are_merged() {
+    let ast = normalized(
+        r#"{"children":[{"type":"paragraph","children":[
+            {"type":"strong","children":[{"type":"text","content":"a"}]},
+            {"type":"strong","children":[{"type":"text","content":"b"}]},
+            {"type":"em","children":[{"type":"text","content":"c"}]}
+        ]}]}"#,
+    );
+    assert_eq!(
+        ast.children,
+        vec![paragraph(vec![strong(vec![text("ab")]), em(vec![text("c")])])]
+    );
+}
+
+#[test]
+fn trailing_empty_paragraphs_are_trimmed() {
+    let ast = normalized(
+        r#"{"children":[
+            {"type":"paragraph","children":[]},
+            {"type":"paragraph","children":[{"type":"text","content":"a"}]},
+            {"type":"paragraph","children":[]},
+            {"type":"paragraph","children":[{"type":"text","content":""}]}
+        ]}"#,
+    );
+    assert_eq!(
+        ast.children,
+        vec![paragraph(vec![]), paragraph(vec![text("a")])]
+    );
+}
+
+#[test]
+fn normalization_is_idempotent() {
+    let mut ast = parse_delta(
+        r#"{"ops":[{"insert":"a","attributes":{"italic":true,"bold":true}},{"insert":"b","attributes":{"bold":true,"italic":true}},{"insert":"\n\n"}]}"#,
+    )
+    .unwrap();
+    normalize(&mut ast);
+    let once = ast.clone();
+    normalize(&mut ast);
+    assert_eq!(ast, once);
+}
+
+#[test]
+fn parsers_apply_normalization_when_enabled() {
+    let expected = vec![paragraph(vec![strong(vec![em(vec![text("ab")])])])];
+
+    let delta = r#"{"ops":[{"insert":"a","attributes":{"italic":true,"bold":true}},{"insert":"b","attributes":{"bold":true,"italic":true}},{"insert":"\n"}]}"#;
+    let ast = DeltaParser::new().with_normalize(true).parse(delta).unwrap();
+    assert_eq!(ast.children, expected);
+
+    let ast = MarkdownParser::new().with_normalize(true).parse("*__a__*__*b*__").unwrap();
+    assert_eq!(ast.children, expected);
+    let ast = MarkdownParser::new().with_normalize(true).parse("a\nb").unwrap();
+    assert_eq!(ast.children, vec![paragraph(vec![text("a b")])]);
+
+    let ast = HtmlParser::new().with_normalize(true).parse("<p><i><b>a</b></i><b><i>b</i></b></p>").unwrap();
+    assert_eq!(ast.children, expected);
+
+    let ast = SlackParser::new().with_normalize(true).parse_mrkdwn("_*a*_*_b_*").unwrap();
+    assert_eq!(ast.children, expected);
+
+    let plain = MarkdownParser::new().parse("a\nb").unwrap();
+    assert_eq!(plain.children[0], paragraph(vec![text("a"), text(" "), text("b")]));
+}
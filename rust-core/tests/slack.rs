@@ -0,0 +1,230 @@
+//! Slack mrkdwn、Block Kit 解析与 mrkdwn 渲染测试
+
+use im_parse_core::*;
+use serde_json::json;
+
+fn mrkdwn(input: &str) -> serde_json::Value {
+    let ast = SlackParser::new().parse_mrkdwn(input).unwrap();
+    serde_json::to_value(&ast.children).unwrap()
+}
+
+fn blocks(input: serde_json::Value) -> serde_json::Value {
+    let ast = SlackParser::new().parse_blocks(&input.to_string()).unwrap();
+    serde_json::to_value(&ast.children).unwrap()
+}
+
+fn text(content: &str) -> serde_json::Value {
+    json!({"type": "text", "content": content})
+}
+
+fn mention(id: &str, name: &str, kind: &str) -> serde_json::Value {
+    json!({"type": "mention", "id": id, "name": name, "kind": kind, "status": "unresolved"})
+}
+
+#[test]
+fn mrkdwn_inline_markup_and_angle_sequences() {
+    assert_eq!(
+        mrkdwn("*bold* _it_ ~st~ `co*de*` a*b*c\nline\n\n<https://a.b|link> <@U1> <#C1|gen> <!here> <!subteam^S1|@team> &amp;&lt;&gt;"),
+        json!([
+            {"type": "paragraph", "children": [
+                {"type": "strong", "children": [text("bold")]},
+                text(" "),
+                {"type": "em", "children": [text("it")]},
+                text(" "),
+                {"type": "strike", "children": [text("st")]},
+                text(" "),
+                {"type": "code", "content": "co*de*"},
+                // 标记外侧不是空白或标点时不算格式
+                text(" a*b*c\nline")
+            ]},
+            {"type": "paragraph", "children": [
+                {"type": "link", "url": "https://a.b", "children": [text("link")]},
+                text(" "),
+                mention("U1", "", "user"),
+                text(" "),
+                mention("C1", "gen", "channel"),
+                text(" "),
+                mention("here", "here", "here"),
+                text(" @team &<>")
+            ]}
+        ])
+    );
+    // 格式标记不跨行
+    assert_eq!(mrkdwn("*not\nbold*"), json!([{"type": "paragraph", "children": [text("*not\nbold*")]}]));
+}
+
+#[test]
+fn mrkdwn_quotes_and_code_blocks() {
+    assert_eq!(
+        mrkdwn("&gt; quote\n&gt; more\nafter\n```\nfn x() {}\n```"),
+        json!([
+            {"type": "blockquote", "children": [{"type": "paragraph", "children": [text("quote\nmore")]}]},
+            {"type": "paragraph", "children": [text("after")]},
+            {"type": "codeBlock", "language": null, "content": "fn x() {}\n"}
+        ])
+    );
+    assert_eq!(mrkdwn("```a &lt;b&gt;```"), json!([{"type": "codeBlock", "language": null, "content": "a <b>\n"}]));
+    assert_eq!(
+        mrkdwn("<javascript:alert(1)|x>"),
+        json!([{"type": "paragraph", "children": [text("x")]}])
+    );
+}
+
+#[test]
+fn block_kit_layout_blocks() {
+    let value = blocks(json!([
+        {"type": "header", "text": {"type": "plain_text", "text": "Title"}},
+        {"type": "section", "text": {"type": "mrkdwn", "text": "*b* x"}, "fields": [{"type": "plain_text", "text": "f1"}],
+         "accessory": {"type": "image", "image_url": "https://a.b/i.png", "alt_text": "i"}},
+        {"type": "context", "elements": [{"type": "mrkdwn", "text": "_c_"}, {"type": "image", "image_url": "https://a.b/c.png", "alt_text": "c"}]},
+        {"type": "divider"}
+    ]));
+    let image = |url: &str, alt: &str| json!({"type": "image", "url": url, "width": null, "height": null, "alt": alt});
+    assert_eq!(
+        value,
+        json!([
+            {"type": "heading", "level": 1, "children": [text("Title")]},
+            {"type": "paragraph", "children": [{"type": "strong", "children": [text("b")]}, text(" x")]},
+            {"type": "paragraph", "children": [text("f1")]},
+            {"type": "paragraph", "children": [image("https://a.b/i.png", "i")]},
+            {"type": "paragraph", "children": [{"type": "em", "children": [text("c")]}, text(" "), image("https://a.b/c.png", "c")]},
+            {"type": "horizontalRule"}
+        ])
+    );
+}
+
+#[test]
+fn block_kit_rich_text() {
+    let value = blocks(json!([{"type": "rich_text", "elements": [
+        {"type": "rich_text_section", "elements": [
+            {"type": "text", "text": "hi ", "style": {"bold": true}},
+            {"type": "user", "user_id": "U1"},
+            {"type": "link", "url": "https://a.b", "text": "l"}
+        ]},
+        {"type": "rich_text_list", "style": "bullet", "indent": 0, "elements": [
+            {"type": "rich_text_section", "elements": [{"type": "text", "text": "a"}]}
+        ]},
+        {"type": "rich_text_list", "style": "ordered", "indent": 1, "elements": [
+            {"type": "rich_text_section", "elements": [{"type": "text", "text": "b"}]}
+        ]},
+        {"type": "rich_text_preformatted", "elements": [{"type": "text", "text": "code"}]},
+        {"type": "rich_text_quote", "elements": [{"type": "text", "text": "q"}]}
+    ]}]));
+    assert_eq!(
+        value,
+        json!([
+            {"type": "paragraph", "children": [
+                {"type": "strong", "children": [text("hi ")]},
+                mention("U1", "", "user"),
+                {"type": "link", "url": "https://a.b", "children": [text("l")]}
+            ]},
+            // indent 更深的列表嵌套到上一项中
+            {"type": "list", "listType": "bullet", "items": [{"checked": null, "children": [
+                text("a"),
+                {"type": "list", "listType": "ordered", "items": [{"checked": null, "children": [text("b")]}]}
+            ]}]},
+            {"type": "codeBlock", "language": null, "content": "code\n"},
+            {"type": "blockquote", "children": [{"type": "paragraph", "children": [text("q")]}]}
+        ])
+    );
+}
+
+#[test]
+fn block_kit_interactive_blocks_become_cards() {
+    let value = blocks(json!([{"type": "actions", "block_id": "b1", "elements": [
+        {"type": "button", "action_id": "ok", "text": {"type": "plain_text", "text": "OK"}, "style": "primary", "value": "v"},
+        {"type": "static_select", "action_id": "s", "placeholder": {"type": "plain_text", "text": "Pick"},
+         "options": [{"text": {"type": "plain_text", "text": "One"}, "value": "1"}]},
+        {"type": "users_select", "action_id": "u"}
+    ]}]));
+    let card = &value[0];
+    assert_eq!(card["subtype"], "slack.actions");
+    assert_eq!(card["metadata"]["block_id"], "b1");
+    assert_eq!(
+        card["actions"],
+        json!([
+            {"id": "ok", "kind": "button", "label": "OK", "style": "primary", "value": "v", "url": null, "placeholder": null, "options": []},
+            {"id": "s", "kind": "select", "label": "", "style": "default", "value": null, "url": null, "placeholder": "Pick",
+             "options": [{"label": "One", "value": "1"}]},
+            // 没有静态选项的下拉框转换为输入框
+            {"id": "u", "kind": "input", "label": "", "style": "default", "value": null, "url": null, "placeholder": null, "options": []}
+        ])
+    );
+}
+
+#[test]
+fn block_kit_accepts_messages_and_single_blocks() {
+    assert_eq!(blocks(json!({"type": "divider"})), json!([{"type": "horizontalRule"}]));
+    assert_eq!(blocks(json!({"blocks": [{"type": "divider"}], "text": "fallback"})), json!([{"type": "horizontalRule"}]));
+    assert_eq!(
+        blocks(json!({"text": "*hi*"})),
+        json!([{"type": "paragraph", "children": [{"type": "strong", "children": [text("hi")]}]}])
+    );
+    assert_eq!(blocks(json!({})), json!([]));
+}
+
+#[test]
+fn block_kit_rejects_malformed_blocks() {
+    let parser = SlackParser::new();
+    for input in [
+        r#"[1, "x", {"no": 1}]"#,
+        r#"[{"type": 1}]"#,
+        r#"{"blocks": [{"type": "divider"}, 2]}"#,
+        r#"{"blocks": {}}"#,
+        r#""x""#,
+        "not json",
+    ] {
+        assert!(parser.parse_blocks(input).is_err(), "{}", input);
+    }
+    match parser.parse_blocks(r#"{"blocks": [{"type": "divider"}, null]}"#) {
+        Err(ParseError::SlackError(message)) => assert_eq!(message, "block 1 must be an object with a string `type`"),
+        other => panic!("expected SlackError, got {:?}", other),
+    }
+}
+
+#[test]
+fn mrkdwn_round_trips_through_the_renderer() {
+    let parser = SlackParser::new();
+    let renderer = MrkdwnRenderer::new();
+    for input in [
+        "*bold* _it_ ~st~ `code`",
+        "a &amp; b &lt;c&gt;",
+        "<https://a.b|link> <@U1> <#C1> <!here> <!channel>",
+        "para\nline\n\nnext",
+        "```\nfn x() {}\n```",
+        "• a\n• b",
+    ] {
+        let ast = parser.parse_mrkdwn(input).unwrap();
+        let output = renderer.render(&ast);
+        assert_eq!(output, input);
+        assert_eq!(parser.parse_mrkdwn(&output).unwrap(), ast, "{}", input);
+    }
+
+    // 引用块输出为未转义的 `>`，再次解析得到相同的 AST
+    let ast = parser.parse_mrkdwn("&gt; quote\n\npara").unwrap();
+    let output = renderer.render(&ast);
+    assert_eq!(output, "> quote\n\npara");
+    assert_eq!(parser.parse_mrkdwn(&output).unwrap(), ast);
+}
+
+#[test]
+fn mrkdwn_renderer_maps_unsupported_nodes() {
+    let ast = MarkdownParser::new()
+        .parse("# T\n\n**b** *i* ~~s~~ `c`\n\n- a\n- b\n\n1. x\n2. y\n\n![alt](https://a.b/i.png)\n\n| a | b |\n|---|---|\n| 1 | 2 |")
+        .unwrap();
+    assert_eq!(
+        MrkdwnRenderer::new().render(&ast),
+        "*T*\n\n*b* _i_ ~s~ `c`\n\n• a\n• b\n\n1. x\n2. y\n\n<https://a.b/i.png|alt>\n\na | b\n1 | 2"
+    );
+
+    // 格式标记内侧的首尾空白移到标记外
+    let mut root = RootNode::new();
+    root.children.push(ASTNode::Paragraph(ParagraphNode {
+        children: vec![
+            ASTNode::Text(TextNode { content: "a".to_string() }),
+            ASTNode::Strong(StrongNode { children: vec![ASTNode::Text(TextNode { content: " x ".to_string() })] }),
+            ASTNode::Text(TextNode { content: "b".to_string() }),
+        ],
+    }));
+    assert_eq!(MrkdwnRenderer::new().render(&root), "a *x* b");
+}
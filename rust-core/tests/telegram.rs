@@ -0,0 +1,87 @@
+//! Telegram 实体解析与 MarkdownV2 渲染测试
+
+use im_parse_core::*;
+use serde_json::json;
+
+fn entity(kind: &str, offset: usize, length: usize) -> TelegramEntity {
+    TelegramEntity { kind: kind.to_string(), offset, length, ..TelegramEntity::default() }
+}
+
+fn parse(text: &str, entities: &[TelegramEntity]) -> serde_json::Value {
+    let ast = TelegramParser::new().parse_entities(text, entities).unwrap();
+    serde_json::to_value(&ast.children).unwrap()
+}
+
+fn text(content: &str) -> serde_json::Value {
+    json!({"type": "text", "content": content})
+}
+
+#[test]
+fn offsets_count_utf16_code_units() {
+    // 😀 占两个 UTF-16 码元，é 和 中 各占一个
+    let value = parse("😀é中 bold 😀x", &[entity("bold", 5, 4), entity("italic", 10, 3)]);
+    assert_eq!(
+        value,
+        json!([{"type": "paragraph", "children": [
+            text("😀é中 "),
+            {"type": "strong", "children": [text("bold")]},
+            text(" "),
+            {"type": "em", "children": [text("😀x")]}
+        ]}])
+    );
+}
+
+#[test]
+fn offsets_inside_surrogate_pairs_and_out_of_range_are_safe() {
+    // 偏移 1 落在 😀 的代理对中间，指向下一个字符
+    let value = parse("😀ab", &[entity("bold", 1, 2)]);
+    assert_eq!(value[0]["children"][1], json!({"type": "strong", "children": [text("a")]}));
+
+    for entities in [
+        vec![entity("bold", 1, usize::MAX)],
+        vec![entity("bold", usize::MAX, usize::MAX)],
+        vec![entity("bold", 100, 1)],
+        vec![entity("bold", 0, 0)],
+    ] {
+        TelegramParser::new().parse_entities("😀ab", &entities).unwrap();
+    }
+    let value = parse("😀ab", &[entity("bold", 2, usize::MAX)]);
+    assert_eq!(value[0]["children"][1], json!({"type": "strong", "children": [text("ab")]}));
+}
+
+#[test]
+fn partially_overlapping_entities_are_clipped() {
+    let value = parse("abcdef", &[entity("bold", 0, 4), entity("italic", 2, 4)]);
+    assert_eq!(
+        value,
+        json!([{"type": "paragraph", "children": [
+            {"type": "strong", "children": [text("ab"), {"type": "em", "children": [text("cd")]}]},
+            text("ef")
+        ]}])
+    );
+}
+
+#[test]
+fn markdown_v2_escapes_reserved_characters() {
+    let renderer = MarkdownV2Renderer::new();
+    let paragraph = |children: Vec<ASTNode>| {
+        let mut root = RootNode::new();
+        root.children.push(ASTNode::Paragraph(ParagraphNode { children }));
+        root
+    };
+    let plain = paragraph(vec![ASTNode::Text(TextNode { content: r"_*[]()~`>#+-=|{}.!\ ok".to_string() })]);
+    assert_eq!(renderer.render(&plain), r"\_\*\[\]\(\)\~\`\>\#\+\-\=\|\{\}\.\!\\ ok");
+
+    let code = paragraph(vec![ASTNode::Code(CodeNode { content: r"a_b `c` \d".to_string() })]);
+    assert_eq!(renderer.render(&code), r"`a_b \`c\` \\d`");
+
+    let link = paragraph(vec![ASTNode::Link(LinkNode {
+        url: r"https://e.x/a_(b)\c".to_string(),
+        children: vec![ASTNode::Text(TextNode { content: "a.b".to_string() })],
+    })]);
+    assert_eq!(renderer.render(&link), r"[a\.b](https://e.x/a_(b\)\\c)");
+
+    let mut block = RootNode::new();
+    block.children.push(ASTNode::CodeBlock(CodeBlockNode { language: Some("rs".to_string()), content: "let _ = `x`;\n".to_string() }));
+    assert_eq!(renderer.render(&block), "```rs\nlet _ = \\`x\\`;\n```");
+}
@@ -0,0 +1,141 @@
+//! AST 结构校验与修复测试
+
+use im_parse_core::*;
+
+fn root(json: &str) -> RootNode {
+    serde_json::from_str(json).unwrap()
+}
+
+fn kinds(issues: &[ValidationIssue]) -> Vec<(&str, ValidationIssueKind)> {
+    issues.iter().map(|issue| (issue.path.as_str(), issue.kind)).collect()
+}
+
+#[test]
+fn parsed_markdown_is_valid() {
+    let ast = parse_markdown("# Title\n\n- a\n- b\n\n| x | y |\n|---|---|\n| 1 | 2 |\n\n[link](https://x.y)").unwrap();
+    assert!(validate(&ast).is_empty());
+}
+
+#[test]
+fn validate_reports_issues_without_modifying() {
+    let ast = root(
+        r#"{"children":[
+            {"type":"heading","level":9,"children":[]},
+            {"type":"paragraph","children":[{"type":"codeBlock","language":null,"content":"x"}]},
+            {"type":"listItem","children":[],"checked":null},
+            {"type":"link","url":" ","children":[{"type":"text","content":"t"}]}
+        ]}"#,
+    );
+    let before = ast.clone();
+    let issues = validate(&ast);
+    assert_eq!(
+        kinds(&issues),
+        vec![
+            ("/children/0", ValidationIssueKind::InvalidHeadingLevel),
+            ("/children/1/children/0", ValidationIssueKind::BlockInInline),
+            ("/children/2", ValidationIssueKind::MisplacedNode),
+            ("/children/3", ValidationIssueKind::EmptyUrl),
+        ]
+    );
+    assert_eq!(ast, before);
+}
+
+#[test]
+fn repair_returns_same_issues_as_validate() {
+    let mut ast = root(
+        r#"{"children":[
+            {"type":"heading","level":0,"children":[]},
+            {"type":"tableRow","cells":[]},
+            {"type":"table","rows":[
+                {"cells":[{"children":[],"align":null},{"children":[],"align":null}]},
+                {"cells":[{"children":[],"align":null}]}
+            ]}
+        ]}"#,
+    );
+    let expected = validate(&ast);
+    assert_eq!(repair(&mut ast), expected);
+    assert!(validate(&ast).is_empty());
+
+    assert!(matches!(&ast.children[0], ASTNode::Heading(heading) if heading.level == 1));
+    assert!(matches!(&ast.children[1], ASTNode::Table(table) if table.rows.len() == 1));
+    match &ast.children[2] {
+        ASTNode::Table(table) => assert!(table.rows.iter().all(|row| row.cells.len() == 2)),
+        other => panic!("expected table, got {:?}", other),
+    }
+}
+
+#[test]
+fn repair_separates_flattened_paragraphs() {
+    let mut ast = root(
+        r#"{"children":[{"type":"heading","level":1,"children":[
+            {"type":"blockquote","children":[
+                {"type":"paragraph","children":[{"type":"text","content":"one"}]},
+                {"type":"paragraph","children":[{"type":"text","content":"two"}]}
+            ]}
+        ]}]}"#,
+    );
+    repair(&mut ast);
+    let text = |content: &str| ASTNode::Text(TextNode { content: content.to_string() });
+    match &ast.children[0] {
+        ASTNode::Heading(heading) => assert_eq!(heading.children, vec![text("one"), text("\n"), text("two")]),
+        other => panic!("expected heading, got {:?}", other),
+    }
+}
+
+#[test]
+fn repair_separates_blocks_placed_directly_in_headings() {
+    let mut ast = root(
+        r#"{"children":[
+            {"type":"heading","level":1,"children":[
+                {"type":"paragraph","children":[{"type":"text","content":"one"}]},
+                {"type":"paragraph","children":[{"type":"text","content":"two"}]}
+            ]},
+            {"type":"heading","level":2,"children":[
+                {"type":"text","content":"a"},
+                {"type":"codeBlock","language":null,"content":"b"},
+                {"type":"strong","children":[{"type":"text","content":"c"}]}
+            ]}
+        ]}"#,
+    );
+    repair(&mut ast);
+    assert!(validate(&ast).is_empty());
+    let text = |content: &str| ASTNode::Text(TextNode { content: content.to_string() });
+    match &ast.children[0] {
+        ASTNode::Heading(heading) => assert_eq!(heading.children, vec![text("one"), text("\n"), text("two")]),
+        other => panic!("expected heading, got {:?}", other),
+    }
+    match &ast.children[1] {
+        ASTNode::Heading(heading) => assert_eq!(
+            heading.children,
+            vec![
+                text("a"),
+                text("\n"),
+                ASTNode::Code(CodeNode { content: "b".to_string() }),
+                text("\n"),
+                ASTNode::Strong(StrongNode { children: vec![text("c")] }),
+            ]
+        ),
+        other => panic!("expected heading, got {:?}", other),
+    }
+}
+
+#[test]
+fn repair_unwraps_empty_links_and_images() {
+    let mut ast = root(
+        r#"{"children":[{"type":"paragraph","children":[
+            {"type":"link","url":"","children":[{"type":"text","content":"label"}]},
+            {"type":"image","url":"","width":null,"height":null,"alt":"alt"}
+        ]}]}"#,
+    );
+    assert_eq!(repair(&mut ast).len(), 2);
+    match &ast.children[0] {
+        ASTNode::Paragraph(para) => assert_eq!(
+            para.children,
+            vec![
+                ASTNode::Text(TextNode { content: "label".to_string() }),
+                ASTNode::Text(TextNode { content: "alt".to_string() }),
+            ]
+        ),
+        other => panic!("expected paragraph, got {:?}", other),
+    }
+}
@@ -0,0 +1,151 @@
+//! AST 访问器测试
+
+use im_parse_core::*;
+use serde_json::Value;
+
+const DOCUMENT: &str = r#"{"children":[
+    {"type":"heading","level":2,"children":[
+        {"type":"strong","children":[{"type":"em","children":[{"type":"text","content":"title"}]}]}
+    ]},
+    {"type":"paragraph","children":[
+        {"type":"link","url":"http://a.b","title":null,"children":[{"type":"text","content":"link"}]},
+        {"type":"highlight","children":[{"type":"text","content":"marked"}]},
+        {"type":"spoiler","children":[{"type":"text","content":"hidden"}]},
+        {"type":"footnoteReference","label":"1","index":1},
+        {"type":"mention","id":"U1","name":"U1","kind":"user","status":"unknown"}
+    ]},
+    {"type":"list","listType":"bullet","items":[
+        {"checked":null,"children":[
+            {"type":"paragraph","children":[{"type":"text","content":"outer"}]},
+            {"type":"list","listType":"ordered","items":[
+                {"checked":true,"children":[{"type":"paragraph","children":[{"type":"text","content":"inner"}]}]}
+            ]}
+        ]}
+    ]},
+    {"type":"table","rows":[
+        {"cells":[{"align":null,"children":[{"type":"text","content":"cell"}]},{"align":"right","children":[{"type":"code","content":"x"}]}]}
+    ]},
+    {"type":"blockquote","children":[{"type":"paragraph","children":[{"type":"text","content":"quote"}]}]},
+    {"type":"custom","kind":"location","attrs":{"lat":1},"children":[{"type":"text","content":"here"}]},
+    {"type":"footnoteDefinition","label":"1","index":1,"children":[{"type":"paragraph","children":[{"type":"text","content":"note"}]}]},
+    {"type":"poll","question":"?"}
+]}"#;
+
+fn document() -> RootNode {
+    serde_json::from_str(DOCUMENT).unwrap()
+}
+
+/// 统计 JSON 中带 `type` 的对象数量，即 `ASTNode` 的数量
+fn count_typed(value: &Value) -> usize {
+    match value {
+        Value::Object(map) => {
+            let own = usize::from(map.contains_key("type"));
+            own + map.values().map(count_typed).sum::<usize>()
+        }
+        Value::Array(items) => items.iter().map(count_typed).sum(),
+        _ => 0,
+    }
+}
+
+#[derive(Default)]
+struct Counter {
+    nodes: usize,
+    items: usize,
+    rows: usize,
+    cells: usize,
+    texts: Vec<String>,
+}
+
+impl Visitor for Counter {
+    fn visit_node(&mut self, node: &ASTNode) {
+        self.nodes += 1;
+        walk_node(self, node);
+    }
+
+    fn visit_list_item(&mut self, item: &ListItemNode) {
+        self.items += 1;
+        walk_list_item(self, item);
+    }
+
+    fn visit_table_row(&mut self, row: &TableRow) {
+        self.rows += 1;
+        walk_table_row(self, row);
+    }
+
+    fn visit_table_cell(&mut self, cell: &TableCell) {
+        self.cells += 1;
+        walk_table_cell(self, cell);
+    }
+
+    fn visit_text(&mut self, text: &TextNode) {
+        self.texts.push(text.content.clone());
+    }
+}
+
+#[test]
+fn visitor_reaches_every_node() {
+    let ast = document();
+    let mut counter = Counter::default();
+    counter.visit_root(&ast);
+
+    let json: Value = serde_json::from_str(DOCUMENT).unwrap();
+    assert_eq!(counter.nodes, count_typed(&json));
+    assert_eq!((counter.items, counter.rows, counter.cells), (2, 1, 2));
+    assert_eq!(
+        counter.texts,
+        vec!["title", "link", "marked", "hidden", "outer", "inner", "cell", "quote", "here", "note"]
+    );
+}
+
+struct Shout;
+
+impl VisitorMut for Shout {
+    fn visit_text_mut(&mut self, text: &mut TextNode) {
+        text.content = text.content.to_uppercase();
+    }
+
+    fn visit_link_mut(&mut self, link: &mut LinkNode) {
+        link.url = link.url.replace("http://", "https://");
+        walk_link_mut(self, link);
+    }
+
+    fn visit_mention_mut(&mut self, mention: &mut MentionNode) {
+        mention.name = "Alice".to_string();
+    }
+
+    fn visit_list_item_mut(&mut self, item: &mut ListItemNode) {
+        item.checked = Some(item.checked.unwrap_or(false));
+        walk_list_item_mut(self, item);
+    }
+}
+
+#[test]
+fn visitor_mut_edits_are_applied() {
+    let mut ast = document();
+    Shout.visit_root_mut(&mut ast);
+
+    let mut counter = Counter::default();
+    counter.visit_root(&ast);
+    assert_eq!(
+        counter.texts,
+        vec!["TITLE", "LINK", "MARKED", "HIDDEN", "OUTER", "INNER", "CELL", "QUOTE", "HERE", "NOTE"]
+    );
+
+    match &ast.children[1] {
+        ASTNode::Paragraph(paragraph) => {
+            assert!(matches!(&paragraph.children[0], ASTNode::Link(link) if link.url == "https://a.b"));
+            assert!(matches!(&paragraph.children[4], ASTNode::Mention(mention) if mention.name == "Alice"));
+        }
+        other => panic!("expected paragraph, got {:?}", other),
+    }
+    match &ast.children[2] {
+        ASTNode::List(list) => {
+            assert_eq!(list.items[0].checked, Some(false));
+            match &list.items[0].children[1] {
+                ASTNode::List(inner) => assert_eq!(inner.items[0].checked, Some(true)),
+                other => panic!("expected nested list, got {:?}", other),
+            }
+        }
+        other => panic!("expected list, got {:?}", other),
+    }
+}
@@ -1,4 +1,7 @@
-// AST 节点类型定义
+// 此文件由 rust-core 的 ast-codegen 根据 ast.rs 自动生成，请勿手动修改
+// 重新生成：cd rust-core && cargo run --features schema --bin ast-codegen -- typescript > ../web/src/types.ts
+
+export const AST_SCHEMA_VERSION = 10;
 
 export type ASTNode =
   | RootNode
@@ -23,129 +26,283 @@ export type ASTNode =
   | CardNode
   | MentionNode
   | HorizontalRuleNode
-  | BlockquoteNode;
+  | BlockquoteNode
+  | FootnoteReferenceNode
+  | FootnoteDefinitionNode
+  | HighlightNode
+  | SpoilerNode
+  | CustomNode
+  | TimestampNode
+  | CustomEmojiNode;
+
+/** 未知节点（前向兼容），保留原始 JSON 的全部字段 */
+export interface UnknownNode {
+  type: string;
+  [key: string]: unknown;
+}
 
+/** 可能包含未知节点类型的 AST 节点 */
+export type AnyASTNode = ASTNode | UnknownNode;
+
+/** 根节点 */
 export interface RootNode {
-  type: 'root';
+  type?: 'root';
+  schemaVersion?: number;
   children: ASTNode[];
 }
 
+/** 段落节点 */
 export interface ParagraphNode {
   type: 'paragraph';
   children: ASTNode[];
 }
 
+/** 标题节点 */
 export interface HeadingNode {
   type: 'heading';
   level: number;
   children: ASTNode[];
 }
 
+/** 文本节点 */
 export interface TextNode {
   type: 'text';
   content: string;
 }
 
+/** 粗体节点 */
 export interface StrongNode {
   type: 'strong';
   children: ASTNode[];
 }
 
+/** 斜体节点 */
 export interface EmNode {
   type: 'em';
   children: ASTNode[];
 }
 
+/** 下划线节点 */
 export interface UnderlineNode {
   type: 'underline';
   children: ASTNode[];
 }
 
+/** 删除线节点 */
 export interface StrikeNode {
   type: 'strike';
   children: ASTNode[];
 }
 
+/** 行内代码节点 */
 export interface CodeNode {
   type: 'code';
   content: string;
 }
 
+/** 代码块节点 */
 export interface CodeBlockNode {
   type: 'codeBlock';
-  language?: string;
+  language?: string | null;
   content: string;
 }
 
+/** 链接节点 */
 export interface LinkNode {
   type: 'link';
   url: string;
   children: ASTNode[];
 }
 
+/** 图片节点 */
 export interface ImageNode {
   type: 'image';
   url: string;
-  width?: number;
-  height?: number;
-  alt?: string;
+  width?: number | null;
+  height?: number | null;
+  alt?: string | null;
 }
 
+/** 列表节点 */
 export interface ListNode {
   type: 'list';
-  listType: 'bullet' | 'ordered';
+  listType: ListType;
   items: ListItemNode[];
 }
 
+/** 列表项节点 */
 export interface ListItemNode {
+  type?: 'listItem';
   children: ASTNode[];
-  checked?: boolean;
+  checked?: boolean | null;
 }
 
+/** 表格节点 */
 export interface TableNode {
   type: 'table';
   rows: TableRow[];
 }
 
+/** 表格行 */
 export interface TableRow {
+  type?: 'tableRow';
   cells: TableCell[];
 }
 
+/** 表格单元格 */
 export interface TableCell {
+  type?: 'tableCell';
   children: ASTNode[];
-  align?: 'left' | 'center' | 'right';
+  align?: TextAlign | null;
 }
 
+/** 数学公式节点 */
 export interface MathNode {
   type: 'math';
   content: string;
   display: boolean;
 }
 
+/** Mermaid 图表节点 */
 export interface MermaidNode {
   type: 'mermaid';
   content: string;
 }
 
+/** 卡片节点 */
 export interface CardNode {
   type: 'card';
   subtype: string;
   content: string;
-  metadata: Record<string, string>;
+  metadata: Record<string, unknown>;
+  header?: CardHeader | null;
+  fields?: CardField[];
+  images?: CardImage[];
+  actions?: CardAction[];
 }
 
+/** @提及节点 */
 export interface MentionNode {
   type: 'mention';
   id: string;
   name: string;
+  kind?: MentionKind;
+  status?: MentionStatus;
 }
 
+/** 水平分割线节点 */
 export interface HorizontalRuleNode {
   type: 'horizontalRule';
 }
 
+/** 引用块节点 */
 export interface BlockquoteNode {
   type: 'blockquote';
   children: ASTNode[];
 }
 
+/** 脚注引用节点 */
+export interface FootnoteReferenceNode {
+  type: 'footnoteReference';
+  label: string;
+  index: number;
+}
+
+/** 脚注定义节点 */
+export interface FootnoteDefinitionNode {
+  type: 'footnoteDefinition';
+  label: string;
+  index: number;
+  children: ASTNode[];
+}
+
+/** 高亮节点（`==text==`） */
+export interface HighlightNode {
+  type: 'highlight';
+  children: ASTNode[];
+}
+
+/** 剧透节点（`||text||`），子节点可以是行内内容，也可以是块级内容（块级剧透） */
+export interface SpoilerNode {
+  type: 'spoiler';
+  children: ASTNode[];
+}
+
+/** 自定义节点 */
+export interface CustomNode {
+  type: 'custom';
+  kind: string;
+  attrs?: Record<string, unknown>;
+  children?: ASTNode[];
+}
+
+/** 时间戳节点 */
+export interface TimestampNode {
+  type: 'timestamp';
+  timestamp: number;
+  style?: string | null;
+}
+
+/** 自定义表情节点 */
+export interface CustomEmojiNode {
+  type: 'customEmoji';
+  id: string;
+  name: string;
+  animated?: boolean;
+}
+
+/** 卡片交互元素 */
+export interface CardAction {
+  id: string;
+  kind?: CardActionKind;
+  label?: string;
+  style?: CardActionStyle;
+  value?: string | null;
+  url?: string | null;
+  placeholder?: string | null;
+  options?: CardSelectOption[];
+}
+
+/** 卡片交互元素类型 */
+export type CardActionKind = 'button' | 'select' | 'input';
+
+/** 按钮样式 */
+export type CardActionStyle = 'default' | 'primary' | 'danger';
+
+/** 卡片键值字段 */
+export interface CardField {
+  label: string;
+  value: string;
+  short?: boolean;
+}
+
+/** 卡片头部 */
+export interface CardHeader {
+  title: string;
+  subtitle?: string | null;
+  icon?: string | null;
+}
+
+/** 卡片图片 */
+export interface CardImage {
+  url: string;
+  alt?: string | null;
+}
+
+/** 下拉框选项 */
+export interface CardSelectOption {
+  label: string;
+  value: string;
+}
+
+/** 列表类型 */
+export type ListType = 'bullet' | 'ordered';
+
+/** 提及类型 */
+export type MentionKind = 'user' | 'channel' | 'all' | 'here' | 'role';
+
+/** 提及目标的解析状态，由 [`resolve_mentions`](crate::resolve_mentions) 填写 */
+export type MentionStatus = 'active' | 'unresolved' | 'deactivated' | 'unknown';
+
+/** 文本对齐方式 */
+export type TextAlign = 'left' | 'center' | 'right';